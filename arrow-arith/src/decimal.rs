@@ -0,0 +1,676 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Lossless decimal arithmetic kernels, such as [`add`] and [`div`]
+//!
+//! Unlike the kernels in [`numeric`](crate::numeric), which require both inputs to
+//! be of the same decimal type, these kernels accept any combination of
+//! [`DataType::Decimal32`], [`DataType::Decimal64`], [`DataType::Decimal128`] and
+//! [`DataType::Decimal256`], and derive the precision and scale of the result
+//! following the SQL rules used by Spark and Hive:
+//!
+//! | Operation | Result scale              | Result precision                    |
+//! |-----------|---------------------------|-------------------------------------|
+//! | `l + r`   | `max(s1, s2)`             | `max(p1 - s1, p2 - s2) + scale + 1` |
+//! | `l - r`   | `max(s1, s2)`             | `max(p1 - s1, p2 - s2) + scale + 1` |
+//! | `l * r`   | `s1 + s2`                 | `p1 + p2 + 1`                       |
+//! | `l / r`   | `max(6, s1 + p2 + 1)`     | `p1 - s1 + s2 + scale`              |
+//! | `l % r`   | `max(s1, s2)`             | `min(p1 - s1, p2 - s2) + scale`     |
+//!
+//! If the result precision exceeds that of [`DataType::Decimal256`], the precision
+//! is capped and the scale is reduced to preserve the integral digits, but not below
+//! `min(scale, 6)`. Digits discarded by reducing the scale are rounded according to
+//! the provided [`RoundingMode`].
+//!
+//! The result is of the narrowest decimal type able to hold the result precision,
+//! but no narrower than the widest input, e.g. adding two `Decimal64(18, 0)`
+//! results in a `Decimal128(19, 0)`.
+
+use std::cmp::Ordering;
+use std::sync::Arc;
+
+use arrow_array::cast::AsArray;
+use arrow_array::types::*;
+use arrow_array::*;
+use arrow_buffer::i256;
+use arrow_schema::{
+    ArrowError, DECIMAL32_MAX_PRECISION, DECIMAL64_MAX_PRECISION, DECIMAL128_MAX_PRECISION,
+    DECIMAL256_MAX_PRECISION, DataType,
+};
+
+use crate::arity::try_binary;
+
+/// The minimum scale retained when reducing the scale of a result to fit
+/// within [`DECIMAL256_MAX_PRECISION`]
+const MIN_ADJUSTED_SCALE: i32 = 6;
+
+/// How to round digits discarded when reducing the scale of a decimal value
+#[derive(Debug, Copy, Clone, Default, PartialEq, Eq, Hash)]
+pub enum RoundingMode {
+    /// Round towards the nearest value, with ties rounded away from zero
+    #[default]
+    HalfUp,
+    /// Round towards the nearest value, with ties rounded to the nearest even value
+    HalfEven,
+    /// Round towards zero, discarding the digits
+    Truncate,
+}
+
+/// Perform `lhs + rhs` on decimals, see the [module docs](self) for the result type
+pub fn add(
+    lhs: &dyn Datum,
+    rhs: &dyn Datum,
+    rounding: RoundingMode,
+) -> Result<ArrayRef, ArrowError> {
+    decimal_op(Op::Add, lhs, rhs, rounding)
+}
+
+/// Perform `lhs - rhs` on decimals, see the [module docs](self) for the result type
+pub fn sub(
+    lhs: &dyn Datum,
+    rhs: &dyn Datum,
+    rounding: RoundingMode,
+) -> Result<ArrayRef, ArrowError> {
+    decimal_op(Op::Sub, lhs, rhs, rounding)
+}
+
+/// Perform `lhs * rhs` on decimals, see the [module docs](self) for the result type
+///
+/// The product is computed before reducing its scale, and so will result in an
+/// error if it does not fit in an [`i256`], even if the rounded result would
+pub fn mul(
+    lhs: &dyn Datum,
+    rhs: &dyn Datum,
+    rounding: RoundingMode,
+) -> Result<ArrayRef, ArrowError> {
+    decimal_op(Op::Mul, lhs, rhs, rounding)
+}
+
+/// Perform `lhs / rhs` on decimals, see the [module docs](self) for the result type
+///
+/// Division by zero will result in an error
+pub fn div(
+    lhs: &dyn Datum,
+    rhs: &dyn Datum,
+    rounding: RoundingMode,
+) -> Result<ArrayRef, ArrowError> {
+    decimal_op(Op::Div, lhs, rhs, rounding)
+}
+
+/// Perform `lhs % rhs` on decimals, see the [module docs](self) for the result type
+///
+/// The result has the sign of `lhs`, division by zero will result in an error
+pub fn rem(
+    lhs: &dyn Datum,
+    rhs: &dyn Datum,
+    rounding: RoundingMode,
+) -> Result<ArrayRef, ArrowError> {
+    decimal_op(Op::Rem, lhs, rhs, rounding)
+}
+
+/// Returns the type of the result of `lhs op rhs` for the decimal types `lhs` and `rhs`,
+/// where `op` is one of `+`, `-`, `*`, `/` or `%`
+///
+/// ```
+/// # use arrow_arith::decimal::result_type;
+/// # use arrow_schema::DataType;
+/// let l = DataType::Decimal32(5, 2);
+/// let r = DataType::Decimal64(10, 4);
+/// assert_eq!(result_type('+', &l, &r).unwrap(), DataType::Decimal64(11, 4));
+/// assert_eq!(result_type('*', &l, &r).unwrap(), DataType::Decimal64(16, 6));
+/// assert_eq!(result_type('/', &l, &r).unwrap(), DataType::Decimal128(20, 13));
+/// ```
+pub fn result_type(op: char, lhs: &DataType, rhs: &DataType) -> Result<DataType, ArrowError> {
+    let op = match op {
+        '+' => Op::Add,
+        '-' => Op::Sub,
+        '*' => Op::Mul,
+        '/' => Op::Div,
+        '%' => Op::Rem,
+        _ => {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Unsupported decimal arithmetic operator: {op}"
+            )));
+        }
+    };
+    let (.., result) = op.result_type(lhs, rhs)?;
+    Ok(result)
+}
+
+/// Rounds each value of the decimal `array` to `scale` digits after the decimal point
+///
+/// If `scale` is less than the scale of `array`, the discarded digits are rounded
+/// according to `rounding` and the precision is reduced accordingly, leaving room
+/// for a carry, e.g. rounding a `Decimal128(5, 3)` to a scale of 1 results in a
+/// `Decimal128(4, 1)`. If `scale` is greater, values are padded with zeros and the
+/// precision increased.
+///
+/// Negative scales round to the left of the decimal point.
+///
+/// ```
+/// # use arrow_arith::decimal::{round, RoundingMode};
+/// # use arrow_array::Decimal128Array;
+/// # use arrow_array::cast::AsArray;
+/// # use arrow_array::types::Decimal128Type;
+/// let a = Decimal128Array::from(vec![1250, 1350, -1251])
+///     .with_precision_and_scale(5, 3)
+///     .unwrap();
+///
+/// let half_up = round(&a, 1, RoundingMode::HalfUp).unwrap();
+/// assert_eq!(half_up.as_primitive::<Decimal128Type>().values(), &[13, 14, -13]);
+///
+/// let half_even = round(&a, 1, RoundingMode::HalfEven).unwrap();
+/// assert_eq!(half_even.as_primitive::<Decimal128Type>().values(), &[12, 14, -13]);
+/// ```
+pub fn round(array: &dyn Array, scale: i8, rounding: RoundingMode) -> Result<ArrayRef, ArrowError> {
+    let data_type = array.data_type();
+    let (p, s) = precision_scale(data_type).ok_or_else(|| {
+        ArrowError::InvalidArgumentError(format!("Cannot round non-decimal type {data_type}"))
+    })?;
+    let (p, s, to_scale) = (p as i32, s as i32, scale as i32);
+    // Rounding may carry into an additional integral digit
+    let precision = match to_scale.cmp(&s) {
+        Ordering::Less => p - s + to_scale + 1,
+        _ => p - s + to_scale,
+    };
+    let precision = precision.clamp(1, DECIMAL256_MAX_PRECISION as i32) as u8;
+    let result = result_data_type(precision, scale, data_type, data_type);
+
+    let rescale = Rescale::try_new(s, to_scale)?;
+    let values = widen(array).try_unary::<_, Decimal256Type, _>(|v| rescale.apply(v, rounding))?;
+    narrow(&values, &result)
+}
+
+#[derive(Debug, Copy, Clone)]
+enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+    Rem,
+}
+
+impl std::fmt::Display for Op {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Op::Add => write!(f, "+"),
+            Op::Sub => write!(f, "-"),
+            Op::Mul => write!(f, "*"),
+            Op::Div => write!(f, "/"),
+            Op::Rem => write!(f, "%"),
+        }
+    }
+}
+
+impl Op {
+    /// Returns the scales of `lhs` and `rhs`, and the type of `lhs op rhs`
+    fn result_type(
+        self,
+        lhs: &DataType,
+        rhs: &DataType,
+    ) -> Result<(i32, i32, i32, DataType), ArrowError> {
+        let (Some((p1, s1)), Some((p2, s2))) = (precision_scale(lhs), precision_scale(rhs)) else {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Invalid decimal arithmetic operation: {lhs} {self} {rhs}"
+            )));
+        };
+        let (p1, s1, p2, s2) = (p1 as i32, s1 as i32, p2 as i32, s2 as i32);
+        let (precision, scale) = match self {
+            Op::Add | Op::Sub => {
+                let scale = s1.max(s2);
+                ((p1 - s1).max(p2 - s2) + scale + 1, scale)
+            }
+            Op::Mul => (p1 + p2 + 1, s1 + s2),
+            Op::Div => {
+                let scale = MIN_ADJUSTED_SCALE.max(s1 + p2 + 1);
+                (p1 - s1 + s2 + scale, scale)
+            }
+            Op::Rem => {
+                let scale = s1.max(s2);
+                ((p1 - s1).min(p2 - s2) + scale, scale)
+            }
+        };
+        let (precision, scale) = adjust_precision_scale(precision, scale);
+        let result = result_data_type(precision, scale as i8, lhs, rhs);
+        Ok((s1, s2, scale, result))
+    }
+}
+
+/// Caps `precision` at [`DECIMAL256_MAX_PRECISION`], reducing `scale` to retain the
+/// integral digits, but not below `min(scale, MIN_ADJUSTED_SCALE)`
+fn adjust_precision_scale(precision: i32, scale: i32) -> (u8, i32) {
+    let max = DECIMAL256_MAX_PRECISION as i32;
+    if precision <= max {
+        // The precision can be less than the scale if there are no integral digits
+        let precision = precision.max(scale).max(1);
+        return (precision as u8, scale);
+    }
+    let integral_digits = precision - scale;
+    let min_scale = scale.min(MIN_ADJUSTED_SCALE);
+    (max as u8, (max - integral_digits).max(min_scale))
+}
+
+/// Returns the precision and scale of `data_type` if it is a decimal
+fn precision_scale(data_type: &DataType) -> Option<(u8, i8)> {
+    match data_type {
+        DataType::Decimal32(p, s)
+        | DataType::Decimal64(p, s)
+        | DataType::Decimal128(p, s)
+        | DataType::Decimal256(p, s) => Some((*p, *s)),
+        _ => None,
+    }
+}
+
+/// Returns the narrowest decimal type that can hold `precision` and is at least
+/// as wide as both `lhs` and `rhs`
+fn result_data_type(precision: u8, scale: i8, lhs: &DataType, rhs: &DataType) -> DataType {
+    let rank = |data_type: &DataType| match data_type {
+        DataType::Decimal32(_, _) => 0,
+        DataType::Decimal64(_, _) => 1,
+        DataType::Decimal128(_, _) => 2,
+        _ => 3,
+    };
+    let required = match precision {
+        p if p <= DECIMAL32_MAX_PRECISION => 0,
+        p if p <= DECIMAL64_MAX_PRECISION => 1,
+        p if p <= DECIMAL128_MAX_PRECISION => 2,
+        _ => 3,
+    };
+    match required.max(rank(lhs)).max(rank(rhs)) {
+        0 => DataType::Decimal32(precision, scale),
+        1 => DataType::Decimal64(precision, scale),
+        2 => DataType::Decimal128(precision, scale),
+        _ => DataType::Decimal256(precision, scale),
+    }
+}
+
+fn overflow() -> ArrowError {
+    ArrowError::ArithmeticOverflow("Overflow happened on decimal arithmetic".to_string())
+}
+
+/// Returns `10^exp`
+fn pow10(exp: i32) -> Result<i256, ArrowError> {
+    i256::from_i128(10)
+        .checked_pow(exp as u32)
+        .ok_or_else(overflow)
+}
+
+/// Returns `n / d` rounded according to `rounding`
+fn div_round(n: i256, d: i256, rounding: RoundingMode) -> Result<i256, ArrowError> {
+    if d == i256::ZERO {
+        return Err(ArrowError::DivideByZero);
+    }
+    let q = n.checked_div(d).ok_or_else(overflow)?;
+    let r = n.wrapping_rem(d);
+    if r == i256::ZERO || rounding == RoundingMode::Truncate {
+        return Ok(q);
+    }
+
+    // Compare |r| to |d| - |r|, which unlike 2 * |r| cannot overflow,
+    // as r has the sign of n, |d| - |r| is either |d - r| or |d + r|
+    let remaining = match r.is_negative() == d.is_negative() {
+        true => d.wrapping_sub(r),
+        false => d.wrapping_add(r),
+    };
+    let round_away = match r.wrapping_abs().cmp(&remaining.wrapping_abs()) {
+        Ordering::Less => false,
+        Ordering::Greater => true,
+        Ordering::Equal => match rounding {
+            RoundingMode::HalfUp => true,
+            _ => q.wrapping_rem(i256::from_i128(2)) != i256::ZERO,
+        },
+    };
+    match (round_away, n.is_negative() == d.is_negative()) {
+        (false, _) => Ok(q),
+        (true, true) => q.checked_add(i256::ONE).ok_or_else(overflow),
+        (true, false) => q.checked_sub(i256::ONE).ok_or_else(overflow),
+    }
+}
+
+/// Converts a decimal value from one scale to another
+#[derive(Debug, Copy, Clone)]
+enum Rescale {
+    Mul(i256),
+    Div(i256),
+}
+
+impl Rescale {
+    fn try_new(from: i32, to: i32) -> Result<Self, ArrowError> {
+        match to >= from {
+            true => Ok(Self::Mul(pow10(to - from)?)),
+            false => Ok(Self::Div(pow10(from - to)?)),
+        }
+    }
+
+    fn apply(self, v: i256, rounding: RoundingMode) -> Result<i256, ArrowError> {
+        match self {
+            Self::Mul(i256::ONE) => Ok(v),
+            Self::Mul(m) => v.checked_mul(m).ok_or_else(overflow),
+            Self::Div(d) => div_round(v, d, rounding),
+        }
+    }
+}
+
+/// Converts the values of the decimal `array` to [`i256`]
+fn widen(array: &dyn Array) -> Decimal256Array {
+    match array.data_type() {
+        DataType::Decimal32(_, _) => array.as_primitive::<Decimal32Type>().unary(i256::from),
+        DataType::Decimal64(_, _) => array.as_primitive::<Decimal64Type>().unary(i256::from),
+        DataType::Decimal128(_, _) => array
+            .as_primitive::<Decimal128Type>()
+            .unary(i256::from_i128),
+        DataType::Decimal256(_, _) => array.as_primitive::<Decimal256Type>().clone(),
+        d => unreachable!("expected decimal, got {d}"),
+    }
+}
+
+/// Converts `values` to `data_type`, returning an error if a value exceeds its precision
+fn narrow(values: &Decimal256Array, data_type: &DataType) -> Result<ArrayRef, ArrowError> {
+    fn convert<T: DecimalType>(
+        values: &Decimal256Array,
+        precision: u8,
+        scale: i8,
+        f: impl Fn(i256) -> Option<T::Native>,
+    ) -> Result<ArrayRef, ArrowError> {
+        let array = values.try_unary::<_, T, _>(|v| {
+            f(v).filter(|v| T::is_valid_decimal_precision(*v, precision))
+                .ok_or_else(|| {
+                    ArrowError::ArithmeticOverflow(format!(
+                        "Decimal value {v} at scale {scale} exceeds the maximum precision of {precision}"
+                    ))
+                })
+        })?;
+        Ok(Arc::new(array.with_precision_and_scale(precision, scale)?))
+    }
+
+    match *data_type {
+        DataType::Decimal32(p, s) => convert::<Decimal32Type>(values, p, s, |v| {
+            v.to_i128().and_then(|v| v.try_into().ok())
+        }),
+        DataType::Decimal64(p, s) => convert::<Decimal64Type>(values, p, s, |v| {
+            v.to_i128().and_then(|v| v.try_into().ok())
+        }),
+        DataType::Decimal128(p, s) => convert::<Decimal128Type>(values, p, s, |v| v.to_i128()),
+        DataType::Decimal256(p, s) => convert::<Decimal256Type>(values, p, s, Some),
+        ref d => unreachable!("expected decimal, got {d}"),
+    }
+}
+
+fn decimal_op(
+    op: Op,
+    lhs: &dyn Datum,
+    rhs: &dyn Datum,
+    rounding: RoundingMode,
+) -> Result<ArrayRef, ArrowError> {
+    let (l, l_s) = lhs.get();
+    let (r, r_s) = rhs.get();
+    let (s1, s2, scale, result) = op.result_type(l.data_type(), r.data_type())?;
+
+    let l = widen(l);
+    let r = widen(r);
+
+    let values = match op {
+        Op::Add | Op::Sub | Op::Rem => {
+            // Perform the operation at the larger of the input scales
+            let common = s1.max(s2);
+            let (l_mul, r_mul) = (Rescale::try_new(s1, common)?, Rescale::try_new(s2, common)?);
+            let out = Rescale::try_new(common, scale)?;
+            try_op(&l, l_s, &r, r_s, |l, r| {
+                let l = l_mul.apply(l, rounding)?;
+                let r = r_mul.apply(r, rounding)?;
+                let v = match op {
+                    Op::Add => l.checked_add(r).ok_or_else(overflow)?,
+                    Op::Sub => l.checked_sub(r).ok_or_else(overflow)?,
+                    _ if r == i256::ZERO => return Err(ArrowError::DivideByZero),
+                    _ => l.wrapping_rem(r),
+                };
+                out.apply(v, rounding)
+            })?
+        }
+        Op::Mul => {
+            let out = Rescale::try_new(s1 + s2, scale)?;
+            try_op(&l, l_s, &r, r_s, |l, r| {
+                out.apply(l.checked_mul(r).ok_or_else(overflow)?, rounding)
+            })?
+        }
+        Op::Div => {
+            // l / r has a scale of s1 - s2, scale the dividend or divisor to obtain `scale`
+            let exp = scale - s1 + s2;
+            let (l_mul, r_mul) = match exp >= 0 {
+                true => (pow10(exp)?, i256::ONE),
+                false => (i256::ONE, pow10(-exp)?),
+            };
+            try_op(&l, l_s, &r, r_s, |l, r| {
+                if r == i256::ZERO {
+                    return Err(ArrowError::DivideByZero);
+                }
+                let l = l.checked_mul(l_mul).ok_or_else(overflow)?;
+                let r = r.checked_mul(r_mul).ok_or_else(overflow)?;
+                div_round(l, r, rounding)
+            })?
+        }
+    };
+    narrow(&values, &result)
+}
+
+/// Applies `op` to `l` and `r`, either of which may be a scalar
+fn try_op(
+    l: &Decimal256Array,
+    l_s: bool,
+    r: &Decimal256Array,
+    r_s: bool,
+    op: impl Fn(i256, i256) -> Result<i256, ArrowError>,
+) -> Result<Decimal256Array, ArrowError> {
+    match (l_s, r_s) {
+        (true, true) | (false, false) => try_binary(l, r, op),
+        (true, false) => match (l.null_count() == 0).then(|| l.value(0)) {
+            None => Ok(PrimitiveArray::new_null(r.len())),
+            Some(l) => r.try_unary(|r| op(l, r)),
+        },
+        (false, true) => match (r.null_count() == 0).then(|| r.value(0)) {
+            None => Ok(PrimitiveArray::new_null(l.len())),
+            Some(r) => l.try_unary(|l| op(l, r)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn decimal128(values: Vec<Option<i128>>, p: u8, s: i8) -> Decimal128Array {
+        Decimal128Array::from(values)
+            .with_precision_and_scale(p, s)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_add_sub_mixed_width() {
+        // 1.23, -4.56
+        let a = Decimal32Array::from(vec![123, -456])
+            .with_precision_and_scale(5, 2)
+            .unwrap();
+        // 10.0001, 0.0001
+        let b = Decimal64Array::from(vec![100001, 1])
+            .with_precision_and_scale(10, 4)
+            .unwrap();
+
+        let r = add(&a, &b, RoundingMode::default()).unwrap();
+        assert_eq!(r.data_type(), &DataType::Decimal64(11, 4));
+        assert_eq!(
+            r.as_primitive::<Decimal64Type>().values(),
+            &[112301, -45599]
+        );
+
+        let r = sub(&b, &a, RoundingMode::default()).unwrap();
+        assert_eq!(r.data_type(), &DataType::Decimal64(11, 4));
+        assert_eq!(r.as_primitive::<Decimal64Type>().values(), &[87701, 45601]);
+    }
+
+    #[test]
+    fn test_promotion() {
+        let max = Decimal32Array::from(vec![999_999_999])
+            .with_precision_and_scale(9, 0)
+            .unwrap();
+        let r = add(&max, &max, RoundingMode::default()).unwrap();
+        assert_eq!(r.data_type(), &DataType::Decimal64(10, 0));
+        assert_eq!(r.as_primitive::<Decimal64Type>().value(0), 1_999_999_998);
+
+        let r = mul(&max, &max, RoundingMode::default()).unwrap();
+        assert_eq!(r.data_type(), &DataType::Decimal128(19, 0));
+        assert_eq!(
+            r.as_primitive::<Decimal128Type>().value(0),
+            999_999_998_000_000_001
+        );
+
+        let a = decimal128(vec![Some(i128::MAX / 10)], 38, 0);
+        let r = mul(&a, &a, RoundingMode::default()).unwrap();
+        assert_eq!(r.data_type(), &DataType::Decimal256(76, 0));
+        let expected =
+            i256::from_i128(i128::MAX / 10).wrapping_mul(i256::from_i128(i128::MAX / 10));
+        assert_eq!(r.as_primitive::<Decimal256Type>().value(0), expected);
+    }
+
+    #[test]
+    fn test_mul_reduces_scale() {
+        // Decimal256(60, 30) * Decimal256(60, 30) has 61 integral digits, the scale
+        // is reduced from 60 to 76 - 61 = 15
+        let pow = |e| i256::from_i128(10).checked_pow(e).unwrap();
+        // 1.0000000000000005
+        let a = Decimal256Array::from(vec![
+            i256::from_i128(10_000_000_000_000_005).wrapping_mul(pow(14)),
+        ])
+        .with_precision_and_scale(60, 30)
+        .unwrap();
+        // 1, -1
+        let b = Decimal256Array::from(vec![pow(30), pow(30).wrapping_neg()])
+            .with_precision_and_scale(60, 30)
+            .unwrap();
+        let a = Scalar::new(a);
+        for (rounding, expected) in [
+            (RoundingMode::HalfUp, 1_000_000_000_000_001),
+            (RoundingMode::HalfEven, 1_000_000_000_000_000),
+            (RoundingMode::Truncate, 1_000_000_000_000_000),
+        ] {
+            let r = mul(&a, &b, rounding).unwrap();
+            assert_eq!(r.data_type(), &DataType::Decimal256(76, 15));
+            let expected = [i256::from_i128(expected), i256::from_i128(-expected)];
+            assert_eq!(r.as_primitive::<Decimal256Type>().values(), &expected);
+        }
+    }
+
+    #[test]
+    fn test_div() {
+        // 1.00, 2.00, -2.00, 1.00
+        let a = decimal128(vec![Some(100), Some(200), Some(-200), None], 5, 2);
+        // 3, 3, 3, 3
+        let b = Decimal32Array::from(vec![3, 3, 3, 3])
+            .with_precision_and_scale(1, 0)
+            .unwrap();
+
+        // scale = max(6, 2 + 1 + 1) = 6, precision = 5 - 2 + 0 + 6 = 9
+        let r = div(&a, &b, RoundingMode::HalfUp).unwrap();
+        assert_eq!(r.data_type(), &DataType::Decimal128(9, 6));
+        let r = r.as_primitive::<Decimal128Type>();
+        assert_eq!(
+            r.iter().collect::<Vec<_>>(),
+            vec![Some(333333), Some(666667), Some(-666667), None]
+        );
+
+        let r = div(&a, &b, RoundingMode::Truncate).unwrap();
+        let r = r.as_primitive::<Decimal128Type>();
+        assert_eq!(
+            r.iter().collect::<Vec<_>>(),
+            vec![Some(333333), Some(666666), Some(-666666), None]
+        );
+
+        let zero = Scalar::new(decimal128(vec![Some(0)], 1, 0));
+        let err = div(&a, &zero, RoundingMode::HalfUp).unwrap_err();
+        assert!(matches!(err, ArrowError::DivideByZero));
+    }
+
+    #[test]
+    fn test_rem() {
+        // 7.5, -7.5
+        let a = decimal128(vec![Some(75), Some(-75)], 2, 1);
+        // 2.25
+        let b = Scalar::new(decimal128(vec![Some(225)], 3, 2));
+        let r = rem(&a, &b, RoundingMode::default()).unwrap();
+        assert_eq!(r.data_type(), &DataType::Decimal128(3, 2));
+        assert_eq!(r.as_primitive::<Decimal128Type>().values(), &[75, -75]);
+    }
+
+    #[test]
+    fn test_scalar_null() {
+        let a = decimal128(vec![Some(1), Some(2)], 5, 2);
+        let null = Scalar::new(decimal128(vec![None], 5, 2));
+        let r = add(&null, &a, RoundingMode::default()).unwrap();
+        assert_eq!(r.len(), 2);
+        assert_eq!(r.null_count(), 2);
+        assert_eq!(r.data_type(), &DataType::Decimal128(6, 2));
+    }
+
+    #[test]
+    fn test_round() {
+        // 1.25, 1.35, -1.25, -1.35, 9.99
+        let a = decimal128(
+            vec![Some(125), Some(135), Some(-125), Some(-135), Some(999)],
+            3,
+            2,
+        );
+        let cases = [
+            (RoundingMode::HalfUp, [13, 14, -13, -14, 100]),
+            (RoundingMode::HalfEven, [12, 14, -12, -14, 100]),
+            (RoundingMode::Truncate, [12, 13, -12, -13, 99]),
+        ];
+        for (rounding, expected) in cases {
+            let r = round(&a, 1, rounding).unwrap();
+            assert_eq!(r.data_type(), &DataType::Decimal128(3, 1));
+            assert_eq!(r.as_primitive::<Decimal128Type>().values(), &expected);
+        }
+
+        let r = round(&a, 4, RoundingMode::HalfUp).unwrap();
+        assert_eq!(r.data_type(), &DataType::Decimal128(5, 4));
+        assert_eq!(r.as_primitive::<Decimal128Type>().value(0), 12500);
+
+        let r = round(&a, -1, RoundingMode::HalfUp).unwrap();
+        assert_eq!(r.data_type(), &DataType::Decimal128(1, -1));
+        assert_eq!(
+            r.as_primitive::<Decimal128Type>().values(),
+            &[0, 0, 0, 0, 1]
+        );
+    }
+
+    #[test]
+    fn test_overflow() {
+        let a = Decimal256Array::from(vec![i256::MAX])
+            .with_precision_and_scale(76, 0)
+            .unwrap();
+        let err = add(&a, &a, RoundingMode::default()).unwrap_err();
+        assert!(matches!(err, ArrowError::ArithmeticOverflow(_)), "{err}");
+    }
+
+    #[test]
+    fn test_invalid() {
+        let a = Int32Array::from(vec![1]);
+        let err = add(&a, &a, RoundingMode::default()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid argument error: Invalid decimal arithmetic operation: Int32 + Int32"
+        );
+    }
+}
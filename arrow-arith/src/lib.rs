@@ -29,5 +29,6 @@ pub mod arithmetic;
 pub mod arity;
 pub mod bitwise;
 pub mod boolean;
+pub mod decimal;
 pub mod numeric;
 pub mod temporal;
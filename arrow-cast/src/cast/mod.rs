@@ -43,6 +43,7 @@ mod list;
 mod map;
 mod run_array;
 mod string;
+mod union;
 
 use crate::cast::decimal::*;
 use crate::cast::dictionary::*;
@@ -50,6 +51,7 @@ use crate::cast::list::*;
 use crate::cast::map::*;
 use crate::cast::run_array::*;
 use crate::cast::string::*;
+use crate::cast::union::*;
 
 use arrow_buffer::IntervalMonthDayNano;
 use arrow_data::ByteView;
@@ -111,6 +113,13 @@ pub fn can_cast_types(from_type: &DataType, to_type: &DataType) -> bool {
         (RunEndEncoded(_, value_type), _) => can_cast_types(value_type.data_type(), to_type),
         (_, RunEndEncoded(_, value_type)) => can_cast_types(from_type, value_type.data_type()),
         (_, Dictionary(_, value_type)) => can_cast_types(from_type, value_type),
+        (Union(_, _), Union(_, _)) => false,
+        (Union(fields, _), _) => {
+            union_variant(fields, to_type, |f| can_cast_variant(f, to_type)).is_some()
+        }
+        (_, Union(fields, _)) => {
+            union_variant(fields, from_type, |f| can_cast_types(from_type, f)).is_some()
+        }
         (
             List(list_from) | LargeList(list_from) | ListView(list_from) | LargeListView(list_from),
             List(list_to) | LargeList(list_to) | ListView(list_to) | LargeListView(list_to),
@@ -308,13 +317,6 @@ pub fn can_cast_types(from_type: &DataType, to_type: &DataType) -> bool {
         (_, Duration(_)) if from_type.is_numeric() => true,
         (Duration(_), _) if to_type.is_numeric() => true,
         (Duration(_), Duration(_)) => true,
-        (Interval(from_type), Int64) => {
-            match from_type {
-                YearMonth => true,
-                DayTime => true,
-                MonthDayNano => false, // Native type is i128
-            }
-        }
         (Int32, Interval(to_type)) => match to_type {
             YearMonth => true,
             DayTime => false,
@@ -633,6 +635,15 @@ fn timestamp_to_date32<T: ArrowTimestampType>(
 ///   (i.e. casting `6.4999` to `Decimal(10, 1)` becomes `6.5`).
 /// * `Decimal` to `Float32/Float64` is lossy and values outside the representable
 ///   range become `INFINITY` or `-INFINITY` without error.
+/// * To `RunEndEncoded`: values are cast to the value type and consecutive equal
+///   values are encoded as a single run. The cast fails if the run ends do not fit
+///   in the run end type
+/// * `RunEndEncoded` to `RunEndEncoded`: the run ends and values are cast separately
+/// * `Union` to other types: the values of the first variant with the target type, or
+///   failing that the first variant that can be cast to the target type, are cast.
+///   Slots containing other variants become NULL
+/// * To `Union`: values are cast to the first variant with the source type, or failing
+///   that the first variant the source type can be cast to, and every slot is of that variant
 ///
 /// Unsupported Casts (check with `can_cast_types` before calling):
 /// * To or from `StructArray`
@@ -807,6 +818,11 @@ pub fn cast_with_options(
                 "Casting from type {from_type} to dictionary type {to_type} not supported",
             ))),
         },
+        (Union(_, _), Union(_, _)) => Err(ArrowError::CastError(format!(
+            "Casting from {from_type} to {to_type} not supported"
+        ))),
+        (Union(_, _), _) => cast_from_union(array, to_type, cast_options),
+        (_, Union(fields, mode)) => cast_to_union(array, fields, *mode, cast_options),
         // Casting between lists of same types (cast inner values)
         (List(_), List(to)) => cast_list_values::<i32>(array, to, cast_options),
         (LargeList(_), LargeList(to)) => cast_list_values::<i64>(array, to, cast_options),
@@ -13347,4 +13363,104 @@ mod tests {
 
         assert_eq!(expected, actual);
     }
+
+    fn union_fields() -> UnionFields {
+        UnionFields::try_new(
+            vec![0, 1],
+            vec![
+                Field::new("int", DataType::Int32, true),
+                Field::new("str", DataType::Utf8, true),
+            ],
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_cast_from_union() {
+        let fields = UnionFields::try_new(
+            vec![0, 1],
+            vec![
+                Field::new("int", DataType::Int32, true),
+                Field::new("float", DataType::Float64, true),
+            ],
+        )
+        .unwrap();
+        let children: Vec<ArrayRef> = vec![
+            Arc::new(Int32Array::from(vec![Some(1), None, Some(3), None])),
+            Arc::new(Float64Array::from(vec![None, Some(2.5), None, None])),
+        ];
+        let type_ids = vec![0_i8, 1, 0, 0].into();
+        let union = UnionArray::try_new(fields, type_ids, None, children).unwrap();
+
+        // Exact match on the Int32 variant
+        assert!(can_cast_types(union.data_type(), &DataType::Int32));
+        let result = cast(&union, &DataType::Int32).unwrap();
+        let expected = Int32Array::from(vec![Some(1), None, Some(3), None]);
+        assert_eq!(result.as_primitive::<Int32Type>(), &expected);
+
+        // No exact match, the first castable variant is used
+        let result = cast(&union, &DataType::Int64).unwrap();
+        let expected = Int64Array::from(vec![Some(1), None, Some(3), None]);
+        assert_eq!(result.as_primitive::<Int64Type>(), &expected);
+
+        assert!(!can_cast_types(
+            union.data_type(),
+            &DataType::Struct(Fields::empty())
+        ));
+        let err = cast(&union, &DataType::Struct(Fields::empty())).unwrap_err();
+        assert!(err.to_string().contains("no variant can be cast"), "{err}");
+
+        // Slots containing other variants cannot be wrapped in non-nullable lists
+        let field = Arc::new(Field::new("item", DataType::Int32, false));
+        for to_type in [
+            DataType::List(field.clone()),
+            DataType::FixedSizeList(field.clone(), 1),
+        ] {
+            assert!(!can_cast_types(union.data_type(), &to_type));
+            let err = cast(&union, &to_type).unwrap_err();
+            assert!(err.to_string().contains("no variant can be cast"), "{err}");
+        }
+        let to_type = DataType::List(Arc::new(Field::new("item", DataType::Int32, true)));
+        assert!(can_cast_types(union.data_type(), &to_type));
+        assert_eq!(cast(&union, &to_type).unwrap().null_count(), 0);
+    }
+
+    #[test]
+    fn test_cast_to_union() {
+        let array = StringArray::from(vec![Some("a"), None, Some("c")]);
+        for mode in [UnionMode::Sparse, UnionMode::Dense] {
+            let to_type = DataType::Union(union_fields(), mode);
+            assert!(can_cast_types(array.data_type(), &to_type));
+            let result = cast(&array, &to_type).unwrap();
+            assert_eq!(result.data_type(), &to_type);
+
+            let union = result.as_union();
+            assert_eq!(union.len(), 3);
+            assert!(union.type_ids().iter().all(|id| *id == 1));
+            let values = union.child(1);
+            assert_eq!(values.as_string::<i32>(), &array);
+            if mode == UnionMode::Dense {
+                assert_eq!(union.offsets().unwrap(), &[0, 1, 2]);
+                assert_eq!(union.child(0).len(), 0);
+            } else {
+                assert_eq!(union.child(0).null_count(), 3);
+            }
+        }
+
+        // No exact match, Int16 is cast to the Int32 variant
+        let array = Int16Array::from(vec![1, 2]);
+        let to_type = DataType::Union(union_fields(), UnionMode::Sparse);
+        let result = cast(&array, &to_type).unwrap();
+        let union = result.as_union();
+        assert!(union.type_ids().iter().all(|id| *id == 0));
+        assert_eq!(
+            union.child(0).as_primitive::<Int32Type>(),
+            &Int32Array::from(vec![1, 2])
+        );
+
+        // Union to union is not supported
+        let dense = DataType::Union(union_fields(), UnionMode::Dense);
+        assert!(!can_cast_types(&to_type, &dense));
+        assert!(cast(&result, &dense).is_err());
+    }
 }
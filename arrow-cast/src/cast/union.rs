@@ -0,0 +1,129 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::cast::*;
+use arrow_select::union_extract::union_extract;
+
+/// Returns the variant of `fields` that values of type `data_type` are cast to or from
+///
+/// This is the first variant with the same type as `data_type` if any, otherwise
+/// the first variant for which `can_cast` returns true
+pub(crate) fn union_variant<'a>(
+    fields: &'a UnionFields,
+    data_type: &DataType,
+    can_cast: impl Fn(&DataType) -> bool,
+) -> Option<(i8, &'a FieldRef)> {
+    fields
+        .iter()
+        .find(|(_, f)| f.data_type() == data_type)
+        .or_else(|| fields.iter().find(|(_, f)| can_cast(f.data_type())))
+}
+
+/// Returns true if the values of a union variant of type `variant_type` can be
+/// cast to `to_type`
+///
+/// The extracted values are null in slots containing other variants, which
+/// casts that wrap each value in a list with a non-nullable field cannot hold
+pub(crate) fn can_cast_variant(variant_type: &DataType, to_type: &DataType) -> bool {
+    let wraps_in_non_nullable = match to_type {
+        DataType::List(f)
+        | DataType::LargeList(f)
+        | DataType::ListView(f)
+        | DataType::LargeListView(f)
+        | DataType::FixedSizeList(f, _) => !f.is_nullable() && !variant_type.is_list(),
+        _ => false,
+    };
+    !wraps_in_non_nullable && can_cast_types(variant_type, to_type)
+}
+
+/// Casts a [`UnionArray`] to `to_type` by extracting the values of the variant
+/// selected by [`union_variant`], slots containing other variants are null
+pub(crate) fn cast_from_union(
+    array: &dyn Array,
+    to_type: &DataType,
+    cast_options: &CastOptions,
+) -> Result<ArrayRef, ArrowError> {
+    let array = array.as_union();
+    let DataType::Union(fields, _) = array.data_type() else {
+        unreachable!("expected union array")
+    };
+    let (_, field) =
+        union_variant(fields, to_type, |f| can_cast_variant(f, to_type)).ok_or_else(|| {
+            ArrowError::CastError(format!(
+                "Casting from {} to {to_type} not supported, no variant can be cast to {to_type}",
+                array.data_type()
+            ))
+        })?;
+
+    let extracted = union_extract(array, field.name())?;
+    cast_with_options(extracted.as_ref(), to_type, cast_options)
+}
+
+/// Casts `array` to a [`UnionArray`] of type `to_type`, in which every slot is of
+/// the variant selected by [`union_variant`]
+pub(crate) fn cast_to_union(
+    array: &dyn Array,
+    fields: &UnionFields,
+    mode: UnionMode,
+    cast_options: &CastOptions,
+) -> Result<ArrayRef, ArrowError> {
+    let from_type = array.data_type();
+    let (type_id, field) = union_variant(fields, from_type, |f| can_cast_types(from_type, f))
+        .ok_or_else(|| {
+            ArrowError::CastError(format!(
+                "Casting from {from_type} to {} not supported, {from_type} cannot be cast to any variant",
+                DataType::Union(fields.clone(), mode)
+            ))
+        })?;
+    let values = cast_with_options(array, field.data_type(), cast_options)?;
+
+    let len = array.len();
+    let type_ids = vec![type_id; len].into();
+    let (offsets, children) = match mode {
+        UnionMode::Sparse => {
+            let children = fields
+                .iter()
+                .map(|(id, f)| match id == type_id {
+                    true => Arc::clone(&values),
+                    false => new_null_array(f.data_type(), len),
+                })
+                .collect();
+            (None, children)
+        }
+        UnionMode::Dense => {
+            let offsets = i32::try_from(len).map_err(|_| {
+                ArrowError::CastError(format!(
+                    "Cannot cast array of length {len} to dense union, length exceeds i32::MAX"
+                ))
+            })?;
+            let children = fields
+                .iter()
+                .map(|(id, f)| match id == type_id {
+                    true => Arc::clone(&values),
+                    false => new_empty_array(f.data_type()),
+                })
+                .collect();
+            (Some((0..offsets).collect()), children)
+        }
+    };
+    Ok(Arc::new(UnionArray::try_new(
+        fields.clone(),
+        type_ids,
+        offsets,
+        children,
+    )?))
+}
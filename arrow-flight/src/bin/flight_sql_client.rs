@@ -15,20 +15,39 @@
 // specific language governing permissions and limitations
 // under the License.
 
-use std::{sync::Arc, time::Duration};
+#[cfg(feature = "cli-repl")]
+use std::ops::ControlFlow;
+use std::{
+    fs::File,
+    io::{BufReader, Read, Seek, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Context, Result, bail};
-use arrow_array::{ArrayRef, Datum, RecordBatch, StringArray};
+use arrow_array::{ArrayRef, Datum, RecordBatch, RecordBatchReader, StringArray};
 use arrow_cast::{CastOptions, cast_with_options, pretty::pretty_format_batches};
+#[cfg(feature = "cli-repl")]
+use arrow_flight::sql::EndTransaction;
 use arrow_flight::{
     FlightInfo,
+    error::FlightError,
     flight_service_client::FlightServiceClient,
-    sql::{CommandGetDbSchemas, CommandGetTables, client::FlightSqlServiceClient},
+    sql::{
+        CommandGetDbSchemas, CommandGetTables, CommandStatementIngest, TableDefinitionOptions,
+        TableExistsOption, TableNotExistOption, client::FlightSqlServiceClient,
+    },
 };
 use arrow_schema::Schema;
+use bytes::Bytes;
 use clap::{Parser, Subcommand, ValueEnum};
 use core::str;
-use futures::TryStreamExt;
+use futures::{TryStreamExt, stream};
+#[cfg(feature = "cli-formats")]
+use parquet::arrow::{ArrowWriter, arrow_reader::ParquetRecordBatchReaderBuilder};
+#[cfg(feature = "cli-repl")]
+use rustyline::{DefaultEditor, error::ReadlineError};
 use tonic::{
     metadata::MetadataMap,
     transport::{Channel, ClientTlsConfig, Endpoint},
@@ -153,6 +172,44 @@ struct ClientArgs {
     send_compression: Option<CompressionEncoding>,
 }
 
+/// Format of query results.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Pretty printed table.
+    Table,
+    /// Comma separated values, with a header.
+    #[cfg(feature = "cli-formats")]
+    Csv,
+    /// A JSON array of objects.
+    #[cfg(feature = "cli-formats")]
+    Json,
+    /// Newline delimited JSON objects.
+    #[cfg(feature = "cli-formats")]
+    Ndjson,
+    /// Arrow IPC stream.
+    ArrowIpc,
+    /// Parquet file.
+    #[cfg(feature = "cli-formats")]
+    Parquet,
+}
+
+#[derive(Clone, Debug, Parser)]
+struct OutputArgs {
+    /// Format of query results.
+    #[clap(long, value_enum, default_value_t = OutputFormat::Table)]
+    format: OutputFormat,
+
+    /// Write query results to this file instead of stdout.
+    ///
+    /// The file is overwritten by every query.
+    #[clap(long, short = 'o')]
+    output: Option<PathBuf>,
+
+    /// Print timing statistics of every query to stderr.
+    #[clap(long)]
+    timing: bool,
+}
+
 #[derive(Debug, Parser)]
 struct Args {
     /// Logging args.
@@ -163,6 +220,10 @@ struct Args {
     #[clap(flatten)]
     client_args: ClientArgs,
 
+    /// Output args.
+    #[clap(flatten)]
+    output_args: OutputArgs,
+
     #[clap(subcommand)]
     cmd: Command,
 }
@@ -243,6 +304,119 @@ enum Command {
         #[clap(short, value_parser = parse_key_val)]
         params: Vec<(String, String)>,
     },
+
+    /// Upload a local CSV, Parquet or Arrow IPC file into a table.
+    ///
+    /// CSV and Parquet files require the `cli-formats` feature.
+    Ingest(IngestArgs),
+
+    /// Start an interactive session.
+    ///
+    /// Each line is executed as a SQL query. Lines starting with `\` are commands,
+    /// type `\help` to list them.
+    #[cfg(feature = "cli-repl")]
+    Repl {
+        /// File to load the command history from and save it to.
+        ///
+        /// Defaults to `.flight_sql_client_history` in the home directory.
+        #[clap(long)]
+        history_file: Option<PathBuf>,
+    },
+}
+
+/// Format of a file to ingest.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum FileFormat {
+    /// Comma separated values, with a header.
+    #[cfg(feature = "cli-formats")]
+    Csv,
+    /// Parquet file.
+    #[cfg(feature = "cli-formats")]
+    Parquet,
+    /// Arrow IPC file or stream.
+    ArrowIpc,
+}
+
+/// Behavior when the target table of an ingest does not exist.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum IfNotExists {
+    /// Create the table.
+    Create,
+    /// Fail the ingest.
+    Fail,
+}
+
+impl From<IfNotExists> for TableNotExistOption {
+    fn from(option: IfNotExists) -> Self {
+        match option {
+            IfNotExists::Create => Self::Create,
+            IfNotExists::Fail => Self::Fail,
+        }
+    }
+}
+
+/// Behavior when the target table of an ingest already exists.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+pub enum IfExists {
+    /// Fail the ingest.
+    Fail,
+    /// Append the data to the table.
+    Append,
+    /// Replace the contents of the table.
+    Replace,
+}
+
+impl From<IfExists> for TableExistsOption {
+    fn from(option: IfExists) -> Self {
+        match option {
+            IfExists::Fail => Self::Fail,
+            IfExists::Append => Self::Append,
+            IfExists::Replace => Self::Replace,
+        }
+    }
+}
+
+#[derive(Debug, Parser)]
+struct IngestArgs {
+    /// Name of the target table.
+    ///
+    /// Required.
+    table: String,
+
+    /// File to upload.
+    ///
+    /// Required.
+    file: PathBuf,
+
+    /// Format of the file.
+    ///
+    /// Inferred from the file extension if not given.
+    #[clap(long, value_enum)]
+    file_format: Option<FileFormat>,
+
+    /// Catalog of the target table.
+    #[clap(long)]
+    catalog: Option<String>,
+
+    /// Db schema of the target table.
+    #[clap(long)]
+    db_schema: Option<String>,
+
+    /// Ingest into a temporary table.
+    #[clap(long)]
+    temporary: bool,
+
+    /// Behavior if the table does not exist.
+    #[clap(long, value_enum, default_value_t = IfNotExists::Create)]
+    if_not_exists: IfNotExists,
+
+    /// Behavior if the table already exists.
+    #[clap(long, value_enum, default_value_t = IfExists::Fail)]
+    if_exists: IfExists,
+
+    /// Number of rows per record batch sent to the server, for CSV and Parquet files.
+    #[clap(long, default_value_t = 8192)]
+    batch_size: usize,
 }
 
 #[tokio::main]
@@ -253,6 +427,7 @@ async fn main() -> Result<()> {
         .await
         .context("setup client")?;
 
+    let start = Instant::now();
     let flight_info = match args.cmd {
         Command::Catalogs => client.get_catalogs().await.context("get catalogs")?,
         Command::DbSchemas {
@@ -313,18 +488,332 @@ async fn main() -> Result<()> {
                 .await
                 .context("execute prepared statement")?
         }
+        Command::Ingest(ingest_args) => {
+            let rows = ingest(&mut client, ingest_args, None).await?;
+            println!("{rows}");
+            return Ok(());
+        }
+        #[cfg(feature = "cli-repl")]
+        Command::Repl { history_file } => {
+            return run_repl(client, args.output_args, history_file).await;
+        }
     };
 
-    let batches = execute_flight(&mut client, flight_info)
+    print_flight(&mut client, flight_info, &args.output_args, start).await
+}
+
+/// Fetch the results of a query started at `start` and write them as
+/// requested by `output_args`
+async fn print_flight(
+    client: &mut FlightSqlServiceClient<Channel>,
+    info: FlightInfo,
+    output_args: &OutputArgs,
+    start: Instant,
+) -> Result<()> {
+    let planning = start.elapsed();
+    let batches = execute_flight(client, info)
         .await
         .context("read flight data")?;
+    let total = start.elapsed();
 
-    let res = pretty_format_batches(batches.as_slice()).context("format results")?;
-    println!("{res}");
+    match &output_args.output {
+        Some(path) => {
+            let file = File::create(path)
+                .with_context(|| format!("create output file {}", path.display()))?;
+            write_batches(file, output_args.format, &batches)?;
+        }
+        None => write_batches(std::io::stdout(), output_args.format, &batches)?,
+    }
 
+    if output_args.timing {
+        let rows: usize = batches.iter().map(|b| b.num_rows()).sum();
+        eprintln!(
+            "{rows} row(s) in {total:.3?} (planning: {planning:.3?}, fetching: {:.3?})",
+            total - planning
+        );
+    }
     Ok(())
 }
 
+/// Write `batches` in the given format.
+///
+/// The first batch determines the schema, see [`execute_flight`].
+fn write_batches<W: Write + Send>(
+    mut writer: W,
+    format: OutputFormat,
+    batches: &[RecordBatch],
+) -> Result<()> {
+    let Some(schema) = batches.first().map(|b| b.schema()) else {
+        return Ok(());
+    };
+    match format {
+        OutputFormat::Table => {
+            let res = pretty_format_batches(batches).context("format results")?;
+            writeln!(writer, "{res}")?;
+        }
+        #[cfg(feature = "cli-formats")]
+        OutputFormat::Csv => {
+            let mut writer = arrow_csv::Writer::new(writer);
+            for batch in batches {
+                writer.write(batch).context("write CSV")?;
+            }
+        }
+        #[cfg(feature = "cli-formats")]
+        OutputFormat::Json => {
+            let mut writer = arrow_json::ArrayWriter::new(writer);
+            writer.write_batches(&batches.iter().collect::<Vec<_>>())?;
+            writer.finish().context("write JSON")?;
+            writeln!(writer.into_inner())?;
+        }
+        #[cfg(feature = "cli-formats")]
+        OutputFormat::Ndjson => {
+            let mut writer = arrow_json::LineDelimitedWriter::new(writer);
+            writer.write_batches(&batches.iter().collect::<Vec<_>>())?;
+            writer.finish().context("write NDJSON")?;
+        }
+        OutputFormat::ArrowIpc => {
+            let mut writer = arrow_ipc::writer::StreamWriter::try_new(writer, &schema)?;
+            for batch in batches {
+                writer.write(batch).context("write Arrow IPC")?;
+            }
+            writer.finish().context("write Arrow IPC")?;
+        }
+        #[cfg(feature = "cli-formats")]
+        OutputFormat::Parquet => {
+            let mut writer = ArrowWriter::try_new(writer, schema, None)?;
+            for batch in batches {
+                writer.write(batch).context("write Parquet")?;
+            }
+            writer.close().context("write Parquet")?;
+        }
+    }
+    Ok(())
+}
+
+/// Upload the file described by `args`, returning the number of ingested rows.
+async fn ingest(
+    client: &mut FlightSqlServiceClient<Channel>,
+    args: IngestArgs,
+    transaction_id: Option<Bytes>,
+) -> Result<i64> {
+    let format = match args.file_format {
+        Some(format) => format,
+        None => file_format_from_extension(&args.file)?,
+    };
+    let reader = open_file(&args.file, format, args.batch_size)
+        .with_context(|| format!("open {}", args.file.display()))?;
+    info!("ingesting {} as {format:?}", args.file.display());
+
+    let command = CommandStatementIngest {
+        table_definition_options: Some(TableDefinitionOptions {
+            if_not_exist: TableNotExistOption::from(args.if_not_exists).into(),
+            if_exists: TableExistsOption::from(args.if_exists).into(),
+        }),
+        table: args.table,
+        schema: args.db_schema,
+        catalog: args.catalog,
+        temporary: args.temporary,
+        transaction_id,
+        options: Default::default(),
+    };
+    let batches = stream::iter(reader.map(|batch| batch.map_err(FlightError::Arrow)));
+    client
+        .execute_ingest(command, batches)
+        .await
+        .context("ingest")
+}
+
+fn file_format_from_extension(path: &Path) -> Result<FileFormat> {
+    let extension = path
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase());
+    Ok(match extension.as_deref() {
+        #[cfg(feature = "cli-formats")]
+        Some("csv") => FileFormat::Csv,
+        #[cfg(feature = "cli-formats")]
+        Some("parquet" | "pq") => FileFormat::Parquet,
+        Some("arrow" | "arrows" | "ipc" | "feather") => FileFormat::ArrowIpc,
+        _ => bail!(
+            "cannot infer the format of {}, use --file-format",
+            path.display()
+        ),
+    })
+}
+
+fn open_file(
+    path: &Path,
+    format: FileFormat,
+    // Arrow IPC files keep the batches they were written with
+    #[cfg_attr(not(feature = "cli-formats"), allow(unused_variables))] batch_size: usize,
+) -> Result<Box<dyn RecordBatchReader + Send>> {
+    let mut file = File::open(path)?;
+    Ok(match format {
+        #[cfg(feature = "cli-formats")]
+        FileFormat::Csv => {
+            let csv_format = arrow_csv::reader::Format::default().with_header(true);
+            let (schema, _) = csv_format
+                .infer_schema(&mut file, Some(1000))
+                .context("infer CSV schema")?;
+            file.rewind()?;
+            let reader = arrow_csv::ReaderBuilder::new(Arc::new(schema))
+                .with_format(csv_format)
+                .with_batch_size(batch_size)
+                .build(file)?;
+            Box::new(reader)
+        }
+        #[cfg(feature = "cli-formats")]
+        FileFormat::Parquet => {
+            let reader = ParquetRecordBatchReaderBuilder::try_new(file)?
+                .with_batch_size(batch_size)
+                .build()?;
+            Box::new(reader)
+        }
+        FileFormat::ArrowIpc => {
+            // Arrow IPC files start with a magic number, streams do not
+            let mut magic = [0; 6];
+            let is_file = file.read_exact(&mut magic).is_ok() && &magic == b"ARROW1";
+            file.rewind()?;
+            if is_file {
+                Box::new(arrow_ipc::reader::FileReader::try_new(file, None)?)
+            } else {
+                Box::new(arrow_ipc::reader::StreamReader::try_new(
+                    BufReader::new(file),
+                    None,
+                )?)
+            }
+        }
+    })
+}
+
+#[cfg(feature = "cli-repl")]
+const REPL_HELP: &str = r"Enter a SQL query to execute it, or one of the following commands:
+
+  \help               Show this help
+  \quit               Exit, also `\q`, `quit` or `exit`
+  \format <format>    Set the output format: table, csv, json, ndjson, arrow-ipc or parquet
+  \timing             Toggle printing timing statistics
+  \begin              Begin a transaction
+  \commit             Commit the current transaction
+  \rollback           Roll back the current transaction";
+
+/// Run an interactive session until the user quits or the input is closed.
+#[cfg(feature = "cli-repl")]
+async fn run_repl(
+    mut client: FlightSqlServiceClient<Channel>,
+    mut output_args: OutputArgs,
+    history_file: Option<PathBuf>,
+) -> Result<()> {
+    let history_file = history_file.or_else(|| {
+        std::env::var_os("HOME").map(|home| Path::new(&home).join(".flight_sql_client_history"))
+    });
+
+    let mut editor = DefaultEditor::new().context("create line editor")?;
+    if let Some(path) = &history_file {
+        // The history file does not exist on first use
+        let _ = editor.load_history(path);
+    }
+
+    let mut transaction_id = None;
+    loop {
+        let prompt = match transaction_id {
+            Some(_) => "flight_sql*> ",
+            None => "flight_sql> ",
+        };
+        let line = match editor.readline(prompt) {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) => continue,
+            Err(ReadlineError::Eof) => break,
+            Err(e) => return Err(e).context("read line"),
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        editor.add_history_entry(line)?;
+
+        match run_repl_line(&mut client, line, &mut output_args, &mut transaction_id).await {
+            Ok(ControlFlow::Continue(())) => {}
+            Ok(ControlFlow::Break(())) => break,
+            Err(e) => eprintln!("Error: {e:#}"),
+        }
+    }
+
+    if let Some(transaction_id) = transaction_id {
+        eprintln!("Rolling back open transaction");
+        client
+            .end_transaction(transaction_id, EndTransaction::Rollback)
+            .await
+            .context("roll back transaction")?;
+    }
+    if let Some(path) = &history_file {
+        if let Err(e) = editor.save_history(path) {
+            info!("failed to save history to {}: {e}", path.display());
+        }
+    }
+    Ok(())
+}
+
+/// Execute a single line of an interactive session.
+#[cfg(feature = "cli-repl")]
+async fn run_repl_line(
+    client: &mut FlightSqlServiceClient<Channel>,
+    line: &str,
+    output_args: &mut OutputArgs,
+    transaction_id: &mut Option<Bytes>,
+) -> Result<ControlFlow<()>> {
+    let (command, arg) = match line.split_once(char::is_whitespace) {
+        Some((command, arg)) => (command, arg.trim()),
+        None => (line, ""),
+    };
+    match command {
+        r"\q" | r"\quit" | "quit" | "exit" => return Ok(ControlFlow::Break(())),
+        r"\?" | r"\help" => println!("{REPL_HELP}"),
+        r"\format" => match OutputFormat::from_str(arg, true) {
+            Ok(format) => output_args.format = format,
+            Err(e) => bail!("invalid format {arg:?}: {e}"),
+        },
+        r"\timing" => {
+            output_args.timing = !output_args.timing;
+            let state = if output_args.timing { "on" } else { "off" };
+            println!("Timing is {state}");
+        }
+        r"\begin" => {
+            if transaction_id.is_some() {
+                bail!("a transaction is already in progress");
+            }
+            let id = client.begin_transaction().await.context("begin")?;
+            *transaction_id = Some(id);
+        }
+        r"\commit" | r"\rollback" => {
+            // Keep the transaction if ending it fails, so it can be retried
+            let Some(id) = transaction_id.clone() else {
+                bail!("no transaction in progress");
+            };
+            let action = match command {
+                r"\commit" => EndTransaction::Commit,
+                _ => EndTransaction::Rollback,
+            };
+            client
+                .end_transaction(id, action)
+                .await
+                .context("end transaction")?;
+            *transaction_id = None;
+        }
+        _ if command.starts_with('\\') => bail!(r"unknown command {command}, try \help"),
+        _ => {
+            let query = line.trim_end_matches(';').to_string();
+            let start = Instant::now();
+            let info = client
+                .execute(query, transaction_id.clone())
+                .await
+                .context("execute statement")?;
+            print_flight(client, info, output_args, start).await?;
+        }
+    }
+    Ok(ControlFlow::Continue(()))
+}
+
 async fn execute_flight(
     client: &mut FlightSqlServiceClient<Channel>,
     info: FlightInfo,
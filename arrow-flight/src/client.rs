@@ -15,25 +15,36 @@
 // specific language governing permissions and limitations
 // under the License.
 
+use std::collections::HashMap;
+use std::fmt::Debug;
+use std::sync::{Arc, Mutex};
+
 use crate::{
     Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
     HandshakeRequest, PollInfo, PutResult, Ticket,
-    decode::FlightRecordBatchStream,
+    decode::{DecodedPayload, FlightDataDecoder, FlightRecordBatchStream},
     flight_service_client::FlightServiceClient,
     r#gen::{CancelFlightInfoRequest, CancelFlightInfoResult, RenewFlightEndpointRequest},
     trailers::extract_lazy_trailers,
 };
-use arrow_schema::Schema;
+use arrow_array::RecordBatch;
+use arrow_schema::{Schema, SchemaRef};
 use bytes::Bytes;
 use futures::{
-    Stream, StreamExt, TryStreamExt,
-    future::ready,
+    FutureExt, Stream, StreamExt, TryStreamExt,
+    future::{BoxFuture, ready},
     stream::{self, BoxStream},
 };
 use prost::Message;
-use tonic::{metadata::MetadataMap, transport::Channel};
+use tonic::{
+    metadata::MetadataMap,
+    transport::{Channel, Endpoint},
+};
 
+use crate::encode::FlightDataEncoderBuilder;
 use crate::error::{FlightError, Result};
+use crate::exchange::ExchangeSender;
+use crate::middleware::{FlightClientInterceptor, FlightMethod};
 use crate::streams::{FallibleRequestStream, FallibleTonicResponseStream};
 
 /// A "Mid level" [Apache Arrow Flight](https://arrow.apache.org/docs/format/Flight.html) client.
@@ -66,15 +77,27 @@ use crate::streams::{FallibleRequestStream, FallibleTonicResponseStream};
 /// assert_eq!(response, Bytes::from("Ho"));
 /// # }
 /// ```
-#[derive(Debug)]
 pub struct FlightClient {
     /// Optional grpc header metadata to include with each request
     metadata: MetadataMap,
 
+    /// Interceptors invoked before each request
+    interceptors: Vec<Arc<dyn FlightClientInterceptor>>,
+
     /// The inner client
     inner: FlightServiceClient<Channel>,
 }
 
+impl Debug for FlightClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FlightClient")
+            .field("metadata", &self.metadata)
+            .field("interceptors", &self.interceptors.len())
+            .field("inner", &self.inner)
+            .finish()
+    }
+}
+
 impl FlightClient {
     /// Creates a client client with the provided [`Channel`]
     pub fn new(channel: Channel) -> Self {
@@ -85,6 +108,7 @@ impl FlightClient {
     pub fn new_from_inner(inner: FlightServiceClient<Channel>) -> Self {
         Self {
             metadata: MetadataMap::new(),
+            interceptors: vec![],
             inner,
         }
     }
@@ -118,6 +142,15 @@ impl FlightClient {
         Ok(())
     }
 
+    /// Add an interceptor that is invoked before all subsequent requests,
+    /// after any previously added interceptors
+    ///
+    /// See [`FlightClientInterceptor`] for details and
+    /// [`AuthInterceptor`](crate::middleware::AuthInterceptor) for an example.
+    pub fn add_interceptor(&mut self, interceptor: impl FlightClientInterceptor) {
+        self.interceptors.push(Arc::new(interceptor));
+    }
+
     /// Return a reference to the underlying tonic
     /// [`FlightServiceClient`]
     pub fn inner(&self) -> &FlightServiceClient<Channel> {
@@ -149,7 +182,7 @@ impl FlightClient {
         };
 
         // apply headers, etc
-        let request = self.make_request(stream::once(ready(request)));
+        let request = self.make_request(FlightMethod::Handshake, stream::once(ready(request)))?;
 
         let mut response_stream = self.inner.handshake(request).await?.into_inner();
 
@@ -169,7 +202,7 @@ impl FlightClient {
 
     /// Make a `DoGet` call to the server with the provided ticket,
     /// returning a [`FlightRecordBatchStream`] for reading
-    /// [`RecordBatch`](arrow_array::RecordBatch)es.
+    /// [`RecordBatch`]es.
     ///
     /// # Note
     ///
@@ -204,7 +237,7 @@ impl FlightClient {
     /// # }
     /// ```
     pub async fn do_get(&mut self, ticket: Ticket) -> Result<FlightRecordBatchStream> {
-        let request = self.make_request(ticket);
+        let request = self.make_request(FlightMethod::DoGet, ticket)?;
 
         let (md, response_stream, _ext) = self.inner.do_get(request).await?.into_parts();
         let (response_stream, trailers) = extract_lazy_trailers(response_stream);
@@ -216,6 +249,41 @@ impl FlightClient {
         .with_trailers(trailers))
     }
 
+    /// Retrieve the data for every [`FlightEndpoint`] in `info` as a
+    /// single stream of [`RecordBatch`]es,
+    /// using a [`FlightInfoFetcher`] with default options.
+    ///
+    /// See [`FlightInfoFetcher`] for how endpoints are fetched.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # async fn run() {
+    /// # use arrow_flight::FlightClient;
+    /// # use arrow_flight::FlightDescriptor;
+    /// # use arrow_array::RecordBatch;
+    /// # use futures::stream::TryStreamExt;
+    /// # let channel: tonic::transport::Channel = unimplemented!();
+    /// let mut client = FlightClient::new(channel);
+    ///
+    /// let request = FlightDescriptor::new_cmd(b"MOAR DATA".to_vec());
+    /// let flight_info = client
+    ///   .get_flight_info(request)
+    ///   .await
+    ///   .expect("error getting flight info");
+    ///
+    /// // Fetch the batches from all endpoints
+    /// let batches: Vec<RecordBatch> = client
+    ///   .do_get_flight_info(flight_info)
+    ///   .expect("invalid flight info")
+    ///   .try_collect()
+    ///   .await
+    ///   .expect("no stream errors");
+    /// # }
+    /// ```
+    pub fn do_get_flight_info(&self, info: FlightInfo) -> Result<FlightRecordBatchStream> {
+        FlightInfoFetcher::new(self).fetch(info)
+    }
+
     /// Make a `GetFlightInfo` call to the server with the provided
     /// [`FlightDescriptor`] and return the [`FlightInfo`] from the
     /// server. The [`FlightInfo`] can be used with [`Self::do_get`]
@@ -252,7 +320,7 @@ impl FlightClient {
     /// # }
     /// ```
     pub async fn get_flight_info(&mut self, descriptor: FlightDescriptor) -> Result<FlightInfo> {
-        let request = self.make_request(descriptor);
+        let request = self.make_request(FlightMethod::GetFlightInfo, descriptor)?;
 
         let response = self.inner.get_flight_info(request).await?.into_inner();
         Ok(response)
@@ -310,7 +378,7 @@ impl FlightClient {
     /// # }
     /// ```
     pub async fn poll_flight_info(&mut self, descriptor: FlightDescriptor) -> Result<PollInfo> {
-        let request = self.make_request(descriptor);
+        let request = self.make_request(FlightMethod::PollFlightInfo, descriptor)?;
 
         let response = self.inner.poll_flight_info(request).await?.into_inner();
         Ok(response)
@@ -368,7 +436,7 @@ impl FlightClient {
         let request = Box::pin(request); // Pin to heap
         let request_stream = FallibleRequestStream::new(sender, request);
 
-        let request = self.make_request(request_stream);
+        let request = self.make_request(FlightMethod::DoPut, request_stream)?;
         let response_stream = self.inner.do_put(request).await?.into_inner();
 
         // Forwards errors from the error oneshot with priority over responses from server
@@ -422,7 +490,7 @@ impl FlightClient {
         // Intercepts client errors and sends them to the oneshot channel above
         let request_stream = FallibleRequestStream::new(sender, request);
 
-        let request = self.make_request(request_stream);
+        let request = self.make_request(FlightMethod::DoExchange, request_stream)?;
         let response_stream = self.inner.do_exchange(request).await?.into_inner();
 
         let response_stream = Box::pin(response_stream);
@@ -432,6 +500,66 @@ impl FlightClient {
         Ok(FlightRecordBatchStream::new_from_flight_data(error_stream))
     }
 
+    /// Make a `DoExchange` call to the server with `descriptor`, sending the
+    /// [`RecordBatch`]es of `input` and returning the decoded output
+    ///
+    /// The descriptor is sent immediately, in a message of its own, and
+    /// `input` is encoded as the server is ready to receive it. The input of
+    /// the call ends when `input` does. See the [`exchange`](crate::exchange)
+    /// module for the server side.
+    ///
+    /// # Example:
+    /// ```no_run
+    /// # async fn run() {
+    /// # use arrow_flight::{FlightClient, FlightDescriptor};
+    /// # use arrow_array::{ArrayRef, RecordBatch, UInt64Array};
+    /// # use futures::stream::{self, TryStreamExt};
+    /// # use std::sync::Arc;
+    /// # let channel: tonic::transport::Channel = unimplemented!();
+    /// let mut client = FlightClient::new(channel);
+    /// let c1 = Arc::new(UInt64Array::from(vec![1, 2, 3])) as ArrayRef;
+    /// let batch = RecordBatch::try_from_iter(vec![("c1", c1)]).unwrap();
+    ///
+    /// let output: Vec<RecordBatch> = client
+    ///   .exchange_batches(FlightDescriptor::new_cmd("udf"), stream::iter([Ok(batch)]))
+    ///   .await
+    ///   .expect("error calling do_exchange")
+    ///   .try_collect()
+    ///   .await
+    ///   .expect("error reading output");
+    /// # }
+    /// ```
+    pub async fn exchange_batches<S>(
+        &mut self,
+        descriptor: FlightDescriptor,
+        input: S,
+    ) -> Result<FlightRecordBatchStream>
+    where
+        S: Stream<Item = Result<RecordBatch>> + Send + 'static,
+    {
+        let descriptor = FlightData::new().with_descriptor(descriptor);
+        let input = FlightDataEncoderBuilder::new().build(input);
+        self.do_exchange(stream::once(ready(Ok(descriptor))).chain(input))
+            .await
+    }
+
+    /// Make a `DoExchange` call to the server with `descriptor`, returning an
+    /// [`ExchangeSender`] to send [`RecordBatch`]es and the decoded output
+    ///
+    /// Batches can be sent while the output is read, for example to send a
+    /// batch and wait for its result before sending the next one.
+    /// [`ExchangeSender::send`] waits while the server is not ready to
+    /// receive more data. Dropping the sender ends the input of the call. See
+    /// [`Self::exchange_batches`] for details.
+    pub async fn exchange_channel(
+        &mut self,
+        descriptor: FlightDescriptor,
+    ) -> Result<(ExchangeSender, FlightRecordBatchStream)> {
+        let (sender, input) = ExchangeSender::new();
+        let output = self.exchange_batches(descriptor, input).await?;
+        Ok((sender, output))
+    }
+
     /// Make a `ListFlights` call to the server with the provided
     /// criteria and returning a [`Stream`] of [`FlightInfo`].
     ///
@@ -463,7 +591,7 @@ impl FlightClient {
             expression: expression.into(),
         };
 
-        let request = self.make_request(request);
+        let request = self.make_request(FlightMethod::ListFlights, request)?;
 
         let response = self
             .inner
@@ -497,7 +625,7 @@ impl FlightClient {
     /// # }
     /// ```
     pub async fn get_schema(&mut self, flight_descriptor: FlightDescriptor) -> Result<Schema> {
-        let request = self.make_request(flight_descriptor);
+        let request = self.make_request(FlightMethod::GetSchema, flight_descriptor)?;
 
         let schema_result = self.inner.get_schema(request).await?.into_inner();
 
@@ -530,7 +658,7 @@ impl FlightClient {
     /// # }
     /// ```
     pub async fn list_actions(&mut self) -> Result<BoxStream<'static, Result<ActionType>>> {
-        let request = self.make_request(Empty {});
+        let request = self.make_request(FlightMethod::ListActions, Empty {})?;
 
         let action_stream = self
             .inner
@@ -568,7 +696,7 @@ impl FlightClient {
     /// # }
     /// ```
     pub async fn do_action(&mut self, action: Action) -> Result<BoxStream<'static, Result<Bytes>>> {
-        let request = self.make_request(action);
+        let request = self.make_request(FlightMethod::DoAction, action)?;
 
         let result_stream = self
             .inner
@@ -664,10 +792,262 @@ impl FlightClient {
     }
 
     /// return a Request, adding any configured metadata
-    fn make_request<T>(&self, t: T) -> tonic::Request<T> {
-        // Pass along metadata
-        let mut request = tonic::Request::new(t);
-        *request.metadata_mut() = self.metadata.clone();
-        request
+    fn make_request<T>(&self, method: FlightMethod, t: T) -> Result<tonic::Request<T>> {
+        make_request(&self.metadata, &self.interceptors, method, t)
+    }
+}
+
+/// return a Request with `metadata`, after applying `interceptors`
+fn make_request<T>(
+    metadata: &MetadataMap,
+    interceptors: &[Arc<dyn FlightClientInterceptor>],
+    method: FlightMethod,
+    t: T,
+) -> Result<tonic::Request<T>> {
+    // Pass along metadata
+    let mut request = tonic::Request::new(t);
+    *request.metadata_mut() = metadata.clone();
+    for interceptor in interceptors {
+        interceptor.on_request(method, request.metadata_mut())?;
     }
+    Ok(request)
+}
+
+/// Location URI indicating that a [`FlightEndpoint`] can be fetched using the
+/// connection the [`FlightInfo`] was retrieved on
+pub const REUSE_CONNECTION_LOCATION: &str = "arrow-flight-reuse-connection://?";
+
+/// The default maximum number of endpoints a [`FlightInfoFetcher`] fetches
+/// concurrently
+pub const DEFAULT_FETCH_CONCURRENCY: usize = 4;
+
+/// A function that connects to the location URI of a [`FlightEndpoint`],
+/// see [`FlightInfoFetcher::with_connector`]
+pub type LocationConnector =
+    Arc<dyn Fn(String) -> BoxFuture<'static, Result<Channel>> + Send + Sync>;
+
+/// Retrieves the data for every [`FlightEndpoint`] of a [`FlightInfo`]
+///
+/// Each endpoint's [`Ticket`] is redeemed with a `DoGet` call to one of its
+/// locations:
+///
+/// - An endpoint without locations, or with the [`REUSE_CONNECTION_LOCATION`],
+///   is fetched on the connection of the [`FlightClient`] the fetcher was
+///   created from
+/// - Other locations are connected to with the [`LocationConnector`], and
+///   connections are shared by endpoints with the same location
+/// - If the `DoGet` call fails, the next location of the endpoint is tried.
+///   Errors after the call has succeeded, while streaming data, are not retried
+///
+/// Up to [`Self::with_max_concurrency`] endpoints are fetched concurrently.
+/// If [`FlightInfo::ordered`] is set, batches are returned in endpoint order,
+/// otherwise batches from different endpoints are interleaved in the order
+/// they arrive.
+///
+/// The metadata and interceptors of the [`FlightClient`] are applied to every
+/// request, including those to other locations.
+///
+/// # Example:
+/// ```no_run
+/// # async fn run() {
+/// # use arrow_flight::{FlightClient, FlightInfo};
+/// # use arrow_flight::client::FlightInfoFetcher;
+/// # use arrow_array::RecordBatch;
+/// # use futures::stream::TryStreamExt;
+/// # let channel: tonic::transport::Channel = unimplemented!();
+/// # let flight_info: FlightInfo = unimplemented!();
+/// let client = FlightClient::new(channel);
+///
+/// let batches: Vec<RecordBatch> = FlightInfoFetcher::new(&client)
+///   .with_max_concurrency(8)
+///   .fetch(flight_info)
+///   .expect("invalid flight info")
+///   .try_collect()
+///   .await
+///   .expect("no stream errors");
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct FlightInfoFetcher {
+    /// Client for the connection the `FlightInfo` was retrieved on
+    inner: FlightServiceClient<Channel>,
+    /// grpc header metadata to include with each request
+    metadata: MetadataMap,
+    interceptors: Vec<Arc<dyn FlightClientInterceptor>>,
+    max_concurrency: usize,
+    connector: LocationConnector,
+    /// Connections to other locations, keyed by location URI
+    channels: Arc<Mutex<HashMap<String, Channel>>>,
+}
+
+impl Debug for FlightInfoFetcher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FlightInfoFetcher")
+            .field("inner", &self.inner)
+            .field("metadata", &self.metadata)
+            .field("interceptors", &self.interceptors.len())
+            .field("max_concurrency", &self.max_concurrency)
+            .field("connector", &"<connector>")
+            .finish()
+    }
+}
+
+impl FlightInfoFetcher {
+    /// Create a new [`FlightInfoFetcher`] that reuses the connection,
+    /// metadata and interceptors of `client`
+    pub fn new(client: &FlightClient) -> Self {
+        Self {
+            inner: client.inner.clone(),
+            metadata: client.metadata.clone(),
+            interceptors: client.interceptors.clone(),
+            max_concurrency: DEFAULT_FETCH_CONCURRENCY,
+            connector: Arc::new(|uri| connect_location(uri).boxed()),
+            channels: Default::default(),
+        }
+    }
+
+    /// Set the maximum number of endpoints fetched concurrently,
+    /// defaults to [`DEFAULT_FETCH_CONCURRENCY`]
+    pub fn with_max_concurrency(mut self, max_concurrency: usize) -> Self {
+        self.max_concurrency = max_concurrency.max(1);
+        self
+    }
+
+    /// Set the function used to connect to endpoint locations
+    ///
+    /// The default connector supports `grpc`, `grpc+tcp`, `grpc+tls`, `http`
+    /// and `https` URIs, without any TLS configuration. Provide a custom
+    /// connector to configure TLS, timeouts or other transports.
+    pub fn with_connector<F, Fut>(mut self, connector: F) -> Self
+    where
+        F: Fn(String) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Channel>> + Send + 'static,
+    {
+        self.connector = Arc::new(move |uri| connector(uri).boxed());
+        self
+    }
+
+    /// Return a stream of the data for every [`FlightEndpoint`] in `info`
+    ///
+    /// Endpoints are fetched lazily as the stream is polled. Returns an error
+    /// if an endpoint has no [`Ticket`].
+    pub fn fetch(self, info: FlightInfo) -> Result<FlightRecordBatchStream> {
+        let endpoints = info.endpoint;
+        if endpoints.iter().any(|endpoint| endpoint.ticket.is_none()) {
+            return Err(FlightError::protocol("FlightEndpoint is missing a ticket"));
+        }
+
+        let max_concurrency = self.max_concurrency;
+        let fetcher = Arc::new(self);
+        let endpoints = stream::iter(endpoints).map(move |endpoint| {
+            let fetcher = Arc::clone(&fetcher);
+            async move { fetcher.do_get_endpoint(endpoint).await }
+        });
+
+        let decoded = match info.ordered {
+            true => endpoints.buffered(max_concurrency).try_flatten().boxed(),
+            false => endpoints
+                .map(|fetch| stream::once(fetch).try_flatten().boxed())
+                .flatten_unordered(max_concurrency)
+                .boxed(),
+        };
+
+        // Each endpoint starts with its own schema message, only pass on the first
+        let mut schema: Option<SchemaRef> = None;
+        let decoded = decoded.try_filter_map(move |data| {
+            ready(match (&data.payload, &schema) {
+                (DecodedPayload::Schema(s), None) => {
+                    schema = Some(Arc::clone(s));
+                    Ok(Some(data))
+                }
+                (DecodedPayload::Schema(s), Some(existing)) if s.fields() == existing.fields() => {
+                    Ok(None)
+                }
+                (DecodedPayload::Schema(s), Some(existing)) => Err(FlightError::protocol(format!(
+                    "FlightEndpoints returned different schemas: {existing:?} and {s:?}"
+                ))),
+                _ => Ok(Some(data)),
+            })
+        });
+
+        Ok(FlightRecordBatchStream::new(
+            FlightDataDecoder::new_from_decoded(decoded),
+        ))
+    }
+
+    /// Redeem the ticket of `endpoint`, trying each of its locations in turn
+    async fn do_get_endpoint(&self, endpoint: FlightEndpoint) -> Result<FlightDataDecoder> {
+        let ticket = endpoint.ticket.unwrap_or_default();
+        let mut locations = endpoint.location.into_iter().map(|location| location.uri);
+
+        let Some(first) = locations.next() else {
+            return self
+                .do_get_location(REUSE_CONNECTION_LOCATION, ticket)
+                .await;
+        };
+        let mut result = self.do_get_location(&first, ticket.clone()).await;
+        for uri in locations {
+            if result.is_ok() {
+                break;
+            }
+            result = self.do_get_location(&uri, ticket.clone()).await;
+        }
+        result
+    }
+
+    async fn do_get_location(&self, uri: &str, ticket: Ticket) -> Result<FlightDataDecoder> {
+        let mut client = match is_reuse_connection(uri) {
+            true => self.inner.clone(),
+            false => FlightServiceClient::new(self.channel(uri).await?),
+        };
+
+        let request = make_request(
+            &self.metadata,
+            &self.interceptors,
+            FlightMethod::DoGet,
+            ticket,
+        )?;
+        let response = client.do_get(request).await?.into_inner();
+        Ok(FlightDataDecoder::new(
+            response.map_err(|status| status.into()),
+        ))
+    }
+
+    /// Return a connection to `uri`, connecting if necessary
+    async fn channel(&self, uri: &str) -> Result<Channel> {
+        if let Some(channel) = self.channels.lock().unwrap().get(uri) {
+            return Ok(channel.clone());
+        }
+        let channel = (self.connector)(uri.to_string()).await?;
+        self.channels
+            .lock()
+            .unwrap()
+            .insert(uri.to_string(), channel.clone());
+        Ok(channel)
+    }
+}
+
+fn is_reuse_connection(uri: &str) -> bool {
+    uri.split_once("://")
+        .is_some_and(|(scheme, _)| scheme == "arrow-flight-reuse-connection")
+}
+
+/// The default [`LocationConnector`]
+async fn connect_location(uri: String) -> Result<Channel> {
+    let uri = match uri.split_once("://") {
+        Some(("grpc" | "grpc+tcp", rest)) => format!("http://{rest}"),
+        Some(("grpc+tls", rest)) => format!("https://{rest}"),
+        Some(("http" | "https", _)) => uri,
+        _ => {
+            return Err(FlightError::NotYetImplemented(format!(
+                "Unsupported location: {uri}"
+            )));
+        }
+    };
+    let endpoint =
+        Endpoint::from_shared(uri).map_err(|e| FlightError::ExternalError(Box::new(e)))?;
+    endpoint
+        .connect()
+        .await
+        .map_err(|e| FlightError::ExternalError(Box::new(e)))
 }
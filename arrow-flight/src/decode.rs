@@ -223,7 +223,7 @@ impl futures::Stream for FlightRecordBatchStream {
 ///    handling multiple schema messages separately.
 pub struct FlightDataDecoder {
     /// Underlying data stream
-    response: BoxStream<'static, Result<FlightData>>,
+    response: DecoderInput,
     /// Decoding state
     state: Option<FlightStreamState>,
     /// Seen the end of the inner stream?
@@ -240,6 +240,15 @@ impl Debug for FlightDataDecoder {
     }
 }
 
+/// The input to a [`FlightDataDecoder`]
+enum DecoderInput {
+    /// Raw [`FlightData`] messages that must be decoded
+    FlightData(BoxStream<'static, Result<FlightData>>),
+    /// Messages that have already been decoded, for example by combining
+    /// the output of several decoders
+    Decoded(BoxStream<'static, Result<DecodedFlightData>>),
+}
+
 impl FlightDataDecoder {
     /// Create a new wrapper around the stream of [`FlightData`]
     pub fn new<S>(response: S) -> Self
@@ -248,7 +257,23 @@ impl FlightDataDecoder {
     {
         Self {
             state: None,
-            response: response.boxed(),
+            response: DecoderInput::FlightData(response.boxed()),
+            done: false,
+        }
+    }
+
+    /// Create a new wrapper around a stream of already decoded messages
+    ///
+    /// Only the schema is tracked; the stream must not contain more than
+    /// one [`DecodedPayload::Schema`] if it is wrapped in a
+    /// [`FlightRecordBatchStream`]
+    pub(crate) fn new_from_decoded<S>(response: S) -> Self
+    where
+        S: Stream<Item = Result<DecodedFlightData>> + Send + 'static,
+    {
+        Self {
+            state: None,
+            response: DecoderInput::Decoded(response.boxed()),
             done: false,
         }
     }
@@ -351,7 +376,26 @@ impl futures::Stream for FlightDataDecoder {
             return Poll::Ready(None);
         }
         loop {
-            let res = ready!(self.response.poll_next_unpin(cx));
+            let res = match &mut self.response {
+                DecoderInput::FlightData(response) => ready!(response.poll_next_unpin(cx)),
+                DecoderInput::Decoded(response) => {
+                    let res = ready!(response.poll_next_unpin(cx));
+                    if let Some(Ok(DecodedFlightData {
+                        payload: DecodedPayload::Schema(schema),
+                        ..
+                    })) = &res
+                    {
+                        self.state = Some(FlightStreamState {
+                            schema: Arc::clone(schema),
+                            dictionaries_by_field: HashMap::new(),
+                        });
+                    }
+                    if res.is_none() {
+                        self.done = true;
+                    }
+                    return Poll::Ready(res);
+                }
+            };
 
             return Poll::Ready(match res {
                 None => {
@@ -20,11 +20,14 @@ use std::{collections::VecDeque, fmt::Debug, pin::Pin, sync::Arc, task::Poll};
 use crate::{FlightData, FlightDescriptor, SchemaAsIpc, error::Result};
 
 use arrow_array::{Array, ArrayRef, RecordBatch, RecordBatchOptions, UnionArray};
-use arrow_ipc::writer::{CompressionContext, DictionaryTracker, IpcDataGenerator, IpcWriteOptions};
+use arrow_ipc::writer::{
+    CompressionContext, DictionaryTracker, EncodedData, IpcDataGenerator, IpcWriteOptions,
+};
 
 use arrow_schema::{DataType, Field, FieldRef, Fields, Schema, SchemaRef, UnionMode};
 use bytes::Bytes;
 use futures::{Stream, StreamExt, ready, stream::BoxStream};
+use prost::Message;
 
 /// Creates a [`Stream`] of [`FlightData`]s from a
 /// `Stream` of [`Result`]<[`RecordBatch`], [`FlightError`]>.
@@ -157,6 +160,8 @@ pub struct FlightDataEncoderBuilder {
     /// Deterimines how `DictionaryArray`s are encoded for transport.
     /// See [`DictionaryHandling`] for more information.
     dictionary_handling: DictionaryHandling,
+    /// Optional adaptive compression, see [`AdaptiveCompression`]
+    adaptive_compression: Option<AdaptiveCompression>,
 }
 
 /// Default target size for encoded [`FlightData`].
@@ -174,6 +179,7 @@ impl Default for FlightDataEncoderBuilder {
             schema: None,
             descriptor: None,
             dictionary_handling: DictionaryHandling::Hydrate,
+            adaptive_compression: None,
         }
     }
 }
@@ -234,6 +240,13 @@ impl FlightDataEncoderBuilder {
         self
     }
 
+    /// Enable adaptive compression and splitting of batches based on their
+    /// encoded size. See [`AdaptiveCompression`] for details.
+    pub fn with_adaptive_compression(mut self, adaptive_compression: AdaptiveCompression) -> Self {
+        self.adaptive_compression = Some(adaptive_compression);
+        self
+    }
+
     /// Takes a [`Stream`] of [`Result<RecordBatch>`] and returns a [`Stream`]
     /// of [`FlightData`], consuming self.
     ///
@@ -249,6 +262,7 @@ impl FlightDataEncoderBuilder {
             schema,
             descriptor,
             dictionary_handling,
+            adaptive_compression,
         } = self;
 
         FlightDataEncoder::new(
@@ -259,6 +273,7 @@ impl FlightDataEncoderBuilder {
             app_metadata,
             descriptor,
             dictionary_handling,
+            adaptive_compression,
         )
     }
 }
@@ -290,6 +305,7 @@ pub struct FlightDataEncoder {
 }
 
 impl FlightDataEncoder {
+    #[allow(clippy::too_many_arguments)]
     fn new(
         inner: BoxStream<'static, Result<RecordBatch>>,
         schema: Option<SchemaRef>,
@@ -298,6 +314,7 @@ impl FlightDataEncoder {
         app_metadata: Bytes,
         descriptor: Option<FlightDescriptor>,
         dictionary_handling: DictionaryHandling,
+        adaptive_compression: Option<AdaptiveCompression>,
     ) -> Self {
         let mut encoder = Self {
             inner,
@@ -306,6 +323,7 @@ impl FlightDataEncoder {
             encoder: FlightIpcEncoder::new(
                 options,
                 dictionary_handling != DictionaryHandling::Resend,
+                adaptive_compression,
             ),
             app_metadata: Some(app_metadata),
             queue: VecDeque::new(),
@@ -379,15 +397,38 @@ impl FlightDataEncoder {
             DictionaryHandling::Hydrate => hydrate_dictionaries(&batch, schema)?,
         };
 
-        for batch in split_batch_for_grpc_response(batch, self.max_flight_data_size) {
-            let (flight_dictionaries, flight_batch) = self.encoder.encode_batch(&batch)?;
+        // Account for the expected compression when estimating the encoded size
+        let ratio = self.encoder.expected_compression_ratio();
+        let max_flight_data_size = (self.max_flight_data_size as f64 / ratio) as usize;
 
-            self.queue_messages(flight_dictionaries);
-            self.queue_message(flight_batch);
+        for batch in split_batch_for_grpc_response(batch, max_flight_data_size) {
+            self.encode_split_batch(batch)?;
         }
 
         Ok(())
     }
+
+    /// Encodes a batch returned by [`split_batch_for_grpc_response`], splitting
+    /// it further if adaptive compression is enabled and the encoded message
+    /// exceeds `max_flight_data_size`
+    fn encode_split_batch(&mut self, batch: RecordBatch) -> Result<()> {
+        let (flight_dictionaries, flight_batch) = self.encoder.encode_batch(&batch)?;
+        self.queue_messages(flight_dictionaries);
+
+        let num_rows = batch.num_rows();
+        if self.encoder.adaptive.is_none()
+            || num_rows <= 1
+            || flight_batch.encoded_len() <= self.max_flight_data_size
+        {
+            self.queue_message(flight_batch);
+            return Ok(());
+        }
+
+        // Dictionaries of the halves have already been sent above
+        let half = num_rows / 2;
+        self.encode_split_batch(batch.slice(0, half))?;
+        self.encode_split_batch(batch.slice(half, num_rows - half))
+    }
 }
 
 impl Stream for FlightDataEncoder {
@@ -605,6 +646,97 @@ fn prepare_schema_for_flight(
     Schema::new(fields).with_metadata(schema.metadata().clone())
 }
 
+/// Configures adaptive compression for a [`FlightDataEncoder`], see
+/// [`FlightDataEncoderBuilder::with_adaptive_compression`]
+///
+/// Batches are compressed with the compression configured in the
+/// [`IpcWriteOptions`] (see [`FlightDataEncoderBuilder::with_options`]), and
+/// the compression ratio of each compressed batch is measured. If compression
+/// reduces the size of a batch by less than [`Self::with_min_savings`], the
+/// following [`Self::with_probe_interval`] batches are sent uncompressed,
+/// after which compression is tried again. Compression is indicated per IPC
+/// message, so receivers can decode both.
+///
+/// Buffers that do not compress are always sent uncompressed, with an
+/// uncompressed length of `-1` as allowed by the IPC format, even when
+/// adaptive compression is not enabled.
+///
+/// In addition, batches are split based on their encoded size: the size
+/// estimate described in [`FlightDataEncoderBuilder::with_max_flight_data_size`]
+/// is scaled by the last measured compression ratio, and any encoded batch
+/// that still exceeds the maximum size is split in half and encoded again,
+/// unless it contains a single row.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AdaptiveCompression {
+    min_savings: f64,
+    probe_interval: usize,
+}
+
+impl Default for AdaptiveCompression {
+    fn default() -> Self {
+        Self {
+            min_savings: 0.1,
+            probe_interval: 16,
+        }
+    }
+}
+
+impl AdaptiveCompression {
+    /// Create a new [`AdaptiveCompression`] with the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the minimum fraction of the uncompressed size that compression
+    /// must save for it to be used, defaults to `0.1`
+    pub fn with_min_savings(mut self, min_savings: f64) -> Self {
+        self.min_savings = min_savings;
+        self
+    }
+
+    /// Set the number of batches sent uncompressed before compression is
+    /// tried again, defaults to `16`
+    pub fn with_probe_interval(mut self, probe_interval: usize) -> Self {
+        self.probe_interval = probe_interval;
+        self
+    }
+}
+
+/// State of [`AdaptiveCompression`] in a [`FlightIpcEncoder`]
+#[derive(Debug)]
+struct AdaptiveState {
+    config: AdaptiveCompression,
+    /// The number of batches to send uncompressed before trying again
+    skip: usize,
+    /// The last measured compression ratio
+    ratio: f64,
+}
+
+/// Returns the ratio of the compressed to the uncompressed body size of an
+/// encoded record batch, or `None` if the batch is not compressed
+fn compression_ratio(encoded: &EncodedData) -> Option<f64> {
+    let message = arrow_ipc::root_as_message(&encoded.ipc_message).ok()?;
+    let batch = message.header_as_record_batch()?;
+    batch.compression()?;
+
+    let (mut compressed, mut uncompressed) = (0, 0);
+    for buffer in batch.buffers()? {
+        let (offset, length) = (buffer.offset() as usize, buffer.length() as usize);
+        // Empty buffers are written without a length prefix
+        if length < 8 {
+            continue;
+        }
+        let prefix = encoded.arrow_data.get(offset..offset + 8)?;
+        let prefix = i64::from_le_bytes(prefix.try_into().unwrap());
+        compressed += length;
+        uncompressed += match prefix {
+            -1 => length - 8,
+            len => len as usize,
+        };
+    }
+    (uncompressed > 0).then(|| compressed as f64 / uncompressed as f64)
+}
+
 /// Split [`RecordBatch`] so it hopefully fits into a gRPC response.
 ///
 /// Data is zero-copy sliced into batches.
@@ -645,21 +777,46 @@ fn split_batch_for_grpc_response(
 /// TODO limit on the number of dictionaries???
 struct FlightIpcEncoder {
     options: IpcWriteOptions,
+    /// `options` without compression, used by adaptive compression
+    uncompressed_options: IpcWriteOptions,
+    adaptive: Option<AdaptiveState>,
     data_gen: IpcDataGenerator,
     dictionary_tracker: DictionaryTracker,
     compression_context: CompressionContext,
 }
 
 impl FlightIpcEncoder {
-    fn new(options: IpcWriteOptions, error_on_replacement: bool) -> Self {
+    fn new(
+        options: IpcWriteOptions,
+        error_on_replacement: bool,
+        adaptive_compression: Option<AdaptiveCompression>,
+    ) -> Self {
+        let uncompressed_options = options
+            .clone()
+            .try_with_compression(None)
+            .expect("disabling compression is always valid");
         Self {
             options,
+            uncompressed_options,
+            adaptive: adaptive_compression.map(|config| AdaptiveState {
+                config,
+                skip: 0,
+                ratio: 1.0,
+            }),
             data_gen: IpcDataGenerator::default(),
             dictionary_tracker: DictionaryTracker::new(error_on_replacement),
             compression_context: CompressionContext::default(),
         }
     }
 
+    /// The expected ratio of encoded to unencoded size of the next batch
+    fn expected_compression_ratio(&self) -> f64 {
+        match &self.adaptive {
+            Some(state) if state.skip == 0 => state.ratio,
+            _ => 1.0,
+        }
+    }
+
     /// Encode a schema as a FlightData
     fn encode_schema(&self, schema: &Schema) -> FlightData {
         SchemaAsIpc::new(schema, &self.options).into()
@@ -668,13 +825,34 @@ impl FlightIpcEncoder {
     /// Convert a `RecordBatch` to a Vec of `FlightData` representing
     /// dictionaries and a `FlightData` representing the batch
     fn encode_batch(&mut self, batch: &RecordBatch) -> Result<(Vec<FlightData>, FlightData)> {
+        let compress = match &mut self.adaptive {
+            Some(state) if state.skip > 0 => {
+                state.skip -= 1;
+                false
+            }
+            _ => true,
+        };
+        let options = match compress {
+            true => &self.options,
+            false => &self.uncompressed_options,
+        };
+
         let (encoded_dictionaries, encoded_batch) = self.data_gen.encode(
             batch,
             &mut self.dictionary_tracker,
-            &self.options,
+            options,
             &mut self.compression_context,
         )?;
 
+        if let Some(state) = self.adaptive.as_mut().filter(|_| compress) {
+            if let Some(ratio) = compression_ratio(&encoded_batch) {
+                state.ratio = ratio;
+                if ratio > 1.0 - state.config.min_savings {
+                    state.skip = state.config.probe_interval;
+                }
+            }
+        }
+
         let flight_dictionaries = encoded_dictionaries.into_iter().map(Into::into).collect();
         let flight_batch = encoded_batch.into();
 
@@ -727,7 +905,7 @@ fn hydrate_dictionary(array: &ArrayRef, data_type: &DataType) -> Result<ArrayRef
 
 #[cfg(test)]
 mod tests {
-    use crate::decode::{DecodedPayload, FlightDataDecoder};
+    use crate::decode::{DecodedPayload, FlightDataDecoder, FlightRecordBatchStream};
     use arrow_array::builder::{
         GenericByteDictionaryBuilder, ListBuilder, StringDictionaryBuilder, StructBuilder,
     };
@@ -738,6 +916,7 @@ mod tests {
     use arrow_ipc::MetadataVersion;
     use arrow_schema::{UnionFields, UnionMode};
     use builder::{GenericStringBuilder, MapBuilder};
+    use futures::TryStreamExt;
     use std::collections::HashMap;
 
     use super::*;
@@ -1897,4 +2076,173 @@ mod tests {
             "Specified overage was too high"
         );
     }
+
+    /// Encode `batches`, returning the record batch messages
+    async fn encode_record_batches(
+        builder: FlightDataEncoderBuilder,
+        batches: Vec<RecordBatch>,
+    ) -> Vec<FlightData> {
+        let encoded: Vec<_> = builder
+            .build(futures::stream::iter(batches.clone()).map(Ok))
+            .try_collect()
+            .await
+            .unwrap();
+
+        // round trip
+        let decoded: Vec<_> = FlightRecordBatchStream::new_from_flight_data(
+            futures::stream::iter(encoded.clone()).map(Ok),
+        )
+        .try_collect()
+        .await
+        .unwrap();
+        // decoded batches are slices of the input batches
+        let mut input = batches.iter();
+        let (mut current, mut offset) = (input.next().unwrap(), 0);
+        for batch in decoded {
+            if offset == current.num_rows() {
+                (current, offset) = (input.next().unwrap(), 0);
+            }
+            assert_eq!(batch, current.slice(offset, batch.num_rows()));
+            offset += batch.num_rows();
+        }
+        assert!(offset == current.num_rows() && input.next().is_none());
+
+        encoded
+            .into_iter()
+            .filter(|data| {
+                let message = arrow_ipc::root_as_message(&data.data_header).unwrap();
+                message.header_type() == arrow_ipc::MessageHeader::RecordBatch
+            })
+            .collect()
+    }
+
+    fn is_compressed(data: &FlightData) -> bool {
+        let message = arrow_ipc::root_as_message(&data.data_header).unwrap();
+        let batch = message.header_as_record_batch().unwrap();
+        batch.compression().is_some()
+    }
+
+    /// A batch of pseudo random values that do not compress
+    fn random_batch(num_rows: usize) -> RecordBatch {
+        let mut state = 0x2545F4914F6CDD1D_u64;
+        let values = UInt64Array::from_iter_values((0..num_rows).map(|_| {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        }));
+        RecordBatch::try_from_iter([("v", Arc::new(values) as _)]).unwrap()
+    }
+
+    /// A batch of values that compress well
+    fn zeros_batch(num_rows: usize) -> RecordBatch {
+        let values = UInt64Array::from(vec![0; num_rows]);
+        RecordBatch::try_from_iter([("v", Arc::new(values) as _)]).unwrap()
+    }
+
+    fn lz4_options() -> IpcWriteOptions {
+        IpcWriteOptions::default()
+            .try_with_compression(Some(arrow_ipc::CompressionType::LZ4_FRAME))
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_compression() {
+        let mut batches = vec![random_batch(1024); 4];
+        batches.extend(vec![zeros_batch(1024); 4]);
+
+        // Without adaptive compression every batch is compressed
+        let builder = FlightDataEncoderBuilder::new().with_options(lz4_options());
+        let encoded = encode_record_batches(builder, batches.clone()).await;
+        assert!(encoded.iter().all(is_compressed));
+
+        let builder = FlightDataEncoderBuilder::new()
+            .with_options(lz4_options())
+            .with_adaptive_compression(AdaptiveCompression::new().with_probe_interval(2));
+        let encoded = encode_record_batches(builder, batches).await;
+        let compressed: Vec<_> = encoded.iter().map(is_compressed).collect();
+        // random data is probed every third batch, zeros remain compressed once probed
+        assert_eq!(
+            compressed,
+            [true, false, false, true, false, false, true, true]
+        );
+
+        // Without compression configured, batches are never compressed
+        let builder = FlightDataEncoderBuilder::new()
+            .with_adaptive_compression(AdaptiveCompression::new().with_probe_interval(2));
+        let encoded = encode_record_batches(builder, vec![zeros_batch(1024); 3]).await;
+        assert!(!encoded.iter().any(is_compressed));
+    }
+
+    #[test]
+    fn test_compression_ratio() {
+        let mut encoder = FlightIpcEncoder::new(lz4_options(), true, None);
+        let mut ratio = |batch: &RecordBatch| {
+            let (_, encoded) = encoder
+                .data_gen
+                .encode(
+                    batch,
+                    &mut encoder.dictionary_tracker,
+                    &encoder.options,
+                    &mut encoder.compression_context,
+                )
+                .unwrap();
+            compression_ratio(&encoded).unwrap()
+        };
+        let random = ratio(&random_batch(1024));
+        assert!(random > 0.9 && random < 1.01, "{random}");
+        let zeros = ratio(&zeros_batch(1024));
+        assert!(zeros < 0.1, "{zeros}");
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_split_compressed_size() {
+        let max_flight_data_size = 64 * 1024;
+        let batches = vec![zeros_batch(64 * 1024); 2];
+
+        // Split by the estimated uncompressed size of 512KB
+        let builder = FlightDataEncoderBuilder::new()
+            .with_options(lz4_options())
+            .with_max_flight_data_size(max_flight_data_size);
+        let encoded = encode_record_batches(builder, batches.clone()).await;
+        assert_eq!(encoded.len(), 16);
+
+        // The second batch is split using the compression ratio of the first
+        let builder = FlightDataEncoderBuilder::new()
+            .with_options(lz4_options())
+            .with_max_flight_data_size(max_flight_data_size)
+            .with_adaptive_compression(AdaptiveCompression::new());
+        let encoded = encode_record_batches(builder, batches).await;
+        assert_eq!(encoded.len(), 9);
+        for data in encoded {
+            assert!(data.encoded_len() <= max_flight_data_size);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_adaptive_split_encoded_size() {
+        // Many small columns, where the encoded size is dominated by padding
+        // and metadata that are not included in the size estimate
+        let columns = (0..50).map(|i| {
+            let values = Int8Array::from_iter_values((0..200).map(|v| v as i8));
+            (format!("c{i}"), Arc::new(values) as ArrayRef)
+        });
+        let batch = RecordBatch::try_from_iter(columns).unwrap();
+        let max_flight_data_size = 13_000;
+
+        let builder =
+            FlightDataEncoderBuilder::new().with_max_flight_data_size(max_flight_data_size);
+        let encoded = encode_record_batches(builder, vec![batch.clone()]).await;
+        assert_eq!(encoded.len(), 1);
+        assert!(encoded[0].encoded_len() > max_flight_data_size);
+
+        let builder = FlightDataEncoderBuilder::new()
+            .with_max_flight_data_size(max_flight_data_size)
+            .with_adaptive_compression(AdaptiveCompression::new());
+        let encoded = encode_record_batches(builder, vec![batch]).await;
+        assert_eq!(encoded.len(), 2);
+        for data in encoded {
+            assert!(data.encoded_len() <= max_flight_data_size);
+        }
+    }
 }
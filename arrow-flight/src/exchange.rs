@@ -0,0 +1,279 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Typed helpers for bidirectional `DoExchange` calls
+//!
+//! Server side, an [`ExchangeHandler`] receives the input of a call as a
+//! [`FlightRecordBatchStream`] and returns a stream of [`RecordBatch`]es,
+//! see [`serve_exchange`].
+//!
+//! Client side, [`FlightClient::exchange_batches`] sends a stream of
+//! [`RecordBatch`]es, and [`FlightClient::exchange_channel`] returns an
+//! [`ExchangeSender`] to send batches one at a time while reading the output.
+//!
+//! The first message of an exchange created by the client helpers contains
+//! only the [`FlightDescriptor`], so the server can start processing before
+//! any data has been sent. Both directions are lazy: input is only encoded as
+//! the transport is ready to send it, and the output is only produced as it is
+//! read. Ending the input half-closes the call, and the server can continue
+//! to send output until the handler's stream is exhausted.
+//!
+//! [`FlightClient::exchange_batches`]: crate::FlightClient::exchange_batches
+//! [`FlightClient::exchange_channel`]: crate::FlightClient::exchange_channel
+
+use std::sync::Arc;
+
+use arrow_array::RecordBatch;
+use bytes::Bytes;
+use futures::channel::mpsc;
+use futures::{SinkExt, Stream, StreamExt, TryStreamExt, stream, stream::BoxStream};
+use tonic::metadata::MetadataMap;
+use tonic::{Extensions, Request, Response, Status};
+
+use crate::FlightData;
+use crate::FlightDescriptor;
+use crate::decode::FlightRecordBatchStream;
+use crate::encode::FlightDataEncoderBuilder;
+use crate::error::{FlightError, Result};
+
+/// The input of a `DoExchange` call, see [`ExchangeHandler`]
+#[derive(Debug)]
+pub struct ExchangeRequest {
+    /// The descriptor of the first message
+    pub descriptor: FlightDescriptor,
+    /// The [`FlightData::app_metadata`] of the first message
+    pub app_metadata: Bytes,
+    /// The gRPC request headers
+    pub metadata: MetadataMap,
+    /// The request extensions, for example set by
+    /// [`FlightServerMiddleware`](crate::middleware::FlightServerMiddleware)
+    pub extensions: Extensions,
+    /// The batches sent by the client
+    ///
+    /// Use [`FlightRecordBatchStream::into_inner`] to access the
+    /// [`FlightData::app_metadata`] of each message.
+    pub input: FlightRecordBatchStream,
+}
+
+/// The output of a `DoExchange` call, see [`ExchangeHandler`]
+pub struct ExchangeResponse {
+    batches: BoxStream<'static, Result<RecordBatch>>,
+    encoder: FlightDataEncoderBuilder,
+}
+
+impl std::fmt::Debug for ExchangeResponse {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ExchangeResponse")
+            .field("encoder", &self.encoder)
+            .finish_non_exhaustive()
+    }
+}
+
+impl ExchangeResponse {
+    /// Send the batches of `batches` to the client
+    pub fn new<S>(batches: S) -> Self
+    where
+        S: Stream<Item = Result<RecordBatch>> + Send + 'static,
+    {
+        Self {
+            batches: batches.boxed(),
+            encoder: FlightDataEncoderBuilder::new(),
+        }
+    }
+
+    /// Set the [`FlightDataEncoderBuilder`] used to encode the batches, for
+    /// example to send app metadata or a schema for an empty response
+    pub fn with_encoder(mut self, encoder: FlightDataEncoderBuilder) -> Self {
+        self.encoder = encoder;
+        self
+    }
+}
+
+/// Handles `DoExchange` calls, see [`serve_exchange`]
+#[tonic::async_trait]
+pub trait ExchangeHandler: Send + Sync + 'static {
+    /// Process a `DoExchange` call
+    ///
+    /// This is called once the first message of the call, containing the
+    /// [`FlightDescriptor`], has been received. Errors returned here, or from
+    /// the output stream, are sent to the client as the status of the call.
+    async fn exchange(
+        &self,
+        request: ExchangeRequest,
+    ) -> std::result::Result<ExchangeResponse, Status>;
+}
+
+/// Serve a `DoExchange` call with `handler`
+///
+/// The response is returned immediately, and `handler` is invoked once the
+/// first message of the call has been received. The first message must
+/// contain a [`FlightDescriptor`], and may also contain data, such as the
+/// schema.
+///
+/// # Example
+/// ```
+/// # use std::sync::Arc;
+/// # use arrow_flight::FlightData;
+/// # use arrow_flight::exchange::{ExchangeHandler, serve_exchange};
+/// # use futures::stream::BoxStream;
+/// # use tonic::{Request, Response, Status, Streaming};
+/// struct Service {
+///     handler: Arc<dyn ExchangeHandler>,
+/// }
+///
+/// impl Service {
+///     // Called from FlightService::do_exchange
+///     async fn do_exchange(
+///         &self,
+///         request: Request<Streaming<FlightData>>,
+///     ) -> Result<Response<BoxStream<'static, Result<FlightData, Status>>>, Status> {
+///         Ok(serve_exchange(Arc::clone(&self.handler), request))
+///     }
+/// }
+/// ```
+pub fn serve_exchange<H, S>(
+    handler: Arc<H>,
+    request: Request<S>,
+) -> Response<BoxStream<'static, std::result::Result<FlightData, Status>>>
+where
+    H: ExchangeHandler + ?Sized,
+    S: Stream<Item = std::result::Result<FlightData, Status>> + Send + 'static,
+{
+    let (metadata, extensions, input) = request.into_parts();
+    let output = stream::once(async move {
+        let mut input = input.boxed();
+        let first =
+            input.next().await.transpose()?.ok_or_else(|| {
+                Status::invalid_argument("DoExchange request contained no messages")
+            })?;
+        let descriptor = first.flight_descriptor.clone().ok_or_else(|| {
+            Status::invalid_argument("First DoExchange message must contain a FlightDescriptor")
+        })?;
+        let app_metadata = first.app_metadata.clone();
+
+        // A message containing only the descriptor has no IPC payload to decode
+        let first = (!first.data_header.is_empty()).then_some(Ok(first));
+        let input = stream::iter(first).chain(input).map_err(FlightError::from);
+
+        let request = ExchangeRequest {
+            descriptor,
+            app_metadata,
+            metadata,
+            extensions,
+            input: FlightRecordBatchStream::new_from_flight_data(input),
+        };
+        let response = handler.exchange(request).await?;
+        Ok::<_, Status>(
+            response
+                .encoder
+                .build(response.batches)
+                .map_err(Status::from),
+        )
+    })
+    .try_flatten()
+    .boxed();
+    Response::new(output)
+}
+
+/// Sends batches to a `DoExchange` call, see
+/// [`FlightClient::exchange_channel`](crate::FlightClient::exchange_channel)
+///
+/// Dropping the sender, or calling [`Self::finish`], ends the input of the
+/// call, while the output can continue to be read.
+#[derive(Debug)]
+pub struct ExchangeSender {
+    sender: mpsc::Sender<Result<RecordBatch>>,
+}
+
+impl ExchangeSender {
+    /// Return a sender and the stream of batches it sends
+    pub(crate) fn new() -> (
+        Self,
+        impl Stream<Item = Result<RecordBatch>> + Send + 'static,
+    ) {
+        // Only buffer a single batch, so `send` waits for the call to accept
+        // the previous one
+        let (sender, receiver) = mpsc::channel(0);
+        (Self { sender }, receiver)
+    }
+
+    /// Send `batch` to the server
+    ///
+    /// Waits until the previous batch has been accepted by the call, and
+    /// returns an error if the call has ended.
+    pub async fn send(&mut self, batch: RecordBatch) -> Result<()> {
+        self.sender
+            .send(Ok(batch))
+            .await
+            .map_err(|_| FlightError::protocol("DoExchange call has ended"))
+    }
+
+    /// End the input of the call
+    pub fn finish(self) {}
+
+    /// Cancel the call, `error` is returned from the output stream
+    pub async fn abort(mut self, error: FlightError) {
+        // the call has already ended if this fails
+        let _ = self.sender.send(Err(error)).await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct EchoHandler;
+
+    #[tonic::async_trait]
+    impl ExchangeHandler for EchoHandler {
+        async fn exchange(
+            &self,
+            request: ExchangeRequest,
+        ) -> std::result::Result<ExchangeResponse, Status> {
+            let encoder = FlightDataEncoderBuilder::new().with_metadata(request.app_metadata);
+            Ok(ExchangeResponse::new(request.input).with_encoder(encoder))
+        }
+    }
+
+    async fn exchange(input: Vec<FlightData>) -> std::result::Result<Vec<FlightData>, Status> {
+        let input = Request::new(stream::iter(input).map(Ok));
+        serve_exchange(Arc::new(EchoHandler), input)
+            .into_inner()
+            .try_collect()
+            .await
+    }
+
+    #[tokio::test]
+    async fn test_serve_exchange_first_message() {
+        let err = exchange(vec![]).await.unwrap_err();
+        assert_eq!(err.message(), "DoExchange request contained no messages");
+
+        let err = exchange(vec![FlightData::new()]).await.unwrap_err();
+        assert_eq!(
+            err.message(),
+            "First DoExchange message must contain a FlightDescriptor"
+        );
+
+        // A descriptor only message is not decoded
+        let descriptor = FlightDescriptor::new_cmd("echo");
+        let first = FlightData::new()
+            .with_descriptor(descriptor)
+            .with_app_metadata("meta");
+        let output = exchange(vec![first]).await.unwrap();
+        assert!(output.is_empty());
+    }
+}
@@ -72,6 +72,26 @@ pub mod flight_descriptor {
     pub use r#gen::flight_descriptor::DescriptorType;
 }
 
+/// Value types of a [`SessionOptionValue`].
+pub mod session_option_value {
+    use super::r#gen;
+    pub use r#gen::session_option_value::OptionValue;
+    pub use r#gen::session_option_value::StringListValue;
+}
+
+/// Per-option errors reported in a [`SetSessionOptionsResult`].
+pub mod set_session_options_result {
+    use super::r#gen;
+    pub use r#gen::set_session_options_result::Error;
+    pub use r#gen::set_session_options_result::ErrorValue;
+}
+
+/// Status reported in a [`CloseSessionResult`].
+pub mod close_session_result {
+    use super::r#gen;
+    pub use r#gen::close_session_result::Status;
+}
+
 /// Low Level [tonic] [`FlightServiceClient`](gen::flight_service_client::FlightServiceClient).
 pub mod flight_service_client {
     use super::r#gen;
@@ -101,18 +121,26 @@ pub mod encode;
 /// Common error types
 pub mod error;
 
+pub mod exchange;
+
+pub mod middleware;
+
 pub use r#gen::Action;
 pub use r#gen::ActionType;
 pub use r#gen::BasicAuth;
 pub use r#gen::CancelFlightInfoRequest;
 pub use r#gen::CancelFlightInfoResult;
 pub use r#gen::CancelStatus;
+pub use r#gen::CloseSessionRequest;
+pub use r#gen::CloseSessionResult;
 pub use r#gen::Criteria;
 pub use r#gen::Empty;
 pub use r#gen::FlightData;
 pub use r#gen::FlightDescriptor;
 pub use r#gen::FlightEndpoint;
 pub use r#gen::FlightInfo;
+pub use r#gen::GetSessionOptionsRequest;
+pub use r#gen::GetSessionOptionsResult;
 pub use r#gen::HandshakeRequest;
 pub use r#gen::HandshakeResponse;
 pub use r#gen::Location;
@@ -121,6 +149,9 @@ pub use r#gen::PutResult;
 pub use r#gen::RenewFlightEndpointRequest;
 pub use r#gen::Result;
 pub use r#gen::SchemaResult;
+pub use r#gen::SessionOptionValue;
+pub use r#gen::SetSessionOptionsRequest;
+pub use r#gen::SetSessionOptionsResult;
 pub use r#gen::Ticket;
 
 /// Helper to extract HTTP/gRPC trailers from a tonic stream.
@@ -361,6 +392,20 @@ impl fmt::Display for Ticket {
     }
 }
 
+impl fmt::Display for SessionOptionValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        use session_option_value::OptionValue;
+        match &self.option_value {
+            None => write!(f, "<unset>"),
+            Some(OptionValue::StringValue(v)) => write!(f, "{v}"),
+            Some(OptionValue::BoolValue(v)) => write!(f, "{v}"),
+            Some(OptionValue::Int64Value(v)) => write!(f, "{v}"),
+            Some(OptionValue::DoubleValue(v)) => write!(f, "{v}"),
+            Some(OptionValue::StringListValue(v)) => write!(f, "[{}]", v.values.join(", ")),
+        }
+    }
+}
+
 // From...
 
 impl From<EncodedData> for FlightData {
@@ -753,6 +798,88 @@ impl Result {
     }
 }
 
+impl SessionOptionValue {
+    /// Create a new [`SessionOptionValue`] holding `value`
+    pub fn new(value: impl Into<session_option_value::OptionValue>) -> Self {
+        Self {
+            option_value: Some(value.into()),
+        }
+    }
+
+    /// Create a valueless [`SessionOptionValue`], which by convention
+    /// requests that the server unset the named option
+    pub fn unset() -> Self {
+        Self { option_value: None }
+    }
+}
+
+impl From<String> for session_option_value::OptionValue {
+    fn from(value: String) -> Self {
+        Self::StringValue(value)
+    }
+}
+
+impl From<&str> for session_option_value::OptionValue {
+    fn from(value: &str) -> Self {
+        Self::StringValue(value.to_string())
+    }
+}
+
+impl From<bool> for session_option_value::OptionValue {
+    fn from(value: bool) -> Self {
+        Self::BoolValue(value)
+    }
+}
+
+impl From<i64> for session_option_value::OptionValue {
+    fn from(value: i64) -> Self {
+        Self::Int64Value(value)
+    }
+}
+
+impl From<f64> for session_option_value::OptionValue {
+    fn from(value: f64) -> Self {
+        Self::DoubleValue(value)
+    }
+}
+
+impl From<Vec<String>> for session_option_value::OptionValue {
+    fn from(values: Vec<String>) -> Self {
+        Self::StringListValue(session_option_value::StringListValue { values })
+    }
+}
+
+impl<T: Into<session_option_value::OptionValue>> From<T> for SessionOptionValue {
+    fn from(value: T) -> Self {
+        Self::new(value)
+    }
+}
+
+impl SetSessionOptionsRequest {
+    /// Create a new [`SetSessionOptionsRequest`] from `(name, value)` pairs
+    pub fn new<K, V>(options: impl IntoIterator<Item = (K, V)>) -> Self
+    where
+        K: Into<String>,
+        V: Into<SessionOptionValue>,
+    {
+        Self {
+            session_options: options
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        }
+    }
+}
+
+impl CloseSessionResult {
+    /// Create a new [`CloseSessionResult`] from the provided [`close_session_result::Status`].
+    pub fn new(status: close_session_result::Status) -> Self {
+        Self {
+            status: status as i32,
+        }
+    }
+}
+
 impl Ticket {
     /// Create a new `Ticket`
     ///
@@ -924,4 +1051,34 @@ mod tests {
         let new_schema = Schema::try_from(flight_info).unwrap();
         assert_eq!(schema, new_schema);
     }
+
+    #[test]
+    fn test_session_option_value() {
+        use session_option_value::{OptionValue, StringListValue};
+
+        let value = SessionOptionValue::from("foo");
+        assert_eq!(
+            value.option_value,
+            Some(OptionValue::StringValue("foo".to_string()))
+        );
+        assert_eq!(value.to_string(), "foo");
+
+        let value = SessionOptionValue::from(vec!["a".to_string(), "b".to_string()]);
+        assert_eq!(
+            value.option_value,
+            Some(OptionValue::StringListValue(StringListValue {
+                values: vec!["a".to_string(), "b".to_string()]
+            }))
+        );
+        assert_eq!(value.to_string(), "[a, b]");
+
+        assert_eq!(SessionOptionValue::from(true).to_string(), "true");
+        assert_eq!(SessionOptionValue::from(-3_i64).to_string(), "-3");
+        assert_eq!(SessionOptionValue::from(1.5).to_string(), "1.5");
+        assert_eq!(SessionOptionValue::unset().to_string(), "<unset>");
+
+        let request = SetSessionOptionsRequest::new([("catalog", "sales"), ("schema", "public")]);
+        assert_eq!(request.session_options.len(), 2);
+        assert_eq!(request.session_options["schema"], "public".into());
+    }
 }
@@ -0,0 +1,779 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Server middleware and client interceptors
+//!
+//! [`MiddlewareFlightService`] wraps any [`FlightService`], including
+//! implementations of `FlightSqlService`, and invokes a chain of
+//! [`FlightServerMiddleware`] around every call. This can be used to
+//! authenticate requests ([`AuthMiddleware`]), record per-call metrics
+//! ([`MetricsMiddleware`]) or open and close tracing spans.
+//!
+//! [`FlightClientInterceptor`]s are the client side equivalent, and are
+//! registered with [`FlightClient::add_interceptor`].
+//!
+//! # Example
+//! ```
+//! # use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+//! # use arrow_flight::middleware::{AuthMiddleware, MetricsMiddleware, MiddlewareFlightService};
+//! # use tonic::Status;
+//! # fn wrap(service: impl FlightService) {
+//! let metrics = MetricsMiddleware::new();
+//! let auth = AuthMiddleware::new().with_bearer(|token| match token {
+//!     "secret" => Ok("admin".to_string()),
+//!     _ => Err(Status::unauthenticated("invalid token")),
+//! });
+//!
+//! let service = MiddlewareFlightService::new(service)
+//!     .with_middleware(metrics.clone())
+//!     .with_middleware(auth);
+//! let server = FlightServiceServer::new(service);
+//! # }
+//! ```
+//!
+//! [`FlightClient::add_interceptor`]: crate::FlightClient::add_interceptor
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::{Debug, Display, Formatter};
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+
+use arrow_ipc::{MessageHeader, root_as_message};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use futures::{Stream, StreamExt, stream::BoxStream};
+use prost::Message;
+use tonic::metadata::{Ascii, MetadataMap, MetadataValue};
+use tonic::{Extensions, Request, Response, Status, Streaming};
+
+use crate::error::FlightError;
+use crate::flight_service_server::FlightService;
+use crate::{
+    Action, ActionType, Criteria, Empty, FlightData, FlightDescriptor, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PollInfo, PutResult, SchemaResult, Ticket,
+};
+
+/// The RPCs of the Flight protocol
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FlightMethod {
+    /// `Handshake`
+    Handshake,
+    /// `ListFlights`
+    ListFlights,
+    /// `GetFlightInfo`
+    GetFlightInfo,
+    /// `PollFlightInfo`
+    PollFlightInfo,
+    /// `GetSchema`
+    GetSchema,
+    /// `DoGet`
+    DoGet,
+    /// `DoPut`
+    DoPut,
+    /// `DoExchange`
+    DoExchange,
+    /// `DoAction`
+    DoAction,
+    /// `ListActions`
+    ListActions,
+}
+
+impl FlightMethod {
+    /// Return the name of the RPC, as it appears in the gRPC path
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Handshake => "Handshake",
+            Self::ListFlights => "ListFlights",
+            Self::GetFlightInfo => "GetFlightInfo",
+            Self::PollFlightInfo => "PollFlightInfo",
+            Self::GetSchema => "GetSchema",
+            Self::DoGet => "DoGet",
+            Self::DoPut => "DoPut",
+            Self::DoExchange => "DoExchange",
+            Self::DoAction => "DoAction",
+            Self::ListActions => "ListActions",
+        }
+    }
+}
+
+impl Display for FlightMethod {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Statistics about a completed call, see [`FlightServerMiddleware::on_complete`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CallMetrics {
+    /// Time from the start of the call until the last response message was
+    /// sent, or the call was cancelled
+    pub duration: Duration,
+    /// Number of response messages sent
+    pub messages_sent: u64,
+    /// Encoded size of the response messages sent, in bytes
+    pub bytes_sent: u64,
+    /// Number of response [`FlightData`] messages containing a record batch
+    pub batches_sent: u64,
+}
+
+/// Hooks invoked by [`MiddlewareFlightService`] around every call
+///
+/// Middleware is invoked in the order it was added for
+/// [`on_request`](Self::on_request), and in reverse order for
+/// [`on_complete`](Self::on_complete). If a call is rejected, only the
+/// middleware whose `on_request` was invoked, including the one that
+/// rejected it, have their `on_complete` invoked.
+pub trait FlightServerMiddleware: Send + Sync + 'static {
+    /// Called before the request is passed to the wrapped service
+    ///
+    /// Returning an error rejects the call without invoking the wrapped
+    /// service or any later middleware. Values inserted into `extensions` are
+    /// visible to the wrapped service, via [`Request::extensions`], and are
+    /// passed to [`Self::on_complete`].
+    fn on_request(
+        &self,
+        _method: FlightMethod,
+        _metadata: &mut MetadataMap,
+        _extensions: &mut Extensions,
+    ) -> Result<(), Status> {
+        Ok(())
+    }
+
+    /// Called once the call has completed
+    ///
+    /// For streaming responses this is when the last message has been sent,
+    /// or when the stream is dropped because the client went away. `status`
+    /// is the error the call failed with, if any, and a
+    /// [`Code::Cancelled`](tonic::Code::Cancelled) status if the response
+    /// stream was not read to completion.
+    fn on_complete(
+        &self,
+        _method: FlightMethod,
+        _extensions: &Extensions,
+        _metrics: &CallMetrics,
+        _status: Option<&Status>,
+    ) {
+    }
+}
+
+type MiddlewareChain = Arc<Vec<Arc<dyn FlightServerMiddleware>>>;
+
+/// A [`FlightService`] that invokes a chain of [`FlightServerMiddleware`]
+/// around every call to the wrapped service
+///
+/// See the [module level documentation](self) for an example.
+#[derive(Clone)]
+pub struct MiddlewareFlightService<S> {
+    inner: S,
+    middleware: MiddlewareChain,
+}
+
+impl<S: Debug> Debug for MiddlewareFlightService<S> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MiddlewareFlightService")
+            .field("inner", &self.inner)
+            .field("middleware", &self.middleware.len())
+            .finish()
+    }
+}
+
+impl<S: FlightService> MiddlewareFlightService<S> {
+    /// Wrap `inner`, without any middleware
+    pub fn new(inner: S) -> Self {
+        Self {
+            inner,
+            middleware: Default::default(),
+        }
+    }
+
+    /// Append `middleware` to the chain
+    pub fn with_middleware(mut self, middleware: impl FlightServerMiddleware) -> Self {
+        Arc::make_mut(&mut self.middleware).push(Arc::new(middleware));
+        self
+    }
+
+    /// Return a reference to the wrapped service
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Run the `on_request` hooks for `request`
+    fn start<T>(
+        &self,
+        method: FlightMethod,
+        request: Request<T>,
+    ) -> Result<(Call, Request<T>), Status> {
+        let (mut metadata, mut extensions, message) = request.into_parts();
+        let mut call = Call {
+            method,
+            middleware: Arc::clone(&self.middleware),
+            extensions: Extensions::new(),
+            start: Instant::now(),
+            metrics: CallMetrics::default(),
+            status: None,
+            started: 0,
+        };
+        for middleware in self.middleware.iter() {
+            call.started += 1;
+            if let Err(status) = middleware.on_request(method, &mut metadata, &mut extensions) {
+                call.extensions = extensions;
+                call.status = Some(status.clone());
+                return Err(status);
+            }
+        }
+        call.extensions = extensions.clone();
+        Ok((call, Request::from_parts(metadata, extensions, message)))
+    }
+}
+
+/// A response message that is counted in [`CallMetrics`]
+trait SentMessage: Message {
+    fn is_record_batch(&self) -> bool {
+        false
+    }
+}
+
+impl SentMessage for HandshakeResponse {}
+impl SentMessage for FlightInfo {}
+impl SentMessage for PollInfo {}
+impl SentMessage for SchemaResult {}
+impl SentMessage for PutResult {}
+impl SentMessage for crate::Result {}
+impl SentMessage for ActionType {}
+
+impl SentMessage for FlightData {
+    fn is_record_batch(&self) -> bool {
+        root_as_message(&self.data_header)
+            .is_ok_and(|message| message.header_type() == MessageHeader::RecordBatch)
+    }
+}
+
+/// An in-progress call, invokes the `on_complete` hooks when dropped
+struct Call {
+    method: FlightMethod,
+    middleware: MiddlewareChain,
+    extensions: Extensions,
+    start: Instant,
+    metrics: CallMetrics,
+    status: Option<Status>,
+    /// Number of middleware whose `on_request` hook was invoked
+    started: usize,
+}
+
+impl Call {
+    fn record(&mut self, message: &impl SentMessage) {
+        self.metrics.messages_sent += 1;
+        self.metrics.bytes_sent += message.encoded_len() as u64;
+        if message.is_record_batch() {
+            self.metrics.batches_sent += 1;
+        }
+    }
+
+    fn unary<T: SentMessage>(
+        mut self,
+        response: Result<Response<T>, Status>,
+    ) -> Result<Response<T>, Status> {
+        match &response {
+            Ok(response) => self.record(response.get_ref()),
+            Err(status) => self.status = Some(status.clone()),
+        }
+        response
+    }
+
+    fn streaming<T, St>(
+        mut self,
+        response: Result<Response<St>, Status>,
+    ) -> Result<Response<BoxStream<'static, Result<T, Status>>>, Status>
+    where
+        T: SentMessage + Send + 'static,
+        St: Stream<Item = Result<T, Status>> + Send + 'static,
+    {
+        match response {
+            Ok(response) => Ok(response.map(|stream| {
+                MeteredStream {
+                    inner: stream.boxed(),
+                    call: self,
+                    done: false,
+                }
+                .boxed()
+            })),
+            Err(status) => {
+                self.status = Some(status.clone());
+                Err(status)
+            }
+        }
+    }
+}
+
+impl Drop for Call {
+    fn drop(&mut self) {
+        self.metrics.duration = self.start.elapsed();
+        for middleware in self.middleware[..self.started].iter().rev() {
+            middleware.on_complete(
+                self.method,
+                &self.extensions,
+                &self.metrics,
+                self.status.as_ref(),
+            );
+        }
+    }
+}
+
+/// Counts the messages of a response stream
+struct MeteredStream<T> {
+    inner: BoxStream<'static, Result<T, Status>>,
+    call: Call,
+    done: bool,
+}
+
+impl<T: SentMessage> Stream for MeteredStream<T> {
+    type Item = Result<T, Status>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let next = this.inner.poll_next_unpin(cx);
+        match &next {
+            Poll::Ready(Some(Ok(message))) => this.call.record(message),
+            Poll::Ready(Some(Err(status))) => {
+                this.call.status.get_or_insert_with(|| status.clone());
+            }
+            Poll::Ready(None) => this.done = true,
+            Poll::Pending => {}
+        }
+        next
+    }
+}
+
+impl<T> Drop for MeteredStream<T> {
+    fn drop(&mut self) {
+        if !self.done && self.call.status.is_none() {
+            self.call.status = Some(Status::cancelled("response stream dropped"));
+        }
+    }
+}
+
+#[tonic::async_trait]
+impl<S: FlightService> FlightService for MiddlewareFlightService<S> {
+    type HandshakeStream = BoxStream<'static, Result<HandshakeResponse, Status>>;
+    type ListFlightsStream = BoxStream<'static, Result<FlightInfo, Status>>;
+    type DoGetStream = BoxStream<'static, Result<FlightData, Status>>;
+    type DoPutStream = BoxStream<'static, Result<PutResult, Status>>;
+    type DoExchangeStream = BoxStream<'static, Result<FlightData, Status>>;
+    type DoActionStream = BoxStream<'static, Result<crate::Result, Status>>;
+    type ListActionsStream = BoxStream<'static, Result<ActionType, Status>>;
+
+    async fn handshake(
+        &self,
+        request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        let (call, request) = self.start(FlightMethod::Handshake, request)?;
+        call.streaming(self.inner.handshake(request).await)
+    }
+
+    async fn list_flights(
+        &self,
+        request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        let (call, request) = self.start(FlightMethod::ListFlights, request)?;
+        call.streaming(self.inner.list_flights(request).await)
+    }
+
+    async fn get_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let (call, request) = self.start(FlightMethod::GetFlightInfo, request)?;
+        call.unary(self.inner.get_flight_info(request).await)
+    }
+
+    async fn poll_flight_info(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<PollInfo>, Status> {
+        let (call, request) = self.start(FlightMethod::PollFlightInfo, request)?;
+        call.unary(self.inner.poll_flight_info(request).await)
+    }
+
+    async fn get_schema(
+        &self,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        let (call, request) = self.start(FlightMethod::GetSchema, request)?;
+        call.unary(self.inner.get_schema(request).await)
+    }
+
+    async fn do_get(
+        &self,
+        request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        let (call, request) = self.start(FlightMethod::DoGet, request)?;
+        call.streaming(self.inner.do_get(request).await)
+    }
+
+    async fn do_put(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        let (call, request) = self.start(FlightMethod::DoPut, request)?;
+        call.streaming(self.inner.do_put(request).await)
+    }
+
+    async fn do_exchange(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        let (call, request) = self.start(FlightMethod::DoExchange, request)?;
+        call.streaming(self.inner.do_exchange(request).await)
+    }
+
+    async fn do_action(
+        &self,
+        request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        let (call, request) = self.start(FlightMethod::DoAction, request)?;
+        call.streaming(self.inner.do_action(request).await)
+    }
+
+    async fn list_actions(
+        &self,
+        request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        let (call, request) = self.start(FlightMethod::ListActions, request)?;
+        call.streaming(self.inner.list_actions(request).await)
+    }
+}
+
+/// The principal of an authenticated call, inserted into the request
+/// [`Extensions`] by [`AuthMiddleware`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuthenticatedPrincipal(pub String);
+
+type BasicValidator = Arc<dyn Fn(&str, &str) -> Result<String, Status> + Send + Sync>;
+type BearerValidator = Arc<dyn Fn(&str) -> Result<String, Status> + Send + Sync>;
+
+/// [`FlightServerMiddleware`] that authenticates calls using the
+/// `authorization` header
+///
+/// `Basic` and `Bearer` credentials are passed to the validators registered
+/// with [`Self::with_basic`] and [`Self::with_bearer`], which return the
+/// authenticated principal. The principal is made available to the wrapped
+/// service as an [`AuthenticatedPrincipal`] request extension.
+///
+/// Calls without a header, with an unsupported scheme, or rejected by the
+/// validator fail with [`Code::Unauthenticated`](tonic::Code::Unauthenticated),
+/// unless the method was registered with
+/// [`Self::with_unauthenticated_method`].
+#[derive(Clone, Default)]
+pub struct AuthMiddleware {
+    basic: Option<BasicValidator>,
+    bearer: Option<BearerValidator>,
+    unauthenticated: HashSet<FlightMethod>,
+}
+
+impl Debug for AuthMiddleware {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthMiddleware")
+            .field("basic", &self.basic.is_some())
+            .field("bearer", &self.bearer.is_some())
+            .field("unauthenticated", &self.unauthenticated)
+            .finish()
+    }
+}
+
+impl AuthMiddleware {
+    /// Create a new [`AuthMiddleware`] that rejects every call
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Accept `Basic` credentials, `validator` is called with the username
+    /// and password
+    pub fn with_basic<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str, &str) -> Result<String, Status> + Send + Sync + 'static,
+    {
+        self.basic = Some(Arc::new(validator));
+        self
+    }
+
+    /// Accept `Bearer` credentials, `validator` is called with the token
+    pub fn with_bearer<F>(mut self, validator: F) -> Self
+    where
+        F: Fn(&str) -> Result<String, Status> + Send + Sync + 'static,
+    {
+        self.bearer = Some(Arc::new(validator));
+        self
+    }
+
+    /// Allow calls to `method` without credentials, for example
+    /// [`FlightMethod::Handshake`] if the service issues tokens itself
+    pub fn with_unauthenticated_method(mut self, method: FlightMethod) -> Self {
+        self.unauthenticated.insert(method);
+        self
+    }
+
+    fn authenticate(&self, header: &str) -> Result<String, Status> {
+        let (scheme, credentials) = header.split_once(' ').unwrap_or((header, ""));
+        let credentials = credentials.trim();
+        match (&self.basic, &self.bearer) {
+            (Some(basic), _) if scheme.eq_ignore_ascii_case("basic") => {
+                let invalid = || Status::unauthenticated("invalid basic credentials");
+                let decoded = BASE64_STANDARD.decode(credentials).map_err(|_| invalid())?;
+                let decoded = String::from_utf8(decoded).map_err(|_| invalid())?;
+                let (username, password) = decoded.split_once(':').ok_or_else(invalid)?;
+                basic(username, password)
+            }
+            (_, Some(bearer)) if scheme.eq_ignore_ascii_case("bearer") => bearer(credentials),
+            _ => Err(Status::unauthenticated(format!(
+                "unsupported authorization scheme: {scheme}"
+            ))),
+        }
+    }
+}
+
+impl FlightServerMiddleware for AuthMiddleware {
+    fn on_request(
+        &self,
+        method: FlightMethod,
+        metadata: &mut MetadataMap,
+        extensions: &mut Extensions,
+    ) -> Result<(), Status> {
+        if self.unauthenticated.contains(&method) {
+            return Ok(());
+        }
+        let header = metadata
+            .get("authorization")
+            .ok_or_else(|| Status::unauthenticated("missing authorization header"))?
+            .to_str()
+            .map_err(|_| Status::unauthenticated("invalid authorization header"))?;
+        let principal = self.authenticate(header)?;
+        extensions.insert(AuthenticatedPrincipal(principal));
+        Ok(())
+    }
+}
+
+/// Aggregated [`CallMetrics`] for a [`FlightMethod`], see [`MetricsMiddleware`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct MethodMetrics {
+    /// Number of completed calls
+    pub calls: u64,
+    /// Number of calls that failed, including rejected and cancelled calls
+    pub errors: u64,
+    /// Total [`CallMetrics::duration`]
+    pub duration: Duration,
+    /// Total [`CallMetrics::messages_sent`]
+    pub messages_sent: u64,
+    /// Total [`CallMetrics::bytes_sent`]
+    pub bytes_sent: u64,
+    /// Total [`CallMetrics::batches_sent`]
+    pub batches_sent: u64,
+}
+
+/// [`FlightServerMiddleware`] that aggregates [`CallMetrics`] per [`FlightMethod`]
+///
+/// Clones share the same metrics, so a clone can be kept to read the metrics
+/// after adding the middleware to a [`MiddlewareFlightService`].
+#[derive(Debug, Clone, Default)]
+pub struct MetricsMiddleware {
+    metrics: Arc<Mutex<HashMap<FlightMethod, MethodMetrics>>>,
+}
+
+impl MetricsMiddleware {
+    /// Create a new [`MetricsMiddleware`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Return the metrics of `method`
+    pub fn get(&self, method: FlightMethod) -> MethodMetrics {
+        let metrics = self.metrics.lock().unwrap();
+        metrics.get(&method).copied().unwrap_or_default()
+    }
+
+    /// Return the metrics of every method that has been called
+    pub fn snapshot(&self) -> HashMap<FlightMethod, MethodMetrics> {
+        self.metrics.lock().unwrap().clone()
+    }
+}
+
+impl FlightServerMiddleware for MetricsMiddleware {
+    fn on_complete(
+        &self,
+        method: FlightMethod,
+        _extensions: &Extensions,
+        metrics: &CallMetrics,
+        status: Option<&Status>,
+    ) {
+        let mut all = self.metrics.lock().unwrap();
+        let entry = all.entry(method).or_default();
+        entry.calls += 1;
+        entry.errors += status.is_some() as u64;
+        entry.duration += metrics.duration;
+        entry.messages_sent += metrics.messages_sent;
+        entry.bytes_sent += metrics.bytes_sent;
+        entry.batches_sent += metrics.batches_sent;
+    }
+}
+
+/// Hook invoked by [`FlightClient`](crate::FlightClient) before every request
+///
+/// Interceptors are invoked in the order they were added, after the client's
+/// [`metadata`](crate::FlightClient::metadata) has been copied into the
+/// request, and may add, modify or remove headers. Returning an error fails
+/// the request without sending it.
+///
+/// Implemented for closures with a matching signature.
+pub trait FlightClientInterceptor: Send + Sync + 'static {
+    /// Called before sending a request for `method`
+    fn on_request(
+        &self,
+        method: FlightMethod,
+        metadata: &mut MetadataMap,
+    ) -> Result<(), FlightError>;
+}
+
+impl<F> FlightClientInterceptor for F
+where
+    F: Fn(FlightMethod, &mut MetadataMap) -> Result<(), FlightError> + Send + Sync + 'static,
+{
+    fn on_request(
+        &self,
+        method: FlightMethod,
+        metadata: &mut MetadataMap,
+    ) -> Result<(), FlightError> {
+        self(method, metadata)
+    }
+}
+
+/// [`FlightClientInterceptor`] that sets the `authorization` header of every
+/// request, see [`AuthMiddleware`] for the server side
+#[derive(Debug, Clone)]
+pub struct AuthInterceptor {
+    value: MetadataValue<Ascii>,
+}
+
+impl AuthInterceptor {
+    /// Send `Basic` credentials
+    pub fn basic(username: &str, password: &str) -> Self {
+        let encoded = BASE64_STANDARD.encode(format!("{username}:{password}"));
+        let value = format!("Basic {encoded}")
+            .parse()
+            .expect("base64 is valid ASCII");
+        Self::new(value)
+    }
+
+    /// Send a `Bearer` token, returns an error if `token` is not a valid
+    /// header value
+    pub fn bearer(token: &str) -> Result<Self, FlightError> {
+        let value = format!("Bearer {token}")
+            .parse()
+            .map_err(|e| FlightError::ExternalError(Box::new(e)))?;
+        Ok(Self::new(value))
+    }
+
+    fn new(mut value: MetadataValue<Ascii>) -> Self {
+        value.set_sensitive(true);
+        Self { value }
+    }
+}
+
+impl FlightClientInterceptor for AuthInterceptor {
+    fn on_request(
+        &self,
+        _method: FlightMethod,
+        metadata: &mut MetadataMap,
+    ) -> Result<(), FlightError> {
+        metadata.insert("authorization", self.value.clone());
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn authenticate(auth: &AuthMiddleware, header: Option<&str>) -> Result<String, Status> {
+        let mut metadata = MetadataMap::new();
+        if let Some(header) = header {
+            metadata.insert("authorization", header.parse().unwrap());
+        }
+        let mut extensions = Extensions::new();
+        auth.on_request(FlightMethod::DoGet, &mut metadata, &mut extensions)?;
+        Ok(extensions
+            .get::<AuthenticatedPrincipal>()
+            .unwrap()
+            .0
+            .clone())
+    }
+
+    #[test]
+    fn test_auth_middleware() {
+        let auth = AuthMiddleware::new()
+            .with_basic(|user, password| match (user, password) {
+                ("user", "pass:word") => Ok(user.to_string()),
+                _ => Err(Status::unauthenticated("invalid password")),
+            })
+            .with_bearer(|token| match token {
+                "token" => Ok("bearer".to_string()),
+                _ => Err(Status::unauthenticated("invalid token")),
+            });
+
+        let basic = AuthInterceptor::basic("user", "pass:word");
+        let basic = basic.value.to_str().unwrap();
+        assert_eq!(authenticate(&auth, Some(basic)).unwrap(), "user");
+        assert_eq!(authenticate(&auth, Some("bearer token")).unwrap(), "bearer");
+
+        for (header, expected) in [
+            (None, "missing authorization header"),
+            (Some("Bearer other"), "invalid token"),
+            (Some("Basic !!!"), "invalid basic credentials"),
+            (
+                Some("Digest abc"),
+                "unsupported authorization scheme: Digest",
+            ),
+        ] {
+            let err = authenticate(&auth, header).unwrap_err();
+            assert_eq!(err.code(), tonic::Code::Unauthenticated);
+            assert_eq!(err.message(), expected);
+        }
+
+        let auth = auth.with_unauthenticated_method(FlightMethod::DoGet);
+        let mut extensions = Extensions::new();
+        auth.on_request(
+            FlightMethod::DoGet,
+            &mut MetadataMap::new(),
+            &mut extensions,
+        )
+        .unwrap();
+        assert!(extensions.get::<AuthenticatedPrincipal>().is_none());
+    }
+
+    #[test]
+    fn test_closure_interceptor() {
+        let interceptor = |method: FlightMethod, metadata: &mut MetadataMap| {
+            metadata.insert("x-method", method.as_str().parse().unwrap());
+            Ok(())
+        };
+        let mut metadata = MetadataMap::new();
+        interceptor
+            .on_request(FlightMethod::ListActions, &mut metadata)
+            .unwrap();
+        assert_eq!(metadata.get("x-method").unwrap(), "ListActions");
+    }
+}
@@ -17,10 +17,8 @@
 
 //! A FlightSQL Client [`FlightSqlServiceClient`]
 
-use arrow_buffer::Buffer;
 use arrow_ipc::MessageHeader;
 use arrow_ipc::convert::fb_to_schema;
-use arrow_ipc::reader::read_record_batch;
 use arrow_ipc::root_as_message;
 use arrow_schema::SchemaRef;
 use base64::Engine;
@@ -28,6 +26,7 @@ use base64::prelude::BASE64_STANDARD;
 use bytes::Bytes;
 use std::collections::HashMap;
 use std::str::FromStr;
+use std::sync::Arc;
 use tonic::metadata::AsciiMetadataKey;
 
 use crate::decode::FlightRecordBatchStream;
@@ -37,8 +36,10 @@ use crate::error::Result;
 use crate::flight_service_client::FlightServiceClient;
 use crate::sql::r#gen::action_end_transaction_request::EndTransaction;
 use crate::sql::server::{
-    BEGIN_TRANSACTION, CLOSE_PREPARED_STATEMENT, CREATE_PREPARED_STATEMENT, END_TRANSACTION,
+    BEGIN_TRANSACTION, CLOSE_PREPARED_STATEMENT, CLOSE_SESSION, CREATE_PREPARED_STATEMENT,
+    END_TRANSACTION, GET_SESSION_OPTIONS, SET_SESSION_OPTIONS,
 };
+use crate::sql::session::{SESSION_COOKIE_NAME, SessionCookie, session_cookie_from_set_cookie};
 use crate::sql::{
     ActionBeginTransactionRequest, ActionBeginTransactionResult,
     ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest,
@@ -51,11 +52,14 @@ use crate::sql::{
 };
 use crate::streams::FallibleRequestStream;
 use crate::trailers::extract_lazy_trailers;
+use crate::utils::{flight_data_to_arrow_batch, flight_data_to_dictionary};
 use crate::{
-    Action, FlightData, FlightDescriptor, FlightInfo, HandshakeRequest, HandshakeResponse,
-    IpcMessage, PutResult, Ticket,
+    Action, CloseSessionRequest, CloseSessionResult, FlightData, FlightDescriptor, FlightInfo,
+    GetSessionOptionsRequest, GetSessionOptionsResult, HandshakeRequest, HandshakeResponse,
+    IpcMessage, PutResult, SessionOptionValue, SetSessionOptionsRequest, SetSessionOptionsResult,
+    Ticket, close_session_result,
 };
-use arrow_array::RecordBatch;
+use arrow_array::{ArrayRef, RecordBatch};
 use arrow_schema::{ArrowError, Schema};
 use futures::{Stream, TryStreamExt, stream};
 use prost::Message;
@@ -67,6 +71,7 @@ use tonic::{IntoRequest, IntoStreamingRequest, Streaming};
 #[derive(Debug)]
 pub struct FlightSqlServiceClient<T> {
     token: Option<String>,
+    session_id: Option<String>,
     headers: HashMap<String, String>,
     flight_client: FlightServiceClient<T>,
 }
@@ -90,6 +95,7 @@ where
     pub fn new_from_inner(inner: FlightServiceClient<T>) -> Self {
         Self {
             token: None,
+            session_id: None,
             flight_client: inner,
             headers: HashMap::default(),
         }
@@ -125,6 +131,26 @@ where
         self.token.as_ref()
     }
 
+    /// Set the session id sent to the server in the [`SESSION_COOKIE_NAME`]
+    /// cookie.
+    ///
+    /// This is normally managed automatically: the session id is taken from
+    /// the server's response to [`Self::set_session_options`] and cleared by
+    /// [`Self::close_session`].
+    pub fn set_session_id(&mut self, session_id: String) {
+        self.session_id = Some(session_id);
+    }
+
+    /// Clear the session id.
+    pub fn clear_session_id(&mut self) {
+        self.session_id = None;
+    }
+
+    /// Share the session id with potentially different `DoGet` clients
+    pub fn session_id(&self) -> Option<&String> {
+        self.session_id.as_ref()
+    }
+
     /// Set header value.
     pub fn set_header(&mut self, key: impl Into<String>, value: impl Into<String>) {
         let key: String = key.into();
@@ -440,6 +466,67 @@ where
         Ok(())
     }
 
+    /// Set options for the current session, creating a session on the server
+    /// if one does not already exist.
+    ///
+    /// Options that could not be set are reported in
+    /// [`SetSessionOptionsResult::errors`]. Setting an option to
+    /// [`SessionOptionValue::unset`] requests that it be cleared.
+    pub async fn set_session_options(
+        &mut self,
+        options: HashMap<String, SessionOptionValue>,
+    ) -> Result<SetSessionOptionsResult> {
+        let cmd = SetSessionOptionsRequest {
+            session_options: options,
+        };
+        self.do_session_action(SET_SESSION_OPTIONS, cmd).await
+    }
+
+    /// Get the options of the current session.
+    pub async fn get_session_options(&mut self) -> Result<HashMap<String, SessionOptionValue>> {
+        let result: GetSessionOptionsResult = self
+            .do_session_action(GET_SESSION_OPTIONS, GetSessionOptionsRequest {})
+            .await?;
+        Ok(result.session_options)
+    }
+
+    /// Close the current session, clearing the session id once the server
+    /// reports it as closed.
+    pub async fn close_session(&mut self) -> Result<close_session_result::Status> {
+        let result: CloseSessionResult = self
+            .do_session_action(CLOSE_SESSION, CloseSessionRequest {})
+            .await?;
+        let status = result.status();
+        if status == close_session_result::Status::Closed {
+            self.session_id = None;
+        }
+        Ok(status)
+    }
+
+    /// Perform a session action, whose request and result are not wrapped in
+    /// [`Any`], updating the session id from any `set-cookie` response header
+    async fn do_session_action<R: Message + Default>(
+        &mut self,
+        action_type: &str,
+        cmd: impl Message,
+    ) -> Result<R> {
+        let action = Action {
+            r#type: action_type.to_string(),
+            body: cmd.encode_to_vec().into(),
+        };
+        let req = self.set_request_headers(action.into_request())?;
+        let response = self.flight_client.do_action(req).await?;
+        match session_cookie_from_set_cookie(response.metadata()) {
+            Some(SessionCookie::Set(id)) => self.session_id = Some(id),
+            Some(SessionCookie::Expired) => self.session_id = None,
+            None => {}
+        }
+        let result = response.into_inner().message().await?.ok_or_else(|| {
+            FlightError::protocol(format!("No result returned for {action_type}"))
+        })?;
+        Ok(R::decode(&*result.body)?)
+    }
+
     /// Explicitly shut down and clean up the client.
     pub async fn close(&mut self) -> Result<()> {
         // TODO: consume self instead of &mut self to explicitly prevent reuse?
@@ -462,6 +549,16 @@ where
             })?;
             req.metadata_mut().insert("authorization", val);
         }
+        if let Some(session_id) = &self.session_id {
+            let val = format!("{SESSION_COOKIE_NAME}={session_id}")
+                .parse()
+                .map_err(|e| {
+                    ArrowError::ParseError(format!(
+                        "Cannot convert session id to header value: {e}"
+                    ))
+                })?;
+            req.metadata_mut().append("cookie", val);
+        }
         Ok(req)
     }
 }
@@ -471,6 +568,7 @@ impl<T: Clone> Clone for FlightSqlServiceClient<T> {
         Self {
             headers: self.headers.clone(),
             token: self.token.clone(),
+            session_id: self.session_id.clone(),
             flight_client: self.flight_client.clone(),
         }
     }
@@ -620,6 +718,7 @@ where
 }
 
 /// A polymorphic structure to natively represent different types of data contained in `FlightData`
+#[derive(Debug)]
 pub enum ArrowFlightData {
     /// A record batch
     RecordBatch(RecordBatch),
@@ -628,75 +727,172 @@ pub enum ArrowFlightData {
 }
 
 /// Extract `Schema` or `RecordBatch`es from the `FlightData` wire representation
+///
+/// This function is stateless and so can not decode dictionary batches, use
+/// [`ArrowFlightDataReader`] to decode streams containing dictionaries
+#[deprecated(since = "58.0.0", note = "Use `ArrowFlightDataReader` instead")]
 pub fn arrow_data_from_flight_data(
     flight_data: FlightData,
     arrow_schema_ref: &SchemaRef,
 ) -> std::result::Result<ArrowFlightData, ArrowError> {
-    let ipc_message = root_as_message(&flight_data.data_header[..])
-        .map_err(|err| ArrowError::ParseError(format!("Unable to get root as message: {err:?}")))?;
-
-    match ipc_message.header_type() {
-        MessageHeader::RecordBatch => {
-            let ipc_record_batch = ipc_message.header_as_record_batch().ok_or_else(|| {
-                ArrowError::ComputeError(
-                    "Unable to convert flight data header to a record batch".to_string(),
-                )
-            })?;
+    ArrowFlightDataReader::new_with_schema(Arc::clone(arrow_schema_ref))
+        .read(flight_data)?
+        .ok_or_else(|| {
+            ArrowError::NotYetImplemented(
+                "decoding an ipc dictionary batch requires an ArrowFlightDataReader".to_string(),
+            )
+        })
+}
 
-            let dictionaries_by_field = HashMap::new();
-            let record_batch = read_record_batch(
-                &Buffer::from(flight_data.data_body),
-                ipc_record_batch,
-                arrow_schema_ref.clone(),
-                &dictionaries_by_field,
-                None,
-                &ipc_message.version(),
-            )?;
-            Ok(ArrowFlightData::RecordBatch(record_batch))
-        }
-        MessageHeader::Schema => {
-            let ipc_schema = ipc_message.header_as_schema().ok_or_else(|| {
-                ArrowError::ComputeError(
-                    "Unable to convert flight data header to a schema".to_string(),
-                )
-            })?;
+/// Extracts `Schema` and `RecordBatch`es from a sequence of `FlightData`
+/// messages, tracking the schema and dictionaries across messages
+///
+/// Messages are handled the same way as by [`FlightDataDecoder`]:
+///
+/// - **Schema:** The schema is (re-)set and dictionaries are cleared.
+///
+/// - **Dictionary Batch:** The dictionary is registered, replacing any
+///   existing dictionary with the same id or, for a delta dictionary, being
+///   appended to it. No data is returned.
+///
+/// - **Record Batch:** The record batch is decoded with the current schema
+///   and dictionaries.
+///
+/// [`FlightDataDecoder`]: crate::decode::FlightDataDecoder
+#[derive(Debug, Default)]
+pub struct ArrowFlightDataReader {
+    schema: Option<SchemaRef>,
+    dictionaries_by_id: HashMap<i64, ArrayRef>,
+}
 
-            let arrow_schema = fb_to_schema(ipc_schema);
-            Ok(ArrowFlightData::Schema(arrow_schema))
-        }
-        MessageHeader::DictionaryBatch => {
-            let _ = ipc_message.header_as_dictionary_batch().ok_or_else(|| {
-                ArrowError::ComputeError(
-                    "Unable to convert flight data header to a dictionary batch".to_string(),
-                )
-            })?;
-            Err(ArrowError::NotYetImplemented(
-                "no idea on how to convert an ipc dictionary batch to an arrow type".to_string(),
-            ))
+impl ArrowFlightDataReader {
+    /// Create a new [`ArrowFlightDataReader`] that expects the stream to start
+    /// with a schema message
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new [`ArrowFlightDataReader`] for a stream whose schema is
+    /// already known, for example from a [`FlightInfo`]
+    pub fn new_with_schema(schema: SchemaRef) -> Self {
+        Self {
+            schema: Some(schema),
+            dictionaries_by_id: HashMap::new(),
         }
-        MessageHeader::Tensor => {
-            let _ = ipc_message.header_as_tensor().ok_or_else(|| {
-                ArrowError::ComputeError(
-                    "Unable to convert flight data header to a tensor".to_string(),
-                )
-            })?;
-            Err(ArrowError::NotYetImplemented(
-                "no idea on how to convert an ipc tensor to an arrow type".to_string(),
-            ))
+    }
+
+    /// Returns the current schema, if any
+    pub fn schema(&self) -> Option<&SchemaRef> {
+        self.schema.as_ref()
+    }
+
+    /// Extract the `Schema` or `RecordBatch` from the next message, returning
+    /// `None` for dictionary batches
+    pub fn read(
+        &mut self,
+        flight_data: FlightData,
+    ) -> std::result::Result<Option<ArrowFlightData>, ArrowError> {
+        let ipc_message = root_as_message(&flight_data.data_header[..]).map_err(|err| {
+            ArrowError::ParseError(format!("Unable to get root as message: {err:?}"))
+        })?;
+
+        match ipc_message.header_type() {
+            MessageHeader::RecordBatch => {
+                let schema = self.schema.as_ref().ok_or_else(|| {
+                    ArrowError::ComputeError("Received RecordBatch prior to Schema".to_string())
+                })?;
+                let record_batch = flight_data_to_arrow_batch(
+                    &flight_data,
+                    Arc::clone(schema),
+                    &self.dictionaries_by_id,
+                )?;
+                Ok(Some(ArrowFlightData::RecordBatch(record_batch)))
+            }
+            MessageHeader::Schema => {
+                let ipc_schema = ipc_message.header_as_schema().ok_or_else(|| {
+                    ArrowError::ComputeError(
+                        "Unable to convert flight data header to a schema".to_string(),
+                    )
+                })?;
+
+                let arrow_schema = fb_to_schema(ipc_schema);
+                self.schema = Some(Arc::new(arrow_schema.clone()));
+                self.dictionaries_by_id.clear();
+                Ok(Some(ArrowFlightData::Schema(arrow_schema)))
+            }
+            MessageHeader::DictionaryBatch => {
+                let schema = self.schema.as_ref().ok_or_else(|| {
+                    ArrowError::ComputeError("Received DictionaryBatch prior to Schema".to_string())
+                })?;
+                flight_data_to_dictionary(&flight_data, schema, &mut self.dictionaries_by_id)?;
+                Ok(None)
+            }
+            MessageHeader::Tensor => {
+                let _ = ipc_message.header_as_tensor().ok_or_else(|| {
+                    ArrowError::ComputeError(
+                        "Unable to convert flight data header to a tensor".to_string(),
+                    )
+                })?;
+                Err(ArrowError::NotYetImplemented(
+                    "no idea on how to convert an ipc tensor to an arrow type".to_string(),
+                ))
+            }
+            MessageHeader::SparseTensor => {
+                let _ = ipc_message.header_as_sparse_tensor().ok_or_else(|| {
+                    ArrowError::ComputeError(
+                        "Unable to convert flight data header to a sparse tensor".to_string(),
+                    )
+                })?;
+                Err(ArrowError::NotYetImplemented(
+                    "no idea on how to convert an ipc sparse tensor to an arrow type".to_string(),
+                ))
+            }
+            _ => Err(ArrowError::ComputeError(format!(
+                "Unable to convert message with header_type: '{:?}' to arrow data",
+                ipc_message.header_type()
+            ))),
         }
-        MessageHeader::SparseTensor => {
-            let _ = ipc_message.header_as_sparse_tensor().ok_or_else(|| {
-                ArrowError::ComputeError(
-                    "Unable to convert flight data header to a sparse tensor".to_string(),
-                )
-            })?;
-            Err(ArrowError::NotYetImplemented(
-                "no idea on how to convert an ipc sparse tensor to an arrow type".to_string(),
-            ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::batches_to_flight_data;
+    use arrow_array::{DictionaryArray, types::Int32Type};
+
+    fn dictionary_batch(values: &[&str]) -> RecordBatch {
+        let array: DictionaryArray<Int32Type> = values.iter().copied().collect();
+        RecordBatch::try_from_iter([("d", Arc::new(array) as ArrayRef)]).unwrap()
+    }
+
+    #[test]
+    fn test_arrow_flight_data_reader_dictionaries() {
+        let batches = vec![dictionary_batch(&["a", "b", "a"]), dictionary_batch(&["c"])];
+        let schema = batches[0].schema();
+        let flight_data = batches_to_flight_data(&schema, batches.clone()).unwrap();
+
+        let mut reader = ArrowFlightDataReader::new();
+        let mut decoded = vec![];
+        for data in flight_data {
+            match reader.read(data).unwrap() {
+                Some(ArrowFlightData::Schema(s)) => assert_eq!(&s, schema.as_ref()),
+                Some(ArrowFlightData::RecordBatch(batch)) => decoded.push(batch),
+                None => {}
+            }
         }
-        _ => Err(ArrowError::ComputeError(format!(
-            "Unable to convert message with header_type: '{:?}' to arrow data",
-            ipc_message.header_type()
-        ))),
+        assert_eq!(decoded, batches);
+        assert_eq!(reader.schema(), Some(&schema));
+    }
+
+    #[test]
+    fn test_arrow_flight_data_reader_requires_schema() {
+        let batch = dictionary_batch(&["a"]);
+        let flight_data = batches_to_flight_data(&batch.schema(), vec![batch]).unwrap();
+
+        let err = ArrowFlightDataReader::new()
+            .read(flight_data[1].clone())
+            .unwrap_err();
+        assert!(err.to_string().contains("prior to Schema"), "{err}");
     }
 }
@@ -0,0 +1,859 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! An in-memory FlightSQL server, [`MemoryFlightSqlServer`], useful for
+//! testing FlightSQL clients and for embedding.
+//!
+//! Tables of [`RecordBatch`]es are registered with the server, which answers
+//! the metadata commands (catalogs, schemas, tables, table types, primary
+//! keys, [`SqlInfo`] and XDBC type info) from the registered tables, accepts
+//! bulk ingestion into new or existing tables, and executes queries, including
+//! prepared statements with bound parameters, with a pluggable
+//! [`QueryHandler`].
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use std::sync::Arc;
+//! # use arrow_array::{Int64Array, RecordBatch};
+//! # use arrow_flight::sql::memory::{MemoryFlightSqlServer, MemoryTable};
+//! # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+//! let batch = RecordBatch::try_from_iter([(
+//!     "id",
+//!     Arc::new(Int64Array::from(vec![1, 2, 3])) as _,
+//! )])?;
+//!
+//! let server = MemoryFlightSqlServer::new();
+//! server.register_table("my_table", MemoryTable::from(batch));
+//!
+//! // `SELECT * FROM my_table` now returns the registered batch
+//! tonic::transport::Server::builder()
+//!     .add_service(server.service())
+//!     .serve("127.0.0.1:50051".parse()?)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::{Debug, Formatter};
+use std::pin::Pin;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+
+use arrow_array::RecordBatch;
+use arrow_schema::{ArrowError, Schema, SchemaRef};
+use arrow_select::concat::concat_batches;
+use bytes::Bytes;
+use futures::{Stream, TryStreamExt, stream};
+use prost::Message;
+use tonic::{Request, Response, Status};
+
+use super::metadata::{
+    SqlInfoData, SqlInfoDataBuilder, XdbcTypeInfo, XdbcTypeInfoData, XdbcTypeInfoDataBuilder,
+};
+use super::server::{FlightSqlService, PeekableFlightDataStream};
+use super::{
+    ActionClosePreparedStatementRequest, ActionCreatePreparedStatementRequest,
+    ActionCreatePreparedStatementResult, CommandGetCatalogs, CommandGetDbSchemas,
+    CommandGetPrimaryKeys, CommandGetSqlInfo, CommandGetTableTypes, CommandGetTables,
+    CommandGetXdbcTypeInfo, CommandPreparedStatementQuery, CommandStatementIngest,
+    CommandStatementQuery, DoPutPreparedStatementResult, Nullable, ProstMessageExt, Searchable,
+    SqlInfo, TableExistsOption, TableNotExistOption, TicketStatementQuery, XdbcDataType,
+};
+use crate::decode::FlightRecordBatchStream;
+use crate::encode::FlightDataEncoderBuilder;
+use crate::flight_service_server::FlightServiceServer;
+use crate::{FlightData, FlightDescriptor, FlightEndpoint, FlightInfo, IpcMessage, Ticket};
+
+/// The catalog of tables registered without a catalog name
+pub const DEFAULT_CATALOG: &str = "memory";
+
+/// The schema of tables registered without a schema name
+pub const DEFAULT_SCHEMA: &str = "public";
+
+/// The only table type of a [`MemoryFlightSqlServer`]
+const TABLE_TYPE: &str = "TABLE";
+
+/// A table of [`RecordBatch`]es registered with a [`MemoryFlightSqlServer`],
+/// also used for query results
+#[derive(Debug, Clone)]
+pub struct MemoryTable {
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+    primary_key: Vec<String>,
+}
+
+impl MemoryTable {
+    /// Create a new [`MemoryTable`] with the provided schema and data
+    ///
+    /// Returns an error if the batches do not have the same fields as `schema`
+    pub fn try_new(schema: SchemaRef, batches: Vec<RecordBatch>) -> Result<Self, ArrowError> {
+        if let Some(batch) = batches
+            .iter()
+            .find(|b| b.schema().fields() != schema.fields())
+        {
+            return Err(ArrowError::SchemaError(format!(
+                "RecordBatch schema {:?} does not match table schema {schema:?}",
+                batch.schema()
+            )));
+        }
+        Ok(Self {
+            schema,
+            batches,
+            primary_key: vec![],
+        })
+    }
+
+    /// Create a new, empty [`MemoryTable`] with the provided schema
+    pub fn new_empty(schema: SchemaRef) -> Self {
+        Self {
+            schema,
+            batches: vec![],
+            primary_key: vec![],
+        }
+    }
+
+    /// Set the columns of the primary key of this table, reported in
+    /// response to [`CommandGetPrimaryKeys`]
+    ///
+    /// Returns an error if a column does not exist
+    pub fn with_primary_key(
+        mut self,
+        columns: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Result<Self, ArrowError> {
+        self.primary_key = columns.into_iter().map(Into::into).collect();
+        for column in &self.primary_key {
+            self.schema.index_of(column)?;
+        }
+        Ok(self)
+    }
+
+    /// The schema of this table
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// The data of this table
+    pub fn batches(&self) -> &[RecordBatch] {
+        &self.batches
+    }
+
+    /// The columns of the primary key of this table
+    pub fn primary_key(&self) -> &[String] {
+        &self.primary_key
+    }
+
+    /// The total number of rows in this table
+    pub fn num_rows(&self) -> usize {
+        self.batches.iter().map(|b| b.num_rows()).sum()
+    }
+}
+
+impl From<RecordBatch> for MemoryTable {
+    fn from(batch: RecordBatch) -> Self {
+        Self {
+            schema: batch.schema(),
+            batches: vec![batch],
+            primary_key: vec![],
+        }
+    }
+}
+
+/// Plans and executes queries for a [`MemoryFlightSqlServer`]
+///
+/// Both methods are called with the server, to access its registered tables,
+/// and the query text.
+pub trait QueryHandler: Send + Sync {
+    /// Returns the schema of the result of `query`, without executing it
+    ///
+    /// Called when a client requests the [`FlightInfo`] of a query, or
+    /// creates a prepared statement.
+    fn schema(&self, server: &MemoryFlightSqlServer, query: &str) -> Result<SchemaRef, Status>;
+
+    /// Executes `query` with the bound `parameters` of a prepared statement,
+    /// if any
+    ///
+    /// Called when a client retrieves the results of a query with `DoGet`.
+    fn execute(
+        &self,
+        server: &MemoryFlightSqlServer,
+        query: &str,
+        parameters: Option<&RecordBatch>,
+    ) -> Result<MemoryTable, Status>;
+}
+
+/// A prepared statement created by a client
+#[derive(Debug, Clone)]
+struct PreparedStatement {
+    query: String,
+    parameters: Option<RecordBatch>,
+}
+
+/// Fully qualified table name: catalog, schema, table
+type TableName = (String, String, String);
+
+/// The statement of a [`TicketStatementQuery`], encoded in its handle so
+/// that no state is kept for tickets that are never retrieved
+#[derive(Debug, Clone, PartialEq)]
+enum TicketStatement {
+    /// A query
+    Query(String),
+    /// The handle of a prepared statement
+    Prepared(Bytes),
+}
+
+impl TicketStatement {
+    const QUERY: u8 = 0;
+    const PREPARED: u8 = 1;
+
+    fn encode(&self) -> Bytes {
+        let (tag, payload) = match self {
+            Self::Query(query) => (Self::QUERY, query.as_bytes()),
+            Self::Prepared(handle) => (Self::PREPARED, handle.as_ref()),
+        };
+        let mut handle = Vec::with_capacity(payload.len() + 1);
+        handle.push(tag);
+        handle.extend_from_slice(payload);
+        handle.into()
+    }
+
+    fn decode(handle: &Bytes) -> Result<Self, Status> {
+        let invalid = || Status::invalid_argument("Invalid statement handle");
+        match handle.first() {
+            Some(&Self::QUERY) => String::from_utf8(handle[1..].to_vec())
+                .map(Self::Query)
+                .map_err(|_| invalid()),
+            Some(&Self::PREPARED) => Ok(Self::Prepared(handle.slice(1..))),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// An in-memory [`FlightSqlService`], see the [module level documentation](self)
+///
+/// Cloning the server is cheap, and clones share the same tables.
+///
+/// By default only queries of the form `SELECT * FROM <table>` are supported,
+/// use [`Self::with_query_handler`] to execute other queries. Queries are
+/// executed when their results are retrieved with `DoGet`, so the
+/// [`FlightInfo`] of a query does not report its number of rows. Update
+/// statements, substrait plans and transactions are not supported.
+#[derive(Clone)]
+pub struct MemoryFlightSqlServer {
+    tables: Arc<RwLock<BTreeMap<TableName, MemoryTable>>>,
+    prepared_statements: Arc<Mutex<HashMap<Bytes, PreparedStatement>>>,
+    next_handle: Arc<AtomicU64>,
+    sql_info: Arc<SqlInfoData>,
+    xdbc_type_info: Arc<XdbcTypeInfoData>,
+    query_handler: Arc<dyn QueryHandler>,
+}
+
+impl Debug for MemoryFlightSqlServer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MemoryFlightSqlServer")
+            .field("tables", &self.tables)
+            .field("prepared_statements", &self.prepared_statements)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Default for MemoryFlightSqlServer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemoryFlightSqlServer {
+    /// Create a new [`MemoryFlightSqlServer`] without any tables
+    pub fn new() -> Self {
+        Self {
+            tables: Default::default(),
+            prepared_statements: Default::default(),
+            next_handle: Default::default(),
+            sql_info: Arc::new(default_sql_info()),
+            xdbc_type_info: Arc::new(default_xdbc_type_info()),
+            query_handler: Arc::new(SelectStarQueryHandler),
+        }
+    }
+
+    /// Set the [`QueryHandler`] used to execute queries and prepared statements
+    pub fn with_query_handler(mut self, handler: impl QueryHandler + 'static) -> Self {
+        self.query_handler = Arc::new(handler);
+        self
+    }
+
+    /// Set the [`SqlInfoData`] returned in response to [`CommandGetSqlInfo`]
+    pub fn with_sql_info(mut self, sql_info: SqlInfoData) -> Self {
+        self.sql_info = Arc::new(sql_info);
+        self
+    }
+
+    /// Set the [`XdbcTypeInfoData`] returned in response to [`CommandGetXdbcTypeInfo`]
+    pub fn with_xdbc_type_info(mut self, xdbc_type_info: XdbcTypeInfoData) -> Self {
+        self.xdbc_type_info = Arc::new(xdbc_type_info);
+        self
+    }
+
+    /// Return a [`FlightServiceServer`] that can be used with a
+    /// [`Server`](tonic::transport::Server)
+    pub fn service(&self) -> FlightServiceServer<Self> {
+        FlightServiceServer::new(self.clone())
+    }
+
+    /// Register `table` as `name`, returning the table previously registered
+    /// with that name, if any
+    ///
+    /// `name` may be qualified as `schema.table` or `catalog.schema.table`,
+    /// otherwise [`DEFAULT_CATALOG`] and [`DEFAULT_SCHEMA`] are used.
+    pub fn register_table(&self, name: &str, table: MemoryTable) -> Option<MemoryTable> {
+        self.tables.write().unwrap().insert(parse_name(name), table)
+    }
+
+    /// Remove the table registered as `name`, returning it if it existed
+    pub fn deregister_table(&self, name: &str) -> Option<MemoryTable> {
+        self.tables.write().unwrap().remove(&parse_name(name))
+    }
+
+    /// Return the table registered as `name`, if any
+    pub fn table(&self, name: &str) -> Option<MemoryTable> {
+        self.tables.read().unwrap().get(&parse_name(name)).cloned()
+    }
+
+    /// Return the fully qualified names of all registered tables
+    pub fn table_names(&self) -> Vec<String> {
+        self.tables
+            .read()
+            .unwrap()
+            .keys()
+            .map(|(catalog, schema, table)| format!("{catalog}.{schema}.{table}"))
+            .collect()
+    }
+
+    fn next_handle(&self) -> Bytes {
+        let handle = self.next_handle.fetch_add(1, Ordering::Relaxed);
+        handle.to_string().into()
+    }
+
+    /// Returns a [`FlightInfo`] for `statement`, the query of which is
+    /// executed when its [`TicketStatementQuery`] is retrieved
+    fn statement_flight_info(
+        &self,
+        statement: TicketStatement,
+        query: &str,
+        descriptor: FlightDescriptor,
+    ) -> Result<FlightInfo, Status> {
+        let schema = self.query_handler.schema(self, query)?;
+        let ticket = TicketStatementQuery {
+            statement_handle: statement.encode(),
+        };
+        let endpoint =
+            FlightEndpoint::new().with_ticket(Ticket::new(ticket.as_any().encode_to_vec()));
+        let info = FlightInfo::new()
+            .try_with_schema(&schema)
+            .map_err(|e| Status::internal(format!("Unable to encode schema: {e}")))?
+            .with_endpoint(endpoint)
+            .with_descriptor(descriptor)
+            .with_ordered(true);
+        Ok(info)
+    }
+
+    fn prepared_statement(&self, handle: &Bytes) -> Result<PreparedStatement, Status> {
+        self.prepared_statements
+            .lock()
+            .unwrap()
+            .get(handle)
+            .cloned()
+            .ok_or_else(|| Status::not_found("Prepared statement not found"))
+    }
+}
+
+/// The default [`QueryHandler`], which supports `SELECT * FROM <table>`
+#[derive(Debug)]
+struct SelectStarQueryHandler;
+
+impl SelectStarQueryHandler {
+    /// Returns the table selected by `query`
+    fn table(server: &MemoryFlightSqlServer, query: &str) -> Result<MemoryTable, Status> {
+        let words: Vec<_> = query
+            .trim()
+            .trim_end_matches(';')
+            .split_whitespace()
+            .collect();
+        match words.as_slice() {
+            [select, "*", from, name]
+                if select.eq_ignore_ascii_case("select") && from.eq_ignore_ascii_case("from") =>
+            {
+                server
+                    .table(name)
+                    .ok_or_else(|| Status::not_found(format!("Table not found: {name}")))
+            }
+            _ => Err(Status::unimplemented(format!("Unsupported query: {query}"))),
+        }
+    }
+}
+
+impl QueryHandler for SelectStarQueryHandler {
+    fn schema(&self, server: &MemoryFlightSqlServer, query: &str) -> Result<SchemaRef, Status> {
+        Self::table(server, query).map(|table| table.schema)
+    }
+
+    fn execute(
+        &self,
+        server: &MemoryFlightSqlServer,
+        query: &str,
+        _parameters: Option<&RecordBatch>,
+    ) -> Result<MemoryTable, Status> {
+        Self::table(server, query)
+    }
+}
+
+/// Split a possibly qualified table name into catalog, schema and table
+fn parse_name(name: &str) -> TableName {
+    let mut parts = name.rsplitn(3, '.');
+    let table = parts.next().unwrap_or_default();
+    let schema = parts.next().unwrap_or(DEFAULT_SCHEMA);
+    let catalog = parts.next().unwrap_or(DEFAULT_CATALOG);
+    (catalog.to_string(), schema.to_string(), table.to_string())
+}
+
+fn default_sql_info() -> SqlInfoData {
+    let mut builder = SqlInfoDataBuilder::new();
+    builder.append(SqlInfo::FlightSqlServerName, "Memory Flight SQL Server");
+    builder.append(SqlInfo::FlightSqlServerVersion, env!("CARGO_PKG_VERSION"));
+    // 1.3 comes from https://github.com/apache/arrow/blob/f9324b79bf4fc1ec7e97b32e3cce16e75ef0f5e3/format/Schema.fbs#L24
+    builder.append(SqlInfo::FlightSqlServerArrowVersion, "1.3");
+    builder.append(SqlInfo::FlightSqlServerReadOnly, false);
+    builder.append(SqlInfo::FlightSqlServerSql, true);
+    builder.append(SqlInfo::FlightSqlServerSubstrait, false);
+    builder.append(SqlInfo::FlightSqlServerBulkIngestion, true);
+    builder.build().unwrap()
+}
+
+fn default_xdbc_type_info() -> XdbcTypeInfoData {
+    let types = [
+        ("BOOLEAN", XdbcDataType::XdbcBit),
+        ("INTEGER", XdbcDataType::XdbcInteger),
+        ("BIGINT", XdbcDataType::XdbcBigint),
+        ("DOUBLE", XdbcDataType::XdbcDouble),
+        ("VARCHAR", XdbcDataType::XdbcVarchar),
+    ];
+    let mut builder = XdbcTypeInfoDataBuilder::new();
+    for (name, data_type) in types {
+        builder.append(XdbcTypeInfo {
+            type_name: name.into(),
+            data_type,
+            nullable: Nullable::NullabilityNullable,
+            searchable: Searchable::Full,
+            local_type_name: Some(name.into()),
+            sql_data_type: data_type,
+            ..Default::default()
+        });
+    }
+    builder.build().unwrap()
+}
+
+type DoGetStream = Pin<Box<dyn Stream<Item = Result<FlightData, Status>> + Send + 'static>>;
+
+/// Encode `batches` as a `DoGet` response
+fn batches_response(
+    schema: SchemaRef,
+    batches: Vec<RecordBatch>,
+) -> Result<Response<DoGetStream>, Status> {
+    let stream = FlightDataEncoderBuilder::new()
+        .with_schema(schema)
+        .build(stream::iter(batches.into_iter().map(Ok)))
+        .map_err(Status::from);
+    Ok(Response::new(Box::pin(stream)))
+}
+
+/// Encode the result of a metadata command as a `DoGet` response
+fn metadata_response(
+    batch: crate::error::Result<RecordBatch>,
+) -> Result<Response<DoGetStream>, Status> {
+    let batch = batch?;
+    batches_response(batch.schema(), vec![batch])
+}
+
+/// A [`FlightInfo`] for a metadata command, retrieved by using the command
+/// itself as the ticket
+fn metadata_flight_info(
+    command: impl ProstMessageExt,
+    schema: &Schema,
+    request: Request<FlightDescriptor>,
+) -> Result<Response<FlightInfo>, Status> {
+    let endpoint = FlightEndpoint::new().with_ticket(Ticket::new(command.as_any().encode_to_vec()));
+    let info = FlightInfo::new()
+        .try_with_schema(schema)
+        .map_err(|e| Status::internal(format!("Unable to encode schema: {e}")))?
+        .with_endpoint(endpoint)
+        .with_descriptor(request.into_inner());
+    Ok(Response::new(info))
+}
+
+/// Decode the record batches sent with a `DoPut` request
+async fn read_batches(
+    request: Request<PeekableFlightDataStream>,
+) -> Result<(Option<SchemaRef>, Vec<RecordBatch>), Status> {
+    let mut stream =
+        FlightRecordBatchStream::new_from_flight_data(request.into_inner().map_err(|e| e.into()));
+    let batches: Vec<_> = (&mut stream).try_collect().await?;
+    Ok((stream.schema().cloned(), batches))
+}
+
+#[tonic::async_trait]
+impl FlightSqlService for MemoryFlightSqlServer {
+    type FlightService = MemoryFlightSqlServer;
+
+    async fn get_flight_info_statement(
+        &self,
+        query: CommandStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let statement = TicketStatement::Query(query.query.clone());
+        let info = self.statement_flight_info(statement, &query.query, request.into_inner())?;
+        Ok(Response::new(info))
+    }
+
+    async fn get_flight_info_prepared_statement(
+        &self,
+        query: CommandPreparedStatementQuery,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let handle = query.prepared_statement_handle;
+        let query = self.prepared_statement(&handle)?.query;
+        let statement = TicketStatement::Prepared(handle);
+        let info = self.statement_flight_info(statement, &query, request.into_inner())?;
+        Ok(Response::new(info))
+    }
+
+    async fn get_flight_info_catalogs(
+        &self,
+        query: CommandGetCatalogs,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.into_builder().schema();
+        metadata_flight_info(query, &schema, request)
+    }
+
+    async fn get_flight_info_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder().schema();
+        metadata_flight_info(query, &schema, request)
+    }
+
+    async fn get_flight_info_tables(
+        &self,
+        query: CommandGetTables,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder().schema();
+        metadata_flight_info(query, &schema, request)
+    }
+
+    async fn get_flight_info_table_types(
+        &self,
+        query: CommandGetTableTypes,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.into_builder().schema();
+        metadata_flight_info(query, &schema, request)
+    }
+
+    async fn get_flight_info_sql_info(
+        &self,
+        query: CommandGetSqlInfo,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder(&self.sql_info).schema();
+        metadata_flight_info(query, &schema, request)
+    }
+
+    async fn get_flight_info_primary_keys(
+        &self,
+        query: CommandGetPrimaryKeys,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.clone().into_builder().schema();
+        metadata_flight_info(query, &schema, request)
+    }
+
+    async fn get_flight_info_xdbc_type_info(
+        &self,
+        query: CommandGetXdbcTypeInfo,
+        request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        let schema = query.into_builder(&self.xdbc_type_info).schema();
+        metadata_flight_info(query, &schema, request)
+    }
+
+    async fn do_get_statement(
+        &self,
+        ticket: TicketStatementQuery,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let result = match TicketStatement::decode(&ticket.statement_handle)? {
+            TicketStatement::Query(query) => self.query_handler.execute(self, &query, None)?,
+            TicketStatement::Prepared(handle) => {
+                let statement = self.prepared_statement(&handle)?;
+                self.query_handler
+                    .execute(self, &statement.query, statement.parameters.as_ref())?
+            }
+        };
+        batches_response(result.schema, result.batches)
+    }
+
+    async fn do_get_catalogs(
+        &self,
+        query: CommandGetCatalogs,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let mut builder = query.into_builder();
+        let tables = self.tables.read().unwrap();
+        let catalogs: BTreeSet<_> = tables.keys().map(|(catalog, _, _)| catalog).collect();
+        for catalog in catalogs {
+            builder.append(catalog);
+        }
+        metadata_response(builder.build())
+    }
+
+    async fn do_get_schemas(
+        &self,
+        query: CommandGetDbSchemas,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let mut builder = query.into_builder();
+        let tables = self.tables.read().unwrap();
+        let schemas: BTreeSet<_> = tables
+            .keys()
+            .map(|(catalog, schema, _)| (catalog, schema))
+            .collect();
+        for (catalog, schema) in schemas {
+            builder.append(catalog, schema);
+        }
+        metadata_response(builder.build())
+    }
+
+    async fn do_get_tables(
+        &self,
+        query: CommandGetTables,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let mut builder = query.into_builder();
+        for ((catalog, schema, name), table) in self.tables.read().unwrap().iter() {
+            builder.append(catalog, schema, name, TABLE_TYPE, &table.schema)?;
+        }
+        metadata_response(builder.build())
+    }
+
+    async fn do_get_table_types(
+        &self,
+        query: CommandGetTableTypes,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let mut builder = query.into_builder();
+        builder.append(TABLE_TYPE);
+        metadata_response(builder.build())
+    }
+
+    async fn do_get_sql_info(
+        &self,
+        query: CommandGetSqlInfo,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        metadata_response(query.into_builder(&self.sql_info).build())
+    }
+
+    async fn do_get_primary_keys(
+        &self,
+        query: CommandGetPrimaryKeys,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        let mut builder = query.into_builder();
+        for ((catalog, schema, name), table) in self.tables.read().unwrap().iter() {
+            for (column, sequence) in table.primary_key.iter().zip(1..) {
+                builder.append(catalog, schema, name, column, None, sequence);
+            }
+        }
+        metadata_response(builder.build())
+    }
+
+    async fn do_get_xdbc_type_info(
+        &self,
+        query: CommandGetXdbcTypeInfo,
+        _request: Request<Ticket>,
+    ) -> Result<Response<DoGetStream>, Status> {
+        metadata_response(query.into_builder(&self.xdbc_type_info).build())
+    }
+
+    async fn do_put_statement_ingest(
+        &self,
+        ticket: CommandStatementIngest,
+        request: Request<PeekableFlightDataStream>,
+    ) -> Result<i64, Status> {
+        let (schema, batches) = read_batches(request).await?;
+        let schema =
+            schema.ok_or_else(|| Status::invalid_argument("Ingested data has no schema"))?;
+        let name = (
+            ticket
+                .catalog
+                .unwrap_or_else(|| DEFAULT_CATALOG.to_string()),
+            ticket.schema.unwrap_or_else(|| DEFAULT_SCHEMA.to_string()),
+            ticket.table,
+        );
+        let options = ticket.table_definition_options.unwrap_or_default();
+        let ingested = MemoryTable::try_new(schema, batches)
+            .map_err(|e| Status::invalid_argument(e.to_string()))?;
+        let num_rows = ingested.num_rows() as i64;
+
+        let mut tables = self.tables.write().unwrap();
+        match tables.get_mut(&name) {
+            Some(table) => match options.if_exists() {
+                TableExistsOption::Append => {
+                    if table.schema.fields() != ingested.schema.fields() {
+                        return Err(Status::invalid_argument(format!(
+                            "Schema of ingested data does not match table {}",
+                            name.2
+                        )));
+                    }
+                    table.batches.extend(ingested.batches);
+                }
+                TableExistsOption::Replace => *table = ingested,
+                TableExistsOption::Fail | TableExistsOption::Unspecified => {
+                    return Err(Status::already_exists(format!(
+                        "Table already exists: {}",
+                        name.2
+                    )));
+                }
+            },
+            None => match options.if_not_exist() {
+                TableNotExistOption::Create | TableNotExistOption::Unspecified => {
+                    tables.insert(name, ingested);
+                }
+                TableNotExistOption::Fail => {
+                    return Err(Status::not_found(format!("Table not found: {}", name.2)));
+                }
+            },
+        }
+        Ok(num_rows)
+    }
+
+    async fn do_put_prepared_statement_query(
+        &self,
+        query: CommandPreparedStatementQuery,
+        request: Request<PeekableFlightDataStream>,
+    ) -> Result<DoPutPreparedStatementResult, Status> {
+        let handle = query.prepared_statement_handle;
+        // check the statement exists before reading the parameters
+        self.prepared_statement(&handle)?;
+
+        let (schema, batches) = read_batches(request).await?;
+        let parameters = match schema {
+            Some(schema) => Some(
+                concat_batches(&schema, &batches)
+                    .map_err(|e| Status::invalid_argument(format!("Invalid parameters: {e}")))?,
+            ),
+            None => None,
+        };
+
+        let mut statements = self.prepared_statements.lock().unwrap();
+        let statement = statements
+            .get_mut(&handle)
+            .ok_or_else(|| Status::not_found("Prepared statement not found"))?;
+        statement.parameters = parameters;
+        Ok(DoPutPreparedStatementResult {
+            prepared_statement_handle: Some(handle),
+        })
+    }
+
+    async fn do_action_create_prepared_statement(
+        &self,
+        query: ActionCreatePreparedStatementRequest,
+        _request: Request<crate::Action>,
+    ) -> Result<ActionCreatePreparedStatementResult, Status> {
+        let schema = self.query_handler.schema(self, &query.query)?;
+        let options = Default::default();
+        let IpcMessage(dataset_schema) = crate::SchemaAsIpc::new(&schema, &options)
+            .try_into()
+            .map_err(|e| Status::internal(format!("Unable to encode schema: {e}")))?;
+
+        let handle = self.next_handle();
+        let statement = PreparedStatement {
+            query: query.query,
+            parameters: None,
+        };
+        self.prepared_statements
+            .lock()
+            .unwrap()
+            .insert(handle.clone(), statement);
+        Ok(ActionCreatePreparedStatementResult {
+            prepared_statement_handle: handle,
+            dataset_schema,
+            parameter_schema: Bytes::new(),
+        })
+    }
+
+    async fn do_action_close_prepared_statement(
+        &self,
+        query: ActionClosePreparedStatementRequest,
+        _request: Request<crate::Action>,
+    ) -> Result<(), Status> {
+        self.prepared_statements
+            .lock()
+            .unwrap()
+            .remove(&query.prepared_statement_handle)
+            .map(|_| ())
+            .ok_or_else(|| Status::not_found("Prepared statement not found"))
+    }
+
+    async fn register_sql_info(&self, _id: i32, _result: &SqlInfo) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_name() {
+        let name = |c: &str, s: &str, t: &str| (c.to_string(), s.to_string(), t.to_string());
+        assert_eq!(parse_name("t"), name("memory", "public", "t"));
+        assert_eq!(parse_name("s.t"), name("memory", "s", "t"));
+        assert_eq!(parse_name("c.s.t"), name("c", "s", "t"));
+        assert_eq!(parse_name("a.c.s.t"), name("a.c", "s", "t"));
+    }
+
+    #[test]
+    fn test_ticket_statement() {
+        for statement in [
+            TicketStatement::Query("SELECT * FROM t".to_string()),
+            TicketStatement::Query(String::new()),
+            TicketStatement::Prepared(Bytes::from("42")),
+        ] {
+            assert_eq!(
+                TicketStatement::decode(&statement.encode()).unwrap(),
+                statement
+            );
+        }
+        for handle in [&b""[..], &[2], &[0, 0xff]] {
+            let err = TicketStatement::decode(&Bytes::copy_from_slice(handle)).unwrap_err();
+            assert_eq!(err.message(), "Invalid statement handle");
+        }
+    }
+}
@@ -21,17 +21,20 @@
 //! - [`GetCatalogsBuilder`] for building responses to [`CommandGetCatalogs`] queries.
 //! - [`GetDbSchemasBuilder`] for building responses to [`CommandGetDbSchemas`] queries.
 //! - [`GetTablesBuilder`]for building responses to [`CommandGetTables`] queries.
+//! - [`GetPrimaryKeysBuilder`] for building responses to [`CommandGetPrimaryKeys`] queries.
 //! - [`SqlInfoDataBuilder`]for building responses to [`CommandGetSqlInfo`] queries.
 //! - [`XdbcTypeInfoDataBuilder`]for building responses to [`CommandGetXdbcTypeInfo`] queries.
 //!
 //! [`CommandGetCatalogs`]: crate::sql::CommandGetCatalogs
 //! [`CommandGetDbSchemas`]: crate::sql::CommandGetDbSchemas
 //! [`CommandGetTables`]: crate::sql::CommandGetTables
+//! [`CommandGetPrimaryKeys`]: crate::sql::CommandGetPrimaryKeys
 //! [`CommandGetSqlInfo`]: crate::sql::CommandGetSqlInfo
 //! [`CommandGetXdbcTypeInfo`]: crate::sql::CommandGetXdbcTypeInfo
 
 mod catalogs;
 mod db_schemas;
+mod primary_keys;
 mod sql_info;
 mod table_types;
 mod tables;
@@ -39,6 +42,7 @@ mod xdbc_info;
 
 pub use catalogs::GetCatalogsBuilder;
 pub use db_schemas::GetDbSchemasBuilder;
+pub use primary_keys::GetPrimaryKeysBuilder;
 pub use sql_info::{SqlInfoData, SqlInfoDataBuilder};
 pub use tables::GetTablesBuilder;
 pub use xdbc_info::{XdbcTypeInfo, XdbcTypeInfoData, XdbcTypeInfoDataBuilder};
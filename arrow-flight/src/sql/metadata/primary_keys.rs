@@ -0,0 +1,214 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! [`GetPrimaryKeysBuilder`] for building responses to [`CommandGetPrimaryKeys`] queries.
+//!
+//! [`CommandGetPrimaryKeys`]: crate::sql::CommandGetPrimaryKeys
+
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, Int32Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use once_cell::sync::Lazy;
+
+use crate::error::*;
+use crate::sql::CommandGetPrimaryKeys;
+
+/// A builder for a [`CommandGetPrimaryKeys`] response.
+///
+/// Builds rows like this:
+///
+/// * catalog_name: utf8,
+/// * db_schema_name: utf8,
+/// * table_name: utf8 not null,
+/// * column_name: utf8 not null,
+/// * key_name: utf8,
+/// * key_sequence: int32 not null
+pub struct GetPrimaryKeysBuilder {
+    // Specifies the Catalog to search for the table.
+    // - An empty string retrieves those without a catalog.
+    // - If omitted the catalog name is not used to narrow the search.
+    catalog_filter: Option<String>,
+    // Specifies the schema to search for the table.
+    // - An empty string retrieves those without a schema.
+    // - If omitted the schema name is not used to narrow the search.
+    db_schema_filter: Option<String>,
+    // Specifies the table to get the primary keys for.
+    table_filter: String,
+    rows: Vec<PrimaryKeyRow>,
+}
+
+/// catalog_name, db_schema_name, table_name, key_name, key_sequence, column_name
+type PrimaryKeyRow = (String, String, String, Option<String>, i32, String);
+
+impl CommandGetPrimaryKeys {
+    /// Create a builder suitable for constructing a response
+    pub fn into_builder(self) -> GetPrimaryKeysBuilder {
+        self.into()
+    }
+}
+
+impl From<CommandGetPrimaryKeys> for GetPrimaryKeysBuilder {
+    fn from(value: CommandGetPrimaryKeys) -> Self {
+        Self::new(value.catalog, value.db_schema, value.table)
+    }
+}
+
+impl GetPrimaryKeysBuilder {
+    /// Create a new instance of [`GetPrimaryKeysBuilder`]
+    ///
+    /// # Parameters
+    ///
+    /// - `catalog`: Specifies the catalog to search for the table.
+    ///   - An empty string retrieves those without a catalog.
+    ///   - If omitted the catalog name is not used to narrow the search.
+    /// - `db_schema`: Specifies the schema to search for the table.
+    ///   - An empty string retrieves those without a schema.
+    ///   - If omitted the schema name is not used to narrow the search.
+    /// - `table`: Specifies the table to get the primary keys for.
+    ///
+    /// [`CommandGetPrimaryKeys`]: crate::sql::CommandGetPrimaryKeys
+    pub fn new(
+        catalog: Option<impl Into<String>>,
+        db_schema: Option<impl Into<String>>,
+        table: impl Into<String>,
+    ) -> Self {
+        Self {
+            catalog_filter: catalog.map(|s| s.into()),
+            db_schema_filter: db_schema.map(|s| s.into()),
+            table_filter: table.into(),
+            rows: vec![],
+        }
+    }
+
+    /// Append a row for the column at position `key_sequence` (starting
+    /// at 1) of a table's primary key
+    ///
+    /// Rows for tables not matching the filters of the builder are ignored.
+    pub fn append(
+        &mut self,
+        catalog_name: impl AsRef<str>,
+        schema_name: impl AsRef<str>,
+        table_name: impl AsRef<str>,
+        column_name: impl AsRef<str>,
+        key_name: Option<&str>,
+        key_sequence: i32,
+    ) {
+        let catalog_name = catalog_name.as_ref();
+        let schema_name = schema_name.as_ref();
+        let table_name = table_name.as_ref();
+        let matches = self
+            .catalog_filter
+            .as_ref()
+            .is_none_or(|catalog| catalog == catalog_name)
+            && self
+                .db_schema_filter
+                .as_ref()
+                .is_none_or(|schema| schema == schema_name)
+            && self.table_filter == table_name;
+        if matches {
+            self.rows.push((
+                catalog_name.to_string(),
+                schema_name.to_string(),
+                table_name.to_string(),
+                key_name.map(|s| s.to_string()),
+                key_sequence,
+                column_name.as_ref().to_string(),
+            ));
+        }
+    }
+
+    /// builds a `RecordBatch` with the correct schema for a `CommandGetPrimaryKeys` response
+    pub fn build(self) -> Result<RecordBatch> {
+        let schema = self.schema();
+        let Self { mut rows, .. } = self;
+
+        // Order by catalog_name, db_schema_name, table_name, key_name, then key_sequence
+        rows.sort_unstable();
+
+        let catalog_name = StringArray::from_iter_values(rows.iter().map(|r| &r.0));
+        let db_schema_name = StringArray::from_iter_values(rows.iter().map(|r| &r.1));
+        let table_name = StringArray::from_iter_values(rows.iter().map(|r| &r.2));
+        let column_name = StringArray::from_iter_values(rows.iter().map(|r| &r.5));
+        let key_name = StringArray::from_iter(rows.iter().map(|r| r.3.as_ref()));
+        let key_sequence = Int32Array::from_iter_values(rows.iter().map(|r| r.4));
+
+        let batch = RecordBatch::try_new(
+            schema,
+            vec![
+                Arc::new(catalog_name) as ArrayRef,
+                Arc::new(db_schema_name) as ArrayRef,
+                Arc::new(table_name) as ArrayRef,
+                Arc::new(column_name) as ArrayRef,
+                Arc::new(key_name) as ArrayRef,
+                Arc::new(key_sequence) as ArrayRef,
+            ],
+        )?;
+
+        Ok(batch)
+    }
+
+    /// Return the schema of the RecordBatch that will be returned
+    /// from [`CommandGetPrimaryKeys`]
+    pub fn schema(&self) -> SchemaRef {
+        get_primary_keys_schema()
+    }
+}
+
+fn get_primary_keys_schema() -> SchemaRef {
+    Arc::clone(&GET_PRIMARY_KEYS_SCHEMA)
+}
+
+/// The schema for GetPrimaryKeys
+static GET_PRIMARY_KEYS_SCHEMA: Lazy<SchemaRef> = Lazy::new(|| {
+    Arc::new(Schema::new(vec![
+        Field::new("catalog_name", DataType::Utf8, true),
+        Field::new("db_schema_name", DataType::Utf8, true),
+        Field::new("table_name", DataType::Utf8, false),
+        Field::new("column_name", DataType::Utf8, false),
+        Field::new("key_name", DataType::Utf8, true),
+        Field::new("key_sequence", DataType::Int32, false),
+    ]))
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sql::metadata::tests::assert_batches_eq;
+
+    #[test]
+    fn test_primary_keys_are_filtered_and_sorted() {
+        let mut builder = GetPrimaryKeysBuilder::new(None::<String>, Some("a_schema"), "t");
+        builder.append("a_catalog", "a_schema", "t", "id2", Some("pk"), 2);
+        builder.append("a_catalog", "a_schema", "t", "id1", Some("pk"), 1);
+        builder.append("a_catalog", "b_schema", "t", "id", None, 1);
+        builder.append("a_catalog", "a_schema", "other", "id", None, 1);
+        let batch = builder.build().unwrap();
+
+        assert_batches_eq(
+            &[batch],
+            &[
+                "+--------------+----------------+------------+-------------+----------+--------------+",
+                "| catalog_name | db_schema_name | table_name | column_name | key_name | key_sequence |",
+                "+--------------+----------------+------------+-------------+----------+--------------+",
+                "| a_catalog    | a_schema       | t          | id1         | pk       | 1            |",
+                "| a_catalog    | a_schema       | t          | id2         | pk       | 2            |",
+                "+--------------+----------------+------------+-------------+----------+--------------+",
+            ],
+        );
+    }
+}
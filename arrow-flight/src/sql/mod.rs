@@ -110,8 +110,10 @@ pub use r#gen::command_statement_ingest::table_definition_options::{
 };
 
 pub mod client;
+pub mod memory;
 pub mod metadata;
 pub mod server;
+pub mod session;
 
 pub use crate::streams::FallibleRequestStream;
 
@@ -0,0 +1,182 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Helpers for tracking FlightSQL sessions with HTTP cookies.
+//!
+//! The `SetSessionOptions`, `GetSessionOptions` and `CloseSession` actions
+//! reference the server session through transport-level state. This module
+//! implements the convention suggested by the Flight protocol: a cookie named
+//! [`SESSION_COOKIE_NAME`], set by the server with a `set-cookie` header and
+//! sent back by the client in a `cookie` header.
+//!
+//! A [`FlightSqlService`](super::server::FlightSqlService) would typically
+//! read the id with [`session_id`] and, when creating a session in
+//! `do_action_set_session_options`, attach it to the response with
+//! [`set_session_id`]:
+//!
+//! ```
+//! # use arrow_flight::{Action, SetSessionOptionsRequest, SetSessionOptionsResult};
+//! # use arrow_flight::sql::session::{session_id, set_session_id};
+//! # use tonic::{Request, Response, Status};
+//! fn set_session_options(
+//!     _query: SetSessionOptionsRequest,
+//!     request: Request<Action>,
+//! ) -> Result<Response<SetSessionOptionsResult>, Status> {
+//!     let mut response = Response::new(SetSessionOptionsResult::default());
+//!     if session_id(request.metadata()).is_none() {
+//!         set_session_id(response.metadata_mut(), "a-new-session-id")?;
+//!     }
+//!     Ok(response)
+//! }
+//! ```
+
+use tonic::Status;
+use tonic::metadata::{MetadataMap, MetadataValue};
+
+/// The cookie name suggested by the Flight protocol for the session id
+pub const SESSION_COOKIE_NAME: &str = "arrow_flight_session_id";
+
+const COOKIE_HEADER: &str = "cookie";
+const SET_COOKIE_HEADER: &str = "set-cookie";
+
+/// Returns the session id sent by a client in the `cookie` headers of
+/// `metadata`, if any
+pub fn session_id(metadata: &MetadataMap) -> Option<String> {
+    metadata
+        .get_all(COOKIE_HEADER)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| {
+            let (name, value) = pair.trim().split_once('=')?;
+            (name == SESSION_COOKIE_NAME && !value.is_empty()).then(|| value.to_string())
+        })
+}
+
+/// Adds a `set-cookie` header to `metadata` instructing the client to use
+/// `id` as its session id for subsequent requests
+///
+/// Returns an error if `id` contains characters not permitted in a cookie value
+pub fn set_session_id(metadata: &mut MetadataMap, id: &str) -> Result<(), Status> {
+    if id.is_empty() || !id.bytes().all(is_cookie_octet) {
+        return Err(Status::internal(format!("Invalid session id: {id:?}")));
+    }
+    append_set_cookie(metadata, &format!("{SESSION_COOKIE_NAME}={id}"))
+}
+
+/// Adds a `set-cookie` header to `metadata` instructing the client to
+/// discard its session id, e.g. after the session has been closed
+pub fn expire_session_id(metadata: &mut MetadataMap) {
+    append_set_cookie(metadata, &format!("{SESSION_COOKIE_NAME}=; Max-Age=0"))
+        .expect("valid cookie")
+}
+
+/// Change to the client session id requested by a server
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum SessionCookie {
+    /// The client should use the given session id
+    Set(String),
+    /// The client should discard its session id
+    Expired,
+}
+
+/// Returns the last change to the session id requested by the `set-cookie`
+/// headers in `metadata`, if any
+pub(crate) fn session_cookie_from_set_cookie(metadata: &MetadataMap) -> Option<SessionCookie> {
+    metadata
+        .get_all(SET_COOKIE_HEADER)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .filter_map(|value| {
+            let mut parts = value.split(';');
+            let (name, id) = parts.next()?.trim().split_once('=')?;
+            if name != SESSION_COOKIE_NAME {
+                return None;
+            }
+            let expired = id.is_empty()
+                || parts.any(|attr| {
+                    attr.trim()
+                        .split_once('=')
+                        .is_some_and(|(k, v)| k.eq_ignore_ascii_case("max-age") && v.trim() == "0")
+                });
+            Some(match expired {
+                true => SessionCookie::Expired,
+                false => SessionCookie::Set(id.to_string()),
+            })
+        })
+        .next_back()
+}
+
+fn append_set_cookie(metadata: &mut MetadataMap, cookie: &str) -> Result<(), Status> {
+    let value = MetadataValue::try_from(cookie)
+        .map_err(|_| Status::internal(format!("Invalid cookie: {cookie:?}")))?;
+    metadata.append(SET_COOKIE_HEADER, value);
+    Ok(())
+}
+
+/// Characters allowed in a cookie value by [RFC 6265]
+///
+/// [RFC 6265]: https://datatracker.ietf.org/doc/html/rfc6265#section-4.1.1
+fn is_cookie_octet(b: u8) -> bool {
+    matches!(b, 0x21 | 0x23..=0x2B | 0x2D..=0x3A | 0x3C..=0x5B | 0x5D..=0x7E)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_id() {
+        let mut metadata = MetadataMap::new();
+        assert_eq!(session_id(&metadata), None);
+
+        metadata.append(COOKIE_HEADER, "foo=bar".parse().unwrap());
+        assert_eq!(session_id(&metadata), None);
+
+        metadata.append(
+            COOKIE_HEADER,
+            "a=b; arrow_flight_session_id=1234".parse().unwrap(),
+        );
+        assert_eq!(session_id(&metadata).as_deref(), Some("1234"));
+    }
+
+    #[test]
+    fn test_set_session_id() {
+        let mut metadata = MetadataMap::new();
+        assert_eq!(session_cookie_from_set_cookie(&metadata), None);
+
+        set_session_id(&mut metadata, "abc-123").unwrap();
+        assert_eq!(
+            metadata.get(SET_COOKIE_HEADER).unwrap(),
+            "arrow_flight_session_id=abc-123"
+        );
+        assert_eq!(
+            session_cookie_from_set_cookie(&metadata),
+            Some(SessionCookie::Set("abc-123".to_string()))
+        );
+
+        expire_session_id(&mut metadata);
+        assert_eq!(
+            session_cookie_from_set_cookie(&metadata),
+            Some(SessionCookie::Expired)
+        );
+
+        let err = set_session_id(&mut metadata, "a;b").unwrap_err();
+        assert_eq!(err.code(), tonic::Code::Internal);
+        set_session_id(&mut metadata, "").unwrap_err();
+    }
+}
@@ -17,7 +17,7 @@
 
 //! Utilities to assist with reading and writing Arrow data as Flight messages
 
-use crate::{FlightData, SchemaAsIpc};
+use crate::FlightData;
 use std::collections::HashMap;
 use std::sync::Arc;
 
@@ -25,10 +25,14 @@ use arrow_array::{ArrayRef, RecordBatch};
 use arrow_buffer::Buffer;
 use arrow_ipc::convert::fb_to_schema;
 use arrow_ipc::writer::CompressionContext;
-use arrow_ipc::{reader, root_as_message, writer, writer::IpcWriteOptions};
+use arrow_ipc::{MessageHeader, reader, root_as_message, writer, writer::IpcWriteOptions};
 use arrow_schema::{ArrowError, Schema, SchemaRef};
 
 /// Convert a slice of wire protocol `FlightData`s into a vector of `RecordBatch`es
+///
+/// The first `FlightData` must contain the schema. Dictionary batches, including
+/// delta dictionaries and replacements, are applied to the record batches that
+/// follow them, and a subsequent schema message resets the schema and dictionaries.
 pub fn flight_data_to_batches(flight_data: &[FlightData]) -> Result<Vec<RecordBatch>, ArrowError> {
     let schema = flight_data.first().ok_or_else(|| {
         ArrowError::CastError("Need at least one FlightData for schema".to_string())
@@ -40,17 +44,59 @@ pub fn flight_data_to_batches(flight_data: &[FlightData]) -> Result<Vec<RecordBa
         .header_as_schema()
         .ok_or_else(|| ArrowError::CastError("Cannot get header as Schema".to_string()))?;
     let schema = fb_to_schema(ipc_schema);
-    let schema = Arc::new(schema);
+    let mut schema = Arc::new(schema);
 
     let mut batches = vec![];
-    let dictionaries_by_id = HashMap::new();
+    let mut dictionaries_by_id = HashMap::new();
     for datum in flight_data[1..].iter() {
-        let batch = flight_data_to_arrow_batch(datum, schema.clone(), &dictionaries_by_id)?;
-        batches.push(batch);
+        let message = root_as_message(&datum.data_header[..]).map_err(|err| {
+            ArrowError::ParseError(format!("Unable to get root as message: {err:?}"))
+        })?;
+        match message.header_type() {
+            MessageHeader::Schema => {
+                schema = Arc::new(Schema::try_from(datum)?);
+                dictionaries_by_id.clear();
+            }
+            MessageHeader::DictionaryBatch => {
+                flight_data_to_dictionary(datum, &schema, &mut dictionaries_by_id)?;
+            }
+            _ => {
+                let batch = flight_data_to_arrow_batch(datum, schema.clone(), &dictionaries_by_id)?;
+                batches.push(batch);
+            }
+        }
     }
     Ok(batches)
 }
 
+/// Decode the dictionary batch in `FlightData` (with supplied schema) into
+/// `dictionaries_by_id`
+///
+/// A delta dictionary is appended to the existing dictionary with the same id,
+/// any other dictionary replaces it.
+pub fn flight_data_to_dictionary(
+    data: &FlightData,
+    schema: &Schema,
+    dictionaries_by_id: &mut HashMap<i64, ArrayRef>,
+) -> Result<(), ArrowError> {
+    let message = root_as_message(&data.data_header[..])
+        .map_err(|err| ArrowError::ParseError(format!("Unable to get root as message: {err:?}")))?;
+
+    let dictionary_batch = message.header_as_dictionary_batch().ok_or_else(|| {
+        ArrowError::ParseError(
+            "Unable to convert flight data header to a dictionary batch".to_string(),
+        )
+    })?;
+
+    reader::read_dictionary(
+        &Buffer::from(data.data_body.as_ref()),
+        dictionary_batch,
+        schema,
+        dictionaries_by_id,
+        &message.version(),
+    )
+}
+
 /// Convert `FlightData` (with supplied schema and dictionaries) to an arrow `RecordBatch`.
 pub fn flight_data_to_arrow_batch(
     data: &FlightData,
@@ -86,14 +132,16 @@ pub fn batches_to_flight_data(
     batches: Vec<RecordBatch>,
 ) -> Result<Vec<FlightData>, ArrowError> {
     let options = IpcWriteOptions::default();
-    let schema_flight_data: FlightData = SchemaAsIpc::new(schema, &options).into();
-    let mut dictionaries = vec![];
-    let mut flight_data = vec![];
-
     let data_gen = writer::IpcDataGenerator::default();
     let mut dictionary_tracker = writer::DictionaryTracker::new(false);
     let mut compression_context = CompressionContext::default();
 
+    // The schema must be encoded with the same tracker as the batches,
+    // so that the dictionary ids match
+    let schema_flight_data =
+        data_gen.schema_to_bytes_with_dictionary_tracker(schema, &mut dictionary_tracker, &options);
+    let mut flight_data = vec![schema_flight_data.into()];
+
     for batch in batches.iter() {
         let (encoded_dictionaries, encoded_batch) = data_gen.encode(
             batch,
@@ -102,15 +150,74 @@ pub fn batches_to_flight_data(
             &mut compression_context,
         )?;
 
-        dictionaries.extend(encoded_dictionaries.into_iter().map(Into::into));
+        // Dictionaries must precede the batch that uses them, as they
+        // may replace the dictionaries of earlier batches
+        flight_data.extend(encoded_dictionaries.into_iter().map(Into::into));
         flight_data.push(encoded_batch.into());
     }
 
-    let mut stream = Vec::with_capacity(1 + dictionaries.len() + flight_data.len());
-
-    stream.push(schema_flight_data);
-    stream.extend(dictionaries);
-    stream.extend(flight_data);
-    let flight_data = stream;
     Ok(flight_data)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::{DictionaryArray, types::Int32Type};
+    use arrow_ipc::writer::DictionaryHandling;
+
+    fn dictionary_batch(values: &[&str]) -> RecordBatch {
+        let array: DictionaryArray<Int32Type> = values.iter().copied().collect();
+        RecordBatch::try_from_iter([("d", Arc::new(array) as ArrayRef)]).unwrap()
+    }
+
+    #[test]
+    fn test_dictionary_replacement_roundtrip() {
+        let batches = vec![
+            dictionary_batch(&["a", "b", "a"]),
+            dictionary_batch(&["c", "d"]),
+        ];
+        let flight_data = batches_to_flight_data(&batches[0].schema(), batches.clone()).unwrap();
+        assert_eq!(flight_data_to_batches(&flight_data).unwrap(), batches);
+    }
+
+    #[test]
+    fn test_delta_dictionary() {
+        let batches = [
+            dictionary_batch(&["a", "b"]),
+            dictionary_batch(&["a", "b", "c"]),
+        ];
+        let schema = batches[0].schema();
+        let options =
+            IpcWriteOptions::default().with_dictionary_handling(DictionaryHandling::Delta);
+
+        let data_gen = writer::IpcDataGenerator::default();
+        let mut dictionary_tracker = writer::DictionaryTracker::new(false);
+        let mut compression_context = CompressionContext::default();
+        let schema_flight_data = data_gen.schema_to_bytes_with_dictionary_tracker(
+            &schema,
+            &mut dictionary_tracker,
+            &options,
+        );
+        let mut flight_data = vec![schema_flight_data.into()];
+        for batch in &batches {
+            let (dictionaries, batch) = data_gen
+                .encode(
+                    batch,
+                    &mut dictionary_tracker,
+                    &options,
+                    &mut compression_context,
+                )
+                .unwrap();
+            flight_data.extend(dictionaries.into_iter().map(FlightData::from));
+            flight_data.push(batch.into());
+        }
+
+        let mut dictionaries_by_id = HashMap::new();
+        flight_data_to_dictionary(&flight_data[3], &schema, &mut dictionaries_by_id).unwrap_err();
+        flight_data_to_dictionary(&flight_data[1], &schema, &mut dictionaries_by_id).unwrap();
+        flight_data_to_dictionary(&flight_data[3], &schema, &mut dictionaries_by_id).unwrap();
+        assert_eq!(dictionaries_by_id.values().next().unwrap().len(), 3);
+
+        assert_eq!(flight_data_to_batches(&flight_data).unwrap(), batches);
+    }
+}
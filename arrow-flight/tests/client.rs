@@ -25,7 +25,10 @@ use arrow_flight::{
     Action, ActionType, CancelFlightInfoRequest, CancelFlightInfoResult, CancelStatus, Criteria,
     Empty, FlightClient, FlightData, FlightDescriptor, FlightEndpoint, FlightInfo,
     HandshakeRequest, HandshakeResponse, PollInfo, PutResult, RenewFlightEndpointRequest, Ticket,
-    decode::FlightRecordBatchStream, encode::FlightDataEncoderBuilder, error::FlightError,
+    client::{FlightInfoFetcher, REUSE_CONNECTION_LOCATION},
+    decode::FlightRecordBatchStream,
+    encode::FlightDataEncoderBuilder,
+    error::FlightError,
 };
 use arrow_schema::{DataType, Field, Schema};
 use bytes::Bytes;
@@ -1110,6 +1113,167 @@ async fn test_flight_data2() -> Vec<FlightData> {
 }
 
 /// Runs the future returned by the function,  passing it a test server and client
+fn make_batch(values: impl IntoIterator<Item = u64>) -> RecordBatch {
+    RecordBatch::try_from_iter(vec![(
+        "col",
+        Arc::new(UInt64Array::from_iter_values(values)) as _,
+    )])
+    .unwrap()
+}
+
+#[tokio::test]
+async fn test_do_get_flight_info() {
+    // The server the client is connected to
+    let test_server = TestFlightServer::new();
+    let fixture = TestFixture::new(test_server.service()).await;
+    // A second server holding part of the data
+    let other_server = TestFlightServer::new();
+    let other_fixture = TestFixture::new(other_server.service()).await;
+    // A server that fails all DoGet requests
+    let failing_server = TestFlightServer::new();
+    let failing_fixture = TestFixture::new(failing_server.service()).await;
+
+    let mut client = FlightClient::new(fixture.channel().await);
+    client.add_header("foo-header", "bar-header-value").unwrap();
+
+    let batch1 = make_batch([1, 2, 3]);
+    let batch2 = make_batch([4, 5]);
+    let batch3 = make_batch([6]);
+    test_server.set_do_get_response(vec![Ok(batch1.clone())]);
+    other_server.set_do_get_response(vec![Ok(batch2.clone()), Ok(batch3.clone())]);
+
+    let info = FlightInfo::new()
+        .with_endpoint(
+            FlightEndpoint::new()
+                .with_ticket(Ticket::new("ticket 1"))
+                .with_location(REUSE_CONNECTION_LOCATION),
+        )
+        .with_endpoint(
+            // the first location fails, so the second should be used
+            FlightEndpoint::new()
+                .with_ticket(Ticket::new("ticket 2"))
+                .with_location(format!("grpc+tcp://{}", failing_fixture.addr))
+                .with_location(format!("grpc+tcp://{}", other_fixture.addr)),
+        )
+        .with_ordered(true);
+
+    let batches: Vec<_> = client
+        .do_get_flight_info(info)
+        .unwrap()
+        .try_collect()
+        .await
+        .expect("Error streaming data");
+    assert_eq!(batches, vec![batch1, batch2, batch3]);
+
+    assert_eq!(
+        test_server.take_do_get_request(),
+        Some(Ticket::new("ticket 1"))
+    );
+    assert_eq!(
+        failing_server.take_do_get_request(),
+        Some(Ticket::new("ticket 2"))
+    );
+    assert_eq!(
+        other_server.take_do_get_request(),
+        Some(Ticket::new("ticket 2"))
+    );
+    // metadata is sent to all locations
+    ensure_metadata(&client, &other_server);
+
+    fixture.shutdown_and_wait().await;
+    other_fixture.shutdown_and_wait().await;
+    failing_fixture.shutdown_and_wait().await;
+}
+
+#[tokio::test]
+async fn test_do_get_flight_info_unordered() {
+    let test_server = TestFlightServer::new();
+    let fixture = TestFixture::new(test_server.service()).await;
+    let other_server = TestFlightServer::new();
+    let other_fixture = TestFixture::new(other_server.service()).await;
+
+    let client = FlightClient::new(fixture.channel().await);
+
+    test_server.set_do_get_response(vec![Ok(make_batch([1, 2])), Ok(make_batch([3]))]);
+    other_server.set_do_get_response(vec![Ok(make_batch([4, 5, 6]))]);
+
+    let info = FlightInfo::new()
+        // endpoints without locations are fetched on the current connection
+        .with_endpoint(FlightEndpoint::new().with_ticket(Ticket::new("ticket 1")))
+        .with_endpoint(
+            FlightEndpoint::new()
+                .with_ticket(Ticket::new("ticket 2"))
+                .with_location(format!("http://{}", other_fixture.addr)),
+        );
+
+    let batches: Vec<_> = FlightInfoFetcher::new(&client)
+        .with_max_concurrency(2)
+        .fetch(info)
+        .unwrap()
+        .try_collect()
+        .await
+        .expect("Error streaming data");
+
+    let mut values: Vec<u64> = batches
+        .iter()
+        .flat_map(|batch| {
+            let array = batch.column(0).as_any().downcast_ref::<UInt64Array>();
+            array.unwrap().values().to_vec()
+        })
+        .collect();
+    values.sort_unstable();
+    assert_eq!(values, vec![1, 2, 3, 4, 5, 6]);
+
+    fixture.shutdown_and_wait().await;
+    other_fixture.shutdown_and_wait().await;
+}
+
+#[tokio::test]
+async fn test_do_get_flight_info_error() {
+    do_test(|test_server, client| async move {
+        // no response configured, so DoGet fails
+        let info = FlightInfo::new()
+            .with_endpoint(FlightEndpoint::new().with_ticket(Ticket::new("ticket")));
+        let err = client
+            .do_get_flight_info(info)
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap_err();
+        expect_status(err, Status::internal("No do_get response configured"));
+        assert_eq!(
+            test_server.take_do_get_request(),
+            Some(Ticket::new("ticket"))
+        );
+
+        // unsupported location
+        let info = FlightInfo::new().with_endpoint(
+            FlightEndpoint::new()
+                .with_ticket(Ticket::new("ticket"))
+                .with_location("grpc+unix:///tmp/flight.sock"),
+        );
+        let err = client
+            .do_get_flight_info(info)
+            .unwrap()
+            .try_collect::<Vec<_>>()
+            .await
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Not yet implemented: Unsupported location: grpc+unix:///tmp/flight.sock"
+        );
+
+        // missing ticket
+        let info = FlightInfo::new().with_endpoint(FlightEndpoint::new());
+        let err = client.do_get_flight_info(info).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Protocol error: FlightEndpoint is missing a ticket"
+        );
+    })
+    .await;
+}
+
 async fn do_test<F, Fut>(f: F)
 where
     F: Fn(TestFlightServer, FlightClient) -> Fut,
@@ -0,0 +1,285 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Integration test for the typed `DoExchange` helpers
+
+mod common;
+
+use crate::common::fixture::TestFixture;
+use crate::common::utils::make_primitive_batch;
+
+use std::sync::Arc;
+
+use arrow_array::{RecordBatch, UInt64Array};
+use arrow_flight::encode::FlightDataEncoderBuilder;
+use arrow_flight::error::FlightError;
+use arrow_flight::exchange::{ExchangeHandler, ExchangeRequest, ExchangeResponse, serve_exchange};
+use arrow_flight::flight_service_server::{FlightService, FlightServiceServer};
+use arrow_flight::{
+    Action, ActionType, Criteria, Empty, FlightClient, FlightData, FlightDescriptor, FlightInfo,
+    HandshakeRequest, HandshakeResponse, PollInfo, PutResult, SchemaResult, Ticket,
+};
+use bytes::Bytes;
+use futures::stream::{self, BoxStream};
+use futures::{StreamExt, TryStreamExt};
+use tonic::{Request, Response, Status, Streaming};
+
+/// Handles exchanges based on the command of the descriptor
+///
+/// - `echo`: returns every input batch, with the request app metadata
+/// - `count`: returns the total number of input rows once the input ends
+/// - anything else fails the call
+struct TestHandler;
+
+#[tonic::async_trait]
+impl ExchangeHandler for TestHandler {
+    async fn exchange(&self, request: ExchangeRequest) -> Result<ExchangeResponse, Status> {
+        match request.descriptor.cmd.as_ref() {
+            b"echo" => {
+                let encoder = FlightDataEncoderBuilder::new().with_metadata(request.app_metadata);
+                Ok(ExchangeResponse::new(request.input).with_encoder(encoder))
+            }
+            b"count" => {
+                let count = stream::once(async move {
+                    let batches: Vec<_> = request.input.try_collect().await?;
+                    let rows = batches.iter().map(|b| b.num_rows() as u64).sum::<u64>();
+                    let rows = Arc::new(UInt64Array::from(vec![rows])) as _;
+                    Ok::<_, FlightError>(RecordBatch::try_from_iter([("rows", rows)])?)
+                });
+                Ok(ExchangeResponse::new(count))
+            }
+            cmd => Err(Status::invalid_argument(format!(
+                "unknown command: {}",
+                String::from_utf8_lossy(cmd)
+            ))),
+        }
+    }
+}
+
+/// Flight service that only supports `DoExchange`
+struct ExchangeService {
+    handler: Arc<TestHandler>,
+}
+
+#[tonic::async_trait]
+impl FlightService for ExchangeService {
+    type HandshakeStream = BoxStream<'static, Result<HandshakeResponse, Status>>;
+    type ListFlightsStream = BoxStream<'static, Result<FlightInfo, Status>>;
+    type DoGetStream = BoxStream<'static, Result<FlightData, Status>>;
+    type DoPutStream = BoxStream<'static, Result<PutResult, Status>>;
+    type DoActionStream = BoxStream<'static, Result<arrow_flight::Result, Status>>;
+    type ListActionsStream = BoxStream<'static, Result<ActionType, Status>>;
+    type DoExchangeStream = BoxStream<'static, Result<FlightData, Status>>;
+
+    async fn handshake(
+        &self,
+        _request: Request<Streaming<HandshakeRequest>>,
+    ) -> Result<Response<Self::HandshakeStream>, Status> {
+        Err(Status::unimplemented("handshake"))
+    }
+
+    async fn list_flights(
+        &self,
+        _request: Request<Criteria>,
+    ) -> Result<Response<Self::ListFlightsStream>, Status> {
+        Err(Status::unimplemented("list_flights"))
+    }
+
+    async fn get_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<FlightInfo>, Status> {
+        Err(Status::unimplemented("get_flight_info"))
+    }
+
+    async fn poll_flight_info(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<PollInfo>, Status> {
+        Err(Status::unimplemented("poll_flight_info"))
+    }
+
+    async fn get_schema(
+        &self,
+        _request: Request<FlightDescriptor>,
+    ) -> Result<Response<SchemaResult>, Status> {
+        Err(Status::unimplemented("get_schema"))
+    }
+
+    async fn do_get(
+        &self,
+        _request: Request<Ticket>,
+    ) -> Result<Response<Self::DoGetStream>, Status> {
+        Err(Status::unimplemented("do_get"))
+    }
+
+    async fn do_put(
+        &self,
+        _request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoPutStream>, Status> {
+        Err(Status::unimplemented("do_put"))
+    }
+
+    async fn do_action(
+        &self,
+        _request: Request<Action>,
+    ) -> Result<Response<Self::DoActionStream>, Status> {
+        Err(Status::unimplemented("do_action"))
+    }
+
+    async fn list_actions(
+        &self,
+        _request: Request<Empty>,
+    ) -> Result<Response<Self::ListActionsStream>, Status> {
+        Err(Status::unimplemented("list_actions"))
+    }
+
+    async fn do_exchange(
+        &self,
+        request: Request<Streaming<FlightData>>,
+    ) -> Result<Response<Self::DoExchangeStream>, Status> {
+        Ok(serve_exchange(Arc::clone(&self.handler), request))
+    }
+}
+
+async fn setup() -> (TestFixture, FlightClient) {
+    let service = ExchangeService {
+        handler: Arc::new(TestHandler),
+    };
+    let fixture = TestFixture::new(FlightServiceServer::new(service)).await;
+    let client = FlightClient::new(fixture.channel().await);
+    (fixture, client)
+}
+
+#[tokio::test]
+async fn test_exchange_batches() {
+    let (fixture, mut client) = setup().await;
+
+    let input = vec![make_primitive_batch(5), make_primitive_batch(3)];
+    let output: Vec<_> = client
+        .exchange_batches(
+            FlightDescriptor::new_cmd("echo"),
+            stream::iter(input.clone()).map(Ok),
+        )
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(output, input);
+
+    // The handler can produce output after the input ends
+    let output: Vec<_> = client
+        .exchange_batches(
+            FlightDescriptor::new_cmd("count"),
+            stream::iter(input).map(Ok),
+        )
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    let rows = output[0].column(0).as_any().downcast_ref::<UInt64Array>();
+    assert_eq!(rows.unwrap().values(), &[8]);
+
+    fixture.shutdown_and_wait().await;
+}
+
+#[tokio::test]
+async fn test_exchange_app_metadata() {
+    let (fixture, mut client) = setup().await;
+
+    // The raw API can send app metadata with the descriptor
+    let descriptor = FlightData::new()
+        .with_descriptor(FlightDescriptor::new_cmd("echo"))
+        .with_app_metadata("request-metadata");
+    let input = FlightDataEncoderBuilder::new().build(stream::iter([Ok(make_primitive_batch(2))]));
+    let output: Vec<_> = client
+        .do_exchange(stream::once(async { Ok(descriptor) }).chain(input))
+        .await
+        .unwrap()
+        .into_inner()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(output.len(), 2);
+    assert_eq!(output[0].app_metadata(), Bytes::from("request-metadata"));
+
+    fixture.shutdown_and_wait().await;
+}
+
+#[tokio::test]
+async fn test_exchange_channel() {
+    let (fixture, mut client) = setup().await;
+
+    let (mut sender, mut output) = client
+        .exchange_channel(FlightDescriptor::new_cmd("echo"))
+        .await
+        .unwrap();
+
+    // Each batch is returned before the next is sent
+    for rows in [1, 4, 2] {
+        let batch = make_primitive_batch(rows);
+        sender.send(batch.clone()).await.unwrap();
+        assert_eq!(output.next().await.unwrap().unwrap(), batch);
+    }
+
+    // Closing the input ends the output
+    sender.finish();
+    assert!(output.next().await.is_none());
+
+    let (mut sender, output) = client
+        .exchange_channel(FlightDescriptor::new_cmd("count"))
+        .await
+        .unwrap();
+    sender.send(make_primitive_batch(3)).await.unwrap();
+    sender.send(make_primitive_batch(4)).await.unwrap();
+    drop(sender);
+    let output: Vec<_> = output.try_collect().await.unwrap();
+    let rows = output[0].column(0).as_any().downcast_ref::<UInt64Array>();
+    assert_eq!(rows.unwrap().values(), &[7]);
+
+    fixture.shutdown_and_wait().await;
+}
+
+#[tokio::test]
+async fn test_exchange_errors() {
+    let (fixture, mut client) = setup().await;
+
+    let (_sender, mut output) = client
+        .exchange_channel(FlightDescriptor::new_cmd("unknown"))
+        .await
+        .unwrap();
+    let err = output.next().await.unwrap().unwrap_err();
+    assert!(
+        err.to_string().contains("unknown command: unknown"),
+        "{err}"
+    );
+
+    // Client errors abort the call
+    let (mut sender, mut output) = client
+        .exchange_channel(FlightDescriptor::new_cmd("echo"))
+        .await
+        .unwrap();
+    sender.send(make_primitive_batch(1)).await.unwrap();
+    output.next().await.unwrap().unwrap();
+    sender.abort(FlightError::protocol("client failure")).await;
+    let err = output.next().await.unwrap().unwrap_err();
+    assert!(err.to_string().contains("client failure"), "{err}");
+
+    fixture.shutdown_and_wait().await;
+}
@@ -25,20 +25,26 @@ use arrow_flight::decode::FlightRecordBatchStream;
 use arrow_flight::encode::FlightDataEncoderBuilder;
 use arrow_flight::error::FlightError;
 use arrow_flight::flight_service_server::FlightServiceServer;
+use arrow_flight::set_session_options_result::{Error as SetSessionOptionError, ErrorValue};
 use arrow_flight::sql::client::FlightSqlServiceClient;
 use arrow_flight::sql::server::{FlightSqlService, PeekableFlightDataStream};
+use arrow_flight::sql::session::{expire_session_id, session_id, set_session_id};
 use arrow_flight::sql::{
     ActionBeginTransactionRequest, ActionBeginTransactionResult, ActionEndTransactionRequest,
     CommandStatementIngest, EndTransaction, FallibleRequestStream, ProstMessageExt, SqlInfo,
     TableDefinitionOptions, TableExistsOption, TableNotExistOption,
 };
-use arrow_flight::{Action, FlightData, FlightDescriptor};
+use arrow_flight::{
+    Action, CloseSessionRequest, CloseSessionResult, FlightData, FlightDescriptor,
+    GetSessionOptionsRequest, GetSessionOptionsResult, SessionOptionValue,
+    SetSessionOptionsRequest, SetSessionOptionsResult, close_session_result,
+};
 use futures::{StreamExt, TryStreamExt};
 use prost::Message;
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
-use tonic::{IntoStreamingRequest, Request, Status};
+use tonic::{IntoStreamingRequest, Request, Response, Status};
 use uuid::Uuid;
 
 #[tokio::test]
@@ -72,6 +78,66 @@ pub async fn test_begin_end_transaction() {
     );
 }
 
+#[tokio::test]
+pub async fn test_session_options() {
+    let test_server = FlightSqlServiceImpl::new();
+    let fixture = TestFixture::new(test_server.service()).await;
+    let channel = fixture.channel().await;
+    let mut flight_sql_client = FlightSqlServiceClient::new(channel);
+
+    // no session yet
+    assert!(flight_sql_client.get_session_options().await.is_err());
+
+    // setting options implicitly creates a session
+    let options = HashMap::from([
+        ("catalog".to_string(), SessionOptionValue::from("sales")),
+        ("read_only".to_string(), SessionOptionValue::from(true)),
+        ("bogus".to_string(), SessionOptionValue::from(1_i64)),
+    ]);
+    let result = flight_sql_client
+        .set_session_options(options)
+        .await
+        .unwrap();
+    assert_eq!(result.errors.len(), 1);
+    assert_eq!(result.errors["bogus"].value(), ErrorValue::InvalidName);
+    let session_id = flight_sql_client.session_id().cloned().unwrap();
+
+    // a second client has its own session
+    let mut other_client = FlightSqlServiceClient::new(fixture.channel().await);
+    let options = HashMap::from([("catalog".to_string(), "other".into())]);
+    other_client.set_session_options(options).await.unwrap();
+    assert_ne!(other_client.session_id(), Some(&session_id));
+
+    // the existing session is updated, and unset options are removed
+    let options = HashMap::from([
+        ("schema".to_string(), "public".into()),
+        ("read_only".to_string(), SessionOptionValue::unset()),
+    ]);
+    flight_sql_client
+        .set_session_options(options)
+        .await
+        .unwrap();
+    assert_eq!(flight_sql_client.session_id(), Some(&session_id));
+
+    let options = flight_sql_client.get_session_options().await.unwrap();
+    let expected = HashMap::from([
+        ("catalog".to_string(), SessionOptionValue::from("sales")),
+        ("schema".to_string(), SessionOptionValue::from("public")),
+    ]);
+    assert_eq!(options, expected);
+
+    // closing the session clears the session id
+    let status = flight_sql_client.close_session().await.unwrap();
+    assert_eq!(status, close_session_result::Status::Closed);
+    assert_eq!(flight_sql_client.session_id(), None);
+    assert_eq!(test_server.sessions.lock().await.len(), 1);
+
+    // the old session can no longer be used
+    flight_sql_client.set_session_id(session_id);
+    let err = flight_sql_client.get_session_options().await.unwrap_err();
+    assert!(err.to_string().contains("Session not found"), "{err}");
+}
+
 #[tokio::test]
 pub async fn test_execute_ingest() {
     let test_server = FlightSqlServiceImpl::new();
@@ -225,6 +291,7 @@ fn make_ingest_command() -> CommandStatementIngest {
 pub struct FlightSqlServiceImpl {
     transactions: Arc<Mutex<HashMap<String, ()>>>,
     ingested_batches: Arc<Mutex<Vec<RecordBatch>>>,
+    sessions: Arc<Mutex<HashMap<String, HashMap<String, SessionOptionValue>>>>,
 }
 
 impl FlightSqlServiceImpl {
@@ -232,6 +299,7 @@ impl FlightSqlServiceImpl {
         Self {
             transactions: Arc::new(Mutex::new(HashMap::new())),
             ingested_batches: Arc::new(Mutex::new(Vec::new())),
+            sessions: Arc::new(Mutex::new(HashMap::new())),
         }
     }
 
@@ -287,6 +355,72 @@ impl FlightSqlService for FlightSqlServiceImpl {
         Ok(())
     }
 
+    async fn do_action_set_session_options(
+        &self,
+        query: SetSessionOptionsRequest,
+        request: Request<Action>,
+    ) -> Result<Response<SetSessionOptionsResult>, Status> {
+        let mut response = Response::new(SetSessionOptionsResult::default());
+        let mut sessions = self.sessions.lock().await;
+        let id = match session_id(request.metadata()) {
+            Some(id) if sessions.contains_key(&id) => id,
+            Some(_) => return Err(Status::not_found("Session not found")),
+            None => {
+                let id = Uuid::new_v4().to_string();
+                set_session_id(response.metadata_mut(), &id)?;
+                id
+            }
+        };
+        let session = sessions.entry(id).or_default();
+        for (name, value) in query.session_options {
+            if !["catalog", "schema", "read_only"].contains(&name.as_str()) {
+                let error = SetSessionOptionError {
+                    value: ErrorValue::InvalidName.into(),
+                };
+                response.get_mut().errors.insert(name, error);
+            } else if value.option_value.is_none() {
+                session.remove(&name);
+            } else {
+                session.insert(name, value);
+            }
+        }
+        Ok(response)
+    }
+
+    async fn do_action_get_session_options(
+        &self,
+        _query: GetSessionOptionsRequest,
+        request: Request<Action>,
+    ) -> Result<Response<GetSessionOptionsResult>, Status> {
+        let id =
+            session_id(request.metadata()).ok_or_else(|| Status::not_found("Session not found"))?;
+        let session_options = self
+            .sessions
+            .lock()
+            .await
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| Status::not_found("Session not found"))?;
+        Ok(Response::new(GetSessionOptionsResult { session_options }))
+    }
+
+    async fn do_action_close_session(
+        &self,
+        _query: CloseSessionRequest,
+        request: Request<Action>,
+    ) -> Result<Response<CloseSessionResult>, Status> {
+        let id =
+            session_id(request.metadata()).ok_or_else(|| Status::not_found("Session not found"))?;
+        if self.sessions.lock().await.remove(&id).is_none() {
+            return Err(Status::not_found("Session not found"));
+        }
+        let mut response = Response::new(CloseSessionResult::new(
+            close_session_result::Status::Closed,
+        ));
+        expire_session_id(response.metadata_mut());
+        Ok(response)
+    }
+
     async fn register_sql_info(&self, _id: i32, _result: &SqlInfo) {}
 
     async fn do_put_statement_ingest(
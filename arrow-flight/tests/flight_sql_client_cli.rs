@@ -28,10 +28,11 @@ use arrow_flight::{
     encode::FlightDataEncoderBuilder,
     flight_service_server::{FlightService, FlightServiceServer},
     sql::{
-        ActionCreatePreparedStatementRequest, ActionCreatePreparedStatementResult, Any,
-        CommandGetCatalogs, CommandGetDbSchemas, CommandGetTableTypes, CommandGetTables,
-        CommandPreparedStatementQuery, CommandStatementQuery, DoPutPreparedStatementResult,
-        ProstMessageExt, SqlInfo,
+        ActionBeginTransactionRequest, ActionBeginTransactionResult,
+        ActionCreatePreparedStatementRequest, ActionCreatePreparedStatementResult,
+        ActionEndTransactionRequest, Any, CommandGetCatalogs, CommandGetDbSchemas,
+        CommandGetTableTypes, CommandGetTables, CommandPreparedStatementQuery,
+        CommandStatementQuery, DoPutPreparedStatementResult, ProstMessageExt, SqlInfo,
         server::{FlightSqlService, PeekableFlightDataStream},
     },
     utils::batches_to_flight_data,
@@ -345,6 +346,187 @@ pub async fn test_do_put_prepared_statement_stateful() {
     .await
 }
 
+#[cfg(feature = "cli-formats")]
+#[tokio::test]
+async fn test_output_formats() {
+    let test_server = FlightSqlServiceImpl::default();
+    let fixture = TestFixture::new(test_server.service()).await;
+    let addr = fixture.addr;
+
+    let run = move |format: &'static str| {
+        tokio::task::spawn_blocking(move || {
+            let stdout = flight_sql_client_cmd()
+                .env_clear()
+                .env("RUST_BACKTRACE", "1")
+                .env("RUST_LOG", "warn")
+                .arg("--host")
+                .arg(addr.ip().to_string())
+                .arg("--port")
+                .arg(addr.port().to_string())
+                .arg("--format")
+                .arg(format)
+                .arg("statement-query")
+                .arg(QUERY)
+                .assert()
+                .success()
+                .get_output()
+                .stdout
+                .clone();
+            String::from_utf8(stdout).unwrap()
+        })
+    };
+
+    let csv = run("csv").await.unwrap();
+    assert_eq!(
+        csv.lines().collect::<Vec<_>>(),
+        [
+            "field_string,field_int,field_timestamp_nano_notz,field_timestamp_nano_berlin",
+            "Hello,42,,",
+            "lovely,,1970-01-01T00:00:00,1970-01-01T01:00:00+01:00",
+            "FlightSQL!,1337,2024-10-30T11:36:57,2024-10-30T12:36:57+01:00",
+        ]
+    );
+
+    let ndjson = run("ndjson").await.unwrap();
+    assert_eq!(ndjson.lines().count(), 3);
+    assert!(
+        ndjson.starts_with(r#"{"field_string":"Hello","field_int":42}"#),
+        "{ndjson}"
+    );
+
+    let json = run("json").await.unwrap();
+    assert!(json.starts_with(r#"[{"field_string":"Hello""#), "{json}");
+    assert!(json.trim_end().ends_with(']'), "{json}");
+
+    fixture.shutdown_and_wait().await;
+}
+
+#[cfg(all(feature = "cli-formats", feature = "cli-repl"))]
+#[tokio::test]
+async fn test_ingest_and_repl() {
+    let server = arrow_flight::sql::memory::MemoryFlightSqlServer::new();
+    let fixture = TestFixture::new(server.service()).await;
+    let addr = fixture.addr;
+
+    let dir = tempfile::tempdir().unwrap();
+    let csv = dir.path().join("data.csv");
+    std::fs::write(&csv, "id,name\n1,one\n2,two\n3,three\n").unwrap();
+
+    let stdout = tokio::task::spawn_blocking(move || {
+        flight_sql_client_cmd()
+            .env_clear()
+            .env("RUST_BACKTRACE", "1")
+            .env("RUST_LOG", "warn")
+            .arg("--host")
+            .arg(addr.ip().to_string())
+            .arg("--port")
+            .arg(addr.port().to_string())
+            .arg("ingest")
+            .arg("numbers")
+            .arg(&csv)
+            .assert()
+            .success()
+            .get_output()
+            .stdout
+            .clone()
+    })
+    .await
+    .unwrap();
+    assert_eq!(std::str::from_utf8(&stdout).unwrap().trim(), "3");
+    assert_eq!(server.table("numbers").unwrap().num_rows(), 3);
+
+    let output = tokio::task::spawn_blocking(move || {
+        flight_sql_client_cmd()
+            .env_clear()
+            .env("RUST_BACKTRACE", "1")
+            .env("RUST_LOG", "warn")
+            .arg("--host")
+            .arg(addr.ip().to_string())
+            .arg("--port")
+            .arg(addr.port().to_string())
+            .arg("repl")
+            .write_stdin(
+                "SELECT * FROM numbers;\n\
+                 \\format csv\n\
+                 \\timing\n\
+                 SELECT * FROM numbers\n\
+                 \\commit\n\
+                 SELECT * FROM missing\n\
+                 \\q\n\
+                 SELECT * FROM numbers\n",
+            )
+            .assert()
+            .success()
+            .get_output()
+            .clone()
+    })
+    .await
+    .unwrap();
+
+    fixture.shutdown_and_wait().await;
+
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(
+        stdout.lines().collect::<Vec<_>>(),
+        [
+            "+----+-------+",
+            "| id | name  |",
+            "+----+-------+",
+            "| 1  | one   |",
+            "| 2  | two   |",
+            "| 3  | three |",
+            "+----+-------+",
+            "Timing is on",
+            "id,name",
+            "1,one",
+            "2,two",
+            "3,three",
+        ]
+    );
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("3 row(s) in "), "{stderr}");
+    assert!(
+        stderr.contains("Error: no transaction in progress"),
+        "{stderr}"
+    );
+    assert!(stderr.contains("Table not found: missing"), "{stderr}");
+}
+
+#[cfg(feature = "cli-repl")]
+#[tokio::test]
+async fn test_repl_failed_commit() {
+    let test_server = FlightSqlServiceImpl::default();
+    let fixture = TestFixture::new(test_server.service()).await;
+    let addr = fixture.addr;
+
+    let output = tokio::task::spawn_blocking(move || {
+        flight_sql_client_cmd()
+            .env_clear()
+            .env("RUST_BACKTRACE", "1")
+            .env("RUST_LOG", "warn")
+            .arg("--host")
+            .arg(addr.ip().to_string())
+            .arg("--port")
+            .arg(addr.port().to_string())
+            .arg("repl")
+            .write_stdin("\\begin\n\\commit\n\\q\n")
+            .assert()
+            .failure()
+            .get_output()
+            .clone()
+    })
+    .await
+    .unwrap();
+
+    fixture.shutdown_and_wait().await;
+
+    // The transaction is still open after the failed commit, and so is rolled back on exit
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("Error: end transaction"), "{stderr}");
+    assert!(stderr.contains("Rolling back open transaction"), "{stderr}");
+    assert!(stderr.contains("roll back transaction"), "{stderr}");
+}
+
 #[derive(Clone)]
 pub struct FlightSqlServiceImpl {
     /// Whether to emulate stateless (true) or stateful (false) behavior for
@@ -750,6 +932,24 @@ impl FlightSqlService for FlightSqlServiceImpl {
             .map_err(|e| Status::internal(format!("Unable to serialize schema: {e}")))
     }
 
+    async fn do_action_begin_transaction(
+        &self,
+        _query: ActionBeginTransactionRequest,
+        _request: Request<Action>,
+    ) -> Result<ActionBeginTransactionResult, Status> {
+        Ok(ActionBeginTransactionResult {
+            transaction_id: "transaction".into(),
+        })
+    }
+
+    async fn do_action_end_transaction(
+        &self,
+        _query: ActionEndTransactionRequest,
+        _request: Request<Action>,
+    ) -> Result<(), Status> {
+        Err(Status::unavailable("cannot end transactions"))
+    }
+
     async fn register_sql_info(&self, _id: i32, _result: &SqlInfo) {}
 }
 
@@ -0,0 +1,382 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tests for [`MemoryFlightSqlServer`] using [`FlightSqlServiceClient`]
+
+mod common;
+
+use crate::common::fixture::TestFixture;
+use crate::common::utils::make_primitive_batch;
+
+use std::sync::Arc;
+
+use arrow_array::{Int64Array, RecordBatch};
+use arrow_cast::pretty::pretty_format_batches;
+use arrow_flight::FlightInfo;
+use arrow_flight::sql::client::FlightSqlServiceClient;
+use arrow_flight::sql::memory::{MemoryFlightSqlServer, MemoryTable, QueryHandler};
+use arrow_flight::sql::{
+    CommandGetDbSchemas, CommandGetPrimaryKeys, CommandGetTables, CommandStatementIngest, SqlInfo,
+    TableDefinitionOptions, TableExistsOption, TableNotExistOption,
+};
+use arrow_schema::SchemaRef;
+use arrow_select::filter::filter_record_batch;
+use futures::{TryStreamExt, stream};
+use tonic::Status;
+use tonic::transport::Channel;
+
+async fn setup(server: &MemoryFlightSqlServer) -> (TestFixture, FlightSqlServiceClient<Channel>) {
+    let fixture = TestFixture::new(server.service()).await;
+    let client = FlightSqlServiceClient::new(fixture.channel().await);
+    (fixture, client)
+}
+
+/// Retrieve the results of `info` as a pretty printed table
+async fn fetch(client: &mut FlightSqlServiceClient<Channel>, info: FlightInfo) -> String {
+    let mut batches = vec![];
+    for endpoint in info.endpoint {
+        let ticket = endpoint.ticket.unwrap();
+        let stream = client.do_get(ticket).await.unwrap();
+        batches.extend(stream.try_collect::<Vec<_>>().await.unwrap());
+    }
+    pretty_format_batches(&batches).unwrap().to_string()
+}
+
+fn assert_table(actual: String, expected: &[&str]) {
+    let actual: Vec<_> = actual.lines().collect();
+    assert_eq!(actual, expected, "\n\nactual:\n{}\n", actual.join("\n"));
+}
+
+#[tokio::test]
+async fn test_statement_query() {
+    let server = MemoryFlightSqlServer::new();
+    server.register_table("numbers", MemoryTable::from(make_primitive_batch(3)));
+    let (fixture, mut client) = setup(&server).await;
+
+    let info = client
+        .execute("SELECT * FROM memory.public.numbers".to_string(), None)
+        .await
+        .unwrap();
+    // Queries are only executed when their results are retrieved
+    assert_eq!(info.total_records, -1);
+    assert_table(
+        fetch(&mut client, info).await,
+        &[
+            "+---+-----+",
+            "| i | f   |",
+            "+---+-----+",
+            "| 0 | 3.0 |",
+            "|   |     |",
+            "| 2 | 1.0 |",
+            "+---+-----+",
+        ],
+    );
+
+    let err = client
+        .execute("SELECT * FROM missing".to_string(), None)
+        .await
+        .unwrap_err();
+    assert!(
+        err.to_string().contains("Table not found: missing"),
+        "{err}"
+    );
+
+    let err = client
+        .execute("DELETE FROM numbers".to_string(), None)
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Unsupported query"), "{err}");
+
+    fixture.shutdown_and_wait().await;
+}
+
+#[tokio::test]
+async fn test_metadata() {
+    let server = MemoryFlightSqlServer::new();
+    let batch = make_primitive_batch(1);
+    let table = MemoryTable::from(batch).with_primary_key(["i"]).unwrap();
+    server.register_table("a", table.clone());
+    server.register_table("other.b", table.clone());
+    server.register_table("cat.other.c", table);
+    assert_eq!(
+        server.table_names(),
+        ["cat.other.c", "memory.other.b", "memory.public.a"]
+    );
+    let (fixture, mut client) = setup(&server).await;
+
+    let info = client.get_catalogs().await.unwrap();
+    assert_table(
+        fetch(&mut client, info).await,
+        &[
+            "+--------------+",
+            "| catalog_name |",
+            "+--------------+",
+            "| cat          |",
+            "| memory       |",
+            "+--------------+",
+        ],
+    );
+
+    let info = client
+        .get_db_schemas(CommandGetDbSchemas {
+            catalog: Some("memory".to_string()),
+            db_schema_filter_pattern: None,
+        })
+        .await
+        .unwrap();
+    assert_table(
+        fetch(&mut client, info).await,
+        &[
+            "+--------------+----------------+",
+            "| catalog_name | db_schema_name |",
+            "+--------------+----------------+",
+            "| memory       | other          |",
+            "| memory       | public         |",
+            "+--------------+----------------+",
+        ],
+    );
+
+    let info = client
+        .get_tables(CommandGetTables {
+            db_schema_filter_pattern: Some("other".to_string()),
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    assert_table(
+        fetch(&mut client, info).await,
+        &[
+            "+--------------+----------------+------------+------------+",
+            "| catalog_name | db_schema_name | table_name | table_type |",
+            "+--------------+----------------+------------+------------+",
+            "| cat          | other          | c          | TABLE      |",
+            "| memory       | other          | b          | TABLE      |",
+            "+--------------+----------------+------------+------------+",
+        ],
+    );
+
+    let info = client.get_table_types().await.unwrap();
+    assert_table(
+        fetch(&mut client, info).await,
+        &[
+            "+------------+",
+            "| table_type |",
+            "+------------+",
+            "| TABLE      |",
+            "+------------+",
+        ],
+    );
+
+    let info = client
+        .get_primary_keys(CommandGetPrimaryKeys {
+            catalog: None,
+            db_schema: Some("other".to_string()),
+            table: "b".to_string(),
+        })
+        .await
+        .unwrap();
+    assert_table(
+        fetch(&mut client, info).await,
+        &[
+            "+--------------+----------------+------------+-------------+----------+--------------+",
+            "| catalog_name | db_schema_name | table_name | column_name | key_name | key_sequence |",
+            "+--------------+----------------+------------+-------------+----------+--------------+",
+            "| memory       | other          | b          | i           |          | 1            |",
+            "+--------------+----------------+------------+-------------+----------+--------------+",
+        ],
+    );
+
+    let info = client
+        .get_sql_info(vec![SqlInfo::FlightSqlServerReadOnly])
+        .await
+        .unwrap();
+    assert_table(
+        fetch(&mut client, info).await,
+        &[
+            "+-----------+--------------------+",
+            "| info_name | value              |",
+            "+-----------+--------------------+",
+            "| 3         | {bool_value=false} |",
+            "+-----------+--------------------+",
+        ],
+    );
+
+    let info = client.get_xdbc_type_info(Default::default()).await.unwrap();
+    assert_eq!(info.endpoint.len(), 1);
+    let types = fetch(&mut client, info).await;
+    assert!(types.contains("BIGINT"), "{types}");
+
+    fixture.shutdown_and_wait().await;
+}
+
+#[tokio::test]
+async fn test_ingest() {
+    let server = MemoryFlightSqlServer::new();
+    let (fixture, mut client) = setup(&server).await;
+
+    let ingest =
+        |if_not_exist: TableNotExistOption, if_exists: TableExistsOption| CommandStatementIngest {
+            table_definition_options: Some(TableDefinitionOptions {
+                if_not_exist: if_not_exist.into(),
+                if_exists: if_exists.into(),
+            }),
+            table: "ingested".to_string(),
+            schema: Some("staging".to_string()),
+            ..Default::default()
+        };
+    let batches = || {
+        stream::iter(vec![
+            Ok(make_primitive_batch(2)),
+            Ok(make_primitive_batch(3)),
+        ])
+    };
+
+    let err = client
+        .execute_ingest(
+            ingest(TableNotExistOption::Fail, TableExistsOption::Fail),
+            batches(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Table not found"), "{err}");
+
+    let count = client
+        .execute_ingest(
+            ingest(TableNotExistOption::Create, TableExistsOption::Fail),
+            batches(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(count, 5);
+    assert_eq!(server.table("staging.ingested").unwrap().num_rows(), 5);
+
+    let err = client
+        .execute_ingest(
+            ingest(TableNotExistOption::Create, TableExistsOption::Fail),
+            batches(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Table already exists"), "{err}");
+
+    client
+        .execute_ingest(
+            ingest(TableNotExistOption::Create, TableExistsOption::Append),
+            batches(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(server.table("staging.ingested").unwrap().num_rows(), 10);
+
+    client
+        .execute_ingest(
+            ingest(TableNotExistOption::Create, TableExistsOption::Replace),
+            stream::iter(vec![Ok(make_primitive_batch(1))]),
+        )
+        .await
+        .unwrap();
+    assert_eq!(server.table("staging.ingested").unwrap().num_rows(), 1);
+
+    fixture.shutdown_and_wait().await;
+}
+
+/// Supports `SELECT * FROM <table> WHERE i = ?`, with the parameter bound to column `i`
+struct FilterQueryHandler;
+
+impl FilterQueryHandler {
+    fn table(server: &MemoryFlightSqlServer, query: &str) -> Result<MemoryTable, Status> {
+        let name = query
+            .strip_prefix("SELECT * FROM ")
+            .and_then(|q| q.strip_suffix(" WHERE i = ?"))
+            .ok_or_else(|| Status::unimplemented(query.to_string()))?;
+        server
+            .table(name)
+            .ok_or_else(|| Status::not_found(name.to_string()))
+    }
+}
+
+impl QueryHandler for FilterQueryHandler {
+    fn schema(&self, server: &MemoryFlightSqlServer, query: &str) -> Result<SchemaRef, Status> {
+        Ok(Self::table(server, query)?.schema().clone())
+    }
+
+    fn execute(
+        &self,
+        server: &MemoryFlightSqlServer,
+        query: &str,
+        params: Option<&RecordBatch>,
+    ) -> Result<MemoryTable, Status> {
+        let table = Self::table(server, query)?;
+        let params = params.ok_or_else(|| Status::invalid_argument("missing parameter"))?;
+        let value = params
+            .column(0)
+            .as_any()
+            .downcast_ref::<Int64Array>()
+            .ok_or_else(|| Status::invalid_argument("expected Int64 parameter"))?
+            .value(0);
+        let batches = table
+            .batches()
+            .iter()
+            .map(|batch| {
+                let i = arrow_cast::cast(batch.column(0), &arrow_schema::DataType::Int64)?;
+                let i = i.as_any().downcast_ref::<Int64Array>().unwrap();
+                let predicate = arrow_ord::cmp::eq(i, &Int64Array::new_scalar(value))?;
+                filter_record_batch(batch, &predicate)
+            })
+            .collect::<Result<_, _>>()
+            .map_err(|e| Status::internal(e.to_string()))?;
+        MemoryTable::try_new(table.schema().clone(), batches)
+            .map_err(|e| Status::internal(e.to_string()))
+    }
+}
+
+#[tokio::test]
+async fn test_prepared_statement() {
+    let server = MemoryFlightSqlServer::new().with_query_handler(FilterQueryHandler);
+    server.register_table("numbers", MemoryTable::from(make_primitive_batch(5)));
+    let (fixture, mut client) = setup(&server).await;
+
+    let mut statement = client
+        .prepare("SELECT * FROM numbers WHERE i = ?".to_string(), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        statement.dataset_schema().unwrap(),
+        make_primitive_batch(5).schema().as_ref()
+    );
+
+    let params =
+        RecordBatch::try_from_iter([("$1", Arc::new(Int64Array::from(vec![3])) as _)]).unwrap();
+    statement.set_parameters(params).unwrap();
+    let info = statement.execute().await.unwrap();
+    assert_table(
+        fetch(&mut client, info).await,
+        &[
+            "+---+-----+",
+            "| i | f   |",
+            "+---+-----+",
+            "| 3 | 2.0 |",
+            "+---+-----+",
+        ],
+    );
+
+    statement.close().await.unwrap();
+    assert!(server.deregister_table("numbers").is_some());
+    assert!(server.table("numbers").is_none());
+
+    fixture.shutdown_and_wait().await;
+}
@@ -0,0 +1,259 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Integration test for server middleware and client interceptors
+
+mod common;
+
+use crate::common::fixture::TestFixture;
+use crate::common::utils::make_primitive_batch;
+
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use arrow_flight::error::FlightError;
+use arrow_flight::flight_service_server::FlightServiceServer;
+use arrow_flight::middleware::{
+    AuthInterceptor, AuthMiddleware, AuthenticatedPrincipal, CallMetrics, FlightMethod,
+    FlightServerMiddleware, MethodMetrics, MetricsMiddleware, MiddlewareFlightService,
+};
+use arrow_flight::{FlightClient, FlightDescriptor, FlightInfo, Ticket};
+use common::server::TestFlightServer;
+use futures::TryStreamExt;
+use tonic::metadata::MetadataMap;
+use tonic::{Code, Extensions, Status};
+
+/// Method, principal and error code of a completed call
+type RecordedCall = (FlightMethod, Option<String>, Option<Code>);
+
+/// Records every completed call
+#[derive(Debug, Clone, Default)]
+struct RecordingMiddleware {
+    calls: Arc<Mutex<Vec<RecordedCall>>>,
+}
+
+impl FlightServerMiddleware for RecordingMiddleware {
+    fn on_complete(
+        &self,
+        method: FlightMethod,
+        extensions: &Extensions,
+        _metrics: &CallMetrics,
+        status: Option<&Status>,
+    ) {
+        let principal = extensions
+            .get::<AuthenticatedPrincipal>()
+            .map(|p| p.0.clone());
+        let code = status.map(|s| s.code());
+        self.calls.lock().unwrap().push((method, principal, code));
+    }
+}
+
+async fn setup(
+    test_server: &TestFlightServer,
+    metrics: &MetricsMiddleware,
+    recording: &RecordingMiddleware,
+) -> (TestFixture, FlightClient) {
+    let auth = AuthMiddleware::new()
+        .with_bearer(|token| match token {
+            "secret" => Ok("admin".to_string()),
+            _ => Err(Status::unauthenticated("invalid token")),
+        })
+        .with_unauthenticated_method(FlightMethod::ListActions);
+    let service = MiddlewareFlightService::new(test_server.clone())
+        .with_middleware(metrics.clone())
+        .with_middleware(recording.clone())
+        .with_middleware(auth);
+    let fixture = TestFixture::new(FlightServiceServer::new(service)).await;
+    let client = FlightClient::new(fixture.channel().await);
+    (fixture, client)
+}
+
+async fn list_actions(test_server: &TestFlightServer, client: &mut FlightClient) {
+    test_server.set_list_actions_response(vec![]);
+    let actions: Vec<_> = client
+        .list_actions()
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert!(actions.is_empty());
+}
+
+/// Completion of streaming calls is observed asynchronously on the server
+async fn wait_for_calls(metrics: &MetricsMiddleware, method: FlightMethod) -> MethodMetrics {
+    for _ in 0..100 {
+        let m = metrics.get(method);
+        if m.calls > 0 {
+            return m;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+    panic!("no completed {method} calls")
+}
+
+#[tokio::test]
+async fn test_auth() {
+    let test_server = TestFlightServer::new();
+    let metrics = MetricsMiddleware::new();
+    let recording = RecordingMiddleware::default();
+    let (fixture, mut client) = setup(&test_server, &metrics, &recording).await;
+
+    let descriptor = FlightDescriptor::new_path(vec!["foo".to_string()]);
+    let err = client
+        .get_flight_info(descriptor.clone())
+        .await
+        .unwrap_err();
+    let FlightError::Tonic(status) = err else {
+        panic!("unexpected error: {err}")
+    };
+    assert_eq!(status.code(), Code::Unauthenticated);
+    assert_eq!(status.message(), "missing authorization header");
+    assert!(test_server.take_get_flight_info_request().is_none());
+
+    // Does not require authentication
+    list_actions(&test_server, &mut client).await;
+    wait_for_calls(&metrics, FlightMethod::ListActions).await;
+
+    client.add_interceptor(AuthInterceptor::bearer("secret").unwrap());
+    test_server.set_get_flight_info_response(Ok(FlightInfo::new()));
+    client.get_flight_info(descriptor.clone()).await.unwrap();
+    let metadata = test_server.take_last_request_metadata().unwrap();
+    assert_eq!(metadata.get("authorization").unwrap(), "Bearer secret");
+
+    assert_eq!(
+        *recording.calls.lock().unwrap(),
+        [
+            (
+                FlightMethod::GetFlightInfo,
+                None,
+                Some(Code::Unauthenticated)
+            ),
+            (FlightMethod::ListActions, None, None),
+            (FlightMethod::GetFlightInfo, Some("admin".to_string()), None),
+        ]
+    );
+    let get_flight_info = metrics.get(FlightMethod::GetFlightInfo);
+    assert_eq!(get_flight_info.calls, 2);
+    assert_eq!(get_flight_info.errors, 1);
+    assert_eq!(get_flight_info.messages_sent, 1);
+
+    fixture.shutdown_and_wait().await;
+}
+
+#[tokio::test]
+async fn test_rejected_call() {
+    let test_server = TestFlightServer::new();
+    let recording = RecordingMiddleware::default();
+    let auth = AuthMiddleware::new().with_bearer(|_| Ok("admin".to_string()));
+    let service = MiddlewareFlightService::new(test_server.clone())
+        .with_middleware(auth)
+        .with_middleware(recording.clone());
+    let fixture = TestFixture::new(FlightServiceServer::new(service)).await;
+    let mut client = FlightClient::new(fixture.channel().await);
+
+    let descriptor = FlightDescriptor::new_path(vec!["foo".to_string()]);
+    let err = client
+        .get_flight_info(descriptor.clone())
+        .await
+        .unwrap_err();
+    let FlightError::Tonic(status) = err else {
+        panic!("unexpected error: {err}")
+    };
+    assert_eq!(status.code(), Code::Unauthenticated);
+
+    client.add_interceptor(AuthInterceptor::bearer("secret").unwrap());
+    test_server.set_get_flight_info_response(Ok(FlightInfo::new()));
+    client.get_flight_info(descriptor).await.unwrap();
+
+    // The rejected call never reached the recording middleware
+    assert_eq!(
+        *recording.calls.lock().unwrap(),
+        [(FlightMethod::GetFlightInfo, Some("admin".to_string()), None)]
+    );
+
+    fixture.shutdown_and_wait().await;
+}
+
+#[tokio::test]
+async fn test_do_get_metrics() {
+    let test_server = TestFlightServer::new();
+    let metrics = MetricsMiddleware::new();
+    let recording = RecordingMiddleware::default();
+    let (fixture, mut client) = setup(&test_server, &metrics, &recording).await;
+    client.add_interceptor(AuthInterceptor::bearer("secret").unwrap());
+
+    let batches = vec![make_primitive_batch(5), make_primitive_batch(3)];
+    test_server.set_do_get_response(batches.iter().cloned().map(Ok).collect());
+    let response: Vec<_> = client
+        .do_get(Ticket::new("ticket"))
+        .await
+        .unwrap()
+        .try_collect()
+        .await
+        .unwrap();
+    assert_eq!(response, batches);
+
+    let do_get = wait_for_calls(&metrics, FlightMethod::DoGet).await;
+    assert_eq!(do_get.calls, 1);
+    assert_eq!(do_get.errors, 0);
+    // one schema message and two batches
+    assert_eq!(do_get.messages_sent, 3);
+    assert_eq!(do_get.batches_sent, 2);
+    assert!(do_get.bytes_sent > 0);
+    assert_eq!(metrics.snapshot().len(), 1);
+
+    fixture.shutdown_and_wait().await;
+}
+
+#[tokio::test]
+async fn test_client_interceptors() {
+    let test_server = TestFlightServer::new();
+    let metrics = MetricsMiddleware::new();
+    let recording = RecordingMiddleware::default();
+    let (fixture, mut client) = setup(&test_server, &metrics, &recording).await;
+
+    client.add_header("x-header", "from-metadata").unwrap();
+    client.add_interceptor(AuthInterceptor::basic("user", "password"));
+    client.add_interceptor(|method: FlightMethod, metadata: &mut MetadataMap| {
+        metadata.insert("x-method", method.as_str().parse().unwrap());
+        Ok(())
+    });
+
+    list_actions(&test_server, &mut client).await;
+    let metadata = test_server.take_last_request_metadata().unwrap();
+    assert_eq!(metadata.get("x-header").unwrap(), "from-metadata");
+    assert_eq!(metadata.get("x-method").unwrap(), "ListActions");
+    assert_eq!(
+        metadata.get("authorization").unwrap(),
+        "Basic dXNlcjpwYXNzd29yZA=="
+    );
+
+    client.add_interceptor(|method: FlightMethod, _: &mut MetadataMap| match method {
+        FlightMethod::DoAction => Err(FlightError::protocol("actions are disabled")),
+        _ => Ok(()),
+    });
+    let err = client
+        .do_action(arrow_flight::Action::new("foo", ""))
+        .await
+        .err()
+        .unwrap();
+    assert!(err.to_string().contains("actions are disabled"), "{err}");
+    assert!(test_server.take_do_action_request().is_none());
+
+    fixture.shutdown_and_wait().await;
+}
@@ -27,9 +27,22 @@ const LENGTH_OF_PREFIX_DATA: i64 = 8;
 /// In the case of zstd, this will contain the zstd context, which can be reused between subsequent
 /// compression calls to avoid the performance overhead of initialising a new context for every
 /// compression.
+///
+/// The context also records [`CompressionMetrics`] for the buffers it compresses.
 pub struct CompressionContext {
     #[cfg(feature = "zstd")]
     compressor: zstd::bulk::Compressor<'static>,
+    /// The level `compressor` is configured with
+    #[cfg(feature = "zstd")]
+    zstd_level: i32,
+    metrics: CompressionMetrics,
+}
+
+impl CompressionContext {
+    /// Return the metrics of the buffers compressed with this context
+    pub fn metrics(&self) -> CompressionMetrics {
+        self.metrics
+    }
 }
 
 // the reason we allow derivable_impls here is because when zstd feature is not enabled, this
@@ -42,6 +55,9 @@ impl Default for CompressionContext {
             #[cfg(feature = "zstd")]
             compressor: zstd::bulk::Compressor::new(zstd::DEFAULT_COMPRESSION_LEVEL)
                 .expect("can use default compression level"),
+            #[cfg(feature = "zstd")]
+            zstd_level: zstd::DEFAULT_COMPRESSION_LEVEL,
+            metrics: CompressionMetrics::default(),
         }
     }
 }
@@ -51,12 +67,61 @@ impl std::fmt::Debug for CompressionContext {
         let mut ds = f.debug_struct("CompressionContext");
 
         #[cfg(feature = "zstd")]
-        ds.field("compressor", &"zstd::bulk::Compressor");
+        ds.field("compressor", &"zstd::bulk::Compressor")
+            .field("zstd_level", &self.zstd_level);
 
+        ds.field("metrics", &self.metrics);
         ds.finish()
     }
 }
 
+/// Statistics about the buffers written by an IPC writer with compression enabled
+///
+/// Buffers of batches written without compression are not counted. See
+/// [`StreamWriter::compression_metrics`] and [`FileWriter::compression_metrics`].
+///
+/// [`StreamWriter::compression_metrics`]: crate::writer::StreamWriter::compression_metrics
+/// [`FileWriter::compression_metrics`]: crate::writer::FileWriter::compression_metrics
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CompressionMetrics {
+    /// The total size of the buffers before compression
+    pub uncompressed_bytes: u64,
+    /// The total size of the buffers as written, including their 8 byte length prefix
+    pub compressed_bytes: u64,
+    /// The number of buffers written compressed
+    pub compressed_buffers: u64,
+    /// The number of buffers written uncompressed, as they were smaller than the
+    /// minimum compression size, or compression did not reduce their size
+    pub uncompressed_buffers: u64,
+}
+
+impl CompressionMetrics {
+    fn record(&mut self, uncompressed_len: usize, written_len: usize, compressed: bool) {
+        self.uncompressed_bytes += uncompressed_len as u64;
+        self.compressed_bytes += written_len as u64;
+        match compressed {
+            true => self.compressed_buffers += 1,
+            false => self.uncompressed_buffers += 1,
+        }
+    }
+}
+
+/// A custom implementation of an IPC compression codec
+///
+/// This allows experimenting with other implementations or parameters of the
+/// codecs supported by the IPC format, see [`IpcWriteOptions::try_with_compressor`].
+/// As readers decompress buffers with their own implementation of
+/// [`Self::compression_type`], the compressed data must be valid for that codec.
+///
+/// [`IpcWriteOptions::try_with_compressor`]: crate::writer::IpcWriteOptions::try_with_compressor
+pub trait BufferCompressor: std::fmt::Debug + Send + Sync {
+    /// The codec of the compressed data, recorded in the IPC metadata
+    fn compression_type(&self) -> CompressionType;
+
+    /// Compress `input`, appending the compressed data to `output`
+    fn compress(&self, input: &[u8], output: &mut Vec<u8>) -> Result<(), ArrowError>;
+}
+
 /// Additional context that may be needed for decompression.
 ///
 /// In the case of zstd, this will contain the zstd decompression context, which can be reused
@@ -115,7 +180,39 @@ impl TryFrom<CompressionType> for CompressionCodec {
     }
 }
 
-impl CompressionCodec {
+/// The compression of the buffers of a batch written by the IPC writer
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct BufferCompression<'a> {
+    codec: Codec<'a>,
+    /// Buffers smaller than this are written uncompressed
+    min_size: usize,
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Codec<'a> {
+    /// A built-in codec, with an optional compression level
+    Builtin(CompressionCodec, Option<i32>),
+    Custom(&'a dyn BufferCompressor),
+}
+
+impl<'a> BufferCompression<'a> {
+    /// Create a [`BufferCompression`], returning an error if the codec of
+    /// `compression_type` is not supported
+    ///
+    /// `level` is ignored if a custom `compressor` is provided
+    pub(crate) fn try_new(
+        compression_type: CompressionType,
+        level: Option<i32>,
+        compressor: Option<&'a dyn BufferCompressor>,
+        min_size: usize,
+    ) -> Result<Self, ArrowError> {
+        let codec = match compressor {
+            Some(compressor) => Codec::Custom(compressor),
+            None => Codec::Builtin(CompressionCodec::try_from(compression_type)?, level),
+        };
+        Ok(Self { codec, min_size })
+    }
+
     /// Compresses the data in `input` to `output` and appends the
     /// data using the specified compression mechanism.
     ///
@@ -137,25 +234,41 @@ impl CompressionCodec {
 
         if input.is_empty() {
             // empty input, nothing to do
-        } else {
+            return Ok(0);
+        }
+
+        let mut compressed = uncompressed_data_len >= self.min_size;
+        if compressed {
             // write compressed data directly into the output buffer
             output.extend_from_slice(&uncompressed_data_len.to_le_bytes());
-            self.compress(input, output, context)?;
+            match self.codec {
+                Codec::Builtin(codec, level) => codec.compress(input, output, level, context)?,
+                Codec::Custom(compressor) => compressor.compress(input, output)?,
+            }
 
             let compression_len = output.len() - original_output_len;
             if compression_len > uncompressed_data_len {
                 // length of compressed data was larger than
-                // uncompressed data, use the uncompressed data with
-                // length -1 to indicate that we don't compress the
-                // data
+                // uncompressed data, use the uncompressed data instead
                 output.truncate(original_output_len);
-                output.extend_from_slice(&LENGTH_NO_COMPRESSED_DATA.to_le_bytes());
-                output.extend_from_slice(input);
+                compressed = false;
             }
         }
-        Ok(output.len() - original_output_len)
+        if !compressed {
+            // use length -1 to indicate that we don't compress the data
+            output.extend_from_slice(&LENGTH_NO_COMPRESSED_DATA.to_le_bytes());
+            output.extend_from_slice(input);
+        }
+
+        let written_len = output.len() - original_output_len;
+        context
+            .metrics
+            .record(uncompressed_data_len, written_len, compressed);
+        Ok(written_len)
     }
+}
 
+impl CompressionCodec {
     /// Decompresses the input into a [`Buffer`]
     ///
     /// The input should look like:
@@ -196,11 +309,22 @@ impl CompressionCodec {
         &self,
         input: &[u8],
         output: &mut Vec<u8>,
+        level: Option<i32>,
         context: &mut CompressionContext,
     ) -> Result<(), ArrowError> {
         match self {
             CompressionCodec::Lz4Frame => compress_lz4(input, output),
-            CompressionCodec::Zstd => compress_zstd(input, output, context),
+            CompressionCodec::Zstd => compress_zstd(input, output, level, context),
+        }
+    }
+
+    /// Return an error if `level` is not a valid compression level
+    pub(crate) fn validate_level(&self, level: i32) -> Result<(), ArrowError> {
+        match self {
+            CompressionCodec::Lz4Frame => Err(ArrowError::InvalidArgumentError(
+                "lz4 IPC compression does not support compression levels".to_string(),
+            )),
+            CompressionCodec::Zstd => validate_zstd_level(level),
         }
     }
 
@@ -265,8 +389,14 @@ fn decompress_lz4(_input: &[u8], _decompressed_size: usize) -> Result<Vec<u8>, A
 fn compress_zstd(
     input: &[u8],
     output: &mut Vec<u8>,
+    level: Option<i32>,
     context: &mut CompressionContext,
 ) -> Result<(), ArrowError> {
+    let level = level.unwrap_or(zstd::DEFAULT_COMPRESSION_LEVEL);
+    if context.zstd_level != level {
+        context.compressor.set_compression_level(level)?;
+        context.zstd_level = level;
+    }
     let result = context.compressor.compress(input)?;
     output.extend_from_slice(&result);
     Ok(())
@@ -277,6 +407,7 @@ fn compress_zstd(
 fn compress_zstd(
     _input: &[u8],
     _output: &mut Vec<u8>,
+    _level: Option<i32>,
     _context: &mut CompressionContext,
 ) -> Result<(), ArrowError> {
     Err(ArrowError::InvalidArgumentError(
@@ -284,6 +415,23 @@ fn compress_zstd(
     ))
 }
 
+#[cfg(feature = "zstd")]
+fn validate_zstd_level(level: i32) -> Result<(), ArrowError> {
+    let range = zstd::compression_level_range();
+    match range.contains(&level) {
+        true => Ok(()),
+        false => Err(ArrowError::InvalidArgumentError(format!(
+            "zstd compression level {level} is not in the range {range:?}"
+        ))),
+    }
+}
+
+#[cfg(not(feature = "zstd"))]
+fn validate_zstd_level(_level: i32) -> Result<(), ArrowError> {
+    // compression will fail without the zstd feature
+    Ok(())
+}
+
 #[cfg(feature = "zstd")]
 fn decompress_zstd(
     input: &[u8],
@@ -327,7 +475,12 @@ mod tests {
         let codec = super::CompressionCodec::Lz4Frame;
         let mut output_bytes: Vec<u8> = Vec::new();
         codec
-            .compress(input_bytes, &mut output_bytes, &mut Default::default())
+            .compress(
+                input_bytes,
+                &mut output_bytes,
+                None,
+                &mut Default::default(),
+            )
             .unwrap();
         let result = codec
             .decompress(
@@ -346,7 +499,12 @@ mod tests {
         let codec = super::CompressionCodec::Zstd;
         let mut output_bytes: Vec<u8> = Vec::new();
         codec
-            .compress(input_bytes, &mut output_bytes, &mut Default::default())
+            .compress(
+                input_bytes,
+                &mut output_bytes,
+                None,
+                &mut Default::default(),
+            )
             .unwrap();
         let result = codec
             .decompress(
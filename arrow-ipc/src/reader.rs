@@ -27,6 +27,11 @@
 mod stream;
 pub use stream::*;
 
+#[cfg(feature = "mmap")]
+mod mmap;
+#[cfg(feature = "mmap")]
+pub use mmap::*;
+
 use arrow_select::concat;
 
 use flatbuffers::{VectorIter, VerifierOptions};
@@ -877,6 +882,33 @@ fn read_block<R: Read + Seek>(mut reader: R, block: &Block) -> Result<Buffer, Ar
     Ok(buf.into())
 }
 
+/// Return the schema of an IPC file footer
+fn footer_schema(footer: crate::Footer<'_>) -> Result<Schema, ArrowError> {
+    let ipc_schema = footer.schema().ok_or_else(|| {
+        ArrowError::ParseError("Unable to get schema from IPC Footer".to_string())
+    })?;
+    if !ipc_schema.endianness().equals_to_target_endianness() {
+        return Err(ArrowError::IpcError(
+            "the endianness of the source system does not match the endianness of the target system.".to_owned()
+        ));
+    }
+    Ok(crate::convert::fb_to_schema(ipc_schema))
+}
+
+/// Return the user defined metadata of an IPC file footer
+fn footer_custom_metadata(footer: crate::Footer<'_>) -> HashMap<String, String> {
+    let mut custom_metadata = HashMap::new();
+    if let Some(fb_custom_metadata) = footer.custom_metadata() {
+        for kv in fb_custom_metadata.into_iter() {
+            custom_metadata.insert(
+                kv.key().unwrap().to_string(),
+                kv.value().unwrap().to_string(),
+            );
+        }
+    }
+    custom_metadata
+}
+
 /// Parse an encapsulated message
 ///
 /// <https://arrow.apache.org/docs/format/Columnar.html#encapsulated-message-format>
@@ -1201,24 +1233,8 @@ impl FileReaderBuilder {
 
         let total_blocks = blocks.len();
 
-        let ipc_schema = footer.schema().unwrap();
-        if !ipc_schema.endianness().equals_to_target_endianness() {
-            return Err(ArrowError::IpcError(
-                "the endianness of the source system does not match the endianness of the target system.".to_owned()
-            ));
-        }
-
-        let schema = crate::convert::fb_to_schema(ipc_schema);
-
-        let mut custom_metadata = HashMap::new();
-        if let Some(fb_custom_metadata) = footer.custom_metadata() {
-            for kv in fb_custom_metadata.into_iter() {
-                custom_metadata.insert(
-                    kv.key().unwrap().to_string(),
-                    kv.value().unwrap().to_string(),
-                );
-            }
-        }
+        let schema = footer_schema(footer)?;
+        let custom_metadata = footer_custom_metadata(footer);
 
         let mut decoder = FileDecoder::new(Arc::new(schema), footer.version());
         if let Some(projection) = self.projection {
@@ -1284,7 +1300,8 @@ impl FileReaderBuilder {
 /// # Example: Reading from `mmap`ed file
 ///
 /// For an example creating Arrays without copying using  memory mapped (`mmap`)
-/// files see the [`zero_copy_ipc`] example.
+/// files see the [`zero_copy_ipc`] example, or the `MmapFileReader` enabled
+/// by the `mmap` feature.
 ///
 /// [IPC File Format]: https://arrow.apache.org/docs/format/Columnar.html#ipc-file-format
 /// [`zero_copy_ipc`]: https://github.com/apache/arrow-rs/blob/main/arrow/examples/zero_copy_ipc.rs
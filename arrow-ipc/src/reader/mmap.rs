@@ -0,0 +1,505 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::fs::File;
+use std::ops::Range;
+use std::ptr::NonNull;
+use std::sync::Arc;
+
+use arrow_array::{ArrayRef, RecordBatch};
+use arrow_buffer::Buffer;
+use arrow_data::UnsafeFlag;
+use arrow_schema::{ArrowError, SchemaRef};
+use flatbuffers::VerifierOptions;
+
+use crate::Block;
+use crate::reader::{FileDecoder, footer_custom_metadata, footer_schema, read_footer_length};
+
+/// The leading magic bytes padded to 8 bytes, and the trailing footer length and magic bytes
+const MIN_FILE_LEN: usize = 8 + 10;
+
+/// Build a [`MmapFileReader`] with custom options
+#[derive(Debug)]
+pub struct MmapFileReaderBuilder {
+    /// Optional projection for which columns to load (zero-based column indices)
+    projection: Option<Vec<usize>>,
+    /// Passed through to [`FileDecoder::with_require_alignment`]
+    require_alignment: bool,
+    /// Passed through to [`FileDecoder::with_skip_validation`]
+    skip_validation: UnsafeFlag,
+    /// Passed through to construct [`VerifierOptions`]
+    max_footer_fb_tables: usize,
+    /// Passed through to construct [`VerifierOptions`]
+    max_footer_fb_depth: usize,
+}
+
+impl Default for MmapFileReaderBuilder {
+    fn default() -> Self {
+        let verifier_options = VerifierOptions::default();
+        Self {
+            projection: None,
+            require_alignment: false,
+            skip_validation: UnsafeFlag::new(),
+            max_footer_fb_tables: verifier_options.max_tables,
+            max_footer_fb_depth: verifier_options.max_depth,
+        }
+    }
+}
+
+impl MmapFileReaderBuilder {
+    /// Options for creating a new [`MmapFileReader`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Optional projection for which columns to load (zero-based column indices).
+    pub fn with_projection(mut self, projection: Vec<usize>) -> Self {
+        self.projection = Some(projection);
+        self
+    }
+
+    /// Specifies if the array data in the file is required to be properly aligned.
+    ///
+    /// If `require_alignment` is false (the default), array data that is not
+    /// properly aligned for its type, for example in files written with a
+    /// smaller alignment than the type requires, is copied into a new aligned
+    /// buffer. Properly aligned array data continues to reference the file.
+    ///
+    /// If `require_alignment` is true, reading a batch with array data that is
+    /// not properly aligned returns an error instead.
+    pub fn with_require_alignment(mut self, require_alignment: bool) -> Self {
+        self.require_alignment = require_alignment;
+        self
+    }
+
+    /// Specifies if validation should be skipped when reading data (defaults to `false`)
+    ///
+    /// # Safety
+    ///
+    /// See [`FileDecoder::with_skip_validation`]
+    pub unsafe fn with_skip_validation(mut self, skip_validation: bool) -> Self {
+        unsafe { self.skip_validation.set(skip_validation) };
+        self
+    }
+
+    /// Flatbuffers option for parsing the footer, see
+    /// [`FileReaderBuilder::with_max_footer_fb_tables`](crate::reader::FileReaderBuilder::with_max_footer_fb_tables)
+    pub fn with_max_footer_fb_tables(mut self, max_footer_fb_tables: usize) -> Self {
+        self.max_footer_fb_tables = max_footer_fb_tables;
+        self
+    }
+
+    /// Flatbuffers option for parsing the footer, see
+    /// [`FileReaderBuilder::with_max_footer_fb_depth`](crate::reader::FileReaderBuilder::with_max_footer_fb_depth)
+    pub fn with_max_footer_fb_depth(mut self, max_footer_fb_depth: usize) -> Self {
+        self.max_footer_fb_depth = max_footer_fb_depth;
+        self
+    }
+
+    /// Memory map `file` and build a [`MmapFileReader`]
+    ///
+    /// # Safety
+    ///
+    /// The file is mapped with [`memmap2::Mmap::map`]. Undefined behavior may
+    /// result if the file is modified or truncated while the reader, or any
+    /// array read from it, exists.
+    pub unsafe fn build(self, file: &File) -> Result<MmapFileReader, ArrowError> {
+        let mmap = unsafe { memmap2::Mmap::map(file)? };
+        let ptr = NonNull::from(mmap.as_ref()).cast::<u8>();
+        let len = mmap.len();
+        // SAFETY: the mapping is valid for `len` bytes until `mmap`, which is
+        // owned by the buffer, is dropped
+        let buffer = unsafe { Buffer::from_custom_allocation(ptr, len, Arc::new(mmap)) };
+        self.build_from_buffer(buffer)
+    }
+
+    /// Build a [`MmapFileReader`] from a [`Buffer`] containing an entire IPC
+    /// file, for example a memory mapping created by the caller
+    pub fn build_from_buffer(self, buffer: Buffer) -> Result<MmapFileReader, ArrowError> {
+        let len = buffer.len();
+        if len < MIN_FILE_LEN || buffer[..6] != crate::ARROW_MAGIC {
+            return Err(ArrowError::ParseError(
+                "Arrow file does not contain correct header".to_string(),
+            ));
+        }
+
+        let trailer_start = len - 10;
+        let footer_len = read_footer_length(buffer[trailer_start..].try_into().unwrap())?;
+        if footer_len > trailer_start - 8 {
+            return Err(ArrowError::ParseError(format!(
+                "Invalid footer length: {footer_len}"
+            )));
+        }
+        let footer_start = trailer_start - footer_len;
+
+        let verifier_options = VerifierOptions {
+            max_tables: self.max_footer_fb_tables,
+            max_depth: self.max_footer_fb_depth,
+            ..Default::default()
+        };
+        let footer = crate::root_as_footer_with_opts(
+            &verifier_options,
+            &buffer[footer_start..trailer_start],
+        )
+        .map_err(|err| ArrowError::ParseError(format!("Unable to get root as footer: {err:?}")))?;
+
+        let blocks = footer.recordBatches().ok_or_else(|| {
+            ArrowError::ParseError("Unable to get record batches from IPC Footer".to_string())
+        })?;
+        // validate the blocks, so that reading them cannot slice outside the file data
+        let blocks = blocks
+            .iter()
+            .map(|block| block_range(block, footer_start).map(|_| *block))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let schema = footer_schema(footer)?;
+        let custom_metadata = footer_custom_metadata(footer);
+
+        let mut decoder = FileDecoder::new(Arc::new(schema), footer.version())
+            .with_require_alignment(self.require_alignment);
+        decoder.skip_validation = self.skip_validation;
+        if let Some(projection) = self.projection {
+            decoder = decoder.with_projection(projection);
+        }
+
+        if let Some(dictionaries) = footer.dictionaries() {
+            for block in dictionaries {
+                let range = block_range(block, footer_start)?;
+                let data = buffer.slice_with_length(range.start, range.len());
+                decoder.read_dictionary(block, &data)?;
+            }
+        }
+
+        Ok(MmapFileReader {
+            buffer,
+            decoder,
+            blocks,
+            custom_metadata,
+        })
+    }
+}
+
+/// Return the range of `block` within the file, which must end before `end`
+fn block_range(block: &Block, end: usize) -> Result<Range<usize>, ArrowError> {
+    let offset = usize::try_from(block.offset()).ok();
+    // the metadata contains at least the continuation marker and metadata length
+    let metadata_len = usize::try_from(block.metaDataLength())
+        .ok()
+        .filter(|len| *len >= 8);
+    let body_len = usize::try_from(block.bodyLength()).ok();
+
+    let range = offset
+        .zip(metadata_len)
+        .zip(body_len)
+        .and_then(|((o, m), b)| {
+            let block_end = o.checked_add(m)?.checked_add(b)?;
+            (block_end <= end).then_some(o..block_end)
+        });
+    range.ok_or_else(|| {
+        ArrowError::ParseError(format!(
+            "Invalid block in IPC file footer, offset: {}, metadata length: {}, body length: {}",
+            block.offset(),
+            block.metaDataLength(),
+            block.bodyLength()
+        ))
+    })
+}
+
+/// Arrow File Reader for memory mapped files
+///
+/// Reads Arrow [`RecordBatch`]es from a memory mapped file in the
+/// [IPC File Format], without copying the array data. The buffers of the
+/// returned [`RecordBatch`]es reference the mapping, which remains valid as
+/// long as any of them, or the reader, exists.
+///
+/// Unlike [`FileReader`](crate::reader::FileReader), the footer and the
+/// locations of all blocks are validated when the reader is created, and
+/// batches can be read in any order through a shared reference.
+///
+/// # Example
+/// ```
+/// # use arrow_array::record_batch;
+/// # use arrow_ipc::reader::MmapFileReaderBuilder;
+/// # use arrow_ipc::writer::FileWriter;
+/// let batch = record_batch!(("a", Int32, [1, 2, 3])).unwrap();
+/// let mut file = tempfile::tempfile().unwrap();
+/// let mut writer = FileWriter::try_new(&mut file, &batch.schema()).unwrap();
+/// writer.write(&batch).unwrap();
+/// writer.write(&batch).unwrap();
+/// writer.finish().unwrap();
+///
+/// // Safety: the file is not modified while it is mapped
+/// let reader = unsafe { MmapFileReaderBuilder::new().build(&file) }.unwrap();
+/// assert_eq!(reader.num_batches(), 2);
+/// assert_eq!(reader.get(1).unwrap(), batch);
+/// ```
+///
+/// [IPC File Format]: https://arrow.apache.org/docs/format/Columnar.html#ipc-file-format
+pub struct MmapFileReader {
+    /// The contents of the file
+    buffer: Buffer,
+    /// The decoder, containing the dictionaries of the file
+    decoder: FileDecoder,
+    /// The validated record batch blocks of the file
+    blocks: Vec<Block>,
+    /// User defined metadata
+    custom_metadata: HashMap<String, String>,
+}
+
+impl fmt::Debug for MmapFileReader {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MmapFileReader")
+            .field("len", &self.buffer.len())
+            .field("decoder", &self.decoder)
+            .field("blocks", &self.blocks)
+            .finish_non_exhaustive()
+    }
+}
+
+impl MmapFileReader {
+    /// Memory map `file` and create a reader with the default options
+    ///
+    /// See [`MmapFileReaderBuilder`] for more options
+    ///
+    /// # Safety
+    ///
+    /// See [`MmapFileReaderBuilder::build`]
+    pub unsafe fn try_new(file: &File) -> Result<Self, ArrowError> {
+        unsafe { MmapFileReaderBuilder::new().build(file) }
+    }
+
+    /// Return the schema of the file
+    pub fn schema(&self) -> SchemaRef {
+        self.decoder.schema.clone()
+    }
+
+    /// Return user defined customized metadata
+    pub fn custom_metadata(&self) -> &HashMap<String, String> {
+        &self.custom_metadata
+    }
+
+    /// Return the number of batches in the file
+    pub fn num_batches(&self) -> usize {
+        self.blocks.len()
+    }
+
+    /// Return the dictionaries of the file, by dictionary id
+    pub fn dictionaries(&self) -> &HashMap<i64, ArrayRef> {
+        &self.decoder.dictionaries
+    }
+
+    /// Return the contents of the file
+    pub fn buffer(&self) -> &Buffer {
+        &self.buffer
+    }
+
+    /// Read the [`RecordBatch`] at `index`
+    pub fn get(&self, index: usize) -> Result<RecordBatch, ArrowError> {
+        let block = self.blocks.get(index).ok_or_else(|| {
+            ArrowError::InvalidArgumentError(format!(
+                "Cannot read batch at index {index} from {} total batches",
+                self.blocks.len()
+            ))
+        })?;
+        let range = block_range(block, self.buffer.len())?;
+        let data = self.buffer.slice_with_length(range.start, range.len());
+        self.decoder
+            .read_record_batch(block, &data)?
+            .ok_or_else(|| {
+                ArrowError::IpcError(format!("Block {index} does not contain a record batch"))
+            })
+    }
+
+    /// Return an iterator over the [`RecordBatch`]es of the file
+    pub fn iter(&self) -> impl Iterator<Item = Result<RecordBatch, ArrowError>> + '_ {
+        (0..self.num_batches()).map(|index| self.get(index))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use arrow_array::types::Int32Type;
+    use arrow_array::{Array, Decimal128Array, DictionaryArray, Int32Array};
+    use arrow_buffer::MutableBuffer;
+
+    use crate::writer::FileWriter;
+
+    fn write_file(batches: &[RecordBatch]) -> File {
+        let mut file = tempfile::tempfile().unwrap();
+        let mut writer = FileWriter::try_new(&mut file, &batches[0].schema()).unwrap();
+        writer.write_metadata("key", "value");
+        for batch in batches {
+            writer.write(batch).unwrap();
+        }
+        writer.finish().unwrap();
+        file
+    }
+
+    fn write_bytes(batch: &RecordBatch) -> Vec<u8> {
+        let mut writer = FileWriter::try_new(vec![], &batch.schema()).unwrap();
+        writer.write(batch).unwrap();
+        writer.finish().unwrap();
+        writer.into_inner().unwrap()
+    }
+
+    fn make_batch(offset: i32) -> RecordBatch {
+        let values = Int32Array::from_iter_values(offset..offset + 10);
+        let dictionary: DictionaryArray<Int32Type> =
+            (0..10).map(|i| ["a", "b", "c"][i % 3]).collect();
+        RecordBatch::try_from_iter([
+            ("values", Arc::new(values) as ArrayRef),
+            ("dictionary", Arc::new(dictionary) as ArrayRef),
+        ])
+        .unwrap()
+    }
+
+    #[test]
+    fn test_mmap_file_reader() {
+        let batches: Vec<_> = (0..3).map(|i| make_batch(i * 10)).collect();
+        let file = write_file(&batches);
+
+        let reader = unsafe { MmapFileReader::try_new(&file) }.unwrap();
+        assert_eq!(reader.num_batches(), 3);
+        assert_eq!(reader.schema(), batches[0].schema());
+        assert_eq!(reader.custom_metadata()["key"], "value");
+        assert_eq!(reader.dictionaries().len(), 1);
+
+        // random access
+        assert_eq!(reader.get(2).unwrap(), batches[2]);
+        assert_eq!(reader.get(0).unwrap(), batches[0]);
+        let read: Vec<_> = reader.iter().collect::<Result<_, _>>().unwrap();
+        assert_eq!(read, batches);
+
+        let err = reader.get(3).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid argument error: Cannot read batch at index 3 from 3 total batches"
+        );
+
+        // the values reference the mapping
+        let batch = reader.get(1).unwrap();
+        let values = batch.column(0).as_any().downcast_ref::<Int32Array>();
+        let values = values.unwrap().values().inner().as_ptr_range();
+        let mapping = reader.buffer().as_ptr_range();
+        assert!(mapping.start <= values.start && values.end <= mapping.end);
+
+        // batches remain valid after the reader is dropped
+        drop(reader);
+        assert_eq!(batch, batches[1]);
+    }
+
+    #[test]
+    fn test_mmap_file_reader_projection() {
+        let batch = make_batch(0);
+        let file = write_file(std::slice::from_ref(&batch));
+
+        let reader = unsafe {
+            MmapFileReaderBuilder::new()
+                .with_projection(vec![1])
+                .build(&file)
+        }
+        .unwrap();
+        assert_eq!(reader.get(0).unwrap(), batch.project(&[1]).unwrap());
+    }
+
+    #[test]
+    fn test_mmap_file_reader_alignment() {
+        let array = Decimal128Array::from_iter_values(0..100);
+        let batch = RecordBatch::try_from_iter([("a", Arc::new(array) as ArrayRef)]).unwrap();
+
+        // offset the file by 8 bytes, misaligning the 16 byte decimal values
+        let mut data = MutableBuffer::from_len_zeroed(8);
+        data.extend_from_slice(&write_bytes(&batch));
+        let buffer = Buffer::from(data).slice(8);
+
+        let reader = MmapFileReaderBuilder::new()
+            .with_require_alignment(true)
+            .build_from_buffer(buffer.clone())
+            .unwrap();
+        let err = reader.get(0).unwrap_err();
+        assert!(err.to_string().contains("Misaligned"), "{err}");
+
+        // the misaligned values are copied
+        let reader = MmapFileReaderBuilder::new()
+            .build_from_buffer(buffer)
+            .unwrap();
+        assert_eq!(reader.get(0).unwrap(), batch);
+    }
+
+    #[test]
+    fn test_mmap_file_reader_invalid() {
+        let build = |data: Vec<u8>| {
+            MmapFileReaderBuilder::new()
+                .build_from_buffer(Buffer::from_vec(data))
+                .unwrap_err()
+                .to_string()
+        };
+        let data = write_bytes(&make_batch(0));
+
+        assert_eq!(
+            build(data[..10].to_vec()),
+            "Parser error: Arrow file does not contain correct header"
+        );
+        assert_eq!(
+            build(data[..data.len() - 1].to_vec()),
+            "Parser error: Arrow file does not contain correct footer"
+        );
+
+        let mut invalid_footer_len = data.clone();
+        let trailer_start = data.len() - 10;
+        invalid_footer_len[trailer_start..trailer_start + 4]
+            .copy_from_slice(&(data.len() as i32).to_le_bytes());
+        assert_eq!(
+            build(invalid_footer_len),
+            format!("Parser error: Invalid footer length: {}", data.len())
+        );
+
+        // remove the data of the blocks
+        let footer_len = read_footer_length(data[trailer_start..].try_into().unwrap()).unwrap();
+        let truncated = [&data[..8], &data[trailer_start - footer_len..]].concat();
+        assert!(
+            build(truncated).contains("Invalid block in IPC file footer"),
+            "expected invalid block"
+        );
+
+        // a footer without a schema
+        let mut fbb = flatbuffers::FlatBufferBuilder::new();
+        let record_batches = fbb.create_vector::<Block>(&[]);
+        let mut footer_builder = crate::FooterBuilder::new(&mut fbb);
+        footer_builder.add_version(crate::MetadataVersion::V5);
+        footer_builder.add_recordBatches(record_batches);
+        let footer = footer_builder.finish();
+        fbb.finish(footer, None);
+        let footer_data = fbb.finished_data();
+        let no_schema = [
+            &crate::ARROW_MAGIC[..],
+            &[0, 0],
+            footer_data,
+            &(footer_data.len() as i32).to_le_bytes(),
+            &crate::ARROW_MAGIC,
+        ]
+        .concat();
+        assert_eq!(
+            build(no_schema),
+            "Parser error: Unable to get schema from IPC Footer"
+        );
+    }
+}
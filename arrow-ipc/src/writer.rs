@@ -42,8 +42,8 @@ use arrow_data::{ArrayData, ArrayDataBuilder, BufferSpec, layout};
 use arrow_schema::*;
 
 use crate::CONTINUATION_MARKER;
-use crate::compression::CompressionCodec;
-pub use crate::compression::CompressionContext;
+use crate::compression::{BufferCompression, CompressionCodec};
+pub use crate::compression::{BufferCompressor, CompressionContext, CompressionMetrics};
 use crate::convert::IpcSchemaEncoder;
 
 /// IPC write options used to control the behaviour of the [`IpcDataGenerator`]
@@ -66,6 +66,12 @@ pub struct IpcWriteOptions {
     /// Compression, if desired. Will result in a runtime error
     /// if the corresponding feature is not enabled
     batch_compression_type: Option<crate::CompressionType>,
+    /// The level of the compression codec, if not the codec's default
+    compression_level: Option<i32>,
+    /// Buffers smaller than this number of bytes are written uncompressed
+    min_compression_size: usize,
+    /// A custom implementation of the compression codec
+    compressor: Option<Arc<dyn BufferCompressor>>,
     /// How to handle updating dictionaries in IPC messages
     dictionary_handling: DictionaryHandling,
 }
@@ -80,6 +86,7 @@ impl IpcWriteOptions {
         batch_compression_type: Option<crate::CompressionType>,
     ) -> Result<Self, ArrowError> {
         self.batch_compression_type = batch_compression_type;
+        self.compressor = None;
 
         if self.batch_compression_type.is_some()
             && self.metadata_version < crate::MetadataVersion::V5
@@ -88,8 +95,83 @@ impl IpcWriteOptions {
                 "Compression only supported in metadata v5 and above".to_string(),
             ));
         }
+        self.validate_compression_level()?;
         Ok(self)
     }
+
+    /// Configures compression with a custom implementation of a compression codec
+    ///
+    /// The compression type is set to [`BufferCompressor::compression_type`].
+    /// Calling [`Self::try_with_compression`] afterwards replaces `compressor`.
+    pub fn try_with_compressor(
+        mut self,
+        compressor: Arc<dyn BufferCompressor>,
+    ) -> Result<Self, ArrowError> {
+        // The level is ignored by custom compressors, so is not validated
+        let level = self.compression_level.take();
+        let mut options = self.try_with_compression(Some(compressor.compression_type()))?;
+        options.compressor = Some(compressor);
+        options.compression_level = level;
+        Ok(options)
+    }
+
+    /// Configures the level of the compression codec, defaults to the
+    /// default level of the codec
+    ///
+    /// zstd accepts the levels supported by the zstd library, where negative
+    /// levels trade compression ratio for speed. lz4 does not support
+    /// compression levels, but a custom implementation can be provided with
+    /// [`Self::try_with_compressor`], in which case the level is ignored.
+    ///
+    /// Returns an error if `level` is not supported by the configured
+    /// compression. [`Self::try_with_compression`] likewise returns an error
+    /// if it does not support a previously configured level.
+    pub fn try_with_compression_level(mut self, level: i32) -> Result<Self, ArrowError> {
+        self.compression_level = Some(level);
+        self.validate_compression_level()?;
+        Ok(self)
+    }
+
+    /// Return an error if the compression level is not supported by the
+    /// built-in codec of the configured compression
+    fn validate_compression_level(&self) -> Result<(), ArrowError> {
+        match (self.batch_compression_type, self.compression_level) {
+            (Some(compression_type), Some(level)) if self.compressor.is_none() => {
+                CompressionCodec::try_from(compression_type)?.validate_level(level)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Configures the size in bytes below which buffers are written
+    /// uncompressed, defaults to `0`
+    ///
+    /// Compressing small buffers often saves little space, at the cost of
+    /// the overhead of compressing and decompressing them.
+    pub fn with_min_compression_size(mut self, min_compression_size: usize) -> Self {
+        self.min_compression_size = min_compression_size;
+        self
+    }
+
+    /// Return the compression applied to record and dictionary batches, if any
+    pub fn batch_compression_type(&self) -> Option<crate::CompressionType> {
+        self.batch_compression_type
+    }
+
+    /// Return the compression of the buffers of record and dictionary batches
+    fn buffer_compression(&self) -> Result<Option<BufferCompression<'_>>, ArrowError> {
+        self.batch_compression_type
+            .map(|compression_type| {
+                BufferCompression::try_new(
+                    compression_type,
+                    self.compression_level,
+                    self.compressor.as_deref(),
+                    self.min_compression_size,
+                )
+            })
+            .transpose()
+    }
+
     /// Try to create IpcWriteOptions, checking for incompatible settings
     pub fn try_new(
         alignment: usize,
@@ -116,6 +198,9 @@ impl IpcWriteOptions {
                 write_legacy_ipc_format,
                 metadata_version,
                 batch_compression_type: None,
+                compression_level: None,
+                min_compression_size: 0,
+                compressor: None,
                 dictionary_handling: DictionaryHandling::default(),
             }),
             crate::MetadataVersion::V5 => {
@@ -129,6 +214,9 @@ impl IpcWriteOptions {
                         write_legacy_ipc_format,
                         metadata_version,
                         batch_compression_type: None,
+                        compression_level: None,
+                        min_compression_size: 0,
+                        compressor: None,
                         dictionary_handling: DictionaryHandling::default(),
                     })
                 }
@@ -153,6 +241,9 @@ impl Default for IpcWriteOptions {
             write_legacy_ipc_format: false,
             metadata_version: crate::MetadataVersion::V5,
             batch_compression_type: None,
+            compression_level: None,
+            min_compression_size: 0,
+            compressor: None,
             dictionary_handling: DictionaryHandling::default(),
         }
     }
@@ -535,8 +626,7 @@ impl IpcDataGenerator {
             c.finish()
         });
 
-        let compression_codec: Option<CompressionCodec> =
-            batch_compression_type.map(TryInto::try_into).transpose()?;
+        let compression_codec = write_options.buffer_compression()?;
 
         let mut variadic_buffer_counts = vec![];
 
@@ -628,9 +718,7 @@ impl IpcDataGenerator {
             c.finish()
         });
 
-        let compression_codec: Option<CompressionCodec> = batch_compression_type
-            .map(|batch_compression_type| batch_compression_type.try_into())
-            .transpose()?;
+        let compression_codec = write_options.buffer_compression()?;
 
         write_array_data(
             array_data,
@@ -1240,6 +1328,11 @@ impl<W: Write> FileWriter<W> {
         &self.schema
     }
 
+    /// Returns the metrics of the buffers compressed by this writer
+    pub fn compression_metrics(&self) -> CompressionMetrics {
+        self.compression_context.metrics()
+    }
+
     /// Gets a reference to the underlying writer.
     pub fn get_ref(&self) -> &W {
         &self.writer
@@ -1435,15 +1528,12 @@ impl<W: Write> StreamWriter<W> {
             ));
         }
 
-        let (encoded_dictionaries, encoded_message) = self
-            .data_gen
-            .encode(
-                batch,
-                &mut self.dictionary_tracker,
-                &self.write_options,
-                &mut self.compression_context,
-            )
-            .expect("StreamWriter is configured to not error on dictionary replacement");
+        let (encoded_dictionaries, encoded_message) = self.data_gen.encode(
+            batch,
+            &mut self.dictionary_tracker,
+            &self.write_options,
+            &mut self.compression_context,
+        )?;
 
         for encoded_dictionary in encoded_dictionaries {
             write_message(&mut self.writer, encoded_dictionary, &self.write_options)?;
@@ -1468,6 +1558,11 @@ impl<W: Write> StreamWriter<W> {
         Ok(())
     }
 
+    /// Returns the metrics of the buffers compressed by this writer
+    pub fn compression_metrics(&self) -> CompressionMetrics {
+        self.compression_context.metrics()
+    }
+
     /// Gets a reference to the underlying writer.
     pub fn get_ref(&self) -> &W {
         &self.writer
@@ -1804,7 +1899,7 @@ fn write_array_data(
     offset: i64,
     num_rows: usize,
     null_count: usize,
-    compression_codec: Option<CompressionCodec>,
+    compression_codec: Option<BufferCompression<'_>>,
     compression_context: &mut CompressionContext,
     write_options: &IpcWriteOptions,
 ) -> Result<i64, ArrowError> {
@@ -2110,7 +2205,7 @@ fn write_buffer(
     buffers: &mut Vec<crate::Buffer>, // output buffer descriptors
     arrow_data: &mut Vec<u8>,         // output stream
     offset: i64,                      // current output stream offset
-    compression_codec: Option<CompressionCodec>,
+    compression_codec: Option<BufferCompression<'_>>,
     compression_context: &mut CompressionContext,
     alignment: u8,
 ) -> Result<i64, ArrowError> {
@@ -2324,6 +2419,156 @@ mod tests {
         }
     }
 
+    #[cfg(any(feature = "lz4", feature = "zstd"))]
+    fn write_compressed_stream(
+        batch: &RecordBatch,
+        options: IpcWriteOptions,
+    ) -> Result<(Vec<u8>, CompressionMetrics), ArrowError> {
+        let mut writer = StreamWriter::try_new_with_options(vec![], batch.schema_ref(), options)?;
+        writer.write(batch)?;
+        writer.finish()?;
+        let metrics = writer.compression_metrics();
+        Ok((writer.into_inner()?, metrics))
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn test_write_stream_with_zstd_compression_level() {
+        let array = Int32Array::from_iter_values((0..4096).map(|i| i % 7));
+        let batch = RecordBatch::try_from_iter([("a", Arc::new(array) as ArrayRef)]).unwrap();
+        let options = IpcWriteOptions::default()
+            .try_with_compression(Some(crate::CompressionType::ZSTD))
+            .unwrap();
+
+        for level in [-5, 1, 19] {
+            let options = options.clone().try_with_compression_level(level).unwrap();
+            let (bytes, metrics) = write_compressed_stream(&batch, options).unwrap();
+            assert_eq!(deserialize_stream(bytes), batch);
+            assert_eq!(metrics.compressed_buffers, 2);
+            assert!(metrics.compressed_bytes < metrics.uncompressed_bytes);
+        }
+
+        let err = options.try_with_compression_level(1000).unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("zstd compression level 1000 is not in the range"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "lz4")]
+    fn test_write_file_with_min_compression_size() {
+        let array = Int32Array::from_iter_values(std::iter::repeat_n(1, 1000));
+        let batch = RecordBatch::try_from_iter([("a", Arc::new(array) as ArrayRef)]).unwrap();
+        let options = IpcWriteOptions::default()
+            .try_with_compression(Some(crate::CompressionType::LZ4_FRAME))
+            .unwrap();
+
+        // the validity buffer is 125 bytes, and the values buffer 4000 bytes
+        for (min_compression_size, compressed_buffers) in [(0, 2), (1000, 1), (5000, 0)] {
+            let options = options
+                .clone()
+                .with_min_compression_size(min_compression_size);
+            let mut writer =
+                FileWriter::try_new_with_options(vec![], batch.schema_ref(), options).unwrap();
+            writer.write(&batch).unwrap();
+            writer.write(&batch).unwrap();
+            writer.finish().unwrap();
+
+            let metrics = writer.compression_metrics();
+            assert_eq!(metrics.uncompressed_bytes, 2 * 4125);
+            assert_eq!(metrics.compressed_buffers, 2 * compressed_buffers);
+            assert_eq!(metrics.uncompressed_buffers, 2 * (2 - compressed_buffers));
+            if compressed_buffers == 0 {
+                // only the length prefixes are added
+                assert_eq!(metrics.compressed_bytes, 2 * (4125 + 2 * 8));
+            }
+            assert_eq!(deserialize_file(writer.into_inner().unwrap()), batch);
+        }
+
+        // lz4 does not support compression levels
+        let expected =
+            "Invalid argument error: lz4 IPC compression does not support compression levels";
+        let err = options.try_with_compression_level(1).unwrap_err();
+        assert_eq!(err.to_string(), expected);
+
+        let options = IpcWriteOptions::default()
+            .try_with_compression_level(1)
+            .unwrap();
+        let err = options
+            .try_with_compression(Some(crate::CompressionType::LZ4_FRAME))
+            .unwrap_err();
+        assert_eq!(err.to_string(), expected);
+    }
+
+    #[test]
+    #[cfg(feature = "zstd")]
+    fn test_write_stream_with_custom_compressor() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        #[derive(Debug, Default)]
+        struct CountingCompressor {
+            calls: AtomicUsize,
+        }
+
+        impl BufferCompressor for CountingCompressor {
+            fn compression_type(&self) -> crate::CompressionType {
+                crate::CompressionType::ZSTD
+            }
+
+            fn compress(&self, input: &[u8], output: &mut Vec<u8>) -> Result<(), ArrowError> {
+                self.calls.fetch_add(1, Ordering::Relaxed);
+                output.extend_from_slice(&zstd::bulk::compress(input, 1)?);
+                Ok(())
+            }
+        }
+
+        let array = StringArray::from_iter_values((0..1000).map(|i| format!("value{}", i % 10)));
+        let batch = RecordBatch::try_from_iter([("a", Arc::new(array) as ArrayRef)]).unwrap();
+
+        let compressor = Arc::new(CountingCompressor::default());
+        let options = IpcWriteOptions::default()
+            .try_with_compressor(Arc::clone(&compressor) as _)
+            .unwrap()
+            // ignored by custom compressors
+            .try_with_compression_level(1000)
+            .unwrap();
+        assert_eq!(
+            options.batch_compression_type(),
+            Some(crate::CompressionType::ZSTD)
+        );
+
+        let (bytes, metrics) = write_compressed_stream(&batch, options.clone()).unwrap();
+        assert_eq!(deserialize_stream(bytes), batch);
+        // validity, offsets and values buffers
+        assert_eq!(compressor.calls.load(Ordering::Relaxed), 3);
+        assert_eq!(metrics.compressed_buffers, 3);
+
+        // The level is validated once the built-in codec replaces the custom compressor
+        let err = options
+            .clone()
+            .try_with_compression(Some(crate::CompressionType::ZSTD))
+            .unwrap_err();
+        assert!(
+            err.to_string().contains("zstd compression level 1000"),
+            "{err}"
+        );
+
+        // Setting the compression replaces the custom compressor
+        let options = options
+            .try_with_compression_level(1)
+            .unwrap()
+            .try_with_compression(Some(crate::CompressionType::ZSTD))
+            .unwrap();
+        assert!(options.compressor.is_none());
+
+        let options = IpcWriteOptions::try_new(8, false, MetadataVersion::V4)
+            .unwrap()
+            .try_with_compressor(compressor);
+        assert!(options.is_err());
+    }
+
     #[test]
     fn test_write_file() {
         let schema = Schema::new(vec![Field::new("field1", DataType::UInt32, true)]);
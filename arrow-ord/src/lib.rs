@@ -56,3 +56,5 @@ pub mod ord;
 pub mod partition;
 pub mod rank;
 pub mod sort;
+pub mod sorted_merge;
+pub mod topk;
@@ -25,7 +25,8 @@ use arrow_array::*;
 use arrow_buffer::ArrowNativeType;
 use arrow_buffer::BooleanBufferBuilder;
 use arrow_data::{ArrayDataBuilder, ByteView, MAX_INLINE_VIEW_LEN};
-use arrow_schema::{ArrowError, DataType};
+use arrow_row::{RowConverter, Rows, SortField};
+use arrow_schema::{ArrowError, DataType, Schema};
 use arrow_select::take::take;
 use std::cmp::Ordering;
 use std::sync::Arc;
@@ -865,6 +866,68 @@ pub struct SortColumn {
     pub options: Option<SortOptions>,
 }
 
+/// One column of a [`RecordBatch`] to be used in lexicographical sort,
+/// identified by its index in the batch's schema
+///
+/// Used by kernels that operate on a sequence of batches, such as
+/// [`SortedMerge`](crate::sorted_merge::SortedMerge) and [`TopK`](crate::topk::TopK)
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SortKey {
+    /// The index of the column to sort
+    pub column: usize,
+    /// Sort options for this column
+    pub options: SortOptions,
+}
+
+impl SortKey {
+    /// Create a new [`SortKey`] for `column` with the provided `options`
+    pub fn new(column: usize, options: SortOptions) -> Self {
+        Self { column, options }
+    }
+}
+
+/// Returns a [`RowConverter`] for the columns of `schema` identified by `keys`
+pub(crate) fn sort_key_converter(
+    schema: &Schema,
+    keys: &[SortKey],
+) -> Result<RowConverter, ArrowError> {
+    if keys.is_empty() {
+        return Err(ArrowError::InvalidArgumentError(
+            "Sort requires at least one sort key".to_string(),
+        ));
+    }
+    let fields = keys
+        .iter()
+        .map(|key| {
+            let field = schema.fields().get(key.column).ok_or_else(|| {
+                ArrowError::InvalidArgumentError(format!(
+                    "Sort key column {} out of bounds for schema with {} fields",
+                    key.column,
+                    schema.fields().len()
+                ))
+            })?;
+            Ok(SortField::new_with_options(
+                field.data_type().clone(),
+                key.options,
+            ))
+        })
+        .collect::<Result<_, ArrowError>>()?;
+    RowConverter::new(fields)
+}
+
+/// Converts the columns of `batch` identified by `keys` to [`Rows`]
+pub(crate) fn sort_key_rows(
+    converter: &RowConverter,
+    batch: &RecordBatch,
+    keys: &[SortKey],
+) -> Result<Rows, ArrowError> {
+    let columns: Vec<_> = keys
+        .iter()
+        .map(|key| Arc::clone(batch.column(key.column)))
+        .collect();
+    converter.convert_columns(&columns)
+}
+
 /// Sort a list of `ArrayRef` using `SortOptions` provided for each array.
 ///
 /// Performs an unstable lexicographical sort on values and indices.
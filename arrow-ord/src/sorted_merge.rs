@@ -0,0 +1,416 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines a streaming k-way merge of sorted [`RecordBatch`] streams
+
+use std::cmp::Ordering;
+
+use arrow_array::{Array, RecordBatch, RecordBatchOptions};
+use arrow_row::{RowConverter, Rows};
+use arrow_schema::{ArrowError, SchemaRef};
+use arrow_select::interleave::interleave;
+
+use crate::sort::{SortKey, sort_key_converter, sort_key_rows};
+
+/// A streaming k-way merge of [`RecordBatch`] streams that are each sorted by
+/// the same [`SortKey`]s, yielding sorted batches of `batch_size` rows
+///
+/// Rows are compared using the [row format](arrow_row), and rows that compare
+/// equal are emitted in the order of the streams they come from, making the
+/// merge stable. Only the current batch of each input stream, and the rows of
+/// the in-progress output batch, are held in memory.
+///
+/// The output is only sorted if each input stream is sorted by `keys`, this is
+/// not checked.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+/// # use arrow_array::cast::AsArray;
+/// # use arrow_array::types::Int32Type;
+/// # use arrow_ord::sorted_merge::SortedMerge;
+/// # use arrow_ord::sort::{SortKey, SortOptions};
+/// let batch = |v: Vec<i32>| {
+///     let a: ArrayRef = Arc::new(Int32Array::from(v));
+///     RecordBatch::try_from_iter([("a", a)]).unwrap()
+/// };
+/// let a = vec![Ok(batch(vec![1, 4])), Ok(batch(vec![7]))];
+/// let b = vec![Ok(batch(vec![2, 3, 8]))];
+///
+/// let schema = a[0].as_ref().unwrap().schema();
+/// let keys = [SortKey::new(0, SortOptions::default())];
+/// let merge = SortedMerge::try_new(schema, [a.into_iter(), b.into_iter()], &keys, 4).unwrap();
+///
+/// let batches = merge.collect::<Result<Vec<_>, _>>().unwrap();
+/// assert_eq!(batches.len(), 2);
+/// assert_eq!(batches[0].column(0).as_primitive::<Int32Type>().values(), &[1, 2, 3, 4]);
+/// assert_eq!(batches[1].column(0).as_primitive::<Int32Type>().values(), &[7, 8]);
+/// ```
+pub struct SortedMerge<I> {
+    schema: SchemaRef,
+    keys: Vec<SortKey>,
+    converter: RowConverter,
+    batch_size: usize,
+    streams: Vec<I>,
+    /// The current position within each stream, `None` once exhausted
+    cursors: Vec<Option<Cursor>>,
+    /// The batches referenced by `indices` or a cursor
+    batches: Vec<RecordBatch>,
+    /// The `(batch, row)` of each row of the in-progress output batch
+    indices: Vec<(usize, usize)>,
+    /// A binary min-heap of the indices of the streams with a cursor
+    heap: Vec<usize>,
+    initialized: bool,
+    done: bool,
+}
+
+/// The position within the current batch of a stream
+struct Cursor {
+    /// The index of the batch in [`SortedMerge::batches`]
+    batch: usize,
+    rows: Rows,
+    offset: usize,
+}
+
+impl<I> std::fmt::Debug for SortedMerge<I> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SortedMerge")
+            .field("schema", &self.schema)
+            .field("keys", &self.keys)
+            .field("batch_size", &self.batch_size)
+            .field("streams", &self.streams.len())
+            .finish()
+    }
+}
+
+impl<I> SortedMerge<I>
+where
+    I: Iterator<Item = Result<RecordBatch, ArrowError>>,
+{
+    /// Create a new [`SortedMerge`] of `streams`, which must yield batches with
+    /// the provided `schema`, each sorted by `keys`
+    ///
+    /// Returns an error if `keys` is empty, refers to columns not in `schema`, or
+    /// contains types not supported by the row format
+    pub fn try_new(
+        schema: SchemaRef,
+        streams: impl IntoIterator<Item = I>,
+        keys: &[SortKey],
+        batch_size: usize,
+    ) -> Result<Self, ArrowError> {
+        if batch_size == 0 {
+            return Err(ArrowError::InvalidArgumentError(
+                "batch_size must be greater than 0".to_string(),
+            ));
+        }
+        let converter = sort_key_converter(&schema, keys)?;
+        let streams: Vec<I> = streams.into_iter().collect();
+        Ok(Self {
+            schema,
+            keys: keys.to_vec(),
+            converter,
+            batch_size,
+            cursors: streams.iter().map(|_| None).collect(),
+            heap: Vec::with_capacity(streams.len()),
+            streams,
+            batches: vec![],
+            indices: Vec::with_capacity(batch_size),
+            initialized: false,
+            done: false,
+        })
+    }
+
+    /// Returns the schema of the merged batches
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Advances `stream` to its next non-empty batch, returning `false` if it is exhausted
+    fn next_batch(&mut self, stream: usize) -> Result<bool, ArrowError> {
+        for batch in self.streams[stream].by_ref() {
+            let batch = batch?;
+            if batch.num_columns() != self.schema.fields().len() {
+                return Err(ArrowError::InvalidArgumentError(format!(
+                    "Expected batch with {} columns, got {}",
+                    self.schema.fields().len(),
+                    batch.num_columns()
+                )));
+            }
+            if batch.num_rows() == 0 {
+                continue;
+            }
+            let rows = sort_key_rows(&self.converter, &batch, &self.keys)?;
+            self.batches.push(batch);
+            self.cursors[stream] = Some(Cursor {
+                batch: self.batches.len() - 1,
+                rows,
+                offset: 0,
+            });
+            return Ok(true);
+        }
+        self.cursors[stream] = None;
+        Ok(false)
+    }
+
+    fn init(&mut self) -> Result<(), ArrowError> {
+        for stream in 0..self.streams.len() {
+            if self.next_batch(stream)? {
+                self.heap.push(stream);
+            }
+        }
+        for idx in (0..self.heap.len() / 2).rev() {
+            sift_down(&mut self.heap, &self.cursors, idx);
+        }
+        self.initialized = true;
+        Ok(())
+    }
+
+    fn next_inner(&mut self) -> Result<Option<RecordBatch>, ArrowError> {
+        if !self.initialized {
+            self.init()?;
+        }
+
+        while self.indices.len() < self.batch_size {
+            let Some(&stream) = self.heap.first() else {
+                break;
+            };
+            let cursor = self.cursors[stream].as_mut().unwrap();
+            self.indices.push((cursor.batch, cursor.offset));
+            cursor.offset += 1;
+
+            if cursor.offset == cursor.rows.num_rows() && !self.next_batch(stream)? {
+                // Stream is exhausted, remove it from the heap
+                self.heap.swap_remove(0);
+            }
+            sift_down(&mut self.heap, &self.cursors, 0);
+        }
+
+        if self.indices.is_empty() {
+            return Ok(None);
+        }
+        self.emit().map(Some)
+    }
+
+    /// Builds the in-progress output batch, releasing batches no longer referenced
+    fn emit(&mut self) -> Result<RecordBatch, ArrowError> {
+        let columns = (0..self.schema.fields().len())
+            .map(|i| {
+                let values: Vec<&dyn Array> =
+                    self.batches.iter().map(|b| b.column(i).as_ref()).collect();
+                interleave(&values, &self.indices)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = RecordBatchOptions::new().with_row_count(Some(self.indices.len()));
+        let batch = RecordBatch::try_new_with_options(self.schema.clone(), columns, &options)?;
+        self.indices.clear();
+
+        // Only retain the batches with an active cursor
+        let batches = std::mem::take(&mut self.batches);
+        for cursor in self.cursors.iter_mut().flatten() {
+            self.batches.push(batches[cursor.batch].clone());
+            cursor.batch = self.batches.len() - 1;
+        }
+        Ok(batch)
+    }
+}
+
+impl<I> Iterator for SortedMerge<I>
+where
+    I: Iterator<Item = Result<RecordBatch, ArrowError>>,
+{
+    type Item = Result<RecordBatch, ArrowError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let next = self.next_inner().transpose();
+        if !matches!(next, Some(Ok(_))) {
+            self.done = true;
+        }
+        next
+    }
+}
+
+/// Compares the current rows of streams `a` and `b`, breaking ties by stream index
+fn compare(cursors: &[Option<Cursor>], a: usize, b: usize) -> Ordering {
+    let row = |stream: usize| {
+        let cursor = cursors[stream].as_ref().unwrap();
+        cursor.rows.row(cursor.offset)
+    };
+    row(a).cmp(&row(b)).then(a.cmp(&b))
+}
+
+/// Restores the heap property of `heap` for the subtree rooted at `idx`
+fn sift_down(heap: &mut [usize], cursors: &[Option<Cursor>], mut idx: usize) {
+    loop {
+        let left = 2 * idx + 1;
+        let right = left + 1;
+        let mut smallest = idx;
+        if left < heap.len() && compare(cursors, heap[left], heap[smallest]).is_lt() {
+            smallest = left;
+        }
+        if right < heap.len() && compare(cursors, heap[right], heap[smallest]).is_lt() {
+            smallest = right;
+        }
+        if smallest == idx {
+            return;
+        }
+        heap.swap(idx, smallest);
+        idx = smallest;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::SortOptions;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int32Type;
+    use arrow_array::{ArrayRef, Int32Array, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+    use std::sync::Arc;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, false),
+        ]))
+    }
+
+    fn batch(a: Vec<Option<i32>>, b: Vec<&str>) -> RecordBatch {
+        let a: ArrayRef = Arc::new(Int32Array::from(a));
+        let b: ArrayRef = Arc::new(StringArray::from(b));
+        RecordBatch::try_new(schema(), vec![a, b]).unwrap()
+    }
+
+    type Stream = std::vec::IntoIter<Result<RecordBatch, ArrowError>>;
+
+    fn merge(streams: Vec<Vec<RecordBatch>>, keys: &[SortKey], size: usize) -> Vec<RecordBatch> {
+        let streams = streams
+            .into_iter()
+            .map(|s| s.into_iter().map(Ok).collect::<Vec<_>>().into_iter());
+        SortedMerge::<Stream>::try_new(schema(), streams, keys, size)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_merge_stable() {
+        let keys = [SortKey::new(0, SortOptions::default())];
+        let streams = vec![
+            vec![batch(vec![None, Some(1), Some(3)], vec!["a0", "a1", "a2"])],
+            vec![
+                batch(vec![], vec![]),
+                batch(vec![None, Some(1)], vec!["b0", "b1"]),
+                batch(vec![Some(3), Some(5)], vec!["b2", "b3"]),
+            ],
+            vec![],
+        ];
+        let batches = merge(streams, &keys, 3);
+        assert_eq!(
+            batches.iter().map(|b| b.num_rows()).collect::<Vec<_>>(),
+            vec![3, 3, 1]
+        );
+
+        let b: Vec<_> = batches
+            .iter()
+            .flat_map(|b| b.column(1).as_string::<i32>().iter().collect::<Vec<_>>())
+            .map(|v| v.unwrap().to_string())
+            .collect();
+        assert_eq!(b, vec!["a0", "b0", "a1", "b1", "a2", "b2", "b3"]);
+    }
+
+    #[test]
+    fn test_merge_multiple_keys() {
+        let keys = [
+            SortKey::new(0, SortOptions::default().desc().nulls_last()),
+            SortKey::new(1, SortOptions::default()),
+        ];
+        let streams = vec![
+            vec![batch(vec![Some(2), Some(2), None], vec!["a", "c", "e"])],
+            vec![batch(vec![Some(2), Some(1)], vec!["b", "d"])],
+        ];
+        let batches = merge(streams, &keys, 10);
+        assert_eq!(batches.len(), 1);
+        assert_eq!(
+            batches[0],
+            batch(
+                vec![Some(2), Some(2), Some(2), Some(1), None],
+                vec!["a", "b", "c", "d", "e"]
+            )
+        );
+    }
+
+    #[test]
+    fn test_merge_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let keys = [SortKey::new(0, SortOptions::default())];
+        let mut expected = vec![];
+        let streams: Vec<Vec<RecordBatch>> = (0..7)
+            .map(|_| {
+                let mut values: Vec<i32> = (0..rng.random_range(0..100))
+                    .map(|_| rng.random_range(0..50))
+                    .collect();
+                values.sort_unstable();
+                expected.extend_from_slice(&values);
+                values
+                    .chunks(rng.random_range(1..20))
+                    .map(|c| {
+                        let b = vec!["x"; c.len()];
+                        batch(c.iter().copied().map(Some).collect(), b)
+                    })
+                    .collect()
+            })
+            .collect();
+        expected.sort_unstable();
+
+        let batches = merge(streams, &keys, 16);
+        assert!(batches.iter().all(|b| b.num_rows() <= 16));
+        let actual: Vec<i32> = batches
+            .iter()
+            .flat_map(|b| b.column(0).as_primitive::<Int32Type>().values().to_vec())
+            .collect();
+        assert_eq!(actual, expected);
+    }
+
+    #[test]
+    fn test_merge_error() {
+        let keys = [SortKey::new(0, SortOptions::default())];
+        let err = ArrowError::ComputeError("failed".to_string());
+        let streams = vec![
+            vec![Ok(batch(vec![Some(1)], vec!["a"]))].into_iter(),
+            vec![Err(err)].into_iter(),
+        ];
+        let mut merge = SortedMerge::try_new(schema(), streams, &keys, 10).unwrap();
+        assert!(merge.next().unwrap().is_err());
+        assert!(merge.next().is_none());
+    }
+
+    #[test]
+    fn test_merge_invalid_keys() {
+        let streams = Vec::<Stream>::new;
+        let err = SortedMerge::try_new(schema(), streams(), &[], 10).unwrap_err();
+        assert!(err.to_string().contains("at least one sort key"));
+
+        let keys = [SortKey::new(2, SortOptions::default())];
+        let err = SortedMerge::try_new(schema(), streams(), &keys, 10).unwrap_err();
+        assert!(err.to_string().contains("out of bounds"));
+    }
+}
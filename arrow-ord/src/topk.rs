@@ -0,0 +1,356 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines [`TopK`], an accumulator of the first `k` rows of a sequence of [`RecordBatch`]
+
+use std::collections::BinaryHeap;
+
+use arrow_array::{Array, RecordBatch, RecordBatchOptions};
+use arrow_row::{OwnedRow, RowConverter};
+use arrow_schema::{ArrowError, SchemaRef};
+use arrow_select::interleave::interleave;
+
+use crate::sort::{SortKey, sort_key_converter, sort_key_rows};
+
+/// The maximum number of rows the heap reserves space for up front, larger
+/// heaps grow as rows are inserted
+const MAX_INITIAL_CAPACITY: usize = 1024;
+
+/// Accumulates the first `k` rows, as ordered by a set of [`SortKey`]s, of a
+/// sequence of [`RecordBatch`], i.e. `ORDER BY .. LIMIT k`
+///
+/// Memory usage is bounded: rows that can no longer be part of the result are
+/// discarded as batches are inserted, and the retained rows are periodically
+/// compacted so that input batches are not kept alive by a handful of rows.
+///
+/// The result is stable, rows that compare equal are returned in the order in
+/// which they were inserted.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+/// # use arrow_array::cast::AsArray;
+/// # use arrow_array::types::Int32Type;
+/// # use arrow_ord::sort::{SortKey, SortOptions};
+/// # use arrow_ord::topk::TopK;
+/// let batch = |v: Vec<i32>| {
+///     let a: ArrayRef = Arc::new(Int32Array::from(v));
+///     RecordBatch::try_from_iter([("a", a)]).unwrap()
+/// };
+/// let b1 = batch(vec![5, 1, 9]);
+/// let b2 = batch(vec![7, 3, 8]);
+///
+/// let keys = [SortKey::new(0, SortOptions::default().desc())];
+/// let mut top = TopK::try_new(b1.schema(), &keys, 3).unwrap();
+/// top.insert_batch(&b1).unwrap();
+/// top.insert_batch(&b2).unwrap();
+///
+/// let result = top.finish().unwrap();
+/// assert_eq!(result.column(0).as_primitive::<Int32Type>().values(), &[9, 8, 7]);
+/// ```
+#[derive(Debug)]
+pub struct TopK {
+    schema: SchemaRef,
+    keys: Vec<SortKey>,
+    converter: RowConverter,
+    k: usize,
+    /// A max-heap of the best `k` rows seen so far
+    heap: BinaryHeap<Entry>,
+    /// The batches referenced by `heap`
+    batches: Vec<RecordBatch>,
+    /// The total number of rows in `batches`
+    buffered_rows: usize,
+    /// The number of rows inserted so far, used to order equal rows
+    inserted: usize,
+}
+
+/// A row retained by [`TopK`]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Entry {
+    row: OwnedRow,
+    /// The insertion order of this row, used to break ties
+    sequence: usize,
+    /// The index of the batch in [`TopK::batches`]
+    batch: usize,
+    /// The index of the row within its batch
+    offset: usize,
+}
+
+impl TopK {
+    /// Create a new [`TopK`] retaining the first `k` rows with the provided `schema`
+    /// ordered by `keys`
+    ///
+    /// Returns an error if `keys` is empty, refers to columns not in `schema`, or
+    /// contains types not supported by the row format
+    pub fn try_new(schema: SchemaRef, keys: &[SortKey], k: usize) -> Result<Self, ArrowError> {
+        let converter = sort_key_converter(&schema, keys)?;
+        Ok(Self {
+            schema,
+            keys: keys.to_vec(),
+            converter,
+            k,
+            heap: BinaryHeap::with_capacity(k.min(MAX_INITIAL_CAPACITY)),
+            batches: vec![],
+            buffered_rows: 0,
+            inserted: 0,
+        })
+    }
+
+    /// Returns the schema of the batches accumulated by this [`TopK`]
+    pub fn schema(&self) -> &SchemaRef {
+        &self.schema
+    }
+
+    /// Returns the number of rows currently retained, at most `k`
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    /// Returns true if no rows are retained
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
+
+    /// Inserts the rows of `batch`, retaining those that are among the first `k`
+    pub fn insert_batch(&mut self, batch: &RecordBatch) -> Result<(), ArrowError> {
+        if batch.num_columns() != self.schema.fields().len() {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Expected batch with {} columns, got {}",
+                self.schema.fields().len(),
+                batch.num_columns()
+            )));
+        }
+        if batch.num_rows() == 0 || self.k == 0 {
+            return Ok(());
+        }
+
+        let rows = sort_key_rows(&self.converter, batch, &self.keys)?;
+        let batch_idx = self.batches.len();
+        let mut retained = false;
+        for (offset, row) in rows.iter().enumerate() {
+            let sequence = self.inserted + offset;
+            if self.heap.len() == self.k {
+                // As the sequence of this row is larger than that of any retained row,
+                // it is only retained if it is strictly less than the current maximum
+                let mut max = self.heap.peek_mut().unwrap();
+                if row >= max.row.row() {
+                    continue;
+                }
+                *max = Entry {
+                    row: row.owned(),
+                    sequence,
+                    batch: batch_idx,
+                    offset,
+                };
+            } else {
+                self.heap.push(Entry {
+                    row: row.owned(),
+                    sequence,
+                    batch: batch_idx,
+                    offset,
+                });
+            }
+            retained = true;
+        }
+        self.inserted += batch.num_rows();
+
+        if retained {
+            self.batches.push(batch.clone());
+            self.buffered_rows += batch.num_rows();
+            if self.buffered_rows > self.k.saturating_mul(2) && self.batches.len() > 1 {
+                self.compact()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the retained rows as a single [`RecordBatch`]
+    fn interleave_retained(&self, entries: &[&Entry]) -> Result<RecordBatch, ArrowError> {
+        let indices: Vec<_> = entries.iter().map(|e| (e.batch, e.offset)).collect();
+        let columns = (0..self.schema.fields().len())
+            .map(|i| {
+                let values: Vec<&dyn Array> =
+                    self.batches.iter().map(|b| b.column(i).as_ref()).collect();
+                interleave(&values, &indices)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let options = RecordBatchOptions::new().with_row_count(Some(indices.len()));
+        RecordBatch::try_new_with_options(self.schema.clone(), columns, &options)
+    }
+
+    /// Copies the retained rows into a single batch, releasing the input batches
+    fn compact(&mut self) -> Result<(), ArrowError> {
+        let entries: Vec<_> = self.heap.iter().collect();
+        let batch = self.interleave_retained(&entries)?;
+
+        let heap = std::mem::take(&mut self.heap);
+        self.heap = heap
+            .into_iter()
+            .enumerate()
+            .map(|(offset, entry)| Entry {
+                batch: 0,
+                offset,
+                ..entry
+            })
+            .collect();
+        self.buffered_rows = batch.num_rows();
+        self.batches = vec![batch];
+        Ok(())
+    }
+
+    /// Returns the retained rows, in sorted order
+    pub fn finish(self) -> Result<RecordBatch, ArrowError> {
+        if self.heap.is_empty() {
+            return Ok(RecordBatch::new_empty(self.schema.clone()));
+        }
+        let mut entries: Vec<_> = self.heap.iter().collect();
+        entries.sort_unstable();
+        self.interleave_retained(&entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sort::{SortColumn, SortOptions, lexsort_to_indices};
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::Int32Type;
+    use arrow_array::{ArrayRef, Int32Array, StringArray};
+    use arrow_schema::{DataType, Field, Schema};
+    use arrow_select::concat::concat_batches;
+    use arrow_select::take::take_record_batch;
+    use rand::{Rng, SeedableRng, rngs::StdRng};
+    use std::sync::Arc;
+
+    fn schema() -> SchemaRef {
+        Arc::new(Schema::new(vec![
+            Field::new("a", DataType::Int32, true),
+            Field::new("b", DataType::Utf8, false),
+        ]))
+    }
+
+    fn batch(a: Vec<Option<i32>>, b: Vec<&str>) -> RecordBatch {
+        let a: ArrayRef = Arc::new(Int32Array::from(a));
+        let b: ArrayRef = Arc::new(StringArray::from(b));
+        RecordBatch::try_new(schema(), vec![a, b]).unwrap()
+    }
+
+    #[test]
+    fn test_topk_stable() {
+        let keys = [SortKey::new(0, SortOptions::default().nulls_last())];
+        let mut top = TopK::try_new(schema(), &keys, 4).unwrap();
+        top.insert_batch(&batch(vec![Some(2), None, Some(1)], vec!["a", "b", "c"]))
+            .unwrap();
+        top.insert_batch(&batch(vec![], vec![])).unwrap();
+        top.insert_batch(&batch(vec![Some(1), Some(2), Some(0)], vec!["d", "e", "f"]))
+            .unwrap();
+        assert_eq!(top.len(), 4);
+
+        let result = top.finish().unwrap();
+        let expected = batch(
+            vec![Some(0), Some(1), Some(1), Some(2)],
+            vec!["f", "c", "d", "a"],
+        );
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_topk_fewer_than_k() {
+        let keys = [SortKey::new(1, SortOptions::default().desc())];
+        let top = TopK::try_new(schema(), &keys, 10).unwrap();
+        assert!(top.is_empty());
+        assert_eq!(top.finish().unwrap().num_rows(), 0);
+
+        let mut top = TopK::try_new(schema(), &keys, 10).unwrap();
+        top.insert_batch(&batch(vec![Some(1), Some(2)], vec!["x", "y"]))
+            .unwrap();
+        let result = top.finish().unwrap();
+        assert_eq!(result, batch(vec![Some(2), Some(1)], vec!["y", "x"]));
+    }
+
+    #[test]
+    fn test_topk_large_k() {
+        let keys = [SortKey::new(0, SortOptions::default())];
+        let mut top = TopK::try_new(schema(), &keys, usize::MAX).unwrap();
+        top.insert_batch(&batch(vec![Some(2), Some(1)], vec!["x", "y"]))
+            .unwrap();
+        top.insert_batch(&batch(vec![Some(0)], vec!["z"])).unwrap();
+        let result = top.finish().unwrap();
+        assert_eq!(
+            result,
+            batch(vec![Some(0), Some(1), Some(2)], vec!["z", "y", "x"])
+        );
+    }
+
+    #[test]
+    fn test_topk_zero() {
+        let keys = [SortKey::new(0, SortOptions::default())];
+        let mut top = TopK::try_new(schema(), &keys, 0).unwrap();
+        top.insert_batch(&batch(vec![Some(1)], vec!["x"])).unwrap();
+        assert_eq!(top.finish().unwrap().num_rows(), 0);
+    }
+
+    #[test]
+    fn test_topk_random() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let keys = [
+            SortKey::new(0, SortOptions::default().desc()),
+            SortKey::new(1, SortOptions::default()),
+        ];
+        let strings: Vec<String> = (0..20).map(|i| format!("s{i}")).collect();
+        let batches: Vec<RecordBatch> = (0..30)
+            .map(|_| {
+                let len = rng.random_range(0..50);
+                let a = (0..len)
+                    .map(|_| rng.random_bool(0.9).then(|| rng.random_range(0..100)))
+                    .collect();
+                let b = (0..len)
+                    .map(|_| strings[rng.random_range(0..20)].as_str())
+                    .collect();
+                batch(a, b)
+            })
+            .collect();
+
+        let all = concat_batches(&schema(), &batches).unwrap();
+        for k in [1, 7, 100, 2000] {
+            let mut top = TopK::try_new(schema(), &keys, k).unwrap();
+            for b in &batches {
+                top.insert_batch(b).unwrap();
+                assert!(top.batches.len() == 1 || top.buffered_rows <= 2 * k);
+            }
+            let result = top.finish().unwrap();
+
+            let columns: Vec<_> = keys
+                .iter()
+                .map(|key| SortColumn {
+                    values: all.column(key.column).clone(),
+                    options: Some(key.options),
+                })
+                .collect();
+            let indices = lexsort_to_indices(&columns, Some(k)).unwrap();
+            let expected = take_record_batch(&all, &indices).unwrap();
+            assert_eq!(
+                result.column(0).as_primitive::<Int32Type>(),
+                expected.column(0).as_primitive::<Int32Type>()
+            );
+            assert_eq!(
+                result.column(1).as_string::<i32>(),
+                expected.column(1).as_string::<i32>()
+            );
+        }
+    }
+}
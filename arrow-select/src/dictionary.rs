@@ -17,10 +17,13 @@
 
 //! Dictionary utilities for Arrow arrays
 
+use std::cmp::Ordering;
 use std::sync::Arc;
 
+use crate::distinct::Groups;
 use crate::filter::filter;
 use crate::interleave::interleave;
+use crate::take::take;
 use ahash::RandomState;
 use arrow_array::builder::BooleanBufferBuilder;
 use arrow_array::types::{
@@ -29,7 +32,7 @@ use arrow_array::types::{
 };
 use arrow_array::{
     AnyDictionaryArray, Array, ArrayRef, ArrowNativeTypeOp, BooleanArray, DictionaryArray,
-    GenericByteArray, PrimitiveArray, downcast_dictionary_array,
+    GenericByteArray, PrimitiveArray, UInt64Array, downcast_dictionary_array,
 };
 use arrow_array::{cast::AsArray, downcast_primitive};
 use arrow_buffer::{ArrowNativeType, BooleanBuffer, ScalarBuffer, ToByteSlice};
@@ -91,6 +94,64 @@ pub fn garbage_collect_any_dictionary(
     )
 }
 
+/// Dictionary encodes `array`, returning a [`DictionaryArray`] with key type `K`
+///
+/// The dictionary contains the distinct non-null values of `array` in the order in
+/// which they first occur, see [`crate::distinct`] for how values are compared.
+/// Null slots in `array` are represented by null keys.
+///
+/// Returns [`ArrowError::DictionaryKeyOverflowError`] if the number of distinct values
+/// cannot be represented by `K`
+///
+/// ```
+/// # use arrow_array::{Array, Int8Array, StringArray};
+/// # use arrow_array::types::Int8Type;
+/// # use arrow_select::dictionary::dictionary_encode;
+/// let array = StringArray::from(vec![Some("b"), None, Some("a"), Some("b")]);
+/// let dictionary = dictionary_encode::<Int8Type>(&array).unwrap();
+/// assert_eq!(dictionary.keys(), &Int8Array::from(vec![Some(0), None, Some(1), Some(0)]));
+/// assert_eq!(dictionary.values().as_ref(), &StringArray::from(vec!["b", "a"]));
+/// ```
+pub fn dictionary_encode<K: ArrowDictionaryKeyType>(
+    array: &dyn Array,
+) -> Result<DictionaryArray<K>, ArrowError> {
+    if let DataType::Dictionary(_, _) = array.data_type() {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "Cannot dictionary encode an array that is already dictionary encoded: {}",
+            array.data_type()
+        )));
+    }
+
+    let groups = Groups::try_new(array, true)?;
+    let null_group = groups.null_group.unwrap_or(usize::MAX);
+
+    let mut keys = Vec::with_capacity(groups.keys.len());
+    for group in groups.keys {
+        // Groups after the null group are shifted down to account for its removal
+        let key = match group.cmp(&null_group) {
+            Ordering::Less => group,
+            Ordering::Equal => {
+                keys.push(K::Native::ZERO);
+                continue;
+            }
+            Ordering::Greater => group - 1,
+        };
+        keys.push(K::Native::from_usize(key).ok_or(ArrowError::DictionaryKeyOverflowError)?);
+    }
+
+    let indices: UInt64Array = groups
+        .first
+        .iter()
+        .enumerate()
+        .filter(|(group, _)| *group != null_group)
+        .map(|(_, idx)| *idx as u64)
+        .collect();
+    let values = take(array, &indices, None)?;
+
+    let keys = PrimitiveArray::<K>::new(keys.into(), array.logical_nulls());
+    DictionaryArray::try_new(keys, values)
+}
+
 /// A best effort interner that maintains a fixed number of buckets
 /// and interns keys based on their hash value
 ///
@@ -527,4 +588,32 @@ mod tests {
         let expected = StringArray::from(vec!["b"]);
         assert_eq!(merged.values.as_ref(), &expected);
     }
+
+    #[test]
+    fn test_dictionary_encode() {
+        let array = Int32Array::from(vec![None, Some(7), Some(3), None, Some(7)]);
+        let dict = dictionary_encode::<Int8Type>(&array).unwrap();
+
+        let expected_keys = Int8Array::from(vec![None, Some(0), Some(1), None, Some(0)]);
+        assert_eq!(dict.keys(), &expected_keys);
+        assert_eq!(dict.values().as_ref(), &Int32Array::from(vec![7, 3]));
+    }
+
+    #[test]
+    fn test_dictionary_encode_overflow() {
+        let array = Int32Array::from_iter_values(0..200);
+        let err = dictionary_encode::<Int8Type>(&array).unwrap_err();
+        assert!(matches!(err, ArrowError::DictionaryKeyOverflowError));
+
+        let dict = dictionary_encode::<Int32Type>(&array).unwrap();
+        assert_eq!(dict.values().len(), 200);
+    }
+
+    #[test]
+    fn test_dictionary_encode_dictionary() {
+        let values = StringArray::from_iter_values(["a", "b"]);
+        let keys = Int32Array::from_iter_values([1, 0]);
+        let dict = DictionaryArray::new(keys, Arc::new(values));
+        assert!(dictionary_encode::<Int32Type>(&dict).is_err());
+    }
 }
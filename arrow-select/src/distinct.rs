@@ -0,0 +1,451 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Defines kernels for finding the distinct values of an array, such as
+//! [`unique`], [`value_counts`] and [`count_distinct`]
+//!
+//! Values are compared for equality as they would be by the row format of
+//! [`arrow_row`], in particular floating point values are compared by their
+//! bit pattern, so `-0.0` and `0.0` are distinct, and `NaN`s with the same
+//! payload are equal. All null slots are considered equal to each other.
+
+use std::collections::HashMap;
+use std::collections::hash_map::Entry;
+
+use crate::take::take;
+use ahash::RandomState;
+use arrow_array::cast::AsArray;
+use arrow_array::types::{
+    ArrowPrimitiveType, BinaryViewType, ByteArrayType, ByteViewType, StringViewType,
+};
+use arrow_array::{
+    Array, ArrayRef, GenericByteArray, GenericByteViewArray, Int64Array, PrimitiveArray,
+    UInt64Array, downcast_primitive, make_array,
+};
+use arrow_buffer::{NullBuffer, ToByteSlice};
+use arrow_row::{RowConverter, SortField};
+use arrow_schema::{ArrowError, DataType, SortOptions};
+
+/// The order in which [`unique`] and [`value_counts`] return distinct values
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum DistinctOrder {
+    /// Return values in the order in which they first occur in the input
+    #[default]
+    FirstSeen,
+    /// Return values sorted according to the provided [`SortOptions`]
+    Sorted(SortOptions),
+}
+
+/// Returns the distinct values of `array`, in the order in which they first occur
+///
+/// If `array` contains any nulls, the result contains a single null
+///
+/// See [`unique_with_order`] to return the values in sorted order
+///
+/// ```
+/// # use arrow_array::{Int32Array, StringArray};
+/// # use arrow_select::distinct::unique;
+/// let array = StringArray::from(vec![Some("b"), None, Some("a"), Some("b"), None]);
+/// let unique = unique(&array).unwrap();
+/// assert_eq!(unique.as_ref(), &StringArray::from(vec![Some("b"), None, Some("a")]));
+/// ```
+pub fn unique(array: &dyn Array) -> Result<ArrayRef, ArrowError> {
+    unique_with_order(array, DistinctOrder::FirstSeen)
+}
+
+/// Returns the distinct values of `array` in the provided [`DistinctOrder`]
+///
+/// ```
+/// # use arrow_array::Int32Array;
+/// # use arrow_schema::SortOptions;
+/// # use arrow_select::distinct::{unique_with_order, DistinctOrder};
+/// let array = Int32Array::from(vec![Some(3), None, Some(1), Some(3)]);
+/// let order = DistinctOrder::Sorted(SortOptions::default().desc().nulls_last());
+/// let unique = unique_with_order(&array, order).unwrap();
+/// assert_eq!(unique.as_ref(), &Int32Array::from(vec![Some(3), Some(1), None]));
+/// ```
+pub fn unique_with_order(array: &dyn Array, order: DistinctOrder) -> Result<ArrayRef, ArrowError> {
+    let groups = Groups::try_new(array, false)?;
+    let values = take(array, &groups.first_indices(), None)?;
+    match order {
+        DistinctOrder::FirstSeen => Ok(values),
+        DistinctOrder::Sorted(options) => {
+            let indices = sort_indices(&values, options)?;
+            take(values.as_ref(), &indices, None)
+        }
+    }
+}
+
+/// Returns the distinct values of `array` and the number of times each occurs
+///
+/// Nulls are counted as a single distinct value
+///
+/// ```
+/// # use arrow_array::{Int64Array, StringArray};
+/// # use arrow_select::distinct::{value_counts, DistinctOrder};
+/// let array = StringArray::from(vec![Some("b"), None, Some("a"), Some("b"), None]);
+/// let (values, counts) = value_counts(&array, DistinctOrder::FirstSeen).unwrap();
+/// assert_eq!(values.as_ref(), &StringArray::from(vec![Some("b"), None, Some("a")]));
+/// assert_eq!(counts, Int64Array::from(vec![2, 2, 1]));
+/// ```
+pub fn value_counts(
+    array: &dyn Array,
+    order: DistinctOrder,
+) -> Result<(ArrayRef, Int64Array), ArrowError> {
+    let groups = Groups::try_new(array, false)?;
+    let values = take(array, &groups.first_indices(), None)?;
+    let counts = Int64Array::from(groups.counts);
+    match order {
+        DistinctOrder::FirstSeen => Ok((values, counts)),
+        DistinctOrder::Sorted(options) => {
+            let indices = sort_indices(&values, options)?;
+            let values = take(values.as_ref(), &indices, None)?;
+            let counts = take(&counts, &indices, None)?;
+            Ok((values, counts.as_primitive().clone()))
+        }
+    }
+}
+
+/// Returns the number of distinct non-null values in `array`
+///
+/// ```
+/// # use arrow_array::Int32Array;
+/// # use arrow_select::distinct::count_distinct;
+/// let array = Int32Array::from(vec![Some(1), None, Some(2), Some(1)]);
+/// assert_eq!(count_distinct(&array).unwrap(), 2);
+/// ```
+pub fn count_distinct(array: &dyn Array) -> Result<usize, ArrowError> {
+    let groups = Groups::try_new(array, false)?;
+    Ok(groups.first.len() - groups.null_group.is_some() as usize)
+}
+
+/// Returns the indices that sort `values` according to `options`
+fn sort_indices(values: &ArrayRef, options: SortOptions) -> Result<UInt64Array, ArrowError> {
+    let converter = RowConverter::new(vec![SortField::new_with_options(
+        values.data_type().clone(),
+        options,
+    )])?;
+    let rows = converter.convert_columns(std::slice::from_ref(values))?;
+    let mut indices: Vec<_> = (0..rows.num_rows() as u64).collect();
+    indices.sort_unstable_by_key(|idx| rows.row(*idx as usize));
+    Ok(UInt64Array::from(indices))
+}
+
+/// The distinct values of an array, identified by the index of their first occurrence
+pub(crate) struct Groups {
+    /// The index of the first occurrence of each group
+    pub(crate) first: Vec<usize>,
+    /// The number of rows in each group
+    pub(crate) counts: Vec<i64>,
+    /// The group of each row, only populated if requested
+    pub(crate) keys: Vec<usize>,
+    /// The group containing the null rows, if any
+    pub(crate) null_group: Option<usize>,
+}
+
+macro_rules! primitive_groups_helper {
+    ($t:ty, $array:expr, $nulls:expr, $with_keys:expr) => {
+        Groups::from_primitive::<$t>($array.as_primitive(), $nulls, $with_keys)
+    };
+}
+
+impl Groups {
+    /// Computes the distinct values of `array`, if `with_keys` is true also
+    /// records the group of each row in [`Self::keys`]
+    pub(crate) fn try_new(array: &dyn Array, with_keys: bool) -> Result<Self, ArrowError> {
+        let nulls = array.logical_nulls();
+        let nulls = nulls.as_ref();
+        downcast_primitive! {
+            array.data_type() => (primitive_groups_helper, array, nulls, with_keys),
+            DataType::Utf8 => Ok(Self::from_bytes(array.as_string::<i32>(), nulls, with_keys)),
+            DataType::LargeUtf8 => Ok(Self::from_bytes(array.as_string::<i64>(), nulls, with_keys)),
+            DataType::Binary => Ok(Self::from_bytes(array.as_binary::<i32>(), nulls, with_keys)),
+            DataType::LargeBinary => Ok(Self::from_bytes(array.as_binary::<i64>(), nulls, with_keys)),
+            DataType::Utf8View => Ok(Self::from_byte_view::<StringViewType>(array.as_string_view(), nulls, with_keys)),
+            DataType::BinaryView => Ok(Self::from_byte_view::<BinaryViewType>(array.as_binary_view(), nulls, with_keys)),
+            DataType::FixedSizeBinary(_) => {
+                let array = array.as_fixed_size_binary();
+                Ok(Self::from_fn(array.len(), nulls, with_keys, |idx| array.value(idx)))
+            }
+            _ => Self::from_rows(array, nulls, with_keys),
+        }
+    }
+
+    fn from_primitive<T: ArrowPrimitiveType>(
+        array: &PrimitiveArray<T>,
+        nulls: Option<&NullBuffer>,
+        with_keys: bool,
+    ) -> Result<Self, ArrowError> {
+        let values = array.values();
+        Ok(Self::from_fn(array.len(), nulls, with_keys, |idx| {
+            values[idx].to_byte_slice()
+        }))
+    }
+
+    fn from_bytes<T: ByteArrayType>(
+        array: &GenericByteArray<T>,
+        nulls: Option<&NullBuffer>,
+        with_keys: bool,
+    ) -> Self {
+        Self::from_fn(array.len(), nulls, with_keys, |idx| {
+            array.value(idx).as_ref()
+        })
+    }
+
+    fn from_byte_view<T: ByteViewType>(
+        array: &GenericByteViewArray<T>,
+        nulls: Option<&NullBuffer>,
+        with_keys: bool,
+    ) -> Self {
+        Self::from_fn(array.len(), nulls, with_keys, |idx| {
+            array.value(idx).as_ref()
+        })
+    }
+
+    /// Falls back to the row format for types without a byte representation
+    /// of their values, such as nested types and dictionaries
+    fn from_rows(
+        array: &dyn Array,
+        nulls: Option<&NullBuffer>,
+        with_keys: bool,
+    ) -> Result<Self, ArrowError> {
+        let converter = RowConverter::new(vec![SortField::new(array.data_type().clone())])?;
+        let rows = converter.convert_columns(&[make_array(array.to_data())])?;
+        Ok(Self::from_fn(array.len(), nulls, with_keys, |idx| {
+            rows.row(idx).data()
+        }))
+    }
+
+    /// Groups the rows `0..len` by the bytes returned by `value`, which is not
+    /// called for rows that are null in `nulls`
+    fn from_fn<'a, F>(len: usize, nulls: Option<&NullBuffer>, with_keys: bool, value: F) -> Self
+    where
+        F: Fn(usize) -> &'a [u8],
+    {
+        let mut map: HashMap<&'a [u8], usize, RandomState> = HashMap::default();
+        let mut groups = Self {
+            first: vec![],
+            counts: vec![],
+            keys: Vec::with_capacity(if with_keys { len } else { 0 }),
+            null_group: None,
+        };
+
+        for idx in 0..len {
+            let group = match nulls.is_some_and(|n| n.is_null(idx)) {
+                true => *groups.null_group.get_or_insert(groups.first.len()),
+                false => match map.entry(value(idx)) {
+                    Entry::Occupied(o) => *o.get(),
+                    Entry::Vacant(v) => *v.insert(groups.first.len()),
+                },
+            };
+
+            if group == groups.first.len() {
+                groups.first.push(idx);
+                groups.counts.push(0);
+            }
+            groups.counts[group] += 1;
+            if with_keys {
+                groups.keys.push(group);
+            }
+        }
+        groups
+    }
+
+    /// Returns the index of the first occurrence of each group
+    pub(crate) fn first_indices(&self) -> UInt64Array {
+        self.first.iter().map(|idx| *idx as u64).collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use arrow_array::builder::{ListBuilder, StringDictionaryBuilder};
+    use arrow_array::types::{Int8Type, Int32Type};
+    use arrow_array::{
+        BooleanArray, Decimal128Array, Float64Array, Int32Array, StringArray, StringViewArray,
+        StructArray,
+    };
+    use arrow_schema::Field;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_unique_primitive() {
+        let array = Int32Array::from(vec![
+            Some(5),
+            Some(1),
+            None,
+            Some(5),
+            Some(3),
+            None,
+            Some(1),
+        ]);
+        let unique = unique(&array).unwrap();
+        let expected = Int32Array::from(vec![Some(5), Some(1), None, Some(3)]);
+        assert_eq!(unique.as_ref(), &expected);
+
+        let sorted =
+            unique_with_order(&array, DistinctOrder::Sorted(SortOptions::default())).unwrap();
+        let expected = Int32Array::from(vec![None, Some(1), Some(3), Some(5)]);
+        assert_eq!(sorted.as_ref(), &expected);
+
+        assert_eq!(count_distinct(&array).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_unique_sliced() {
+        let array = Int32Array::from(vec![1, 2, 2, 3, 3, 4]);
+        let sliced = array.slice(2, 3);
+        let unique = unique(&sliced).unwrap();
+        assert_eq!(unique.as_ref(), &Int32Array::from(vec![2, 3]));
+    }
+
+    #[test]
+    fn test_unique_float() {
+        let array = Float64Array::from(vec![0.0, -0.0, f64::NAN, 0.0, f64::NAN, 1.5]);
+        let (values, counts) = value_counts(&array, DistinctOrder::FirstSeen).unwrap();
+        let values = values.as_primitive::<arrow_array::types::Float64Type>();
+        assert_eq!(values.len(), 4);
+        assert_eq!(values.value(0).to_bits(), 0.0_f64.to_bits());
+        assert_eq!(values.value(1).to_bits(), (-0.0_f64).to_bits());
+        assert!(values.value(2).is_nan());
+        assert_eq!(values.value(3), 1.5);
+        assert_eq!(counts, Int64Array::from(vec![2, 1, 2, 1]));
+    }
+
+    #[test]
+    fn test_value_counts_strings() {
+        let array = StringArray::from(vec![Some("b"), Some("a"), None, Some("b"), Some("c")]);
+        let options = SortOptions::default().desc().nulls_last();
+        let (values, counts) = value_counts(&array, DistinctOrder::Sorted(options)).unwrap();
+        let expected = StringArray::from(vec![Some("c"), Some("b"), Some("a"), None]);
+        assert_eq!(values.as_ref(), &expected);
+        assert_eq!(counts, Int64Array::from(vec![1, 2, 1, 1]));
+    }
+
+    #[test]
+    fn test_unique_string_view() {
+        let array = StringViewArray::from(vec![
+            Some("a string longer than twelve bytes"),
+            Some("short"),
+            Some("a string longer than twelve bytes"),
+            None,
+        ]);
+        let unique = unique(&array).unwrap();
+        let expected = StringViewArray::from(vec![
+            Some("a string longer than twelve bytes"),
+            Some("short"),
+            None,
+        ]);
+        assert_eq!(unique.as_ref(), &expected);
+        assert_eq!(count_distinct(&array).unwrap(), 2);
+    }
+
+    #[test]
+    fn test_unique_decimal() {
+        let array = Decimal128Array::from(vec![123, 456, 123])
+            .with_precision_and_scale(10, 2)
+            .unwrap();
+        let unique = unique(&array).unwrap();
+        let expected = Decimal128Array::from(vec![123, 456])
+            .with_precision_and_scale(10, 2)
+            .unwrap();
+        assert_eq!(unique.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_unique_boolean() {
+        let array = BooleanArray::from(vec![Some(true), Some(true), None, Some(false)]);
+        let (values, counts) = value_counts(&array, DistinctOrder::FirstSeen).unwrap();
+        let expected = BooleanArray::from(vec![Some(true), None, Some(false)]);
+        assert_eq!(values.as_ref(), &expected);
+        assert_eq!(counts, Int64Array::from(vec![2, 1, 1]));
+    }
+
+    #[test]
+    fn test_unique_dictionary() {
+        let mut builder = StringDictionaryBuilder::<Int8Type>::new();
+        builder.append_value("a");
+        builder.append_value("b");
+        builder.append_null();
+        builder.append_value("a");
+        let array = builder.finish();
+
+        let unique = unique(&array).unwrap();
+        let unique = unique.as_dictionary::<Int8Type>();
+        let values: Vec<_> = unique
+            .downcast_dict::<StringArray>()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(values, vec![Some("a"), Some("b"), None]);
+    }
+
+    #[test]
+    fn test_unique_list() {
+        let mut builder = ListBuilder::new(arrow_array::builder::Int32Builder::new());
+        builder.append_value([Some(1), Some(2)]);
+        builder.append_value([Some(1)]);
+        builder.append_null();
+        builder.append_value([Some(1), Some(2)]);
+        builder.append_value([]);
+        builder.append_null();
+        let array = builder.finish();
+
+        let (values, counts) = value_counts(&array, DistinctOrder::FirstSeen).unwrap();
+        let values = values.as_list::<i32>();
+        assert_eq!(values.len(), 4);
+        assert_eq!(
+            values.value(0).as_primitive::<Int32Type>(),
+            &Int32Array::from(vec![1, 2])
+        );
+        assert_eq!(
+            values.value(1).as_primitive::<Int32Type>(),
+            &Int32Array::from(vec![1])
+        );
+        assert!(values.is_null(2));
+        assert!(values.value(3).is_empty());
+        assert_eq!(counts, Int64Array::from(vec![2, 1, 2, 1]));
+        assert_eq!(count_distinct(&array).unwrap(), 3);
+    }
+
+    #[test]
+    fn test_unique_struct() {
+        let a: ArrayRef = Arc::new(Int32Array::from(vec![1, 1, 2, 1]));
+        let b: ArrayRef = Arc::new(StringArray::from(vec!["x", "y", "x", "x"]));
+        let array = StructArray::from(vec![
+            (Arc::new(Field::new("a", DataType::Int32, false)), a),
+            (Arc::new(Field::new("b", DataType::Utf8, false)), b),
+        ]);
+
+        let (values, counts) = value_counts(&array, DistinctOrder::FirstSeen).unwrap();
+        let values = values.as_struct();
+        assert_eq!(values.column(0).as_ref(), &Int32Array::from(vec![1, 1, 2]));
+        assert_eq!(
+            values.column(1).as_ref(),
+            &StringArray::from(vec!["x", "y", "x"])
+        );
+        assert_eq!(counts, Int64Array::from(vec![2, 1, 1]));
+    }
+
+    #[test]
+    fn test_unique_empty() {
+        let array = Int32Array::from(Vec::<i32>::new());
+        assert!(unique(&array).unwrap().is_empty());
+        assert_eq!(count_distinct(&array).unwrap(), 0);
+    }
+}
@@ -27,6 +27,7 @@
 pub mod coalesce;
 pub mod concat;
 pub mod dictionary;
+pub mod distinct;
 pub mod filter;
 pub mod interleave;
 pub mod merge;
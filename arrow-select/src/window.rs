@@ -16,11 +16,26 @@
 // under the License.
 
 //! Defines windowing functions, like `shift`ing
+//!
+//! The analytic kernels in this module, such as [`lag`] and [`cumulative_sum`],
+//! evaluate a function independently over each of a set of partitions, provided
+//! as contiguous ranges covering the whole array in order, such as those returned by
+//! [`Partitions::ranges`](https://docs.rs/arrow-ord/latest/arrow_ord/partition/struct.Partitions.html#method.ranges).
+//! To evaluate a function over the whole array, pass a single range `0..array.len()`.
+
+use std::ops::Range;
 
 use crate::concat::concat;
-use arrow_array::{Array, ArrayRef, make_array, new_null_array};
-use arrow_schema::ArrowError;
-use num_traits::abs;
+use crate::take::take;
+use crate::zip::zip;
+use arrow_array::builder::BooleanBufferBuilder;
+use arrow_array::{
+    Array, ArrayRef, ArrowNativeTypeOp, ArrowNumericType, ArrowPrimitiveType, BooleanArray, Datum,
+    Float64Array, PrimitiveArray, UInt64Array, make_array, new_null_array,
+};
+use arrow_buffer::NullBufferBuilder;
+use arrow_schema::{ArrowError, DataType};
+use num_traits::{ToPrimitive, abs};
 
 /// Shifts array by defined number of items (to left or right)
 /// A positive value for `offset` shifts the array to the right
@@ -78,10 +93,437 @@ pub fn shift(array: &dyn Array, offset: i64) -> Result<ArrayRef, ArrowError> {
     }
 }
 
+/// Returns, for each row, the value `offset` rows before it within its partition,
+/// or `default` if there is no such row
+///
+/// `default` may be a scalar or an array of the same length as `array`, if `None`
+/// rows with no preceding value are null.
+///
+/// ```
+/// # use arrow_array::{Int32Array, Scalar};
+/// # use arrow_select::window::lag;
+/// let a = Int32Array::from(vec![1, 2, 3, 4, 5]);
+/// let default = Scalar::new(Int32Array::from(vec![0]));
+///
+/// // Two partitions, `[1, 2, 3]` and `[4, 5]`
+/// let res = lag(&a, &[0..3, 3..5], 1, Some(&default)).unwrap();
+/// assert_eq!(res.as_ref(), &Int32Array::from(vec![0, 1, 2, 0, 4]));
+/// ```
+pub fn lag(
+    array: &dyn Array,
+    partitions: &[Range<usize>],
+    offset: usize,
+    default: Option<&dyn Datum>,
+) -> Result<ArrayRef, ArrowError> {
+    shift_partitions(array, partitions, default, |partition, idx| {
+        idx.checked_sub(offset)
+            .filter(|src| *src >= partition.start)
+    })
+}
+
+/// Returns, for each row, the value `offset` rows after it within its partition,
+/// or `default` if there is no such row
+///
+/// See [`lag`] for details
+///
+/// ```
+/// # use arrow_array::Int32Array;
+/// # use arrow_select::window::lead;
+/// let a = Int32Array::from(vec![1, 2, 3, 4, 5]);
+/// let res = lead(&a, &[0..3, 3..5], 1, None).unwrap();
+/// assert_eq!(res.as_ref(), &Int32Array::from(vec![Some(2), Some(3), None, Some(5), None]));
+/// ```
+pub fn lead(
+    array: &dyn Array,
+    partitions: &[Range<usize>],
+    offset: usize,
+    default: Option<&dyn Datum>,
+) -> Result<ArrayRef, ArrowError> {
+    shift_partitions(array, partitions, default, |partition, idx| {
+        idx.checked_add(offset).filter(|src| *src < partition.end)
+    })
+}
+
+/// Computes [`lag`] and [`lead`], `source` returns the index of the value of
+/// each row within its partition, if any
+fn shift_partitions<F>(
+    array: &dyn Array,
+    partitions: &[Range<usize>],
+    default: Option<&dyn Datum>,
+    source: F,
+) -> Result<ArrayRef, ArrowError>
+where
+    F: Fn(&Range<usize>, usize) -> Option<usize>,
+{
+    check_partitions(array.len(), partitions)?;
+
+    let mut indices = Vec::with_capacity(array.len());
+    let mut mask = BooleanBufferBuilder::new(array.len());
+    for partition in partitions {
+        for idx in partition.clone() {
+            let src = source(partition, idx);
+            indices.push(src.map(|s| s as u64));
+            mask.append(src.is_some());
+        }
+    }
+    let shifted = take(array, &UInt64Array::from(indices), None)?;
+    match default {
+        Some(default) => zip(&BooleanArray::new(mask.finish(), None), &shifted, default),
+        None => Ok(shifted),
+    }
+}
+
+/// Returns the 1-based position of each row within its partition
+///
+/// ```
+/// # use arrow_array::UInt64Array;
+/// # use arrow_select::window::row_number;
+/// let res = row_number(&[0..3, 3..5]);
+/// assert_eq!(res, UInt64Array::from(vec![1, 2, 3, 1, 2]));
+/// ```
+pub fn row_number(partitions: &[Range<usize>]) -> UInt64Array {
+    partitions.iter().flat_map(|p| 1..=p.len() as u64).collect()
+}
+
+/// Returns, for each row, the first value of its partition
+///
+/// If `ignore_nulls` is true, returns the first non-null value of the partition,
+/// or null if there is none
+///
+/// ```
+/// # use arrow_array::Int32Array;
+/// # use arrow_select::window::first_value;
+/// let a = Int32Array::from(vec![None, Some(2), Some(3), Some(4)]);
+/// let res = first_value(&a, &[0..3, 3..4], true).unwrap();
+/// assert_eq!(res.as_ref(), &Int32Array::from(vec![2, 2, 2, 4]));
+/// ```
+pub fn first_value(
+    array: &dyn Array,
+    partitions: &[Range<usize>],
+    ignore_nulls: bool,
+) -> Result<ArrayRef, ArrowError> {
+    let nulls = array.logical_nulls();
+    let is_valid = |idx: &usize| !ignore_nulls || nulls.as_ref().is_none_or(|n| n.is_valid(*idx));
+    broadcast_partitions(array, partitions, |p| p.clone().find(is_valid))
+}
+
+/// Returns, for each row, the last value of its partition
+///
+/// If `ignore_nulls` is true, returns the last non-null value of the partition,
+/// or null if there is none
+///
+/// ```
+/// # use arrow_array::Int32Array;
+/// # use arrow_select::window::last_value;
+/// let a = Int32Array::from(vec![Some(1), Some(2), None, Some(4)]);
+/// let res = last_value(&a, &[0..3, 3..4], false).unwrap();
+/// assert_eq!(res.as_ref(), &Int32Array::from(vec![None, None, None, Some(4)]));
+/// ```
+pub fn last_value(
+    array: &dyn Array,
+    partitions: &[Range<usize>],
+    ignore_nulls: bool,
+) -> Result<ArrayRef, ArrowError> {
+    let nulls = array.logical_nulls();
+    let is_valid = |idx: &usize| !ignore_nulls || nulls.as_ref().is_none_or(|n| n.is_valid(*idx));
+    broadcast_partitions(array, partitions, |p| p.clone().rev().find(is_valid))
+}
+
+/// Returns an array where each row contains the value at the index returned by
+/// `f` for its partition, or null if `f` returns `None`
+fn broadcast_partitions<F>(
+    array: &dyn Array,
+    partitions: &[Range<usize>],
+    f: F,
+) -> Result<ArrayRef, ArrowError>
+where
+    F: Fn(&Range<usize>) -> Option<usize>,
+{
+    check_partitions(array.len(), partitions)?;
+    let mut indices = Vec::with_capacity(array.len());
+    for partition in partitions {
+        let idx = f(partition).map(|idx| idx as u64);
+        indices.extend(std::iter::repeat_n(idx, partition.len()));
+    }
+    take(array, &UInt64Array::from(indices), None)
+}
+
+/// Returns the cumulative sum of the non-null values of each partition
+///
+/// Rows are null if all preceding values of their partition, and the row itself, are null.
+/// Returns an error on overflow.
+///
+/// ```
+/// # use arrow_array::Int32Array;
+/// # use arrow_select::window::cumulative_sum;
+/// let a = Int32Array::from(vec![Some(1), None, Some(3), None, Some(5)]);
+/// let res = cumulative_sum(&a, &[0..3, 3..5]).unwrap();
+/// assert_eq!(res, Int32Array::from(vec![Some(1), Some(1), Some(4), None, Some(5)]));
+///
+/// let a = Int32Array::from(vec![i32::MAX, 1]);
+/// assert!(cumulative_sum(&a, &[0..2]).is_err());
+/// ```
+pub fn cumulative_sum<T: ArrowNumericType>(
+    array: &PrimitiveArray<T>,
+    partitions: &[Range<usize>],
+) -> Result<PrimitiveArray<T>, ArrowError> {
+    running(array, partitions, |acc, v| acc.add_checked(v))
+}
+
+/// Returns the cumulative product of the non-null values of each partition
+///
+/// See [`cumulative_sum`] for details
+pub fn cumulative_prod<T: ArrowNumericType>(
+    array: &PrimitiveArray<T>,
+    partitions: &[Range<usize>],
+) -> Result<PrimitiveArray<T>, ArrowError> {
+    running(array, partitions, |acc, v| acc.mul_checked(v))
+}
+
+/// Returns the running minimum of the non-null values of each partition
+///
+/// Floating point values are compared using IEEE 754 totalOrder, see [`cumulative_sum`]
+/// for details on null handling
+///
+/// ```
+/// # use arrow_array::Int32Array;
+/// # use arrow_select::window::running_min;
+/// let a = Int32Array::from(vec![Some(3), None, Some(1), Some(2)]);
+/// let res = running_min(&a, &[0..4]).unwrap();
+/// assert_eq!(res, Int32Array::from(vec![3, 3, 1, 1]));
+/// ```
+pub fn running_min<T: ArrowPrimitiveType>(
+    array: &PrimitiveArray<T>,
+    partitions: &[Range<usize>],
+) -> Result<PrimitiveArray<T>, ArrowError> {
+    running(array, partitions, |acc, v| {
+        Ok(if v.is_lt(acc) { v } else { acc })
+    })
+}
+
+/// Returns the running maximum of the non-null values of each partition
+///
+/// See [`running_min`] for details
+pub fn running_max<T: ArrowPrimitiveType>(
+    array: &PrimitiveArray<T>,
+    partitions: &[Range<usize>],
+) -> Result<PrimitiveArray<T>, ArrowError> {
+    running(array, partitions, |acc, v| {
+        Ok(if v.is_gt(acc) { v } else { acc })
+    })
+}
+
+/// Folds the non-null values of each partition with `f`, returning the accumulator at each row
+fn running<T, F>(
+    array: &PrimitiveArray<T>,
+    partitions: &[Range<usize>],
+    f: F,
+) -> Result<PrimitiveArray<T>, ArrowError>
+where
+    T: ArrowPrimitiveType,
+    F: Fn(T::Native, T::Native) -> Result<T::Native, ArrowError>,
+{
+    check_partitions(array.len(), partitions)?;
+
+    let mut values = Vec::with_capacity(array.len());
+    let mut nulls = NullBufferBuilder::new(array.len());
+    for partition in partitions {
+        let mut acc = None;
+        for idx in partition.clone() {
+            if array.is_valid(idx) {
+                let v = array.value(idx);
+                acc = Some(match acc {
+                    Some(acc) => f(acc, v)?,
+                    None => v,
+                });
+            }
+            values.push(acc.unwrap_or_default());
+            nulls.append(acc.is_some());
+        }
+    }
+    Ok(
+        PrimitiveArray::new(values.into(), nulls.finish())
+            .with_data_type(array.data_type().clone()),
+    )
+}
+
+/// Returns, for each row, the sum of the non-null values of the `frame` rows ending
+/// at that row within its partition, i.e. `ROWS BETWEEN frame - 1 PRECEDING AND CURRENT ROW`
+///
+/// Rows are null if all values in their frame are null. Returns an error on overflow,
+/// or if `frame` is 0.
+///
+/// The sum is updated as the frame slides over each partition, and so this takes
+/// `O(array.len())`. For floating point values, this means results can differ from
+/// summing each frame separately by rounding errors.
+///
+/// ```
+/// # use arrow_array::Int32Array;
+/// # use arrow_select::window::moving_sum;
+/// let a = Int32Array::from(vec![1, 2, 3, 4, 5]);
+/// let res = moving_sum(&a, &[0..5], 2).unwrap();
+/// assert_eq!(res, Int32Array::from(vec![1, 3, 5, 7, 9]));
+/// ```
+pub fn moving_sum<T: ArrowNumericType>(
+    array: &PrimitiveArray<T>,
+    partitions: &[Range<usize>],
+    frame: usize,
+) -> Result<PrimitiveArray<T>, ArrowError> {
+    check_partitions(array.len(), partitions)?;
+    check_frame(frame)?;
+
+    let mut values = Vec::with_capacity(array.len());
+    let mut nulls = NullBufferBuilder::new(array.len());
+    let value = |idx| array.is_valid(idx).then(|| array.value(idx));
+    sliding_sum(partitions, frame, value, |sum, count| {
+        values.push(sum);
+        nulls.append(count > 0);
+    })?;
+    Ok(
+        PrimitiveArray::new(values.into(), nulls.finish())
+            .with_data_type(array.data_type().clone()),
+    )
+}
+
+/// Returns, for each row, the average of the non-null values of the `frame` rows
+/// ending at that row within its partition
+///
+/// Decimal values are divided by their scale. See [`moving_sum`] for details
+///
+/// ```
+/// # use arrow_array::{Float64Array, Int32Array};
+/// # use arrow_select::window::moving_avg;
+/// let a = Int32Array::from(vec![Some(1), Some(2), None, Some(4)]);
+/// let res = moving_avg(&a, &[0..4], 2).unwrap();
+/// assert_eq!(res, Float64Array::from(vec![1.0, 1.5, 2.0, 4.0]));
+/// ```
+pub fn moving_avg<T>(
+    array: &PrimitiveArray<T>,
+    partitions: &[Range<usize>],
+    frame: usize,
+) -> Result<Float64Array, ArrowError>
+where
+    T: ArrowNumericType,
+    T::Native: ToPrimitive,
+{
+    check_partitions(array.len(), partitions)?;
+    check_frame(frame)?;
+
+    let divisor = match array.data_type() {
+        DataType::Decimal32(_, scale)
+        | DataType::Decimal64(_, scale)
+        | DataType::Decimal128(_, scale)
+        | DataType::Decimal256(_, scale) => 10_f64.powi(*scale as i32),
+        _ => 1.,
+    };
+    let converted = (0..array.len())
+        .map(|idx| match array.is_valid(idx) {
+            true => array
+                .value(idx)
+                .to_f64()
+                .map(|v| v / divisor)
+                .ok_or_else(|| {
+                    ArrowError::ComputeError(format!(
+                        "Cannot convert {:?} to f64",
+                        array.value(idx)
+                    ))
+                }),
+            false => Ok(0.),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut values = Vec::with_capacity(array.len());
+    let mut nulls = NullBufferBuilder::new(array.len());
+    let value = |idx| array.is_valid(idx).then(|| converted[idx]);
+    sliding_sum(partitions, frame, value, |sum, count| {
+        values.push(if count > 0 { sum / count as f64 } else { 0. });
+        nulls.append(count > 0);
+    })?;
+    Ok(Float64Array::new(values.into(), nulls.finish()))
+}
+
+/// Calls `f` with the sum and the number of the non-null values of the `frame` rows
+/// ending at each row of each partition, where `value` returns the value of a row
+///
+/// The sum is zero if the frame contains only nulls
+fn sliding_sum<N, V, F>(
+    partitions: &[Range<usize>],
+    frame: usize,
+    value: V,
+    mut f: F,
+) -> Result<(), ArrowError>
+where
+    N: ArrowNativeTypeOp,
+    V: Fn(usize) -> Option<N>,
+    F: FnMut(N, usize),
+{
+    for partition in partitions {
+        let (mut sum, mut count) = (N::ZERO, 0);
+        for idx in partition.clone() {
+            if let Some(old) = idx.checked_sub(frame).filter(|i| *i >= partition.start) {
+                if let Some(old) = value(old) {
+                    count -= 1;
+                    sum = if count == 0 {
+                        N::ZERO
+                    } else if old.sub_wrapping(old).is_zero() {
+                        sum.sub_checked(old)?
+                    } else {
+                        // non-finite floating point values cannot be removed from the sum
+                        (idx + 1 - frame..idx)
+                            .filter_map(&value)
+                            .try_fold(N::ZERO, |acc, v| acc.add_checked(v))?
+                    };
+                }
+            }
+            if let Some(v) = value(idx) {
+                sum = sum.add_checked(v)?;
+                count += 1;
+            }
+            f(sum, count);
+        }
+    }
+    Ok(())
+}
+
+fn check_frame(frame: usize) -> Result<(), ArrowError> {
+    match frame {
+        0 => Err(ArrowError::InvalidArgumentError(
+            "Window frame size must be greater than 0".to_string(),
+        )),
+        _ => Ok(()),
+    }
+}
+
+/// Returns an error if `partitions` are not contiguous ranges covering `0..len`
+fn check_partitions(len: usize, partitions: &[Range<usize>]) -> Result<(), ArrowError> {
+    let mut end = 0;
+    for partition in partitions {
+        if partition.start != end || partition.end < partition.start {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Partitions must be contiguous ranges, got {partition:?} after {end}"
+            )));
+        }
+        end = partition.end;
+    }
+    if end != len {
+        return Err(ArrowError::InvalidArgumentError(format!(
+            "Partitions must cover all {len} rows, got {end}"
+        )));
+    }
+    Ok(())
+}
+
 #[cfg(test)]
+#[allow(clippy::single_range_in_vec_init)]
 mod tests {
     use super::*;
-    use arrow_array::{Float64Array, Int32Array, Int32DictionaryArray};
+    use arrow_array::Scalar;
+    use arrow_array::StringArray;
+    use arrow_array::cast::AsArray;
+    use arrow_array::types::{Int8Type, Int32Type};
+    use arrow_array::{
+        Decimal128Array, Float64Array, Int32Array, Int32DictionaryArray, Int64Array,
+    };
 
     #[test]
     fn test_shift_neg() {
@@ -190,4 +632,238 @@ mod tests {
         let expected: Int32Array = vec![None, None, None].into();
         assert_eq!(res.as_ref(), &expected);
     }
+
+    #[test]
+    fn test_lag_lead() {
+        let a = StringArray::from(vec![Some("a"), None, Some("c"), Some("d"), Some("e")]);
+        let partitions = [0..3, 3..5];
+
+        let res = lag(&a, &partitions, 1, None).unwrap();
+        let expected = StringArray::from(vec![None, Some("a"), None, None, Some("d")]);
+        assert_eq!(res.as_ref(), &expected);
+
+        // Nulls in the input are not replaced by the default
+        let default = Scalar::new(StringArray::from(vec!["x"]));
+        let res = lag(&a, &partitions, 1, Some(&default)).unwrap();
+        let expected = StringArray::from(vec![Some("x"), Some("a"), None, Some("x"), Some("d")]);
+        assert_eq!(res.as_ref(), &expected);
+
+        let res = lead(&a, &partitions, 2, Some(&default)).unwrap();
+        let expected = StringArray::from(vec!["c", "x", "x", "x", "x"]);
+        assert_eq!(res.as_ref(), &expected);
+
+        let res = lead(&a, &partitions, 0, None).unwrap();
+        assert_eq!(res.as_ref(), &a);
+
+        let res = lag(&a, &partitions, usize::MAX, None).unwrap();
+        assert_eq!(res.null_count(), 5);
+    }
+
+    #[test]
+    fn test_lag_array_default() {
+        let a = Int32Array::from(vec![1, 2, 3]);
+        let default = Int32Array::from(vec![10, 20, 30]);
+        let res = lag(&a, &[0..1, 1..3], 1, Some(&default)).unwrap();
+        assert_eq!(res.as_ref(), &Int32Array::from(vec![10, 20, 2]));
+    }
+
+    #[test]
+    fn test_invalid_partitions() {
+        let a = Int32Array::from(vec![1, 2, 3]);
+        let err = lag(&a, &[0..1, 2..3], 1, None).unwrap_err();
+        assert!(err.to_string().contains("contiguous"), "{err}");
+
+        let err = cumulative_sum(&a, &[0..2]).unwrap_err();
+        assert!(err.to_string().contains("cover all 3 rows"), "{err}");
+
+        assert!(cumulative_sum(&Int32Array::from(Vec::<i32>::new()), &[]).is_ok());
+        assert!(moving_sum(&a, &[0..3], 0).is_err());
+    }
+
+    #[test]
+    fn test_row_number() {
+        assert_eq!(row_number(&[]), UInt64Array::from(Vec::<u64>::new()));
+        assert_eq!(
+            row_number(&[0..2, 2..3, 3..6]),
+            UInt64Array::from(vec![1, 2, 1, 1, 2, 3])
+        );
+    }
+
+    #[test]
+    fn test_first_last_value() {
+        let a = Int32Array::from(vec![None, Some(1), Some(2), None, None, Some(3), None]);
+        let partitions = [0..4, 4..5, 5..7];
+
+        let res = first_value(&a, &partitions, false).unwrap();
+        let expected = Int32Array::from(vec![None, None, None, None, None, Some(3), Some(3)]);
+        assert_eq!(res.as_ref(), &expected);
+
+        let res = first_value(&a, &partitions, true).unwrap();
+        let expected = Int32Array::from(vec![
+            Some(1),
+            Some(1),
+            Some(1),
+            Some(1),
+            None,
+            Some(3),
+            Some(3),
+        ]);
+        assert_eq!(res.as_ref(), &expected);
+
+        let res = last_value(&a, &partitions, false).unwrap();
+        assert_eq!(res.null_count(), 7);
+
+        let res = last_value(&a, &partitions, true).unwrap();
+        let expected = Int32Array::from(vec![
+            Some(2),
+            Some(2),
+            Some(2),
+            Some(2),
+            None,
+            Some(3),
+            Some(3),
+        ]);
+        assert_eq!(res.as_ref(), &expected);
+    }
+
+    #[test]
+    fn test_first_value_dictionary() {
+        let a: Int32DictionaryArray = [None, Some("alpha"), Some("beta")].into_iter().collect();
+        let res = first_value(&a, &[0..3], true).unwrap();
+        let res = res.as_dictionary::<Int32Type>();
+        let values: Vec<_> = res
+            .downcast_dict::<StringArray>()
+            .unwrap()
+            .into_iter()
+            .collect();
+        assert_eq!(values, vec![Some("alpha"); 3]);
+    }
+
+    #[test]
+    fn test_cumulative() {
+        let a = Int32Array::from(vec![Some(2), None, Some(3), Some(-1), None, Some(4)]);
+        let partitions = [0..4, 4..6];
+
+        let res = cumulative_sum(&a, &partitions).unwrap();
+        let expected = Int32Array::from(vec![Some(2), Some(2), Some(5), Some(4), None, Some(4)]);
+        assert_eq!(res, expected);
+
+        let res = cumulative_prod(&a, &partitions).unwrap();
+        let expected = Int32Array::from(vec![Some(2), Some(2), Some(6), Some(-6), None, Some(4)]);
+        assert_eq!(res, expected);
+
+        let res = running_min(&a, &partitions).unwrap();
+        let expected = Int32Array::from(vec![Some(2), Some(2), Some(2), Some(-1), None, Some(4)]);
+        assert_eq!(res, expected);
+
+        let res = running_max(&a, &partitions).unwrap();
+        let expected = Int32Array::from(vec![Some(2), Some(2), Some(3), Some(3), None, Some(4)]);
+        assert_eq!(res, expected);
+    }
+
+    #[test]
+    fn test_cumulative_overflow() {
+        let a = PrimitiveArray::<Int8Type>::from(vec![100, 27, 1]);
+        assert!(cumulative_sum(&a, &[0..2, 2..3]).is_ok());
+        assert!(cumulative_sum(&a, &[0..3]).is_err());
+        assert!(cumulative_prod(&a, &[0..1, 1..3]).is_ok());
+        assert!(cumulative_prod(&a, &[0..3]).is_err());
+    }
+
+    #[test]
+    fn test_cumulative_preserves_type() {
+        let a = arrow_array::Decimal128Array::from(vec![100, 250])
+            .with_precision_and_scale(10, 2)
+            .unwrap();
+        let res = cumulative_sum(&a, &[0..2]).unwrap();
+        assert_eq!(res.data_type(), a.data_type());
+        assert_eq!(res.values(), &[100, 350]);
+    }
+
+    #[test]
+    fn test_running_float() {
+        let a = Float64Array::from(vec![1.0, f64::NAN, -0.0, 0.0]);
+        let res = running_max(&a, &[0..4]).unwrap();
+        assert!(res.value(1).is_nan());
+        assert!(res.value(3).is_nan());
+
+        let res = running_min(&a, &[0..4]).unwrap();
+        assert_eq!(res.value(3).to_bits(), (-0.0_f64).to_bits());
+    }
+
+    #[test]
+    fn test_moving() {
+        let a = Int32Array::from(vec![Some(1), None, Some(3), Some(4), None, None, Some(7)]);
+        let partitions = [0..4, 4..7];
+
+        let res = moving_sum(&a, &partitions, 2).unwrap();
+        let expected = Int32Array::from(vec![
+            Some(1),
+            Some(1),
+            Some(3),
+            Some(7),
+            None,
+            None,
+            Some(7),
+        ]);
+        assert_eq!(res, expected);
+
+        let res = moving_sum(&a, &partitions, 100).unwrap();
+        assert_eq!(res, cumulative_sum(&a, &partitions).unwrap());
+
+        let res = moving_avg(&a, &partitions, 3).unwrap();
+        let expected = Float64Array::from(vec![
+            Some(1.0),
+            Some(1.0),
+            Some(2.0),
+            Some(3.5),
+            None,
+            None,
+            Some(7.0),
+        ]);
+        assert_eq!(res, expected);
+
+        let a = PrimitiveArray::<Int8Type>::from(vec![100, 27, 1]);
+        assert!(moving_sum(&a, &[0..3], 2).is_ok());
+        assert!(moving_sum(&a, &[0..3], 3).is_err());
+
+        let res = moving_avg(&a, &[0..3], 3).unwrap();
+        assert_eq!(res.value(2), 128.0 / 3.0);
+    }
+
+    #[test]
+    fn test_moving_large_frame() {
+        let a = Int64Array::from_iter_values(0..10_000);
+        let res = moving_sum(&a, &[0..10_000], 5_000).unwrap();
+        assert_eq!(res.value(4_999), 4_999 * 5_000 / 2);
+        assert_eq!(res.value(9_999), (5_000..10_000).sum::<i64>());
+    }
+
+    #[test]
+    fn test_moving_float_non_finite() {
+        let a = Float64Array::from(vec![1.0, f64::NAN, 2.0, 3.0, f64::INFINITY, 4.0, 5.0]);
+        let res = moving_sum(&a, &[0..7], 2).unwrap();
+        assert_eq!(res.value(0), 1.0);
+        assert!(res.value(1).is_nan());
+        assert!(res.value(2).is_nan());
+        assert_eq!(res.value(3), 5.0);
+        assert_eq!(res.value(4), f64::INFINITY);
+        assert_eq!(res.value(5), f64::INFINITY);
+        assert_eq!(res.value(6), 9.0);
+    }
+
+    #[test]
+    fn test_moving_avg_decimal() {
+        let a = Decimal128Array::from(vec![Some(100), Some(250), None, Some(-50)])
+            .with_precision_and_scale(10, 2)
+            .unwrap();
+        let res = moving_avg(&a, &[0..4], 2).unwrap();
+        assert_eq!(res, Float64Array::from(vec![1.0, 1.75, 2.5, -0.5]));
+
+        let a = Decimal128Array::from(vec![5, 7])
+            .with_precision_and_scale(10, -1)
+            .unwrap();
+        let res = moving_avg(&a, &[0..2], 2).unwrap();
+        assert_eq!(res, Float64Array::from(vec![50.0, 60.0]));
+    }
 }
@@ -17,12 +17,13 @@
 
 //! Computation kernels on Arrow Arrays
 
-pub use arrow_arith::{aggregate, arithmetic, arity, bitwise, boolean, numeric, temporal};
+pub use arrow_arith::{aggregate, arithmetic, arity, bitwise, boolean, decimal, numeric, temporal};
 pub use arrow_cast::cast;
 pub use arrow_cast::parse as cast_utils;
-pub use arrow_ord::{cmp, partition, rank, sort};
+pub use arrow_ord::{cmp, partition, rank, sort, sorted_merge, topk};
 pub use arrow_select::{
-    coalesce, concat, filter, interleave, merge, nullif, take, union_extract, window, zip,
+    coalesce, concat, dictionary, distinct, filter, interleave, merge, nullif, take, union_extract,
+    window, zip,
 };
 pub use arrow_string::{concat_elements, length, regexp, substring};
 
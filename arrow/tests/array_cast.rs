@@ -18,9 +18,9 @@
 use arrow_array::builder::{PrimitiveDictionaryBuilder, StringDictionaryBuilder, UnionBuilder};
 use arrow_array::cast::AsArray;
 use arrow_array::types::{
-    ArrowDictionaryKeyType, Decimal32Type, Decimal64Type, Decimal128Type, Decimal256Type, Int8Type,
-    Int16Type, Int32Type, Int64Type, TimestampMicrosecondType, UInt8Type, UInt16Type, UInt32Type,
-    UInt64Type,
+    ArrowDictionaryKeyType, Decimal32Type, Decimal64Type, Decimal128Type, Decimal256Type,
+    Float64Type, Int8Type, Int16Type, Int32Type, Int64Type, RunEndIndexType,
+    TimestampMicrosecondType, UInt8Type, UInt16Type, UInt32Type, UInt64Type,
 };
 use arrow_array::{
     Array, ArrayRef, ArrowPrimitiveType, BinaryArray, BooleanArray, Date32Array, Date64Array,
@@ -28,13 +28,13 @@ use arrow_array::{
     DurationMillisecondArray, DurationNanosecondArray, DurationSecondArray, FixedSizeBinaryArray,
     FixedSizeListArray, Float16Array, Float32Array, Float64Array, Int8Array, Int16Array,
     Int32Array, Int64Array, IntervalDayTimeArray, IntervalMonthDayNanoArray,
-    IntervalYearMonthArray, LargeBinaryArray, LargeListArray, LargeStringArray, ListArray,
-    NullArray, PrimitiveArray, StringArray, StructArray, Time32MillisecondArray, Time32SecondArray,
-    Time64MicrosecondArray, Time64NanosecondArray, TimestampMicrosecondArray,
-    TimestampMillisecondArray, TimestampNanosecondArray, TimestampSecondArray, UInt8Array,
-    UInt16Array, UInt32Array, UInt64Array, UnionArray,
+    IntervalYearMonthArray, LargeBinaryArray, LargeListArray, LargeListViewArray, LargeStringArray,
+    ListArray, ListViewArray, NullArray, PrimitiveArray, RunArray, StringArray, StructArray,
+    Time32MillisecondArray, Time32SecondArray, Time64MicrosecondArray, Time64NanosecondArray,
+    TimestampMicrosecondArray, TimestampMillisecondArray, TimestampNanosecondArray,
+    TimestampSecondArray, UInt8Array, UInt16Array, UInt32Array, UInt64Array, UnionArray,
 };
-use arrow_buffer::{Buffer, IntervalDayTime, IntervalMonthDayNano, i256};
+use arrow_buffer::{ArrowNativeType, Buffer, IntervalDayTime, IntervalMonthDayNano, i256};
 use arrow_cast::pretty::pretty_format_columns;
 use arrow_cast::{can_cast_types, cast};
 use arrow_data::ArrayData;
@@ -226,6 +226,12 @@ fn get_arrays_of_all_types() -> Vec<ArrayRef> {
             ),
         ])),
         Arc::new(make_union_array()),
+        Arc::new(make_sparse_union_array()),
+        Arc::new(make_list_view_array()),
+        Arc::new(make_large_list_view_array()),
+        make_run_array::<Int16Type>(Arc::new(Int32Array::from(vec![1, 2]))),
+        make_run_array::<Int32Type>(Arc::new(StringArray::from(vec!["foo", "bar"]))),
+        make_run_array::<Int64Type>(Arc::new(Int64Array::from(vec![1, 2]))),
         Arc::new(NullArray::new(10)),
         Arc::new(StringArray::from(vec!["foo", "bar"])),
         Arc::new(LargeStringArray::from(vec!["foo", "bar"])),
@@ -423,6 +429,47 @@ fn make_large_list_array() -> LargeListArray {
     LargeListArray::from(list_data)
 }
 
+fn make_list_view_array() -> ListViewArray {
+    // [[0, 1, 2], [3, 4, 5], [6, 7]]
+    let values = Arc::new(Int32Array::from(vec![0, 1, 2, 3, 4, 5, 6, 7]));
+    let field = Arc::new(Field::new_list_field(DataType::Int32, true));
+    ListViewArray::new(
+        field,
+        vec![0, 3, 6].into(),
+        vec![3, 3, 2].into(),
+        values,
+        None,
+    )
+}
+
+fn make_large_list_view_array() -> LargeListViewArray {
+    // [[6, 7], [0, 1, 2], [3, 4, 5]]
+    let values = Arc::new(Int32Array::from(vec![0, 1, 2, 3, 4, 5, 6, 7]));
+    let field = Arc::new(Field::new_list_field(DataType::Int32, true));
+    LargeListViewArray::new(
+        field,
+        vec![6, 0, 3].into(),
+        vec![2, 3, 3].into(),
+        values,
+        None,
+    )
+}
+
+/// Creates a run end encoded array with run ends of type R containing two runs of length 2
+fn make_run_array<R: RunEndIndexType>(values: ArrayRef) -> ArrayRef {
+    let run_ends = PrimitiveArray::<R>::from_iter_values(
+        (1..=values.len()).map(|i| R::Native::from_usize(i * 2).unwrap()),
+    );
+    Arc::new(RunArray::<R>::try_new(&run_ends, values.as_ref()).unwrap())
+}
+
+fn make_sparse_union_array() -> UnionArray {
+    let mut builder = UnionBuilder::with_capacity_sparse(7);
+    builder.append::<Int32Type>("a", 1).unwrap();
+    builder.append::<Float64Type>("b", 2.0).unwrap();
+    builder.build().unwrap()
+}
+
 fn make_union_array() -> UnionArray {
     let mut builder = UnionBuilder::with_capacity_dense(7);
     builder.append::<Int32Type>("a", 1).unwrap();
@@ -562,6 +609,32 @@ fn get_all_types() -> Vec<DataType> {
             .unwrap(),
             UnionMode::Dense,
         ),
+        Union(
+            UnionFields::try_new(
+                vec![0, 1],
+                vec![
+                    Field::new("f1", DataType::Float64, true),
+                    Field::new("f2", DataType::Binary, true),
+                ],
+            )
+            .unwrap(),
+            UnionMode::Sparse,
+        ),
+        ListView(Arc::new(Field::new_list_field(DataType::Int8, true))),
+        ListView(Arc::new(Field::new_list_field(DataType::Utf8, true))),
+        LargeListView(Arc::new(Field::new_list_field(DataType::Int64, true))),
+        RunEndEncoded(
+            Arc::new(Field::new("run_ends", DataType::Int16, false)),
+            Arc::new(Field::new("values", DataType::Int32, true)),
+        ),
+        RunEndEncoded(
+            Arc::new(Field::new("run_ends", DataType::Int32, false)),
+            Arc::new(Field::new("values", DataType::Utf8, true)),
+        ),
+        RunEndEncoded(
+            Arc::new(Field::new("run_ends", DataType::Int64, false)),
+            Arc::new(Field::new("values", DataType::Float64, true)),
+        ),
         Decimal128(38, 0),
     ];
 
@@ -293,7 +293,7 @@ fn visit_collection(
 ///
 /// This can also be derived from bytes 2-5 (possibly endian-swapped according to byte 1)
 /// of the input WKB buffer but is slightly clearer recomputed.
-fn geometry_type(geom: &impl GeometryTrait<T = f64>) -> Result<i32, ArrowError> {
+pub(crate) fn geometry_type(geom: &impl GeometryTrait<T = f64>) -> Result<i32, ArrowError> {
     let dimension_type = match geom.dim() {
         Dimensions::Xy => 0,
         Dimensions::Xyz => 1000,
@@ -0,0 +1,490 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Kernels operating on arrays of well-known binary (WKB) geometries
+//!
+//! These kernels accept the storage arrays of [`WkbType`] columns, that is
+//! `Binary`, `LargeBinary` or `BinaryView` arrays of WKB, and return Arrow
+//! arrays with one value per input row. A null input row results in a null
+//! output row.
+//!
+//! The kernels are intended to cheaply inspect and filter geometries, for
+//! example to discard rows outside a query envelope before handing the
+//! remaining rows to a fully featured GIS library.
+//!
+//! ```
+//! # use arrow_array::{BinaryArray, BooleanArray};
+//! # use parquet_geospatial::{kernels, testing::wkb_point_xy};
+//! let array = BinaryArray::from_iter([
+//!     Some(wkb_point_xy(1.0, 2.0)),
+//!     Some(wkb_point_xy(30.0, 40.0)),
+//!     None,
+//! ]);
+//! let mask = kernels::intersects_bbox(&array, (0.0, 10.0), (0.0, 10.0)).unwrap();
+//! assert_eq!(mask, BooleanArray::from(vec![Some(true), Some(false), None]));
+//! ```
+//!
+//! [`WkbType`]: crate::WkbType
+
+use std::str::FromStr;
+use std::sync::Arc;
+
+use arrow_array::builder::{BinaryBuilder, BooleanBuilder, Float64Builder, StringBuilder};
+use arrow_array::cast::AsArray;
+use arrow_array::{Array, ArrayRef, BinaryArray, BooleanArray, Float64Array, Int32Array};
+use arrow_array::{StringArray, StructArray};
+use arrow_buffer::NullBufferBuilder;
+use arrow_schema::{ArrowError, DataType, Field, Fields};
+use geo_traits::{CoordTrait, GeometryTrait, GeometryType, PointTrait};
+use wkb::Endianness;
+use wkb::reader::Wkb;
+use wkb::writer::WriteOptions;
+use wkt::Wkt;
+
+use crate::bounding::{GeometryBounder, geometry_type as wkb_geometry_type};
+use crate::interval::{Interval, IntervalTrait, WraparoundInterval};
+
+/// Returns the ISO WKB geometry type code of each geometry, for example 1 for
+/// a Point or 1003 for a Polygon Z
+///
+/// The codes are documented as part of the [Parquet specification].
+///
+/// # Errors
+///
+/// * If `array` is not a binary array
+/// * If any value is not valid WKB
+///
+/// [Parquet specification]: https://github.com/apache/parquet-format/blob/master/Geospatial.md#geospatial-types
+pub fn geometry_type(array: &dyn Array) -> Result<Int32Array, ArrowError> {
+    wkb_values(array)?
+        .map(|value| value.map(|v| wkb_geometry_type(&parse_wkb(v)?)).transpose())
+        .collect()
+}
+
+/// The data type of the arrays returned by [`envelope`]
+///
+/// This is a struct of the non-nullable `Float64` fields `xmin`, `ymin`,
+/// `xmax` and `ymax`, matching the two dimensional GeoArrow box type.
+pub fn envelope_data_type() -> DataType {
+    DataType::Struct(envelope_fields())
+}
+
+/// Returns the two dimensional envelope of each geometry, see [`envelope_data_type`]
+///
+/// The envelope is null for null and empty geometries.
+///
+/// # Errors
+///
+/// * If `array` is not a binary array
+/// * If any value is not valid WKB
+pub fn envelope(array: &dyn Array) -> Result<StructArray, ArrowError> {
+    let mut builders: [Float64Builder; 4] = Default::default();
+    let mut nulls = NullBufferBuilder::new(array.len());
+    for value in wkb_values(array)? {
+        let bounds = value.map(bounds).transpose()?.flatten();
+        nulls.append(bounds.is_some());
+        let values = bounds.map_or([0.0; 4], |(x, y)| [x.lo(), y.lo(), x.hi(), y.hi()]);
+        for (builder, value) in builders.iter_mut().zip(values) {
+            builder.append_value(value);
+        }
+    }
+    let arrays = builders
+        .iter_mut()
+        .map(|b| Arc::new(b.finish()) as ArrayRef)
+        .collect();
+    StructArray::try_new(envelope_fields(), arrays, nulls.finish())
+}
+
+/// Returns the X coordinate of each point
+///
+/// The value is null for null and empty points.
+///
+/// # Errors
+///
+/// * If `array` is not a binary array
+/// * If any value is not valid WKB, or is not a point
+pub fn point_x(array: &dyn Array) -> Result<Float64Array, ArrowError> {
+    point_coordinate(array, 0)
+}
+
+/// Returns the Y coordinate of each point
+///
+/// The value is null for null and empty points.
+///
+/// # Errors
+///
+/// * If `array` is not a binary array
+/// * If any value is not valid WKB, or is not a point
+pub fn point_y(array: &dyn Array) -> Result<Float64Array, ArrowError> {
+    point_coordinate(array, 1)
+}
+
+/// Returns whether each value is valid WKB
+///
+/// This checks that a value can be parsed as one of the geometry types of the
+/// [Parquet specification], with XY, XYZ, XYM or XYZM coordinates. It does
+/// not check for topological validity, such as self-intersecting polygons.
+///
+/// # Errors
+///
+/// * If `array` is not a binary array
+///
+/// [Parquet specification]: https://github.com/apache/parquet-format/blob/master/Geospatial.md#geospatial-types
+pub fn is_valid_wkb(array: &dyn Array) -> Result<BooleanArray, ArrowError> {
+    let mut builder = BooleanBuilder::with_capacity(array.len());
+    for value in wkb_values(array)? {
+        builder.append_option(
+            value.map(|v| Wkb::try_new(v).is_ok_and(|wkb| wkb_geometry_type(&wkb).is_ok())),
+        );
+    }
+    Ok(builder.finish())
+}
+
+/// Returns whether the envelope of each geometry intersects the query envelope
+/// given by the `x` and `y` ranges
+///
+/// The `x` range may wrap around the antimeridian, as described by
+/// [`WraparoundInterval`]. Empty geometries intersect nothing. The result can
+/// be passed to the arrow `filter` kernel to discard rows that cannot
+/// intersect the query envelope.
+///
+/// # Errors
+///
+/// * If `array` is not a binary array
+/// * If any value is not valid WKB
+pub fn intersects_bbox(
+    array: &dyn Array,
+    x: impl Into<WraparoundInterval>,
+    y: impl Into<Interval>,
+) -> Result<BooleanArray, ArrowError> {
+    let (query_x, query_y) = (x.into(), y.into());
+    let mut builder = BooleanBuilder::with_capacity(array.len());
+    for value in wkb_values(array)? {
+        let intersects = value.map(bounds).transpose()?.map(|bounds| {
+            bounds.is_some_and(|(x, y)| {
+                query_x.intersects_interval(&x.into()) && query_y.intersects_interval(&y)
+            })
+        });
+        builder.append_option(intersects);
+    }
+    Ok(builder.finish())
+}
+
+/// Converts WKB geometries to well-known text (WKT)
+///
+/// # Errors
+///
+/// * If `array` is not a binary array
+/// * If any value is not valid WKB
+pub fn wkb_to_wkt(array: &dyn Array) -> Result<StringArray, ArrowError> {
+    let mut builder = StringBuilder::with_capacity(array.len(), 0);
+    let mut buffer = String::new();
+    for value in wkb_values(array)? {
+        match value {
+            Some(v) => {
+                buffer.clear();
+                wkt::to_wkt::write_geometry(&mut buffer, &parse_wkb(v)?)
+                    .map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
+                builder.append_value(&buffer);
+            }
+            None => builder.append_null(),
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Converts well-known text (WKT) geometries to little endian WKB
+///
+/// # Errors
+///
+/// * If `array` is not a string array
+/// * If any value is not valid WKT
+pub fn wkt_to_wkb(array: &dyn Array) -> Result<BinaryArray, ArrowError> {
+    let values: Box<dyn Iterator<Item = Option<&str>>> = match array.data_type() {
+        DataType::Utf8 => Box::new(array.as_string::<i32>().iter()),
+        DataType::LargeUtf8 => Box::new(array.as_string::<i64>().iter()),
+        DataType::Utf8View => Box::new(array.as_string_view().iter()),
+        other => {
+            return Err(ArrowError::InvalidArgumentError(format!(
+                "Expected a string array of WKT, got {other}"
+            )));
+        }
+    };
+
+    let options = WriteOptions {
+        endianness: Endianness::LittleEndian,
+    };
+    let mut builder = BinaryBuilder::with_capacity(array.len(), 0);
+    let mut buffer = Vec::new();
+    for value in values {
+        match value {
+            Some(v) => {
+                let geometry = Wkt::<f64>::from_str(v)
+                    .map_err(|e| ArrowError::ParseError(format!("Error parsing WKT '{v}': {e}")))?;
+                buffer.clear();
+                wkb::writer::write_geometry(&mut buffer, &geometry, &options)
+                    .map_err(|e| ArrowError::ExternalError(Box::new(e)))?;
+                builder.append_value(&buffer);
+            }
+            None => builder.append_null(),
+        }
+    }
+    Ok(builder.finish())
+}
+
+/// Returns an iterator over the WKB values of a binary array
+fn wkb_values(
+    array: &dyn Array,
+) -> Result<Box<dyn Iterator<Item = Option<&[u8]>> + '_>, ArrowError> {
+    match array.data_type() {
+        DataType::Binary => Ok(Box::new(array.as_binary::<i32>().iter())),
+        DataType::LargeBinary => Ok(Box::new(array.as_binary::<i64>().iter())),
+        DataType::BinaryView => Ok(Box::new(array.as_binary_view().iter())),
+        other => Err(ArrowError::InvalidArgumentError(format!(
+            "Expected a binary array of WKB, got {other}"
+        ))),
+    }
+}
+
+fn parse_wkb(value: &[u8]) -> Result<Wkb<'_>, ArrowError> {
+    Wkb::try_new(value).map_err(|e| ArrowError::ExternalError(Box::new(e)))
+}
+
+/// Returns the x and y ranges of a geometry, or `None` if it is empty
+fn bounds(value: &[u8]) -> Result<Option<(Interval, Interval)>, ArrowError> {
+    let mut bounder = GeometryBounder::empty();
+    bounder.update_wkb(value)?;
+    let (x, y) = (bounder.x(), bounder.y());
+    if y.is_empty() {
+        return Ok(None);
+    }
+    Ok(Some((Interval::new(x.lo(), x.hi()), y)))
+}
+
+/// Returns the `n`th coordinate dimension of each point
+fn point_coordinate(array: &dyn Array, n: usize) -> Result<Float64Array, ArrowError> {
+    let mut builder = Float64Builder::with_capacity(array.len());
+    for value in wkb_values(array)? {
+        let Some(value) = value else {
+            builder.append_null();
+            continue;
+        };
+        let wkb = parse_wkb(value)?;
+        match wkb.as_type() {
+            GeometryType::Point(point) => {
+                builder.append_option(point.coord().map(|c| c.nth_or_panic(n)))
+            }
+            _ => {
+                return Err(ArrowError::InvalidArgumentError(format!(
+                    "Expected a Point geometry, got geometry type {}",
+                    wkb_geometry_type(&wkb)?
+                )));
+            }
+        }
+    }
+    Ok(builder.finish())
+}
+
+fn envelope_fields() -> Fields {
+    ["xmin", "ymin", "xmax", "ymax"]
+        .into_iter()
+        .map(|name| Field::new(name, DataType::Float64, false))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::{wkb_point_xy, wkb_point_xyzm};
+    use arrow_array::{BinaryViewArray, LargeBinaryArray};
+
+    fn wkb_array(wkt: &[Option<&str>]) -> BinaryArray {
+        wkt_to_wkb(&StringArray::from(wkt.to_vec())).unwrap()
+    }
+
+    #[test]
+    fn test_geometry_type() {
+        let array = wkb_array(&[
+            Some("POINT (1 2)"),
+            None,
+            Some("LINESTRING Z (0 0 0, 1 1 1)"),
+            Some("POLYGON M ((0 0 1, 1 0 1, 1 1 1, 0 0 1))"),
+            Some("GEOMETRYCOLLECTION ZM EMPTY"),
+        ]);
+        assert_eq!(
+            geometry_type(&array).unwrap(),
+            Int32Array::from(vec![Some(1), None, Some(1002), Some(2003), Some(3007)])
+        );
+
+        let invalid = BinaryArray::from_vec(vec![b"not wkb"]);
+        assert!(geometry_type(&invalid).is_err());
+
+        let err = geometry_type(&Int32Array::from(vec![1])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid argument error: Expected a binary array of WKB, got Int32"
+        );
+    }
+
+    #[test]
+    fn test_envelope() {
+        let array = wkb_array(&[
+            Some("POINT (1 2)"),
+            Some("LINESTRING (0 5, 10 -5)"),
+            None,
+            Some("POINT EMPTY"),
+            Some("MULTIPOINT ((1 1), (-2 3))"),
+        ]);
+        let envelopes = envelope(&array).unwrap();
+        assert_eq!(envelopes.data_type(), &envelope_data_type());
+        assert_eq!(envelopes.null_count(), 2);
+        assert!(envelopes.is_null(2));
+        assert!(envelopes.is_null(3));
+
+        let column = |i: usize| {
+            envelopes
+                .column(i)
+                .as_primitive::<arrow_array::types::Float64Type>()
+        };
+        let valid = [0, 1, 4];
+        let values = |i| valid.map(|row| column(i).value(row));
+        assert_eq!(values(0), [1.0, 0.0, -2.0]);
+        assert_eq!(values(1), [2.0, -5.0, 1.0]);
+        assert_eq!(values(2), [1.0, 10.0, 1.0]);
+        assert_eq!(values(3), [2.0, 5.0, 3.0]);
+    }
+
+    #[test]
+    fn test_point_coordinates() {
+        let values = [
+            Some(wkb_point_xy(1.0, 2.0)),
+            None,
+            Some(wkb_point_xyzm(3.0, 4.0, 5.0, 6.0)),
+            Some(wkb_point_xy(f64::NAN, f64::NAN)),
+        ];
+        let expected_x = Float64Array::from(vec![Some(1.0), None, Some(3.0), None]);
+        let expected_y = Float64Array::from(vec![Some(2.0), None, Some(4.0), None]);
+
+        let binary = BinaryArray::from_iter(values.clone());
+        assert_eq!(point_x(&binary).unwrap(), expected_x);
+        assert_eq!(point_y(&binary).unwrap(), expected_y);
+
+        let large = LargeBinaryArray::from_iter(values.clone());
+        assert_eq!(point_x(&large).unwrap(), expected_x);
+
+        let view = BinaryViewArray::from_iter(values);
+        assert_eq!(point_y(&view).unwrap(), expected_y);
+
+        let err = point_x(&wkb_array(&[Some("LINESTRING (0 0, 1 1)")])).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid argument error: Expected a Point geometry, got geometry type 2"
+        );
+    }
+
+    #[test]
+    fn test_is_valid_wkb() {
+        let point = wkb_point_xy(1.0, 2.0);
+        let array = BinaryArray::from_iter([
+            Some(point.as_slice()),
+            None,
+            Some(b"not wkb".as_slice()),
+            Some(&point[..point.len() - 1]),
+            Some(&[]),
+        ]);
+        assert_eq!(
+            is_valid_wkb(&array).unwrap(),
+            BooleanArray::from(vec![
+                Some(true),
+                None,
+                Some(false),
+                Some(false),
+                Some(false)
+            ])
+        );
+    }
+
+    #[test]
+    fn test_intersects_bbox() {
+        let array = wkb_array(&[
+            Some("POINT (1 2)"),
+            Some("LINESTRING (5 5, 20 20)"),
+            None,
+            Some("POINT EMPTY"),
+            Some("POINT (175 0)"),
+            Some("POINT (-175 0)"),
+            Some("POINT (100 0)"),
+        ]);
+
+        let result = intersects_bbox(&array, (0.0, 10.0), (0.0, 10.0)).unwrap();
+        let expected = [true, true, false, false, false, false, false];
+        let expected = BooleanArray::from_iter(
+            expected
+                .into_iter()
+                .enumerate()
+                .map(|(i, v)| (i != 2).then_some(v)),
+        );
+        assert_eq!(result, expected);
+
+        // The query envelope wraps around the antimeridian
+        let result = intersects_bbox(&array, (170.0, -170.0), (-10.0, 10.0)).unwrap();
+        let expected = BooleanArray::from(vec![
+            Some(false),
+            Some(false),
+            None,
+            Some(false),
+            Some(true),
+            Some(true),
+            Some(false),
+        ]);
+        assert_eq!(result, expected);
+    }
+
+    #[test]
+    fn test_wkt_roundtrip() {
+        let wkt = [
+            Some("POINT(1 2)"),
+            None,
+            Some("LINESTRING Z(0 0 0,1 1 1)"),
+            Some("POLYGON((0 0,1 0,1 1,0 0),(0.1 0.1,0.2 0.1,0.2 0.2,0.1 0.1))"),
+            Some("MULTIPOINT M((1 2 3))"),
+            Some("GEOMETRYCOLLECTION(POINT(1 2),LINESTRING(0 0,1 1))"),
+        ];
+        let wkb = wkb_array(&wkt);
+        assert_eq!(wkb.value(0), wkb_point_xy(1.0, 2.0).as_slice());
+        assert_eq!(wkb_to_wkt(&wkb).unwrap(), StringArray::from(wkt.to_vec()));
+
+        for array in [
+            Arc::new(arrow_array::LargeStringArray::from(wkt.to_vec())) as ArrayRef,
+            Arc::new(arrow_array::StringViewArray::from(wkt.to_vec())),
+        ] {
+            assert_eq!(wkt_to_wkb(&array).unwrap(), wkb);
+        }
+
+        let err = wkt_to_wkb(&StringArray::from(vec!["POINT (1"])).unwrap_err();
+        assert!(
+            err.to_string().contains("Error parsing WKT 'POINT (1'"),
+            "{err}"
+        );
+
+        let err = wkt_to_wkb(&wkb).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid argument error: Expected a string array of WKT, got Binary"
+        );
+    }
+}
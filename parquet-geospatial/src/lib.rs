@@ -29,6 +29,7 @@
 
 pub mod bounding;
 pub mod interval;
+pub mod kernels;
 pub mod testing;
 
 mod types;
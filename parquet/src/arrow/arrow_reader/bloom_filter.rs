@@ -0,0 +1,164 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Row group pruning with bloom filters, see [`BloomFilterPredicate`]
+
+use std::collections::HashMap;
+
+use crate::bloom_filter::{Sbbf, hash_as_bytes};
+use crate::data_type::AsBytes;
+use crate::errors::{ParquetError, Result};
+use crate::schema::types::SchemaDescriptor;
+
+/// Equality and IN-list probes of columns, used to skip row groups whose
+/// bloom filters show that no row can match
+///
+/// A row group is skipped if, for any probe, the bloom filter of the probed
+/// column shows that none of the probed values is present in the row group.
+/// Row groups are never skipped due to columns without a bloom filter.
+///
+/// Values are hashed as they are stored in the parquet file, so they must be
+/// of the physical type of the column. For example an arrow `Int8` column is
+/// stored as `INT32`, and must be probed with `i32` values, and a `Utf8`
+/// column must be probed with `str` or `[u8]` values.
+///
+/// See [`ParquetRecordBatchReaderBuilder::with_bloom_filter_predicate`] and
+/// [`ParquetRecordBatchStreamBuilder::with_bloom_filter_predicate`].
+///
+/// # Example
+///
+/// ```
+/// # use parquet::arrow::arrow_reader::BloomFilterPredicate;
+/// // Rows where leaf column 0 is 42 and leaf column 1 is "a" or "b"
+/// let predicate = BloomFilterPredicate::new()
+///     .with_eq(0, &42_i64)
+///     .with_in_list(1, ["a", "b"]);
+/// ```
+///
+/// [`ParquetRecordBatchReaderBuilder::with_bloom_filter_predicate`]: crate::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::with_bloom_filter_predicate
+/// [`ParquetRecordBatchStreamBuilder::with_bloom_filter_predicate`]: crate::arrow::async_reader::ParquetRecordBatchStreamBuilder::with_bloom_filter_predicate
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BloomFilterPredicate {
+    /// The leaf column index and the hashes of the values of each probe
+    probes: Vec<(usize, Vec<u64>)>,
+}
+
+impl BloomFilterPredicate {
+    /// Create a new [`BloomFilterPredicate`] without any probes
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Only read row groups in which the leaf column with index `column` may
+    /// be equal to `value`
+    pub fn with_eq<T: AsBytes + ?Sized>(self, column: usize, value: &T) -> Self {
+        self.with_in_list(column, [value])
+    }
+
+    /// Only read row groups in which the leaf column with index `column` may
+    /// be equal to any of `values`
+    pub fn with_in_list<'a, T, I>(mut self, column: usize, values: I) -> Self
+    where
+        T: AsBytes + ?Sized + 'a,
+        I: IntoIterator<Item = &'a T>,
+    {
+        let hashes = values.into_iter().map(hash_as_bytes).collect();
+        self.probes.push((column, hashes));
+        self
+    }
+
+    /// Returns the distinct leaf columns probed, in order, returning an error
+    /// if any is not a column of `schema`
+    pub(crate) fn columns(&self, schema: &SchemaDescriptor) -> Result<Vec<usize>> {
+        let mut columns: Vec<_> = self.probes.iter().map(|(column, _)| *column).collect();
+        columns.sort_unstable();
+        columns.dedup();
+        match columns.last() {
+            Some(column) if *column >= schema.num_columns() => Err(general_err!(
+                "Bloom filter predicate column {} out of bounds, file has {} columns",
+                column,
+                schema.num_columns()
+            )),
+            _ => Ok(columns),
+        }
+    }
+
+    /// Returns `false` if the bloom `filters` of a row group, by leaf column
+    /// index, show that no row of the row group can match
+    pub(crate) fn matches(&self, filters: &HashMap<usize, Sbbf>) -> bool {
+        self.probes
+            .iter()
+            .all(|(column, hashes)| match filters.get(column) {
+                Some(sbbf) => hashes.iter().any(|hash| sbbf.check_hash(*hash)),
+                None => true,
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::parser::parse_message_type;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_matches() {
+        let mut a = Sbbf::new(&[0; 32]);
+        a.insert(&1_i32);
+        a.insert(&2_i32);
+        let mut b = Sbbf::new(&[0; 32]);
+        b.insert("x");
+        let filters = HashMap::from([(0, a), (1, b)]);
+
+        let matches = |predicate: BloomFilterPredicate| predicate.matches(&filters);
+        assert!(matches(BloomFilterPredicate::new()));
+        assert!(matches(BloomFilterPredicate::new().with_eq(0, &1_i32)));
+        assert!(!matches(BloomFilterPredicate::new().with_eq(0, &3_i32)));
+        assert!(matches(
+            BloomFilterPredicate::new().with_in_list(0, &[3_i32, 2_i32])
+        ));
+        assert!(!matches(
+            BloomFilterPredicate::new()
+                .with_eq(0, &1_i32)
+                .with_eq(1, "y")
+        ));
+        // Columns without a bloom filter never prune
+        assert!(matches(BloomFilterPredicate::new().with_eq(2, &3_i32)));
+    }
+
+    #[test]
+    fn test_columns() {
+        let schema =
+            parse_message_type("message schema { REQUIRED INT32 a; REQUIRED BINARY b; }").unwrap();
+        let schema = SchemaDescriptor::new(Arc::new(schema));
+
+        let predicate = BloomFilterPredicate::new()
+            .with_eq(1, "x")
+            .with_eq(0, &1_i32)
+            .with_eq(1, "y");
+        assert_eq!(predicate.columns(&schema).unwrap(), vec![0, 1]);
+
+        let err = BloomFilterPredicate::new()
+            .with_eq(2, &1_i32)
+            .columns(&schema)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Bloom filter predicate column 2 out of bounds, file has 2 columns"
+        );
+    }
+}
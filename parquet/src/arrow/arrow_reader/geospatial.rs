@@ -0,0 +1,347 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Pruning with, and conversion to arrow of, the [`GeospatialStatistics`] of
+//! `GEOMETRY` and `GEOGRAPHY` columns
+//!
+//! Parquet only stores geospatial statistics per column chunk, and no column
+//! index is written for geospatial columns, so these can be used to prune row
+//! groups but not pages.
+
+use std::sync::Arc;
+
+use arrow_array::builder::{Float64Builder, Int32Builder, ListBuilder};
+use arrow_array::{ArrayRef, ListArray, StructArray};
+use arrow_buffer::NullBufferBuilder;
+use arrow_schema::{DataType, Field, Fields, Schema};
+
+use crate::arrow::parquet_column;
+use crate::errors::{ParquetError, Result};
+use crate::file::metadata::RowGroupMetaData;
+use crate::geospatial::statistics::GeospatialStatistics;
+use crate::schema::types::SchemaDescriptor;
+
+#[cfg(feature = "geospatial")]
+pub use predicate::*;
+
+#[cfg(feature = "geospatial")]
+mod predicate {
+    use super::*;
+    use crate::geospatial::bounding_box::BoundingBox;
+    use parquet_geospatial::interval::{Interval, IntervalTrait, WraparoundInterval};
+
+    /// The spatial relationship of [`GeospatialPredicate`]
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum SpatialRelation {
+        /// Geometries that intersect the query envelope
+        ///
+        /// As geometries within the query envelope intersect it, this can
+        /// also be used to prune row groups for a "within" predicate.
+        Intersects,
+        /// Geometries that contain the query envelope
+        Contains,
+    }
+
+    /// A predicate on the bounding boxes of the geometries of a column, used to
+    /// skip row groups whose [`GeospatialStatistics`] show that no row can match
+    ///
+    /// Row groups without geospatial statistics, or whose statistics have no
+    /// bounding box, are never skipped.
+    ///
+    /// The x range of the query envelope and of the statistics may wrap around
+    /// the antimeridian, in which case `xmin` is greater than `xmax`, as described
+    /// by [`WraparoundInterval`]. Z and M ranges are ignored.
+    ///
+    /// See [`ArrowReaderBuilder::with_geospatial_predicate`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use parquet::arrow::arrow_reader::geospatial::GeospatialPredicate;
+    /// # use parquet::geospatial::bounding_box::BoundingBox;
+    /// // Geometries of leaf column 0 that may intersect the envelope spanning
+    /// // the antimeridian from 170 to -170 degrees longitude
+    /// let predicate =
+    ///     GeospatialPredicate::intersects(0, &BoundingBox::new(170.0, -170.0, -10.0, 10.0));
+    /// ```
+    ///
+    /// [`ArrowReaderBuilder::with_geospatial_predicate`]: crate::arrow::arrow_reader::ArrowReaderBuilder::with_geospatial_predicate
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct GeospatialPredicate {
+        column: usize,
+        relation: SpatialRelation,
+        x: WraparoundInterval,
+        y: Interval,
+    }
+
+    impl GeospatialPredicate {
+        /// Create a new [`GeospatialPredicate`] on the leaf column with index `column`
+        pub fn new(column: usize, relation: SpatialRelation, envelope: &BoundingBox) -> Self {
+            Self {
+                column,
+                relation,
+                x: WraparoundInterval::new(envelope.get_xmin(), envelope.get_xmax()),
+                y: Interval::new(envelope.get_ymin(), envelope.get_ymax()),
+            }
+        }
+
+        /// Geometries of the leaf column with index `column` that intersect `envelope`
+        pub fn intersects(column: usize, envelope: &BoundingBox) -> Self {
+            Self::new(column, SpatialRelation::Intersects, envelope)
+        }
+
+        /// Geometries of the leaf column with index `column` that contain `envelope`
+        pub fn contains(column: usize, envelope: &BoundingBox) -> Self {
+            Self::new(column, SpatialRelation::Contains, envelope)
+        }
+
+        /// The leaf column index of this predicate
+        pub fn column(&self) -> usize {
+            self.column
+        }
+
+        /// The spatial relationship of this predicate
+        pub fn relation(&self) -> SpatialRelation {
+            self.relation
+        }
+
+        /// Returns `false` if `statistics` of a column chunk show that no row can match
+        pub fn matches(&self, statistics: Option<&GeospatialStatistics>) -> bool {
+            let Some(bbox) = statistics.and_then(|s| s.bounding_box()) else {
+                return true;
+            };
+            let x = WraparoundInterval::new(bbox.get_xmin(), bbox.get_xmax());
+            let y = Interval::new(bbox.get_ymin(), bbox.get_ymax());
+            match self.relation {
+                SpatialRelation::Intersects => {
+                    x.intersects_interval(&self.x) && y.intersects_interval(&self.y)
+                }
+                SpatialRelation::Contains => {
+                    x.contains_interval(&self.x) && y.contains_interval(&self.y)
+                }
+            }
+        }
+
+        /// Returns whether each of `row_groups` may contain matching rows
+        pub(crate) fn matches_row_groups<'a>(
+            &self,
+            schema: &SchemaDescriptor,
+            row_groups: impl IntoIterator<Item = &'a RowGroupMetaData>,
+        ) -> Result<Vec<bool>> {
+            if self.column >= schema.num_columns() {
+                return Err(general_err!(
+                    "Geospatial predicate column {} out of bounds, file has {} columns",
+                    self.column,
+                    schema.num_columns()
+                ));
+            }
+            Ok(row_groups
+                .into_iter()
+                .map(|rg| self.matches(rg.column(self.column).geo_statistics()))
+                .collect())
+        }
+    }
+}
+
+/// Converts the [`GeospatialStatistics`] of a column to arrow arrays, similarly
+/// to [`StatisticsConverter`]
+///
+/// [`StatisticsConverter`]: crate::arrow::arrow_reader::statistics::StatisticsConverter
+#[derive(Debug)]
+pub struct GeospatialStatisticsConverter<'a> {
+    /// the index of the matched column in the Parquet schema
+    parquet_column_index: Option<usize>,
+    /// The field of the column in the Arrow schema
+    arrow_field: &'a Field,
+}
+
+impl<'a> GeospatialStatisticsConverter<'a> {
+    /// Create a new [`GeospatialStatisticsConverter`] for the column `column_name`
+    ///
+    /// If there is no corresponding column in the parquet file, the returned
+    /// arrays will be null.
+    ///
+    /// # Errors
+    ///
+    /// * If the column is not found in the arrow schema
+    pub fn try_new(
+        column_name: &str,
+        arrow_schema: &'a Schema,
+        parquet_schema: &'a SchemaDescriptor,
+    ) -> Result<Self> {
+        let Some((_idx, arrow_field)) = arrow_schema.column_with_name(column_name) else {
+            return Err(arrow_err!(format!(
+                "Column '{}' not found in schema for statistics conversion",
+                column_name
+            )));
+        };
+        let parquet_column_index =
+            parquet_column(parquet_schema, arrow_schema, column_name).map(|(idx, _)| idx);
+        Ok(Self {
+            parquet_column_index,
+            arrow_field,
+        })
+    }
+
+    /// Return the index of the column in the Parquet schema, if any
+    pub fn parquet_column_index(&self) -> Option<usize> {
+        self.parquet_column_index
+    }
+
+    /// Return the [`Field`] of the column in the Arrow schema
+    pub fn arrow_field(&self) -> &'a Field {
+        self.arrow_field
+    }
+
+    /// The data type of the arrays returned by [`Self::row_group_bounding_boxes`]
+    ///
+    /// This is a struct of the `Float64` fields `xmin`, `ymin`, `zmin`, `mmin`,
+    /// `xmax`, `ymax`, `zmax` and `mmax`, in the order of the GeoArrow box type.
+    /// The `z` and `m` fields are null where the statistics have no such range.
+    pub fn bounding_box_data_type() -> DataType {
+        DataType::Struct(bounding_box_fields())
+    }
+
+    /// Returns the bounding box of each row group, see [`Self::bounding_box_data_type`]
+    ///
+    /// A value is null if the bounding box is unknown, because the column is not
+    /// present in the parquet file, or the row group has no geospatial
+    /// statistics or no bounding box for it.
+    ///
+    /// Note that `xmin` is greater than `xmax` for bounding boxes that wrap
+    /// around the antimeridian.
+    pub fn row_group_bounding_boxes<I>(&self, metadatas: I) -> Result<StructArray>
+    where
+        I: IntoIterator<Item = &'a RowGroupMetaData>,
+    {
+        let mut builders: [Float64Builder; 8] = Default::default();
+        let mut nulls = NullBufferBuilder::new(0);
+        for bbox in self.statistics(metadatas).map(|s| s?.bounding_box()) {
+            let values = bbox.map(|b| {
+                [
+                    Some(b.get_xmin()),
+                    Some(b.get_ymin()),
+                    b.get_zmin(),
+                    b.get_mmin(),
+                    Some(b.get_xmax()),
+                    Some(b.get_ymax()),
+                    b.get_zmax(),
+                    b.get_mmax(),
+                ]
+            });
+            nulls.append(values.is_some());
+            for (builder, value) in builders.iter_mut().zip(values.unwrap_or_default()) {
+                builder.append_option(value);
+            }
+        }
+        let arrays = builders
+            .iter_mut()
+            .map(|b| Arc::new(b.finish()) as ArrayRef)
+            .collect();
+        Ok(StructArray::try_new(
+            bounding_box_fields(),
+            arrays,
+            nulls.finish(),
+        )?)
+    }
+
+    /// Returns the geospatial types of each row group, as a list of the `Int32`
+    /// WKB type codes of the [specification]
+    ///
+    /// A value is null if the geospatial types are unknown.
+    ///
+    /// [specification]: https://github.com/apache/parquet-format/blob/master/Geospatial.md#geospatial-types
+    pub fn row_group_geospatial_types<I>(&self, metadatas: I) -> Result<ListArray>
+    where
+        I: IntoIterator<Item = &'a RowGroupMetaData>,
+    {
+        let mut builder = ListBuilder::new(Int32Builder::new());
+        for types in self.statistics(metadatas).map(|s| s?.geospatial_types()) {
+            builder.append_option(types.map(|t| t.iter().copied().map(Some)));
+        }
+        Ok(builder.finish())
+    }
+
+    fn statistics<I>(&self, metadatas: I) -> impl Iterator<Item = Option<&'a GeospatialStatistics>>
+    where
+        I: IntoIterator<Item = &'a RowGroupMetaData>,
+    {
+        let index = self.parquet_column_index;
+        metadatas
+            .into_iter()
+            .map(move |rg| rg.column(index?).geo_statistics())
+    }
+}
+
+fn bounding_box_fields() -> Fields {
+    [
+        "xmin", "ymin", "zmin", "mmin", "xmax", "ymax", "zmax", "mmax",
+    ]
+    .into_iter()
+    .map(|name| Field::new(name, DataType::Float64, true))
+    .collect()
+}
+
+#[cfg(all(test, feature = "geospatial"))]
+mod tests {
+    use super::*;
+    use crate::geospatial::bounding_box::BoundingBox;
+
+    fn stats(xmin: f64, xmax: f64, ymin: f64, ymax: f64) -> GeospatialStatistics {
+        GeospatialStatistics::new(Some(BoundingBox::new(xmin, xmax, ymin, ymax)), None)
+    }
+
+    #[test]
+    fn test_intersects() {
+        let predicate = GeospatialPredicate::intersects(0, &BoundingBox::new(0.0, 10.0, 0.0, 10.0));
+        assert!(predicate.matches(None));
+        assert!(predicate.matches(Some(&GeospatialStatistics::default())));
+        assert!(predicate.matches(Some(&stats(5.0, 15.0, 5.0, 15.0))));
+        assert!(predicate.matches(Some(&stats(10.0, 20.0, -5.0, 0.0))));
+        assert!(!predicate.matches(Some(&stats(11.0, 20.0, 0.0, 10.0))));
+        assert!(!predicate.matches(Some(&stats(0.0, 10.0, 11.0, 20.0))));
+
+        // Statistics wrapping around the antimeridian, covering x >= 170 or x <= -170
+        let wraparound = stats(170.0, -170.0, 0.0, 10.0);
+        assert!(!predicate.matches(Some(&wraparound)));
+        let predicate =
+            GeospatialPredicate::intersects(0, &BoundingBox::new(-180.0, -175.0, 0.0, 10.0));
+        assert!(predicate.matches(Some(&wraparound)));
+
+        // Query envelope wrapping around the antimeridian
+        let predicate =
+            GeospatialPredicate::intersects(0, &BoundingBox::new(175.0, -175.0, 0.0, 10.0));
+        assert!(predicate.matches(Some(&wraparound)));
+        assert!(predicate.matches(Some(&stats(178.0, 179.0, 0.0, 1.0))));
+        assert!(!predicate.matches(Some(&stats(0.0, 10.0, 0.0, 10.0))));
+    }
+
+    #[test]
+    fn test_contains() {
+        let predicate = GeospatialPredicate::contains(0, &BoundingBox::new(2.0, 4.0, 2.0, 4.0));
+        assert_eq!(predicate.relation(), SpatialRelation::Contains);
+        assert!(predicate.matches(None));
+        assert!(predicate.matches(Some(&stats(0.0, 10.0, 0.0, 10.0))));
+        assert!(!predicate.matches(Some(&stats(3.0, 10.0, 0.0, 10.0))));
+        assert!(!predicate.matches(Some(&stats(0.0, 10.0, 0.0, 3.0))));
+
+        let predicate =
+            GeospatialPredicate::contains(0, &BoundingBox::new(175.0, -175.0, 0.0, 1.0));
+        assert!(predicate.matches(Some(&stats(170.0, -170.0, 0.0, 10.0))));
+        assert!(!predicate.matches(Some(&stats(170.0, 180.0, 0.0, 10.0))));
+    }
+}
@@ -21,8 +21,10 @@ use arrow_array::cast::AsArray;
 use arrow_array::{Array, RecordBatch, RecordBatchReader};
 use arrow_schema::{ArrowError, DataType as ArrowType, FieldRef, Schema, SchemaRef};
 use arrow_select::filter::filter_record_batch;
+pub use bloom_filter::BloomFilterPredicate;
 pub use filter::{ArrowPredicate, ArrowPredicateFn, RowFilter};
 pub use selection::{RowSelection, RowSelectionCursor, RowSelectionPolicy, RowSelector};
+use std::collections::HashMap;
 use std::fmt::{Debug, Formatter};
 use std::sync::Arc;
 
@@ -51,7 +53,9 @@ use crate::arrow::arrow_reader::metrics::ArrowReaderMetrics;
 // Exposed so integration tests and benchmarks can temporarily override the threshold.
 pub use read_plan::{ReadPlan, ReadPlanBuilder};
 
+pub(crate) mod bloom_filter;
 mod filter;
+pub mod geospatial;
 pub mod metrics;
 mod read_plan;
 pub(crate) mod selection;
@@ -214,6 +218,63 @@ impl<T> ArrowReaderBuilder<T> {
         }
     }
 
+    /// Returns the indexes of the row groups that will be read
+    pub(crate) fn row_groups_to_read(&self) -> Vec<usize> {
+        self.row_groups
+            .clone()
+            .unwrap_or_else(|| (0..self.metadata.num_row_groups()).collect())
+    }
+
+    /// Only read the row groups of `row_groups`, as returned by
+    /// [`Self::row_groups_to_read`], for which `keep` is `true`
+    ///
+    /// Any [`RowSelection`] is reduced to the rows of the retained row groups
+    pub(crate) fn retain_row_groups(self, row_groups: &[usize], keep: &[bool]) -> Self {
+        let selection = self.selection.map(|mut selection| {
+            let mut selectors = vec![];
+            for (row_group, keep) in row_groups.iter().zip(keep) {
+                let num_rows = self.metadata.row_group(*row_group).num_rows() as usize;
+                let row_group_selection = selection.split_off(num_rows);
+                if *keep {
+                    selectors.extend(row_group_selection.iter().copied());
+                }
+            }
+            RowSelection::from(selectors)
+        });
+        let row_groups = row_groups
+            .iter()
+            .zip(keep)
+            .filter_map(|(row_group, keep)| keep.then_some(*row_group))
+            .collect();
+        Self {
+            row_groups: Some(row_groups),
+            selection,
+            ..self
+        }
+    }
+
+    /// Skip the row groups whose geospatial statistics show that no row matches `predicate`
+    ///
+    /// This only uses the metadata of the file, and must be called after
+    /// [`Self::with_row_groups`]. Any [`RowSelection`] is reduced to the rows
+    /// of the remaining row groups.
+    ///
+    /// See [`GeospatialPredicate`] for details.
+    ///
+    /// [`GeospatialPredicate`]: geospatial::GeospatialPredicate
+    #[cfg(feature = "geospatial")]
+    pub fn with_geospatial_predicate(
+        self,
+        predicate: geospatial::GeospatialPredicate,
+    ) -> Result<Self> {
+        let row_groups = self.row_groups_to_read();
+        let keep = predicate.matches_row_groups(
+            self.parquet_schema(),
+            row_groups.iter().map(|idx| self.metadata.row_group(*idx)),
+        )?;
+        Ok(self.retain_row_groups(&row_groups, &keep))
+    }
+
     /// Only read data from the provided column indexes
     pub fn with_projection(self, mask: ProjectionMask) -> Self {
         Self {
@@ -1168,6 +1229,65 @@ impl<T: ChunkReader + 'static> ParquetRecordBatchReaderBuilder<T> {
         Ok(Some(Sbbf::new(&bitset)))
     }
 
+    /// Skip the row groups whose bloom filters show that no row matches `predicate`
+    ///
+    /// This eagerly reads the bloom filters of the columns probed by `predicate`
+    /// for all row groups that would otherwise be read, and must therefore be
+    /// called after [`Self::with_row_groups`]. Any [`RowSelection`] is reduced
+    /// to the rows of the remaining row groups.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// # use std::sync::Arc;
+    /// # use arrow_array::{ArrayRef, Int32Array, RecordBatch};
+    /// # use bytes::Bytes;
+    /// # use parquet::arrow::ArrowWriter;
+    /// # use parquet::arrow::arrow_reader::{BloomFilterPredicate, ParquetRecordBatchReaderBuilder};
+    /// # use parquet::file::properties::WriterProperties;
+    /// # let props = WriterProperties::builder()
+    /// #     .set_bloom_filter_enabled(true)
+    /// #     .set_max_row_group_row_count(Some(10))
+    /// #     .build();
+    /// # let batch = RecordBatch::try_from_iter([
+    /// #     ("id", Arc::new(Int32Array::from_iter(0..100)) as ArrayRef),
+    /// # ]).unwrap();
+    /// # let mut buf = Vec::new();
+    /// # let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), Some(props)).unwrap();
+    /// # writer.write(&batch).unwrap();
+    /// # writer.close().unwrap();
+    /// # let file = Bytes::from(buf);
+    /// // Only read row groups that may contain rows with id 42
+    /// let predicate = BloomFilterPredicate::new().with_eq(0, &42_i32);
+    /// let reader = ParquetRecordBatchReaderBuilder::try_new(file)
+    ///     .unwrap()
+    ///     .with_bloom_filter_predicate(predicate)
+    ///     .unwrap()
+    ///     .build()
+    ///     .unwrap();
+    /// # let num_rows: usize = reader.map(|b| b.unwrap().num_rows()).sum();
+    /// # assert_eq!(num_rows, 10);
+    /// ```
+    pub fn with_bloom_filter_predicate(self, predicate: BloomFilterPredicate) -> Result<Self> {
+        let columns = predicate.columns(self.parquet_schema())?;
+        let row_groups = self.row_groups_to_read();
+        let keep = row_groups
+            .iter()
+            .map(|row_group| {
+                let mut filters = HashMap::with_capacity(columns.len());
+                for column in &columns {
+                    if let Some(sbbf) =
+                        self.get_row_group_column_bloom_filter(*row_group, *column)?
+                    {
+                        filters.insert(*column, sbbf);
+                    }
+                }
+                Ok(predicate.matches(&filters))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(self.retain_row_groups(&row_groups, &keep))
+    }
+
     /// Build a [`ParquetRecordBatchReader`]
     ///
     /// Note: this will eagerly evaluate any `RowFilter` before returning
@@ -1580,8 +1700,9 @@ pub(crate) mod tests {
     use tempfile::tempfile;
 
     use crate::arrow::arrow_reader::{
-        ArrowPredicateFn, ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReader,
-        ParquetRecordBatchReaderBuilder, RowFilter, RowSelection, RowSelector,
+        ArrowPredicateFn, ArrowReaderMetadata, ArrowReaderOptions, BloomFilterPredicate,
+        ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder, RowFilter, RowSelection,
+        RowSelector,
     };
     use crate::arrow::schema::{
         add_encoded_arrow_schema_to_metadata,
@@ -1596,7 +1717,9 @@ pub(crate) mod tests {
     };
     use crate::errors::Result;
     use crate::file::metadata::{PageIndexPolicy, ParquetMetaData, ParquetStatisticsPolicy};
-    use crate::file::properties::{EnabledStatistics, WriterProperties, WriterVersion};
+    use crate::file::properties::{
+        BloomFilterPosition, EnabledStatistics, WriterProperties, WriterVersion,
+    };
     use crate::file::writer::SerializedFileWriter;
     use crate::schema::parser::parse_message_type;
     use crate::schema::types::{Type, TypePtr};
@@ -5825,4 +5948,103 @@ pub(crate) mod tests {
 
         (Bytes::from(buf), metadata)
     }
+
+    /// Writes 10 row groups of 10 rows, with an `id` column of the row numbers
+    /// with bloom filters and a `name` column without
+    pub(crate) fn bloom_filter_test_file(position: BloomFilterPosition) -> Bytes {
+        let props = WriterProperties::builder()
+            .set_max_row_group_row_count(Some(10))
+            .set_column_bloom_filter_ndv("id".into(), 10)
+            .set_column_bloom_filter_fpp("id".into(), 0.001)
+            .set_bloom_filter_position(position)
+            .build();
+        let batch = RecordBatch::try_from_iter([
+            ("id", Arc::new(Int32Array::from_iter(0..100)) as ArrayRef),
+            (
+                "name",
+                Arc::new(StringArray::from_iter_values(
+                    (0..100).map(|i| format!("name_{i}")),
+                )) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+        let mut buf = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+        Bytes::from(buf)
+    }
+
+    fn read_ids(builder: ParquetRecordBatchReaderBuilder<Bytes>) -> Vec<i32> {
+        let batches: Vec<_> = builder.build().unwrap().map(|b| b.unwrap()).collect();
+        batches
+            .iter()
+            .flat_map(|b| {
+                b.column(0)
+                    .as_primitive::<arrow_array::types::Int32Type>()
+                    .values()
+                    .iter()
+            })
+            .copied()
+            .collect()
+    }
+
+    #[test]
+    fn test_bloom_filter_predicate() {
+        let file = bloom_filter_test_file(BloomFilterPosition::AfterRowGroup);
+        let builder = |predicate| {
+            ParquetRecordBatchReaderBuilder::try_new(file.clone())
+                .unwrap()
+                .with_bloom_filter_predicate(predicate)
+                .unwrap()
+        };
+
+        let predicate = BloomFilterPredicate::new().with_eq(0, &42_i32);
+        let b = builder(predicate);
+        assert_eq!(b.row_groups, Some(vec![4]));
+        assert_eq!(read_ids(b), (40..50).collect::<Vec<_>>());
+
+        let predicate = BloomFilterPredicate::new().with_in_list(0, &[5_i32, 95, 1000]);
+        assert_eq!(builder(predicate).row_groups, Some(vec![0, 9]));
+
+        let predicate = BloomFilterPredicate::new().with_eq(0, &1000_i32);
+        let b = builder(predicate);
+        assert_eq!(b.row_groups, Some(vec![]));
+        assert!(read_ids(b).is_empty());
+
+        // Columns without bloom filters don't prune
+        let predicate = BloomFilterPredicate::new().with_eq(1, "none");
+        assert_eq!(builder(predicate).row_groups, Some((0..10).collect()));
+
+        let predicate = BloomFilterPredicate::new().with_eq(2, &1_i32);
+        let err = ParquetRecordBatchReaderBuilder::try_new(file.clone())
+            .unwrap()
+            .with_bloom_filter_predicate(predicate)
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Bloom filter predicate column 2 out of bounds, file has 2 columns"
+        );
+    }
+
+    #[test]
+    fn test_bloom_filter_predicate_with_row_groups_and_selection() {
+        let file = bloom_filter_test_file(BloomFilterPosition::End);
+        let predicate = BloomFilterPredicate::new().with_in_list(0, &[42_i32, 95]);
+
+        let b = ParquetRecordBatchReaderBuilder::try_new(file.clone())
+            .unwrap()
+            .with_row_groups(vec![9, 0, 4])
+            .with_row_selection(RowSelection::from(vec![
+                RowSelector::skip(5),
+                RowSelector::select(1),
+                RowSelector::skip(15),
+                RowSelector::select(2),
+                RowSelector::skip(7),
+            ]))
+            .with_bloom_filter_predicate(predicate)
+            .unwrap();
+        assert_eq!(b.row_groups, Some(vec![9, 4]));
+        assert_eq!(read_ids(b), vec![95, 41, 42]);
+    }
 }
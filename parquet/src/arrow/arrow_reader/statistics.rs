@@ -20,7 +20,8 @@
 /// Notice that all the corresponding tests are in
 /// `arrow-rs/parquet/tests/arrow_reader/statistics.rs`.
 use crate::arrow::buffer::bit_util::sign_extend_be;
-use crate::arrow::parquet_column;
+use crate::arrow::schema::{ParquetField, ParquetFieldType};
+use crate::arrow::{ProjectionMask, parquet_column, parquet_to_arrow_field_levels};
 use crate::basic::Type as PhysicalType;
 use crate::errors::{ParquetError, Result};
 use crate::file::metadata::{ParquetColumnIndex, ParquetOffsetIndex, RowGroupMetaData};
@@ -45,7 +46,7 @@ use arrow_array::{
     UInt16Array, UInt32Array, UInt64Array, new_null_array,
 };
 use arrow_buffer::{NullBufferBuilder, i256};
-use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use arrow_schema::{DataType, Field, FieldRef, Schema, TimeUnit};
 use half::f16;
 use paste::paste;
 use std::sync::Arc;
@@ -1456,6 +1457,9 @@ impl<'a> StatisticsConverter<'a> {
     ///
     /// See example on [`Self::row_group_mins`] for usage
     ///
+    /// Only columns that are not nested are supported, see
+    /// [`Self::try_new_from_column_path`] for leaves of struct, list and map columns.
+    ///
     /// # Errors
     ///
     /// * If the column is not found in the arrow schema
@@ -1495,6 +1499,79 @@ impl<'a> StatisticsConverter<'a> {
         })
     }
 
+    /// Create a new `StatisticsConverter` to extract statistics for the leaf
+    /// column with the dotted parquet `column_path`
+    ///
+    /// Unlike [`Self::try_new`], this supports leaves nested within struct,
+    /// list and map columns, for example `a.b.list.element` for the elements
+    /// of the list `b` in the struct `a`, or `m.key_value.value` for the values
+    /// of the map `m`. See [`ColumnPath`] for the format of the path.
+    ///
+    /// The returned statistics are those of the leaf values, for example the
+    /// minimum of all elements of a list. Note that null counts of a nested
+    /// leaf include values that are null due to a null or empty ancestor, and
+    /// that the number of leaf values may differ from the number of rows, see
+    /// [`Self::row_group_value_counts`].
+    ///
+    /// [`Self::arrow_field`] returns the field of the leaf within `arrow_schema`,
+    /// for example the element field of a list.
+    ///
+    /// # Errors
+    ///
+    /// * If the column path is not found in the parquet schema
+    /// * If the root column is not found in the arrow schema
+    /// * If the arrow schema does not have the structure of the parquet schema
+    ///
+    /// [`ColumnPath`]: crate::schema::types::ColumnPath
+    pub fn try_new_from_column_path(
+        column_path: &str,
+        arrow_schema: &'a Schema,
+        parquet_schema: &'a SchemaDescriptor,
+    ) -> Result<Self> {
+        let Some(parquet_index) = parquet_schema
+            .columns()
+            .iter()
+            .position(|c| c.path().string() == column_path)
+        else {
+            return Err(arrow_err!(format!(
+                "Column path '{}' not found in parquet schema for statistics conversion",
+                column_path
+            )));
+        };
+
+        let root_name = parquet_schema.get_column_root(parquet_index).name();
+        let Some((_idx, root_field)) = arrow_schema.fields.find(root_name) else {
+            return Err(arrow_err!(format!(
+                "Column '{}' not found in schema for statistics conversion",
+                root_name
+            )));
+        };
+
+        // Walk the arrow field alongside the parquet field from which the
+        // reader would decode it, to find the arrow field of the leaf
+        let levels =
+            parquet_to_arrow_field_levels(parquet_schema, ProjectionMask::all(), None)?.levels;
+        let root_levels = levels
+            .as_ref()
+            .and_then(|l| l.children())
+            .and_then(|c| c.get(parquet_schema.get_column_root_idx(parquet_index)));
+        let arrow_field = root_levels
+            .and_then(|levels| nested_leaf_field(root_field, levels, parquet_index))
+            .ok_or_else(|| {
+                arrow_err!(format!(
+                    "Column '{}' does not match the parquet schema of column path '{}'",
+                    root_field, column_path
+                ))
+            })?;
+
+        Ok(Self {
+            parquet_column_index: Some(parquet_index),
+            arrow_field,
+            missing_null_counts_as_zero: true,
+            physical_type: Some(parquet_schema.column(parquet_index).physical_type()),
+        })
+    }
+
     /// Extract the minimum values from row group statistics in [`RowGroupMetaData`]
     ///
     /// # Return Value
@@ -1647,6 +1724,59 @@ impl<'a> StatisticsConverter<'a> {
         Ok(UInt64Array::from_iter(null_counts))
     }
 
+    /// Returns a [`UInt64Array`] with the number of values in each row group
+    ///
+    /// This is the number of leaf values of the column, including nulls, which
+    /// for columns nested within lists differs from the number of rows.
+    ///
+    /// Returns `None` if the column is not present in the parquet file.
+    ///
+    /// See docs on [`Self::row_group_row_counts`] for details
+    pub fn row_group_value_counts<I>(&self, metadatas: I) -> Result<Option<UInt64Array>>
+    where
+        I: IntoIterator<Item = &'a RowGroupMetaData>,
+    {
+        let Some(parquet_index) = self.parquet_column_index else {
+            return Ok(None);
+        };
+
+        let mut builder = UInt64Array::builder(10);
+        for metadata in metadatas.into_iter() {
+            let value_count = metadata.column(parquet_index).num_values();
+            let value_count: u64 = value_count.try_into().map_err(|e| {
+                arrow_err!(format!(
+                    "Parquet value count {value_count} too large to convert to u64: {e}"
+                ))
+            })?;
+            builder.append_value(value_count);
+        }
+        Ok(Some(builder.finish()))
+    }
+
+    /// Extract the unencoded size of `BYTE_ARRAY` values from the size
+    /// statistics in [`RowGroupMetaData`]
+    ///
+    /// This is the total length in bytes of the non-null values, excluding
+    /// length prefixes, which can be used to estimate the memory required to
+    /// decode the column. Values are null if the size statistics are not
+    /// present, for example as the column is not of the `BYTE_ARRAY` type.
+    ///
+    /// See docs on [`Self::row_group_mins`] for details
+    pub fn row_group_unencoded_byte_array_data_bytes<I>(&self, metadatas: I) -> Result<Int64Array>
+    where
+        I: IntoIterator<Item = &'a RowGroupMetaData>,
+    {
+        let Some(parquet_index) = self.parquet_column_index else {
+            let num_row_groups = metadatas.into_iter().count();
+            return Ok(Int64Array::new_null(num_row_groups));
+        };
+
+        let sizes = metadatas
+            .into_iter()
+            .map(|x| x.column(parquet_index).unencoded_byte_array_data_bytes());
+        Ok(Int64Array::from_iter(sizes))
+    }
+
     /// Extract the minimum values from Data Page statistics.
     ///
     /// In Parquet files, in addition to the Column Chunk level statistics
@@ -1786,11 +1916,89 @@ impl<'a> StatisticsConverter<'a> {
         null_counts_page_statistics(iter)
     }
 
+    /// Returns a [`UInt64Array`] with the number of values in each data page
+    ///
+    /// This is the number of leaf values of the column, including nulls, which
+    /// for columns nested within lists differs from the number of rows in the
+    /// page returned by [`Self::data_page_row_counts`]. It is computed from the
+    /// level histograms of the page index, and is null for pages without
+    /// histograms, which are not written for columns that are neither nullable
+    /// nor repeated, as there every row has exactly one value.
+    ///
+    /// See docs on [`Self::data_page_mins`] for details.
+    pub fn data_page_value_counts<I>(
+        &self,
+        column_page_index: &ParquetColumnIndex,
+        column_offset_index: &ParquetOffsetIndex,
+        row_group_indices: I,
+    ) -> Result<UInt64Array>
+    where
+        I: IntoIterator<Item = &'a usize>,
+    {
+        let Some(parquet_index) = self.parquet_column_index else {
+            let num_row_groups = row_group_indices.into_iter().count();
+            return Ok(UInt64Array::new_null(num_row_groups));
+        };
+
+        let mut builder = UInt64Array::builder(10);
+        for rg_index in row_group_indices {
+            let index = &column_page_index[*rg_index][parquet_index];
+            let num_data_pages = column_offset_index[*rg_index][parquet_index]
+                .page_locations()
+                .len();
+            for page in 0..num_data_pages {
+                let histogram = match index {
+                    ColumnIndexMetaData::NONE => None,
+                    index => index
+                        .definition_level_histogram(page)
+                        .or_else(|| index.repetition_level_histogram(page)),
+                };
+                builder.append_option(histogram.map(|h| h.iter().sum::<i64>() as u64));
+            }
+        }
+        Ok(builder.finish())
+    }
+
+    /// Returns an [`Int64Array`] with the unencoded size of `BYTE_ARRAY`
+    /// values in each data page
+    ///
+    /// Values are null if the size statistics are not present in the offset
+    /// index. See [`Self::row_group_unencoded_byte_array_data_bytes`] and
+    /// [`Self::data_page_mins`] for details.
+    pub fn data_page_unencoded_byte_array_data_bytes<I>(
+        &self,
+        column_offset_index: &ParquetOffsetIndex,
+        row_group_indices: I,
+    ) -> Result<Int64Array>
+    where
+        I: IntoIterator<Item = &'a usize>,
+    {
+        let Some(parquet_index) = self.parquet_column_index else {
+            let num_row_groups = row_group_indices.into_iter().count();
+            return Ok(Int64Array::new_null(num_row_groups));
+        };
+
+        let mut builder = Int64Array::builder(10);
+        for rg_index in row_group_indices {
+            let offset_index = &column_offset_index[*rg_index][parquet_index];
+            match offset_index.unencoded_byte_array_data_bytes() {
+                Some(sizes) => builder.append_slice(sizes),
+                None => builder.append_nulls(offset_index.page_locations().len()),
+            }
+        }
+        Ok(builder.finish())
+    }
+
     /// Returns a [`UInt64Array`] with row counts for each data page.
     ///
     /// This function iterates over the given row group indexes and computes
     /// the row count for each page in the specified column.
     ///
+    /// The row counts are computed from the first row index of each page in
+    /// the offset index. As pages of repeated columns always begin at a new
+    /// row, that is a value with a repetition level of zero, the row counts
+    /// of a column nested within lists align with the rows of the file.
+    ///
     /// # Parameters:
     ///
     /// * `column_offset_index`: The parquet column offset indices, read from
@@ -1859,4 +2067,48 @@ impl<'a> StatisticsConverter<'a> {
     }
 }
 
+/// Returns the arrow field of the leaf column `leaf` within `field`, the arrow
+/// field decoded from `levels`, or `None` if the structure of `field` does
+/// not match that of `levels`
+fn nested_leaf_field<'a>(
+    field: &'a FieldRef,
+    levels: &ParquetField,
+    leaf: usize,
+) -> Option<&'a Field> {
+    match &levels.field_type {
+        ParquetFieldType::Primitive { col_idx, .. } => {
+            (*col_idx == leaf && !field.data_type().is_nested()).then_some(field.as_ref())
+        }
+        ParquetFieldType::Group { children } => {
+            let arrow_children = nested_children(field.data_type());
+            if arrow_children.len() != children.len() {
+                return None;
+            }
+            arrow_children
+                .iter()
+                .zip(children)
+                .find_map(|(field, levels)| nested_leaf_field(field, levels, leaf))
+        }
+        ParquetFieldType::Virtual(_) => None,
+    }
+}
+
+/// Returns the child fields of a nested arrow data type, in the order of
+/// the children of the corresponding [`ParquetField`]
+fn nested_children(data_type: &DataType) -> &[FieldRef] {
+    match data_type {
+        DataType::Struct(fields) => fields,
+        DataType::List(field)
+        | DataType::LargeList(field)
+        | DataType::ListView(field)
+        | DataType::LargeListView(field)
+        | DataType::FixedSizeList(field, _) => std::slice::from_ref(field),
+        DataType::Map(field, _) => match field.data_type() {
+            DataType::Struct(fields) => fields,
+            _ => &[],
+        },
+        _ => &[],
+    }
+}
+
 // See tests in parquet/tests/arrow_reader/statistics.rs
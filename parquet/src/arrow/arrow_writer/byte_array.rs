@@ -16,7 +16,7 @@
 // under the License.
 
 use crate::basic::Encoding;
-use crate::bloom_filter::Sbbf;
+use crate::bloom_filter::{Sbbf, SbbfBuilder};
 use crate::column::writer::encoder::{ColumnValueEncoder, DataPageValues, DictionaryPage};
 use crate::data_type::{AsBytes, ByteArray, Int32Type};
 use crate::encodings::encoding::{DeltaBitPackEncoder, Encoder};
@@ -422,7 +422,9 @@ pub struct ByteArrayEncoder {
     statistics_enabled: EnabledStatistics,
     min_value: Option<ByteArray>,
     max_value: Option<ByteArray>,
-    bloom_filter: Option<Sbbf>,
+    bloom_filter: Option<SbbfBuilder>,
+    /// Whether all values written so far were dictionary encoded
+    dictionary_encoded: bool,
     geo_stats_accumulator: Option<Box<dyn GeoStatsAccumulator>>,
 }
 
@@ -430,7 +432,7 @@ impl ColumnValueEncoder for ByteArrayEncoder {
     type T = ByteArray;
     type Values = dyn Array;
     fn flush_bloom_filter(&mut self) -> Option<Sbbf> {
-        self.bloom_filter.take()
+        self.bloom_filter.take()?.finish(self.dictionary_encoded)
     }
 
     fn try_new(descr: &ColumnDescPtr, props: &WriterProperties) -> Result<Self>
@@ -443,10 +445,7 @@ impl ColumnValueEncoder for ByteArrayEncoder {
 
         let fallback = FallbackEncoder::new(descr, props)?;
 
-        let bloom_filter = props
-            .bloom_filter_properties(descr.path())
-            .map(|props| Sbbf::new_with_ndv_fpp(props.ndv, props.fpp))
-            .transpose()?;
+        let bloom_filter = SbbfBuilder::try_new(props, descr.path())?;
 
         let statistics_enabled = props.statistics_enabled(descr.path());
 
@@ -456,6 +455,7 @@ impl ColumnValueEncoder for ByteArrayEncoder {
             fallback,
             statistics_enabled,
             bloom_filter,
+            dictionary_encoded: dictionary.is_some(),
             dict_encoder: dictionary,
             min_value: None,
             max_value: None,
@@ -580,7 +580,10 @@ where
 
     match &mut encoder.dict_encoder {
         Some(dict_encoder) => dict_encoder.encode(values, indices),
-        None => encoder.fallback.encode(values, indices),
+        None => {
+            encoder.dictionary_encoded &= indices.is_empty();
+            encoder.fallback.encode(values, indices)
+        }
     }
 }
 
@@ -3111,6 +3111,64 @@ mod tests {
         check_bloom_filter(files, "col".to_string(), optional_raw_values, vec![""]);
     }
 
+    #[test]
+    fn bloom_filter_auto_size() {
+        let strings: Vec<_> = (0..10_000).map(|i| format!("value_{i}")).collect();
+        let batch = RecordBatch::try_from_iter([
+            ("i", Arc::new(Int32Array::from_iter(0..10_000)) as ArrayRef),
+            (
+                "s",
+                Arc::new(StringArray::from_iter_values(&strings)) as ArrayRef,
+            ),
+            (
+                "low",
+                Arc::new(StringArray::from_iter_values(
+                    strings.iter().map(|s| &s[..7]),
+                )) as ArrayRef,
+            ),
+        ])
+        .unwrap();
+
+        let props = WriterProperties::builder()
+            .set_bloom_filter_auto_size(true)
+            .set_bloom_filter_skip_dictionary_encoded(true)
+            .set_dictionary_page_size_limit(1024)
+            .build();
+        let mut buf = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let file_reader = SerializedFileReader::new_with_options(
+            Bytes::from(buf),
+            ReadOptionsBuilder::new()
+                .with_reader_properties(
+                    ReaderProperties::builder()
+                        .set_read_bloom_filter(true)
+                        .build(),
+                )
+                .build(),
+        )
+        .unwrap();
+        let row_group = file_reader.metadata().row_group(0);
+        let row_group_reader = file_reader.get_row_group(0).unwrap();
+
+        // Sized for 10,000 rather than the default 1,000,000 distinct values,
+        // i.e. 16 KiB rather than 1 MiB
+        for column in [0, 1] {
+            let length = row_group.column(column).bloom_filter_length().unwrap();
+            assert!((16 * 1024..17 * 1024).contains(&length), "{length}");
+        }
+        let sbbf = row_group_reader.get_column_bloom_filter(0).unwrap();
+        assert!((0..10_000).all(|i| sbbf.check(&i)));
+        let sbbf = row_group_reader.get_column_bloom_filter(1).unwrap();
+        assert!(strings.iter().all(|s| sbbf.check(&s.as_str())));
+
+        // Fully dictionary encoded with only 10 distinct values
+        assert!(row_group.column(2).bloom_filter_offset().is_none());
+        assert!(row_group_reader.get_column_bloom_filter(2).is_none());
+    }
+
     #[test]
     fn large_binary_single_column() {
         let one_vec: Vec<u8> = (0..SMALL_SIZE as u8).collect();
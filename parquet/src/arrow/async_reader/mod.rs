@@ -21,6 +21,7 @@
 //!
 //! See example on [`ParquetRecordBatchStreamBuilder::new`]
 
+use std::collections::HashMap;
 use std::fmt::Formatter;
 use std::io::SeekFrom;
 use std::ops::Range;
@@ -37,7 +38,8 @@ use arrow_array::RecordBatch;
 use arrow_schema::{Schema, SchemaRef};
 
 use crate::arrow::arrow_reader::{
-    ArrowReaderBuilder, ArrowReaderMetadata, ArrowReaderOptions, ParquetRecordBatchReader,
+    ArrowReaderBuilder, ArrowReaderMetadata, ArrowReaderOptions, BloomFilterPredicate,
+    ParquetRecordBatchReader,
 };
 
 use crate::basic::{BloomFilterAlgorithm, BloomFilterCompression, BloomFilterHash};
@@ -478,6 +480,87 @@ impl<T: AsyncFileReader + Send + 'static> ParquetRecordBatchStreamBuilder<T> {
         Ok(Some(Sbbf::new(&bitset)))
     }
 
+    /// Skip the row groups whose bloom filters show that no row matches `predicate`
+    ///
+    /// This eagerly fetches the bloom filters of the columns probed by `predicate`
+    /// for all row groups that would otherwise be read, and must therefore be
+    /// called after [`Self::with_row_groups`]. Any [`RowSelection`] is reduced
+    /// to the rows of the remaining row groups.
+    ///
+    /// The bloom filters are fetched with a single call to
+    /// [`AsyncFileReader::get_byte_ranges`] of coalesced byte ranges, or two if
+    /// the file does not record the length of its bloom filters.
+    ///
+    /// See [`ParquetRecordBatchReaderBuilder::with_bloom_filter_predicate`] for an example.
+    ///
+    /// [`RowSelection`]: crate::arrow::arrow_reader::RowSelection
+    /// [`ParquetRecordBatchReaderBuilder::with_bloom_filter_predicate`]: crate::arrow::arrow_reader::ParquetRecordBatchReaderBuilder::with_bloom_filter_predicate
+    pub async fn with_bloom_filter_predicate(
+        mut self,
+        predicate: BloomFilterPredicate,
+    ) -> Result<Self> {
+        let columns = predicate.columns(self.parquet_schema())?;
+        let row_groups = self.row_groups_to_read();
+
+        // The position in `row_groups`, column and byte range of each bloom filter
+        let mut locations = vec![];
+        for (idx, row_group) in row_groups.iter().enumerate() {
+            let row_group_metadata = self.metadata.row_group(*row_group);
+            for column in &columns {
+                let column_metadata = row_group_metadata.column(*column);
+                let Some(offset) = column_metadata.bloom_filter_offset() else {
+                    continue;
+                };
+                let offset: u64 = offset.try_into().map_err(|_| {
+                    ParquetError::General("Bloom filter offset is invalid".to_string())
+                })?;
+                // Without the length, only the header is fetched first
+                let length = match column_metadata.bloom_filter_length() {
+                    Some(length) => length as u64,
+                    None => SBBF_HEADER_SIZE_ESTIMATE as u64,
+                };
+                locations.push((idx, *column, offset..offset + length));
+            }
+        }
+
+        let ranges: Vec<_> = locations
+            .iter()
+            .map(|(_, _, range)| range.clone())
+            .collect();
+        let buffers = get_coalesced_byte_ranges(&mut self.input.0, &ranges).await?;
+
+        let mut filters = vec![HashMap::new(); row_groups.len()];
+        let mut bitset_locations = vec![];
+        for ((idx, column, range), buffer) in locations.into_iter().zip(buffers) {
+            let (header, bitset_offset) =
+                chunk_read_bloom_filter_header_and_offset(range.start, buffer.clone())?;
+            let bitset_length: u64 = header
+                .num_bytes
+                .try_into()
+                .map_err(|_| ParquetError::General("Bloom filter length is invalid".to_string()))?;
+            let bitset_range = bitset_offset..bitset_offset + bitset_length;
+            if bitset_range.end <= range.end {
+                let start = (bitset_range.start - range.start) as usize;
+                let end = (bitset_range.end - range.start) as usize;
+                filters[idx].insert(column, Sbbf::new(&buffer.slice(start..end)));
+            } else {
+                bitset_locations.push((idx, column, bitset_range));
+            }
+        }
+
+        let ranges: Vec<_> = bitset_locations
+            .iter()
+            .map(|(_, _, range)| range.clone())
+            .collect();
+        let buffers = get_coalesced_byte_ranges(&mut self.input.0, &ranges).await?;
+        for ((idx, column, _), buffer) in bitset_locations.into_iter().zip(buffers) {
+            filters[idx].insert(column, Sbbf::new(&buffer));
+        }
+
+        let keep: Vec<_> = filters.iter().map(|f| predicate.matches(f)).collect();
+        Ok(self.retain_row_groups(&row_groups, &keep))
+    }
+
     /// Build a new [`ParquetRecordBatchStream`]
     ///
     /// See examples on [`ParquetRecordBatchStreamBuilder::new`]
@@ -535,6 +618,58 @@ impl<T: AsyncFileReader + Send + 'static> ParquetRecordBatchStreamBuilder<T> {
     }
 }
 
+/// The maximum gap between byte ranges of bloom filters fetched together
+const COALESCE_GAP: u64 = 1024 * 1024;
+
+/// Merges the sorted `ranges` into fewer ranges, joining ranges separated by
+/// at most [`COALESCE_GAP`] bytes
+fn coalesce_ranges(ranges: &[Range<u64>]) -> Vec<Range<u64>> {
+    let mut coalesced: Vec<Range<u64>> = Vec::with_capacity(ranges.len());
+    for range in ranges {
+        match coalesced.last_mut() {
+            Some(last) if range.start <= last.end.saturating_add(COALESCE_GAP) => {
+                last.end = last.end.max(range.end);
+            }
+            _ => coalesced.push(range.clone()),
+        }
+    }
+    coalesced
+}
+
+/// Fetches the byte `ranges` from `input` with a single call to
+/// [`AsyncFileReader::get_byte_ranges`] of coalesced ranges
+async fn get_coalesced_byte_ranges<T: AsyncFileReader>(
+    input: &mut T,
+    ranges: &[Range<u64>],
+) -> Result<Vec<Bytes>> {
+    if ranges.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut sorted = ranges.to_vec();
+    sorted.sort_unstable_by_key(|range| range.start);
+    let coalesced = coalesce_ranges(&sorted);
+    let buffers = input.get_byte_ranges(coalesced.clone()).await?;
+
+    ranges
+        .iter()
+        .map(|range| {
+            let idx = coalesced.partition_point(|c| c.start <= range.start) - 1;
+            let start = (range.start - coalesced[idx].start) as usize;
+            let end = (range.end - coalesced[idx].start) as usize;
+            let buffer = &buffers[idx];
+            if end > buffer.len() {
+                return Err(eof_err!(
+                    "Expected {} bytes at offset {}, got {}",
+                    end - start,
+                    range.start,
+                    buffer.len().saturating_sub(start)
+                ));
+            }
+            Ok(buffer.slice(start..end))
+        })
+        .collect()
+}
+
 /// State machine that tracks outstanding requests to fetch data
 ///
 /// The parameter `T` is the input, typically an `AsyncFileReader`
@@ -770,7 +905,9 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::arrow::arrow_reader::tests::test_row_numbers_with_multiple_row_groups_helper;
+    use crate::arrow::arrow_reader::tests::{
+        bloom_filter_test_file, test_row_numbers_with_multiple_row_groups_helper,
+    };
     use crate::arrow::arrow_reader::{
         ArrowPredicateFn, ParquetRecordBatchReaderBuilder, RowFilter, RowSelection, RowSelector,
     };
@@ -779,7 +916,7 @@ mod tests {
     use crate::arrow::{ArrowWriter, AsyncArrowWriter, ProjectionMask};
     use crate::file::metadata::PageIndexPolicy;
     use crate::file::metadata::ParquetMetaDataReader;
-    use crate::file::properties::WriterProperties;
+    use crate::file::properties::{BloomFilterPosition, WriterProperties};
     use arrow::compute::kernels::cmp::eq;
     use arrow::error::Result as ArrowResult;
     use arrow_array::builder::{Float32Builder, ListBuilder, StringBuilder};
@@ -843,6 +980,42 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_coalesce_ranges() {
+        assert_eq!(coalesce_ranges(&[]), vec![]);
+        assert_eq!(
+            coalesce_ranges(&[0..10, 5..20, 100..200, 3_000_000..3_000_010]),
+            vec![0..200, 3_000_000..3_000_010]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_bloom_filter_predicate() {
+        for position in [BloomFilterPosition::AfterRowGroup, BloomFilterPosition::End] {
+            let reader = TestReader::new(bloom_filter_test_file(position));
+            let requests = reader.requests.clone();
+            let builder = ParquetRecordBatchStreamBuilder::new(reader).await.unwrap();
+            requests.lock().unwrap().clear();
+
+            let predicate = BloomFilterPredicate::new().with_in_list(0, &[42_i32, 1000]);
+            let builder = builder
+                .with_bloom_filter_predicate(predicate)
+                .await
+                .unwrap();
+            // All bloom filters are fetched with one coalesced request
+            assert_eq!(requests.lock().unwrap().len(), 1);
+            assert_eq!(builder.row_groups, Some(vec![4]));
+
+            let batches: Vec<_> = builder.build().unwrap().try_collect().await.unwrap();
+            let ids: Vec<_> = batches
+                .iter()
+                .flat_map(|b| b.column(0).as_primitive::<Int32Type>().values().iter())
+                .copied()
+                .collect();
+            assert_eq!(ids, (40..50).collect::<Vec<_>>());
+        }
+    }
+
     #[tokio::test]
     async fn test_async_reader() {
         let testdata = arrow::util::test_util::parquet_test_data();
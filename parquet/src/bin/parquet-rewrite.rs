@@ -32,19 +32,32 @@
 //! ```
 //! cargo run --features=cli --bin parquet-rewrite -- -i XYZ.parquet -o XYZ2.parquet
 //! ```
+//!
+//! By default the file is decoded and encoded again with Arrow. With `--copy-pages`,
+//! column chunks are instead copied without decoding values, which allows changing the
+//! compression, dropping columns, merging row groups and, with the `encryption` feature,
+//! encrypting or decrypting columns:
+//! ```
+//! parquet-rewrite -i XYZ.parquet -o XYZ2.parquet --copy-pages --compression zstd --drop-column a.b
+//! ```
 
 use std::fs::File;
+use std::sync::Arc;
 
 use arrow_array::RecordBatchReader;
 use clap::{Parser, ValueEnum, builder::PossibleValue};
+#[cfg(feature = "encryption")]
+use parquet::encryption::{decrypt::FileDecryptionProperties, encrypt::FileEncryptionProperties};
 use parquet::{
     arrow::{ArrowWriter, arrow_reader::ParquetRecordBatchReaderBuilder},
     basic::{BrotliLevel, Compression, Encoding, GzipLevel, ZstdLevel},
     file::{
         properties::{BloomFilterPosition, EnabledStatistics, WriterProperties, WriterVersion},
         reader::FileReader,
+        rewriter::ParquetRewriter,
         serialized_reader::SerializedFileReader,
     },
+    schema::types::ColumnPath,
 };
 
 #[derive(Copy, Clone, PartialEq, Eq, PartialOrd, Ord, ValueEnum, Debug)]
@@ -310,30 +323,131 @@ struct Args {
     /// Sets whether to coerce Arrow types to match Parquet specification
     #[clap(long)]
     coerce_types: Option<bool>,
+
+    /// Copy column chunks without decoding values.
+    ///
+    /// Only the compression and the maximum row group size apply, as pages are copied
+    /// with their existing encoding. Columns keep their compression unless `--compression`
+    /// is given.
+    #[clap(long)]
+    copy_pages: bool,
+
+    /// Drop a column or group, given as a dot separated path. Can be repeated.
+    #[clap(long, requires = "copy_pages")]
+    drop_column: Vec<String>,
+
+    /// Merge adjacent row groups up to the maximum row group size.
+    ///
+    /// Values of merged row groups are decoded and encoded again.
+    #[clap(long, requires = "copy_pages")]
+    merge_row_groups: bool,
+
+    /// Hex encoded key used to encrypt the footer, and all columns unless column keys are given.
+    #[cfg(feature = "encryption")]
+    #[clap(long, requires = "copy_pages")]
+    encryption_footer_key: Option<String>,
+
+    /// Encrypt a column with a hex encoded key, given as `<column path>:<key>`. Can be repeated.
+    #[cfg(feature = "encryption")]
+    #[clap(long, requires = "encryption_footer_key")]
+    encryption_column_key: Vec<String>,
+
+    /// Hex encoded key used to decrypt the footer of the input.
+    #[cfg(feature = "encryption")]
+    #[clap(long, requires = "copy_pages")]
+    decryption_footer_key: Option<String>,
+
+    /// Decrypt a column of the input with a hex encoded key, given as
+    /// `<column path>:<key>`. Can be repeated.
+    #[cfg(feature = "encryption")]
+    #[clap(long, requires = "decryption_footer_key")]
+    decryption_column_key: Vec<String>,
 }
 
-fn main() {
-    let args = Args::parse();
+/// Decodes the hex encoded `key`, described by `name` in errors, which must
+/// not include the key itself
+#[cfg(feature = "encryption")]
+fn decode_hex_key(name: &str, key: &str) -> Vec<u8> {
+    let invalid = || -> ! { panic!("invalid hex encoded {name}") };
+    if key.len() % 2 != 0 || !key.is_ascii() {
+        invalid()
+    }
+    (0..key.len())
+        .step_by(2)
+        .map(|i| u8::from_str_radix(&key[i..i + 2], 16).unwrap_or_else(|_| invalid()))
+        .collect()
+}
 
-    // read key-value metadata
-    let parquet_reader =
-        SerializedFileReader::new(File::open(&args.input).expect("Unable to open input file"))
-            .expect("Failed to create reader");
-    let kv_md = parquet_reader
-        .metadata()
-        .file_metadata()
-        .key_value_metadata()
-        .cloned();
+#[cfg(feature = "encryption")]
+fn parse_column_keys(purpose: &str, column_keys: &[String]) -> Vec<(String, Vec<u8>)> {
+    column_keys
+        .iter()
+        .map(|column_key| {
+            let (column, key) = column_key
+                .rsplit_once(':')
+                .expect("column keys must be given as <column path>:<key>");
+            let name = format!("{purpose} key for column {column}");
+            (column.to_string(), decode_hex_key(&name, key))
+        })
+        .collect()
+}
 
-    // create actual parquet reader
-    let parquet_reader = ParquetRecordBatchReaderBuilder::try_new(
-        File::open(args.input).expect("Unable to open input file"),
-    )
-    .expect("parquet open")
-    .build()
-    .expect("parquet open");
+fn copy_pages(args: &Args, writer_properties: WriterProperties) {
+    let input = File::open(&args.input).expect("Unable to open input file");
+    let dropped_columns = args
+        .drop_column
+        .iter()
+        .map(|path| ColumnPath::new(path.split('.').map(String::from).collect()));
+
+    #[cfg(feature = "encryption")]
+    let writer_properties = match &args.encryption_footer_key {
+        Some(footer_key) => {
+            let mut builder = FileEncryptionProperties::builder(decode_hex_key(
+                "encryption footer key",
+                footer_key,
+            ));
+            for (column, key) in parse_column_keys("encryption", &args.encryption_column_key) {
+                builder = builder.with_column_key(&column, key);
+            }
+            let encryption_properties = builder.build().expect("invalid encryption properties");
+            writer_properties
+                .into_builder()
+                .with_file_encryption_properties(encryption_properties)
+                .build()
+        }
+        None => writer_properties,
+    };
+
+    let rewriter = ParquetRewriter::new(input, Arc::new(writer_properties))
+        .with_dropped_columns(dropped_columns)
+        .with_merged_row_groups(args.merge_row_groups)
+        .with_preserved_compression(args.compression.is_none());
+
+    #[cfg(feature = "encryption")]
+    let rewriter = match &args.decryption_footer_key {
+        Some(footer_key) => {
+            let mut builder = FileDecryptionProperties::builder(decode_hex_key(
+                "decryption footer key",
+                footer_key,
+            ));
+            for (column, key) in parse_column_keys("decryption", &args.decryption_column_key) {
+                builder = builder.with_column_key(&column, key);
+            }
+            let decryption_properties = builder.build().expect("invalid decryption properties");
+            rewriter.with_file_decryption_properties(decryption_properties)
+        }
+        None => rewriter,
+    };
 
-    let mut writer_properties_builder = WriterProperties::builder().set_key_value_metadata(kv_md);
+    rewriter
+        .rewrite(File::create(&args.output).expect("Unable to open output file"))
+        .expect("rewriting file");
+}
+
+fn main() {
+    let args = Args::parse();
+
+    let mut writer_properties_builder = WriterProperties::builder();
 
     if let Some(value) = args.compression {
         let compression = compression_from_args(value, args.compression_level);
@@ -409,7 +523,33 @@ fn main() {
     if let Some(value) = args.write_batch_size {
         writer_properties_builder = writer_properties_builder.set_write_batch_size(value);
     }
-    let writer_properties = writer_properties_builder.build();
+
+    if args.copy_pages {
+        copy_pages(&args, writer_properties_builder.build());
+        return;
+    }
+
+    // read key-value metadata
+    let parquet_reader =
+        SerializedFileReader::new(File::open(&args.input).expect("Unable to open input file"))
+            .expect("Failed to create reader");
+    let kv_md = parquet_reader
+        .metadata()
+        .file_metadata()
+        .key_value_metadata()
+        .cloned();
+
+    // create actual parquet reader
+    let parquet_reader = ParquetRecordBatchReaderBuilder::try_new(
+        File::open(args.input).expect("Unable to open input file"),
+    )
+    .expect("parquet open")
+    .build()
+    .expect("parquet open");
+
+    let writer_properties = writer_properties_builder
+        .set_key_value_metadata(kv_md)
+        .build();
     let mut parquet_writer = ArrowWriter::try_new(
         File::create(&args.output).expect("Unable to open output file"),
         parquet_reader.schema(),
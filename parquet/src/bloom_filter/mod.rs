@@ -68,6 +68,28 @@
 //! | 1,000,000 | 0.00001   | 131,072 | 4,096     |
 //! | 1,000,000 | 0.000001  | 262,144 | 8,192     |
 //!
+//! # Auto Sizing
+//!
+//! As the NDV of a column chunk is often not known up front, the writer can instead
+//! be configured with [`WriterProperties::bloom_filter_auto_size`]. The NDV is then treated
+//! as an upper bound. The hashes of the values written are buffered, and when the column
+//! chunk is flushed the filter is created with the size computed from their number and the
+//! FPP as above.
+//!
+//! Should the distinct hashes exceed the size of the filter for the NDV, that filter is
+//! created instead, and the number of distinct values actually written is estimated with a
+//! small [HyperLogLog][hll-paper] sketch. When the column chunk is flushed, the filter is
+//! shrunk to the size computed from the estimated NDV and FPP.
+//!
+//! As `b` is always a power of two, and a hash selects the block `floor(h * b / 2^32)`
+//! for the upper 32 bits `h` of the hash, the block selected by a hash in a filter of
+//! `b / 2` blocks is the block it selects in a filter of `b` blocks divided by two. Therefore,
+//! a filter is halved without rehashing by combining blocks `2i` and `2i + 1` into block `i`,
+//! which yields exactly the filter that inserting the same values into a filter of `b / 2`
+//! blocks would have.
+//!
+//! [`WriterProperties::bloom_filter_auto_size`]: crate::file::properties::WriterProperties::bloom_filter_auto_size
+//! [hll-paper]: https://algo.inria.fr/flajolet/Publications/FlFuGaMe07.pdf
 //! [parquet-bf-spec]: https://github.com/apache/parquet-format/blob/master/BloomFilter.md
 //! [sbbf-paper]: https://arxiv.org/pdf/2101.01719
 //! [bf-formulae]: http://tfk.mit.edu/pdf/bloom.pdf
@@ -76,16 +98,22 @@ use crate::basic::{BloomFilterAlgorithm, BloomFilterCompression, BloomFilterHash
 use crate::data_type::AsBytes;
 use crate::errors::{ParquetError, Result};
 use crate::file::metadata::ColumnChunkMetaData;
+use crate::file::properties::{BloomFilterProperties, WriterProperties};
 use crate::file::reader::ChunkReader;
 use crate::parquet_thrift::{
     ElementType, FieldType, ReadThrift, ThriftCompactInputProtocol, ThriftCompactOutputProtocol,
     ThriftSliceInputProtocol, WriteThrift, WriteThriftField,
 };
+use crate::schema::types::ColumnPath;
 use crate::thrift_struct;
 use bytes::Bytes;
 use std::io::Write;
 use twox_hash::XxHash64;
 
+mod sketch;
+
+use sketch::DistinctCountSketch;
+
 /// Salt as defined in the [spec](https://github.com/apache/parquet-format/blob/master/BloomFilter.md#technical-approach).
 const SALT: [u32; 8] = [
     0x47b6137b_u32,
@@ -116,7 +144,7 @@ pub struct BloomFilterHeader {
 
 /// Each block is 256 bits, broken up into eight contiguous "words", each consisting of 32 bits.
 /// Each word is thought of as an array of bits; each bit is either "set" or "not set".
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[repr(transparent)]
 struct Block([u32; 8]);
 impl Block {
@@ -163,6 +191,14 @@ impl Block {
         }
     }
 
+    /// returns the block with every bit set that is set in either this block or `other`.
+    fn union(mut self, other: &Self) -> Self {
+        for i in 0..8 {
+            self[i] |= other[i];
+        }
+        self
+    }
+
     /// returns true when every bit that is set in the result of mask is also set in the block.
     fn check(&self, hash: u32) -> bool {
         let mask = Self::mask(hash);
@@ -418,7 +454,7 @@ impl Sbbf {
     /// Check if a hash is in the filter. May return
     /// true for values that was never inserted ("false positive")
     /// but will always return false if a hash has not been inserted.
-    fn check_hash(&self, hash: u64) -> bool {
+    pub(crate) fn check_hash(&self, hash: u64) -> bool {
         let block_index = self.hash_to_block_index(hash);
         self.0[block_index].check(hash as u32)
     }
@@ -428,6 +464,30 @@ impl Sbbf {
         self.0.capacity() * std::mem::size_of::<Block>()
     }
 
+    /// Shrink this filter, without rehashing its values, to the size [`Self::new_with_ndv_fpp`]
+    /// would create for `ndv` and `fpp`, if that is smaller than its current size
+    ///
+    /// This filter must have been created by [`Self::new_with_ndv_fpp`] or
+    /// [`Self::new_with_num_of_bytes`], so that its number of blocks is a power of two.
+    pub(crate) fn shrink_to_ndv_fpp(&mut self, ndv: u64, fpp: f64) {
+        debug_assert!(self.0.len().is_power_of_two());
+        let num_bytes = optimal_num_of_bytes(num_of_bits_from_ndv_fpp(ndv, fpp) / 8);
+        let num_blocks = num_bytes / size_of::<Block>();
+        while self.0.len() > num_blocks {
+            self.fold();
+        }
+        self.0.shrink_to_fit();
+    }
+
+    /// Halve the number of blocks of this filter, see [Auto Sizing](self#auto-sizing)
+    fn fold(&mut self) {
+        let half = self.0.len() / 2;
+        for i in 0..half {
+            self.0[i] = self.0[2 * i].union(&self.0[2 * i + 1]);
+        }
+        self.0.truncate(half);
+    }
+
     /// Reads a Sbff from Thrift encoded bytes
     ///
     /// # Examples
@@ -477,17 +537,143 @@ impl Sbbf {
     }
 }
 
+/// Builds the [`Sbbf`] of a column chunk as configured by the [`WriterProperties`]
+#[derive(Debug, Clone)]
+pub(crate) struct SbbfBuilder {
+    state: SbbfBuilderState,
+    fpp: f64,
+    skip_dictionary_encoded: bool,
+}
+
+#[derive(Debug, Clone)]
+enum SbbfBuilderState {
+    /// The filter, sized for the configured number of distinct values
+    Filter(Sbbf),
+    /// The hashes inserted, buffered until the filter is sized when finished,
+    /// see [Auto Sizing](self#auto-sizing)
+    Buffered {
+        hashes: Vec<u64>,
+        /// The size of the filter for the configured number of distinct values
+        max_bytes: usize,
+    },
+    /// The filter sized for the configured number of distinct values, and a
+    /// sketch of the distinct values inserted, once the buffered hashes would
+    /// have exceeded the size of this filter
+    Sketched(Sbbf, DistinctCountSketch),
+}
+
+impl SbbfBuilder {
+    /// Create a new [`SbbfBuilder`] for the column `col`, returning `None` if
+    /// bloom filters are disabled for the column, or an error if their `fpp` is invalid
+    pub(crate) fn try_new(props: &WriterProperties, col: &ColumnPath) -> Result<Option<Self>> {
+        let Some(bloom_filter_props) = props.bloom_filter_properties(col) else {
+            return Ok(None);
+        };
+        let BloomFilterProperties { ndv, fpp } = *bloom_filter_props;
+        let sbbf = Sbbf::new_with_ndv_fpp(ndv, fpp)?;
+        let state = match props.bloom_filter_auto_size(col) {
+            true => SbbfBuilderState::Buffered {
+                hashes: vec![],
+                max_bytes: sbbf.estimated_memory_size(),
+            },
+            false => SbbfBuilderState::Filter(sbbf),
+        };
+        Ok(Some(Self {
+            state,
+            fpp,
+            skip_dictionary_encoded: props.bloom_filter_skip_dictionary_encoded(col),
+        }))
+    }
+
+    /// Insert an [AsBytes] value into the filter
+    #[inline]
+    pub(crate) fn insert<T: AsBytes + ?Sized>(&mut self, value: &T) {
+        let hash = hash_as_bytes(value);
+        match &mut self.state {
+            SbbfBuilderState::Filter(sbbf) => sbbf.insert_hash(hash),
+            SbbfBuilderState::Buffered { hashes, max_bytes } => {
+                hashes.push(hash);
+                if hashes.len() * size_of::<u64>() >= *max_bytes {
+                    self.compact();
+                }
+            }
+            SbbfBuilderState::Sketched(sbbf, sketch) => {
+                sbbf.insert_hash(hash);
+                sketch.insert_hash(hash);
+            }
+        }
+    }
+
+    /// Removes duplicates from the buffered hashes, switching to a filter sized
+    /// for the configured number of distinct values if fewer than half are duplicates
+    #[cold]
+    fn compact(&mut self) {
+        let SbbfBuilderState::Buffered { hashes, max_bytes } = &mut self.state else {
+            return;
+        };
+        hashes.sort_unstable();
+        hashes.dedup();
+        if hashes.len() * size_of::<u64>() * 2 > *max_bytes {
+            let mut sbbf = Sbbf::new_with_num_of_bytes(*max_bytes);
+            let mut sketch = DistinctCountSketch::default();
+            for hash in hashes.iter() {
+                sbbf.insert_hash(*hash);
+                sketch.insert_hash(*hash);
+            }
+            self.state = SbbfBuilderState::Sketched(sbbf, sketch);
+        }
+    }
+
+    /// Return the total in memory size of this builder in bytes
+    pub(crate) fn estimated_memory_size(&self) -> usize {
+        match &self.state {
+            SbbfBuilderState::Filter(sbbf) => sbbf.estimated_memory_size(),
+            SbbfBuilderState::Buffered { hashes, .. } => hashes.capacity() * size_of::<u64>(),
+            SbbfBuilderState::Sketched(sbbf, sketch) => {
+                sbbf.estimated_memory_size() + sketch.estimated_memory_size()
+            }
+        }
+    }
+
+    /// Returns the bloom filter, or `None` if it should not be written because
+    /// the column chunk is `dictionary_encoded`, i.e. all its data pages are
+    /// dictionary encoded
+    pub(crate) fn finish(self, dictionary_encoded: bool) -> Option<Sbbf> {
+        if dictionary_encoded && self.skip_dictionary_encoded {
+            return None;
+        }
+        match self.state {
+            SbbfBuilderState::Filter(sbbf) => Some(sbbf),
+            SbbfBuilderState::Buffered { mut hashes, .. } => {
+                hashes.sort_unstable();
+                hashes.dedup();
+                let num_bits = num_of_bits_from_ndv_fpp(hashes.len() as u64, self.fpp);
+                let mut sbbf = Sbbf::new_with_num_of_bytes(num_bits / 8);
+                for hash in hashes {
+                    sbbf.insert_hash(hash);
+                }
+                Some(sbbf)
+            }
+            SbbfBuilderState::Sketched(mut sbbf, sketch) => {
+                sbbf.shrink_to_ndv_fpp(sketch.estimate(), self.fpp);
+                Some(sbbf)
+            }
+        }
+    }
+}
+
 // per spec we use xxHash with seed=0
 const SEED: u64 = 0;
 
 #[inline]
-fn hash_as_bytes<A: AsBytes + ?Sized>(value: &A) -> u64 {
+pub(crate) fn hash_as_bytes<A: AsBytes + ?Sized>(value: &A) -> u64 {
     XxHash64::oneshot(SEED, value.as_bytes())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::file::properties::DEFAULT_BLOOM_FILTER_FPP;
 
     #[test]
     fn test_hash_bytes() {
@@ -600,6 +786,93 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_shrink_to_ndv_fpp() {
+        let mut sbbf = Sbbf::new_with_ndv_fpp(1_000_000, 0.01).unwrap();
+        let mut expected = Sbbf::new_with_ndv_fpp(1_000, 0.01).unwrap();
+        for i in 0..1_000_i64 {
+            sbbf.insert(&i);
+            expected.insert(&i);
+        }
+        assert!(sbbf.0.len() > expected.0.len());
+
+        sbbf.shrink_to_ndv_fpp(1_000, 0.01);
+        assert_eq!(sbbf.0, expected.0);
+        assert_eq!(
+            sbbf.estimated_memory_size(),
+            expected.estimated_memory_size()
+        );
+
+        // Never grows the filter
+        sbbf.shrink_to_ndv_fpp(1_000_000, 0.01);
+        assert_eq!(sbbf.0, expected.0);
+
+        // Shrinks to the minimum size
+        sbbf.shrink_to_ndv_fpp(0, 0.01);
+        assert_eq!(sbbf.0.len(), 1);
+        for i in 0..1_000_i64 {
+            assert!(sbbf.check(&i));
+        }
+    }
+
+    #[test]
+    fn test_sbbf_builder() {
+        let col = ColumnPath::from("col");
+        let props = WriterProperties::builder()
+            .set_bloom_filter_fpp(0.01)
+            .set_bloom_filter_ndv(1_000_000)
+            .set_bloom_filter_auto_size(true)
+            .set_bloom_filter_skip_dictionary_encoded(true)
+            .build();
+        let full_size = Sbbf::new_with_ndv_fpp(1_000_000, 0.01)
+            .unwrap()
+            .estimated_memory_size();
+
+        // Few distinct values are buffered, without allocating a filter for the ndv
+        let mut builder = SbbfBuilder::try_new(&props, &col).unwrap().unwrap();
+        for i in 0..10_000_i64 {
+            builder.insert(&i);
+            builder.insert(&i);
+        }
+        assert!(builder.estimated_memory_size() < full_size);
+        assert!(builder.clone().finish(true).is_none());
+
+        let sbbf = builder.finish(false).unwrap();
+        let expected = Sbbf::new_with_ndv_fpp(10_000, 0.01).unwrap();
+        assert_eq!(sbbf.0.len(), expected.0.len());
+        for i in 0..10_000_i64 {
+            assert!(sbbf.check(&i));
+        }
+
+        // Once the buffer would exceed the filter for the ndv, the filter is shrunk instead
+        let props = WriterProperties::builder()
+            .set_bloom_filter_ndv(100_000)
+            .set_bloom_filter_auto_size(true)
+            .build();
+        let mut builder = SbbfBuilder::try_new(&props, &col).unwrap().unwrap();
+        for i in 0..50_000_i64 {
+            builder.insert(&i);
+        }
+        assert!(matches!(builder.state, SbbfBuilderState::Sketched(..)));
+        let sbbf = builder.finish(false).unwrap();
+        let expected = Sbbf::new_with_ndv_fpp(50_000, DEFAULT_BLOOM_FILTER_FPP).unwrap();
+        assert_eq!(sbbf.0.len(), expected.0.len());
+        for i in 0..50_000_i64 {
+            assert!(sbbf.check(&i));
+        }
+
+        let props = WriterProperties::builder()
+            .set_bloom_filter_fpp(0.01)
+            .set_bloom_filter_ndv(1_000_000)
+            .build();
+        let builder = SbbfBuilder::try_new(&props, &col).unwrap().unwrap();
+        let sbbf = builder.finish(true).unwrap();
+        assert_eq!(sbbf.estimated_memory_size(), full_size);
+
+        let props = WriterProperties::builder().build();
+        assert!(SbbfBuilder::try_new(&props, &col).unwrap().is_none());
+    }
+
     #[test]
     fn test_sbbf_write_round_trip() {
         // Create a bloom filter with a 32-byte bitset (minimum size)
@@ -0,0 +1,103 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Estimation of the number of distinct values inserted into a bloom filter
+
+/// The number of hash bits used to select a register
+const PRECISION: u32 = 12;
+
+const NUM_REGISTERS: usize = 1 << PRECISION;
+
+/// A [HyperLogLog] sketch estimating the number of distinct hashes inserted
+///
+/// This uses 4 KiB of memory and has a relative standard error of about 1.6%.
+///
+/// [HyperLogLog]: https://algo.inria.fr/flajolet/Publications/FlFuGaMe07.pdf
+#[derive(Debug, Clone)]
+pub(crate) struct DistinctCountSketch {
+    registers: Vec<u8>,
+}
+
+impl Default for DistinctCountSketch {
+    fn default() -> Self {
+        Self {
+            registers: vec![0; NUM_REGISTERS],
+        }
+    }
+}
+
+impl DistinctCountSketch {
+    /// Insert a 64-bit hash into the sketch
+    #[inline]
+    pub(crate) fn insert_hash(&mut self, hash: u64) {
+        let index = (hash >> (64 - PRECISION)) as usize;
+        // The guard bit bounds the rank by the number of remaining hash bits
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() + 1;
+        let register = &mut self.registers[index];
+        *register = (*register).max(rank as u8);
+    }
+
+    /// Returns the estimated number of distinct hashes inserted
+    pub(crate) fn estimate(&self) -> u64 {
+        let m = NUM_REGISTERS as f64;
+        let (sum, zeros) = self
+            .registers
+            .iter()
+            .fold((0.0, 0_usize), |(sum, zeros), r| {
+                (sum + 2_f64.powi(-(*r as i32)), zeros + (*r == 0) as usize)
+            });
+
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let estimate = alpha * m * m / sum;
+        let estimate = if estimate <= 2.5 * m && zeros != 0 {
+            // Linear counting is more accurate for small cardinalities
+            m * (m / zeros as f64).ln()
+        } else {
+            estimate
+        };
+        estimate.round() as u64
+    }
+
+    /// Return the total in memory size of this sketch in bytes
+    pub(crate) fn estimated_memory_size(&self) -> usize {
+        self.registers.capacity()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bloom_filter::hash_as_bytes;
+
+    #[test]
+    fn test_estimate() {
+        let sketch = DistinctCountSketch::default();
+        assert_eq!(sketch.estimate(), 0);
+
+        for ndv in [1, 10, 100, 1_000, 10_000, 100_000, 1_000_000] {
+            let mut sketch = DistinctCountSketch::default();
+            // Insert each value twice, duplicates must not affect the estimate
+            for _ in 0..2 {
+                for i in 0..ndv {
+                    sketch.insert_hash(hash_as_bytes(&(i as i64)));
+                }
+            }
+            let error = (sketch.estimate() as f64 - ndv as f64).abs() / ndv as f64;
+            assert!(error < 0.05, "ndv {ndv} estimated as {}", sketch.estimate());
+        }
+    }
+}
@@ -31,7 +31,8 @@ use std::sync::Arc;
 /// Encrypts page headers and page data for columns
 pub(crate) struct PageEncryptor {
     file_encryptor: Arc<FileEncryptor>,
-    block_encryptor: Box<dyn BlockEncryptor>,
+    data_encryptor: Box<dyn BlockEncryptor>,
+    metadata_encryptor: Box<dyn BlockEncryptor>,
     row_group_index: usize,
     column_index: usize,
     page_index: usize,
@@ -47,10 +48,13 @@ impl PageEncryptor {
     ) -> Result<Option<Self>> {
         match file_encryptor {
             Some(file_encryptor) if file_encryptor.is_column_encrypted(column_path) => {
-                let block_encryptor = file_encryptor.get_column_encryptor(column_path)?;
+                let data_encryptor = file_encryptor.get_column_data_encryptor(column_path)?;
+                let metadata_encryptor =
+                    file_encryptor.get_column_metadata_encryptor(column_path)?;
                 Ok(Some(Self {
                     file_encryptor: file_encryptor.clone(),
-                    block_encryptor,
+                    data_encryptor,
+                    metadata_encryptor,
                     row_group_index,
                     column_index,
                     page_index: 0,
@@ -78,7 +82,7 @@ impl PageEncryptor {
             self.column_index,
             Some(self.page_index),
         )?;
-        let encrypted_buffer = self.block_encryptor.encrypt(page.data(), &aad)?;
+        let encrypted_buffer = self.data_encryptor.encrypt(page.data(), &aad)?;
 
         Ok(encrypted_buffer)
     }
@@ -114,6 +118,6 @@ impl PageEncryptor {
             Some(self.page_index),
         )?;
 
-        encrypt_thrift_object(page_header, &mut self.block_encryptor, sink, &aad)
+        encrypt_thrift_object(page_header, &mut self.metadata_encryptor, sink, &aad)
     }
 }
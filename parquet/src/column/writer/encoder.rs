@@ -19,7 +19,7 @@ use bytes::Bytes;
 use half::f16;
 
 use crate::basic::{ConvertedType, Encoding, LogicalType, Type};
-use crate::bloom_filter::Sbbf;
+use crate::bloom_filter::{Sbbf, SbbfBuilder};
 use crate::column::writer::{
     compare_greater, fallback_encoding, has_dictionary_support, is_nan, update_max, update_min,
 };
@@ -137,7 +137,9 @@ pub struct ColumnValueEncoderImpl<T: DataType> {
     statistics_enabled: EnabledStatistics,
     min_value: Option<T::T>,
     max_value: Option<T::T>,
-    bloom_filter: Option<Sbbf>,
+    bloom_filter: Option<SbbfBuilder>,
+    /// Whether all values written so far were dictionary encoded
+    dictionary_encoded: bool,
     variable_length_bytes: Option<i64>,
     geo_stats_accumulator: Option<Box<dyn GeoStatsAccumulator>>,
 }
@@ -176,7 +178,10 @@ impl<T: DataType> ColumnValueEncoderImpl<T> {
 
         match &mut self.dict_encoder {
             Some(encoder) => encoder.put(slice),
-            _ => self.encoder.put(slice),
+            _ => {
+                self.dictionary_encoded &= slice.is_empty();
+                self.encoder.put(slice)
+            }
         }
     }
 }
@@ -187,7 +192,7 @@ impl<T: DataType> ColumnValueEncoder for ColumnValueEncoderImpl<T> {
     type Values = [T::T];
 
     fn flush_bloom_filter(&mut self) -> Option<Sbbf> {
-        self.bloom_filter.take()
+        self.bloom_filter.take()?.finish(self.dictionary_encoded)
     }
 
     fn try_new(descr: &ColumnDescPtr, props: &WriterProperties) -> Result<Self> {
@@ -205,10 +210,7 @@ impl<T: DataType> ColumnValueEncoder for ColumnValueEncoderImpl<T> {
 
         let statistics_enabled = props.statistics_enabled(descr.path());
 
-        let bloom_filter = props
-            .bloom_filter_properties(descr.path())
-            .map(|props| Sbbf::new_with_ndv_fpp(props.ndv, props.fpp))
-            .transpose()?;
+        let bloom_filter = SbbfBuilder::try_new(props, descr.path())?;
 
         let geo_stats_accumulator = try_new_geo_stats_accumulator(descr);
 
@@ -219,6 +221,7 @@ impl<T: DataType> ColumnValueEncoder for ColumnValueEncoderImpl<T> {
             num_values: 0,
             statistics_enabled,
             bloom_filter,
+            dictionary_encoded: dict_supported,
             min_value: None,
             max_value: None,
             variable_length_bytes: None,
@@ -19,9 +19,11 @@ use crate::errors::ParquetError;
 use crate::errors::ParquetError::General;
 use crate::errors::Result;
 use crate::file::metadata::HeapSize;
+use aes::Aes128;
+use ctr::cipher::{KeyIvInit, StreamCipher};
 use ring::aead::{AES_128_GCM, Aad, LessSafeKey, NonceSequence, UnboundKey};
 use ring::rand::{SecureRandom, SystemRandom};
-use std::fmt::Debug;
+use std::fmt::{Debug, Formatter};
 
 const RIGHT_TWELVE: u128 = 0x0000_0000_ffff_ffff_ffff_ffff_ffff_ffff;
 pub(crate) const NONCE_LEN: usize = 12;
@@ -186,6 +188,118 @@ impl BlockEncryptor for RingGcmBlockEncryptor {
     }
 }
 
+/// AES-CTR with a 32 bit big endian counter, as used by `AES_GCM_CTR_V1`
+type Aes128Ctr = ctr::Ctr32BE<Aes128>;
+
+const CTR_KEY_LEN: usize = 16;
+
+/// Creates an AES-CTR cipher for a module with the given nonce. The initialization
+/// vector is the nonce followed by a 4 byte counter, which starts at 1.
+fn ctr_cipher(key: &[u8; CTR_KEY_LEN], nonce: &[u8]) -> Aes128Ctr {
+    let mut iv = [0u8; 16];
+    iv[..NONCE_LEN].copy_from_slice(nonce);
+    iv[15] = 1;
+    Aes128Ctr::new(key.into(), &iv.into())
+}
+
+/// Returns the AES-CTR key, only 128-bit keys are supported, as for AES-GCM
+fn ctr_key(key_bytes: &[u8]) -> Result<[u8; CTR_KEY_LEN]> {
+    key_bytes.try_into().map_err(|_| {
+        general_err!(
+            "AES-CTR requires a {}-bit key, found a {}-bit key",
+            CTR_KEY_LEN * 8,
+            key_bytes.len() * 8
+        )
+    })
+}
+
+/// Decrypts page data encrypted with AES-CTR by the `AES_GCM_CTR_V1` algorithm.
+/// CTR mode does not authenticate data, so the AAD is unused.
+#[derive(Clone)]
+pub(crate) struct CtrBlockDecryptor {
+    key: [u8; CTR_KEY_LEN],
+}
+
+impl CtrBlockDecryptor {
+    pub(crate) fn new(key_bytes: &[u8]) -> Result<Self> {
+        Ok(Self {
+            key: ctr_key(key_bytes)?,
+        })
+    }
+}
+
+impl Debug for CtrBlockDecryptor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CtrBlockDecryptor").finish_non_exhaustive()
+    }
+}
+
+impl HeapSize for CtrBlockDecryptor {
+    fn heap_size(&self) -> usize {
+        0
+    }
+}
+
+impl BlockDecryptor for CtrBlockDecryptor {
+    fn decrypt(&self, length_and_ciphertext: &[u8], _aad: &[u8]) -> Result<Vec<u8>> {
+        if length_and_ciphertext.len() < SIZE_LEN + NONCE_LEN {
+            return Err(General("Ciphertext is too short".to_string()));
+        }
+        let nonce = &length_and_ciphertext[SIZE_LEN..SIZE_LEN + NONCE_LEN];
+        let mut result = length_and_ciphertext[SIZE_LEN + NONCE_LEN..].to_vec();
+        ctr_cipher(&self.key, nonce).apply_keystream(&mut result);
+        Ok(result)
+    }
+
+    fn compute_plaintext_tag(&self, _aad: &[u8], _plaintext: &[u8]) -> Result<Vec<u8>> {
+        Err(General(
+            "Authentication tags cannot be computed with AES-CTR".to_string(),
+        ))
+    }
+}
+
+/// Encrypts page data with AES-CTR for the `AES_GCM_CTR_V1` algorithm
+#[derive(Clone)]
+pub(crate) struct CtrBlockEncryptor {
+    key: [u8; CTR_KEY_LEN],
+    rng: SystemRandom,
+}
+
+impl CtrBlockEncryptor {
+    /// Create a new `CtrBlockEncryptor` with a given key, using a random nonce
+    /// for each block
+    pub(crate) fn new(key_bytes: &[u8]) -> Result<Self> {
+        Ok(Self {
+            key: ctr_key(key_bytes)?,
+            rng: SystemRandom::new(),
+        })
+    }
+}
+
+impl Debug for CtrBlockEncryptor {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CtrBlockEncryptor").finish_non_exhaustive()
+    }
+}
+
+impl BlockEncryptor for CtrBlockEncryptor {
+    fn encrypt(&mut self, plaintext: &[u8], _aad: &[u8]) -> Result<Vec<u8>> {
+        // Format is: [ciphertext size, nonce, ciphertext]
+        let ciphertext_length: u32 = (NONCE_LEN + plaintext.len())
+            .try_into()
+            .map_err(|err| General(format!("Plaintext data too long. {err:?}")))?;
+        let mut nonce = [0; NONCE_LEN];
+        self.rng.fill(&mut nonce)?;
+
+        let mut ciphertext = Vec::with_capacity(SIZE_LEN + ciphertext_length as usize);
+        ciphertext.extend(ciphertext_length.to_le_bytes());
+        ciphertext.extend(nonce);
+        ciphertext.extend(plaintext);
+        ctr_cipher(&self.key, &nonce).apply_keystream(&mut ciphertext[SIZE_LEN + NONCE_LEN..]);
+        Ok(ciphertext)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -204,4 +318,52 @@ mod tests {
 
         assert_eq!(plaintext, decrypted.as_slice());
     }
+
+    #[test]
+    fn test_ctr_round_trip() {
+        let key = [1u8; 16];
+        let mut encryptor = CtrBlockEncryptor::new(&key).unwrap();
+        let decryptor = CtrBlockDecryptor::new(&key).unwrap();
+
+        let plaintext = b"hello, world!";
+        let ciphertext = encryptor.encrypt(plaintext, b"").unwrap();
+        assert_eq!(ciphertext.len(), SIZE_LEN + NONCE_LEN + plaintext.len());
+        assert_eq!(
+            u32::from_le_bytes(ciphertext[..SIZE_LEN].try_into().unwrap()) as usize,
+            NONCE_LEN + plaintext.len()
+        );
+        assert_ne!(&ciphertext[SIZE_LEN + NONCE_LEN..], plaintext);
+
+        let decrypted = decryptor.decrypt(&ciphertext, b"").unwrap();
+        assert_eq!(plaintext, decrypted.as_slice());
+
+        // Each block uses a new nonce
+        let other = encryptor.encrypt(plaintext, b"").unwrap();
+        assert_ne!(ciphertext, other);
+    }
+
+    #[test]
+    fn test_ctr_keystream() {
+        // The counter starts at 1, so with a zero key and nonce the keystream
+        // is the AES encryption of the block 0x00..01
+        let key = [0u8; 16];
+        let decryptor = CtrBlockDecryptor::new(&key).unwrap();
+        let mut ciphertext = vec![0u8; SIZE_LEN + NONCE_LEN + 16];
+        ciphertext[..SIZE_LEN].copy_from_slice(&((NONCE_LEN + 16) as u32).to_le_bytes());
+        let keystream = decryptor.decrypt(&ciphertext, b"").unwrap();
+        assert_eq!(
+            keystream,
+            [
+                0x58, 0xe2, 0xfc, 0xce, 0xfa, 0x7e, 0x30, 0x61, 0x36, 0x7f, 0x1d, 0x57, 0xa4,
+                0xe7, 0x45, 0x5a
+            ]
+        );
+
+        let err = CtrBlockDecryptor::new(&[0u8; 32]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: AES-CTR requires a 128-bit key, found a 256-bit key"
+        );
+        CtrBlockEncryptor::new(&[0u8; 3]).unwrap_err();
+    }
 }
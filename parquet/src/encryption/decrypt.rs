@@ -17,7 +17,10 @@
 
 //! Configuration and utilities for decryption of files using Parquet Modular Encryption
 
-use crate::encryption::ciphers::{BlockDecryptor, RingGcmBlockDecryptor, TAG_LEN};
+use crate::encryption::ciphers::{
+    BlockDecryptor, CtrBlockDecryptor, RingGcmBlockDecryptor, TAG_LEN,
+};
+use crate::encryption::encrypt::ParquetCipher;
 use crate::encryption::modules::{ModuleType, create_footer_aad, create_module_aad};
 use crate::errors::{ParquetError, Result};
 use crate::file::column_crypto_metadata::ColumnCryptoMetaData;
@@ -144,8 +147,7 @@ impl CryptoContext {
     ) -> Result<Self> {
         let (data_decryptor, metadata_decryptor) = match column_crypto_metadata {
             ColumnCryptoMetaData::ENCRYPTION_WITH_FOOTER_KEY => {
-                // TODO: In GCM-CTR mode will this need to be a non-GCM decryptor?
-                let data_decryptor = file_decryptor.get_footer_decryptor()?;
+                let data_decryptor = file_decryptor.get_footer_data_decryptor();
                 let metadata_decryptor = file_decryptor.get_footer_decryptor()?;
                 (data_decryptor, metadata_decryptor)
             }
@@ -158,11 +160,7 @@ impl CryptoContext {
                     full_column_name = column_key_encryption.path_in_schema.join(".");
                     &full_column_name
                 };
-                let data_decryptor = file_decryptor
-                    .get_column_data_decryptor(column_name, key_metadata.as_deref())?;
-                let metadata_decryptor = file_decryptor
-                    .get_column_metadata_decryptor(column_name, key_metadata.as_deref())?;
-                (data_decryptor, metadata_decryptor)
+                file_decryptor.get_column_decryptors(column_name, key_metadata.as_deref())?
             }
         };
 
@@ -563,6 +561,9 @@ impl DecryptionPropertiesBuilderWithRetriever {
 pub(crate) struct FileDecryptor {
     decryption_properties: Arc<FileDecryptionProperties>,
     footer_decryptor: Arc<dyn BlockDecryptor>,
+    /// Decrypts page data of columns encrypted with the footer key
+    footer_data_decryptor: Arc<dyn BlockDecryptor>,
+    algorithm: ParquetCipher,
     file_aad: Vec<u8>,
 }
 
@@ -593,19 +594,27 @@ impl FileDecryptor {
         footer_key_metadata: Option<&[u8]>,
         aad_file_unique: Vec<u8>,
         aad_prefix: Vec<u8>,
+        algorithm: ParquetCipher,
     ) -> Result<Self> {
         let file_aad = [aad_prefix.as_slice(), aad_file_unique.as_slice()].concat();
         let footer_key = decryption_properties.footer_key(footer_key_metadata)?;
-        let footer_decryptor = RingGcmBlockDecryptor::new(&footer_key).map_err(|e| {
-            general_err!(
-                "Invalid footer key. {}",
-                e.to_string().replace("Parquet error: ", "")
-            )
-        })?;
+        let footer_decryptor: Arc<dyn BlockDecryptor> =
+            Arc::new(RingGcmBlockDecryptor::new(&footer_key).map_err(|e| {
+                general_err!(
+                    "Invalid footer key. {}",
+                    e.to_string().replace("Parquet error: ", "")
+                )
+            })?);
+        let footer_data_decryptor = match algorithm {
+            ParquetCipher::AesGcmV1 => Arc::clone(&footer_decryptor),
+            ParquetCipher::AesGcmCtrV1 => Arc::new(CtrBlockDecryptor::new(&footer_key)?),
+        };
 
         Ok(Self {
-            footer_decryptor: Arc::new(footer_decryptor),
+            footer_decryptor,
+            footer_data_decryptor,
             decryption_properties: Arc::clone(decryption_properties),
+            algorithm,
             file_aad,
         })
     }
@@ -614,6 +623,11 @@ impl FileDecryptor {
         Ok(self.footer_decryptor.clone())
     }
 
+    /// Get the decryptor for page data of columns encrypted with the footer key
+    pub(crate) fn get_footer_data_decryptor(&self) -> Arc<dyn BlockDecryptor> {
+        self.footer_data_decryptor.clone()
+    }
+
     /// Verify the signature of the footer
     pub(crate) fn verify_plaintext_footer_signature(&self, plaintext_footer: &[u8]) -> Result<()> {
         // Plaintext footer format is: [plaintext metadata, nonce, authentication tag]
@@ -633,24 +647,34 @@ impl FileDecryptor {
         Ok(())
     }
 
-    pub(crate) fn get_column_data_decryptor(
+    /// Get the decryptors for the page data and metadata of a column
+    pub(crate) fn get_column_decryptors(
         &self,
         column_name: &str,
         key_metadata: Option<&[u8]>,
-    ) -> Result<Arc<dyn BlockDecryptor>> {
+    ) -> Result<(Arc<dyn BlockDecryptor>, Arc<dyn BlockDecryptor>)> {
         let column_key = self
             .decryption_properties
             .column_key(column_name, key_metadata)?;
-        Ok(Arc::new(RingGcmBlockDecryptor::new(&column_key)?))
+        let metadata_decryptor: Arc<dyn BlockDecryptor> =
+            Arc::new(RingGcmBlockDecryptor::new(&column_key)?);
+        let data_decryptor = match self.algorithm {
+            ParquetCipher::AesGcmV1 => Arc::clone(&metadata_decryptor),
+            ParquetCipher::AesGcmCtrV1 => Arc::new(CtrBlockDecryptor::new(&column_key)?),
+        };
+        Ok((data_decryptor, metadata_decryptor))
     }
 
+    /// Get the decryptor for the metadata of a column, which always uses AES-GCM
     pub(crate) fn get_column_metadata_decryptor(
         &self,
         column_name: &str,
         key_metadata: Option<&[u8]>,
     ) -> Result<Arc<dyn BlockDecryptor>> {
-        // Once GCM CTR mode is implemented, data and metadata decryptors may be different
-        self.get_column_data_decryptor(column_name, key_metadata)
+        let column_key = self
+            .decryption_properties
+            .column_key(column_name, key_metadata)?;
+        Ok(Arc::new(RingGcmBlockDecryptor::new(&column_key)?))
     }
 
     pub(crate) fn file_aad(&self) -> &Vec<u8> {
@@ -18,7 +18,7 @@
 //! Configuration and utilities for Parquet Modular Encryption
 
 use crate::encryption::ciphers::{
-    BlockEncryptor, NONCE_LEN, RingGcmBlockEncryptor, SIZE_LEN, TAG_LEN,
+    BlockEncryptor, CtrBlockEncryptor, NONCE_LEN, RingGcmBlockEncryptor, SIZE_LEN, TAG_LEN,
 };
 use crate::errors::{ParquetError, Result};
 use crate::file::column_crypto_metadata::{ColumnCryptoMetaData, EncryptionWithColumnKey};
@@ -29,6 +29,20 @@ use std::collections::{HashMap, HashSet};
 use std::io::Write;
 use std::sync::Arc;
 
+/// The algorithm used to encrypt a file
+///
+/// See the [specification](https://github.com/apache/parquet-format/blob/master/Encryption.md#4-encryption-algorithms)
+/// for details.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ParquetCipher {
+    /// AES-GCM for all modules, authenticating both data and metadata
+    #[default]
+    AesGcmV1,
+    /// AES-GCM for metadata and AES-CTR for page data. This is faster for large
+    /// pages, but page data is not authenticated.
+    AesGcmCtrV1,
+}
+
 #[derive(Debug, Clone, PartialEq)]
 struct EncryptionKey {
     key: Vec<u8>,
@@ -91,6 +105,7 @@ impl EncryptionKey {
 /// # Ok::<(), parquet::errors::ParquetError>(())
 /// ```
 pub struct FileEncryptionProperties {
+    algorithm: ParquetCipher,
     encrypt_footer: bool,
     footer_key: EncryptionKey,
     column_keys: HashMap<String, EncryptionKey>,
@@ -104,6 +119,11 @@ impl FileEncryptionProperties {
         EncryptionPropertiesBuilder::new(footer_key)
     }
 
+    /// The encryption algorithm
+    pub fn algorithm(&self) -> ParquetCipher {
+        self.algorithm
+    }
+
     /// Should the footer be encrypted
     pub fn encrypt_footer(&self) -> bool {
         self.encrypt_footer
@@ -181,6 +201,7 @@ impl FileEncryptionProperties {
 ///
 /// See [`FileEncryptionProperties`] for example usage.
 pub struct EncryptionPropertiesBuilder {
+    algorithm: ParquetCipher,
     encrypt_footer: bool,
     footer_key: EncryptionKey,
     column_keys: HashMap<String, EncryptionKey>,
@@ -192,6 +213,7 @@ impl EncryptionPropertiesBuilder {
     /// Create a new [`EncryptionPropertiesBuilder`] with the given footer encryption key
     pub fn new(footer_key: Vec<u8>) -> EncryptionPropertiesBuilder {
         Self {
+            algorithm: ParquetCipher::default(),
             footer_key: EncryptionKey::new(footer_key),
             column_keys: HashMap::default(),
             aad_prefix: None,
@@ -200,6 +222,12 @@ impl EncryptionPropertiesBuilder {
         }
     }
 
+    /// Set the encryption algorithm. Defaults to [`ParquetCipher::AesGcmV1`].
+    pub fn with_algorithm(mut self, algorithm: ParquetCipher) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
     /// Set if the footer should be stored in plaintext (not encrypted). Defaults to false.
     pub fn with_plaintext_footer(mut self, plaintext_footer: bool) -> Self {
         self.encrypt_footer = !plaintext_footer;
@@ -277,6 +305,7 @@ impl EncryptionPropertiesBuilder {
     /// Build the encryption properties
     pub fn build(self) -> Result<Arc<FileEncryptionProperties>> {
         Ok(Arc::new(FileEncryptionProperties {
+            algorithm: self.algorithm,
             encrypt_footer: self.encrypt_footer,
             footer_key: self.footer_key,
             column_keys: self.column_keys,
@@ -348,18 +377,38 @@ impl FileEncryptor {
         )?))
     }
 
-    /// Get the encryptor for a column.
+    /// Get the encryptor for the metadata of a column, which always uses AES-GCM.
     /// Will return an error if the column is not an encrypted column.
-    pub(crate) fn get_column_encryptor(
+    pub(crate) fn get_column_metadata_encryptor(
         &self,
         column_path: &str,
     ) -> Result<Box<dyn BlockEncryptor>> {
+        Ok(Box::new(RingGcmBlockEncryptor::new(
+            self.column_key(column_path)?,
+        )?))
+    }
+
+    /// Get the encryptor for the page data of a column, which uses AES-CTR with
+    /// the `AES_GCM_CTR_V1` algorithm.
+    /// Will return an error if the column is not an encrypted column.
+    pub(crate) fn get_column_data_encryptor(
+        &self,
+        column_path: &str,
+    ) -> Result<Box<dyn BlockEncryptor>> {
+        let key = self.column_key(column_path)?;
+        Ok(match self.properties.algorithm {
+            ParquetCipher::AesGcmV1 => Box::new(RingGcmBlockEncryptor::new(key)?),
+            ParquetCipher::AesGcmCtrV1 => Box::new(CtrBlockEncryptor::new(key)?),
+        })
+    }
+
+    fn column_key(&self, column_path: &str) -> Result<&[u8]> {
         if self.properties.column_keys.is_empty() {
-            return self.get_footer_encryptor();
+            return Ok(&self.properties.footer_key.key);
         }
         match self.properties.column_keys.get(column_path) {
             None => Err(general_err!("Column '{}' is not encrypted", column_path)),
-            Some(column_key) => Ok(Box::new(RingGcmBlockEncryptor::new(column_key.key())?)),
+            Some(column_key) => Ok(column_key.key()),
         }
     }
 }
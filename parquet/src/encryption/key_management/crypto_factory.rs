@@ -0,0 +1,621 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Creation of file encryption and decryption properties using a KMS
+
+use crate::encryption::decrypt::{FileDecryptionProperties, KeyRetriever};
+use crate::encryption::encrypt::FileEncryptionProperties;
+use crate::encryption::key_management::key_cache::ExpiringCache;
+use crate::encryption::key_management::key_encryption::{
+    decrypt_key_locally, encrypt_key_locally, random_key,
+};
+use crate::encryption::key_management::key_material::{KeyMaterial, WrappedKek};
+use crate::encryption::key_management::kms::{
+    KmsClientFactory, KmsClientRef, KmsConnectionConfig,
+};
+use crate::errors::{ParquetError, Result};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The default lifetime of cached KMS clients and key encryption keys
+pub const DEFAULT_CACHE_LIFETIME: Duration = Duration::from_secs(600);
+
+/// How to encrypt a file with keys managed by a KMS
+#[derive(Debug, Clone)]
+pub struct EncryptionConfiguration {
+    footer_key_id: String,
+    column_key_ids: HashMap<String, Vec<String>>,
+    plaintext_footer: bool,
+    double_wrapping: bool,
+    cache_lifetime: Option<Duration>,
+}
+
+impl EncryptionConfiguration {
+    /// Returns a new [`EncryptionConfigurationBuilder`], encrypting the footer with
+    /// a data key wrapped by the master key `footer_key_id`
+    pub fn builder(footer_key_id: String) -> EncryptionConfigurationBuilder {
+        EncryptionConfigurationBuilder::new(footer_key_id)
+    }
+
+    /// The ID of the master key used to wrap the footer key
+    pub fn footer_key_id(&self) -> &str {
+        &self.footer_key_id
+    }
+
+    /// The paths of the encrypted columns by the ID of the master key wrapping their keys
+    pub fn column_key_ids(&self) -> &HashMap<String, Vec<String>> {
+        &self.column_key_ids
+    }
+
+    /// If the footer is left unencrypted
+    pub fn plaintext_footer(&self) -> bool {
+        self.plaintext_footer
+    }
+
+    /// If data keys are wrapped with key encryption keys, which are in turn wrapped by the KMS
+    pub fn double_wrapping(&self) -> bool {
+        self.double_wrapping
+    }
+
+    /// How long KMS clients and key encryption keys are cached
+    pub fn cache_lifetime(&self) -> Option<Duration> {
+        self.cache_lifetime
+    }
+}
+
+/// Builder for [`EncryptionConfiguration`]
+#[derive(Debug)]
+pub struct EncryptionConfigurationBuilder {
+    footer_key_id: String,
+    column_key_ids: HashMap<String, Vec<String>>,
+    plaintext_footer: bool,
+    double_wrapping: bool,
+    cache_lifetime: Option<Duration>,
+}
+
+impl EncryptionConfigurationBuilder {
+    /// Create a new [`EncryptionConfigurationBuilder`] with the master key used for the footer
+    pub fn new(footer_key_id: String) -> Self {
+        Self {
+            footer_key_id,
+            column_key_ids: HashMap::new(),
+            plaintext_footer: false,
+            double_wrapping: true,
+            cache_lifetime: Some(DEFAULT_CACHE_LIFETIME),
+        }
+    }
+
+    /// Encrypt the columns at `column_paths` with data keys wrapped by the
+    /// master key `master_key_id`
+    ///
+    /// If no column keys are set, all columns are encrypted with the footer key.
+    pub fn with_column_keys(mut self, master_key_id: &str, column_paths: Vec<&str>) -> Self {
+        self.column_key_ids
+            .entry(master_key_id.to_string())
+            .or_default()
+            .extend(column_paths.into_iter().map(String::from));
+        self
+    }
+
+    /// Leave the footer unencrypted, so readers without keys can read the schema
+    /// and unencrypted columns. Defaults to `false`.
+    pub fn with_plaintext_footer(mut self, plaintext_footer: bool) -> Self {
+        self.plaintext_footer = plaintext_footer;
+        self
+    }
+
+    /// Wrap data keys with key encryption keys (KEKs) that are wrapped by the KMS,
+    /// rather than by the KMS directly. This reduces KMS calls, as KEKs are cached
+    /// and shared between files. Defaults to `true`.
+    pub fn with_double_wrapping(mut self, double_wrapping: bool) -> Self {
+        self.double_wrapping = double_wrapping;
+        self
+    }
+
+    /// Set how long KMS clients and key encryption keys are cached, or `None` to
+    /// disable caching. Defaults to [`DEFAULT_CACHE_LIFETIME`].
+    pub fn with_cache_lifetime(mut self, cache_lifetime: Option<Duration>) -> Self {
+        self.cache_lifetime = cache_lifetime;
+        self
+    }
+
+    /// Finalize the builder and return the created [`EncryptionConfiguration`]
+    pub fn build(self) -> Result<EncryptionConfiguration> {
+        let mut columns = HashSet::new();
+        for column in self.column_key_ids.values().flatten() {
+            if !columns.insert(column) {
+                return Err(general_err!(
+                    "Multiple keys specified for column '{}'",
+                    column
+                ));
+            }
+        }
+        Ok(EncryptionConfiguration {
+            footer_key_id: self.footer_key_id,
+            column_key_ids: self.column_key_ids,
+            plaintext_footer: self.plaintext_footer,
+            double_wrapping: self.double_wrapping,
+            cache_lifetime: self.cache_lifetime,
+        })
+    }
+}
+
+/// How to decrypt files with keys managed by a KMS
+#[derive(Debug, Clone)]
+pub struct DecryptionConfiguration {
+    cache_lifetime: Option<Duration>,
+}
+
+impl Default for DecryptionConfiguration {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl DecryptionConfiguration {
+    /// Returns a new [`DecryptionConfigurationBuilder`]
+    pub fn builder() -> DecryptionConfigurationBuilder {
+        DecryptionConfigurationBuilder::default()
+    }
+
+    /// How long KMS clients and key encryption keys are cached
+    pub fn cache_lifetime(&self) -> Option<Duration> {
+        self.cache_lifetime
+    }
+}
+
+/// Builder for [`DecryptionConfiguration`]
+#[derive(Debug)]
+pub struct DecryptionConfigurationBuilder {
+    cache_lifetime: Option<Duration>,
+}
+
+impl Default for DecryptionConfigurationBuilder {
+    fn default() -> Self {
+        Self {
+            cache_lifetime: Some(DEFAULT_CACHE_LIFETIME),
+        }
+    }
+}
+
+impl DecryptionConfigurationBuilder {
+    /// Set how long KMS clients and key encryption keys are cached, or `None` to
+    /// disable caching. Defaults to [`DEFAULT_CACHE_LIFETIME`].
+    pub fn with_cache_lifetime(mut self, cache_lifetime: Option<Duration>) -> Self {
+        self.cache_lifetime = cache_lifetime;
+        self
+    }
+
+    /// Finalize the builder and return the created [`DecryptionConfiguration`]
+    pub fn build(self) -> DecryptionConfiguration {
+        DecryptionConfiguration {
+            cache_lifetime: self.cache_lifetime,
+        }
+    }
+}
+
+/// A key encryption key, with its wrapped form as stored in key material
+struct Kek {
+    key: Vec<u8>,
+    id: Vec<u8>,
+    wrapped: WrappedKek,
+}
+
+/// (key access token, KMS instance ID, KMS instance URL)
+type ClientCacheKey = (String, String, String);
+
+/// Caches shared by all files encrypted or decrypted by a [`CryptoFactory`],
+/// keyed by the key access token so that tokens cannot access keys retrieved
+/// with other tokens
+#[derive(Default)]
+struct KeyCaches {
+    clients: ExpiringCache<ClientCacheKey, KmsClientRef>,
+    /// KEKs for writing by (token, master key ID)
+    write_keks: ExpiringCache<(String, String), Arc<Kek>>,
+    /// Unwrapped KEKs by (token, base64 encoded KEK ID)
+    read_keks: ExpiringCache<(String, String), Vec<u8>>,
+}
+
+/// The state shared between a [`CryptoFactory`] and the key retrievers it creates
+struct Shared {
+    kms_client_factory: Box<dyn KmsClientFactory>,
+    caches: KeyCaches,
+}
+
+impl Shared {
+    fn kms_client(
+        &self,
+        config: &KmsConnectionConfig,
+        lifetime: Option<Duration>,
+    ) -> Result<KmsClientRef> {
+        let key = (
+            config.key_access_token(),
+            config.kms_instance_id().to_string(),
+            config.kms_instance_url().to_string(),
+        );
+        self.caches.clients.get_or_try_insert(key, lifetime, || {
+            self.kms_client_factory.create_client(config)
+        })
+    }
+}
+
+/// Creates [`FileEncryptionProperties`] and [`FileDecryptionProperties`] for files
+/// whose data keys are wrapped by master keys in a Key Management Server (KMS)
+///
+/// The key material needed to unwrap each data key is stored in the key metadata
+/// of the file using the format of the parquet-java key tools, so files can be
+/// read and written by Spark, PyArrow and other implementations with the same KMS.
+///
+/// KMS clients and key encryption keys are cached within a `CryptoFactory`, so it
+/// should be reused for all files. See the [module docs](super) for an example.
+pub struct CryptoFactory {
+    shared: Arc<Shared>,
+}
+
+impl std::fmt::Debug for CryptoFactory {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CryptoFactory").finish_non_exhaustive()
+    }
+}
+
+impl CryptoFactory {
+    /// Creates a factory using clients created by `kms_client_factory`
+    pub fn new(kms_client_factory: impl KmsClientFactory + 'static) -> Self {
+        Self {
+            shared: Arc::new(Shared {
+                kms_client_factory: Box::new(kms_client_factory),
+                caches: KeyCaches::default(),
+            }),
+        }
+    }
+
+    /// Returns properties for writing a file encrypted with new random data keys,
+    /// wrapped by the master keys of `encryption_configuration`
+    pub fn file_encryption_properties(
+        &self,
+        kms_connection_config: &KmsConnectionConfig,
+        encryption_configuration: &EncryptionConfiguration,
+    ) -> Result<Arc<FileEncryptionProperties>> {
+        let wrapper = KeyWrapper {
+            shared: &self.shared,
+            client: self
+                .shared
+                .kms_client(kms_connection_config, encryption_configuration.cache_lifetime)?,
+            config: kms_connection_config,
+            encryption_configuration,
+        };
+
+        let footer_key = random_key()?;
+        let footer_metadata =
+            wrapper.key_metadata(&footer_key, &encryption_configuration.footer_key_id, true)?;
+        let mut builder = FileEncryptionProperties::builder(footer_key)
+            .with_footer_key_metadata(footer_metadata)
+            .with_plaintext_footer(encryption_configuration.plaintext_footer);
+
+        for (master_key_id, columns) in &encryption_configuration.column_key_ids {
+            for column in columns {
+                let key = random_key()?;
+                let metadata = wrapper.key_metadata(&key, master_key_id, false)?;
+                builder = builder.with_column_key_and_metadata(column, key, metadata);
+            }
+        }
+        builder.build()
+    }
+
+    /// Returns properties for reading files written with
+    /// [`Self::file_encryption_properties`] or other implementations of the
+    /// parquet-java key tools
+    pub fn file_decryption_properties(
+        &self,
+        kms_connection_config: Arc<KmsConnectionConfig>,
+        decryption_configuration: DecryptionConfiguration,
+    ) -> Result<Arc<FileDecryptionProperties>> {
+        let retriever = KeyUnwrapper {
+            shared: Arc::clone(&self.shared),
+            config: kms_connection_config,
+            cache_lifetime: decryption_configuration.cache_lifetime,
+        };
+        FileDecryptionProperties::with_key_retriever(Arc::new(retriever)).build()
+    }
+
+    /// Removes all cached KMS clients and key encryption keys, for example after
+    /// revoking an access token
+    pub fn clear_caches(&self) {
+        let caches = &self.shared.caches;
+        caches.clients.clear();
+        caches.write_keks.clear();
+        caches.read_keks.clear();
+    }
+}
+
+/// Wraps the data keys of a single file
+struct KeyWrapper<'a> {
+    shared: &'a Shared,
+    client: KmsClientRef,
+    config: &'a KmsConnectionConfig,
+    encryption_configuration: &'a EncryptionConfiguration,
+}
+
+impl KeyWrapper<'_> {
+    /// Wraps `key` with the master key `master_key_id`, returning the key metadata
+    fn key_metadata(&self, key: &[u8], master_key_id: &str, is_footer_key: bool) -> Result<Vec<u8>> {
+        let (wrapped_dek, kek) = match self.encryption_configuration.double_wrapping {
+            true => {
+                let kek = self.kek(master_key_id)?;
+                let wrapped_dek = encrypt_key_locally(key, &kek.key, &kek.id)?;
+                (wrapped_dek, Some(kek.wrapped.clone()))
+            }
+            false => (self.client.wrap_key(key, master_key_id)?, None),
+        };
+        let material = KeyMaterial {
+            is_footer_key,
+            kms_instance_id: is_footer_key.then(|| self.config.kms_instance_id().to_string()),
+            kms_instance_url: is_footer_key.then(|| self.config.kms_instance_url().to_string()),
+            master_key_id: master_key_id.to_string(),
+            wrapped_dek,
+            kek,
+        };
+        Ok(material.serialize().into_bytes())
+    }
+
+    /// Returns the cached KEK for `master_key_id`, or creates and wraps a new one
+    fn kek(&self, master_key_id: &str) -> Result<Arc<Kek>> {
+        let token = self.config.key_access_token();
+        let lifetime = self.encryption_configuration.cache_lifetime;
+        let key = (token.clone(), master_key_id.to_string());
+        self.shared
+            .caches
+            .write_keks
+            .get_or_try_insert(key, lifetime, || {
+                let key = random_key()?;
+                let id = random_key()?;
+                let wrapped = WrappedKek {
+                    id: BASE64_STANDARD.encode(&id),
+                    wrapped: self.client.wrap_key(&key, master_key_id)?,
+                };
+                // Files written by this process can be read without unwrapping the KEK
+                let read_key = (token.clone(), wrapped.id.clone());
+                self.shared
+                    .caches
+                    .read_keks
+                    .get_or_try_insert(read_key, lifetime, || Ok(key.clone()))?;
+                Ok(Arc::new(Kek { key, id, wrapped }))
+            })
+    }
+}
+
+/// A [`KeyRetriever`] unwrapping data keys from their key material
+struct KeyUnwrapper {
+    shared: Arc<Shared>,
+    config: Arc<KmsConnectionConfig>,
+    cache_lifetime: Option<Duration>,
+}
+
+impl KeyRetriever for KeyUnwrapper {
+    fn retrieve_key(&self, key_metadata: &[u8]) -> Result<Vec<u8>> {
+        let material = KeyMaterial::parse(key_metadata)?;
+        let with_defaults = self.config.with_defaults(
+            material.kms_instance_id.as_deref(),
+            material.kms_instance_url.as_deref(),
+        );
+        let config = with_defaults.as_ref().unwrap_or(&self.config);
+        let client = self.shared.kms_client(config, self.cache_lifetime)?;
+
+        let Some(kek) = &material.kek else {
+            return client.unwrap_key(&material.wrapped_dek, &material.master_key_id);
+        };
+        let key = (config.key_access_token(), kek.id.clone());
+        let kek_bytes = self
+            .shared
+            .caches
+            .read_keks
+            .get_or_try_insert(key, self.cache_lifetime, || {
+                client.unwrap_key(&kek.wrapped, &material.master_key_id)
+            })?;
+        let kek_id = BASE64_STANDARD
+            .decode(&kek.id)
+            .map_err(|e| general_err!("Invalid base64 in key encryption key ID: {}", e))?;
+        decrypt_key_locally(&material.wrapped_dek, &kek_bytes, &kek_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::encryption::key_management::kms::{InMemoryKms, KmsClient};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// Counts the calls to an [`InMemoryKms`]
+    #[derive(Debug, Default)]
+    struct Counts {
+        clients: AtomicUsize,
+        wraps: AtomicUsize,
+        unwraps: AtomicUsize,
+    }
+
+    struct CountingKms {
+        inner: InMemoryKms,
+        counts: Arc<Counts>,
+    }
+
+    impl KmsClient for CountingKms {
+        fn wrap_key(&self, key_bytes: &[u8], master_key_identifier: &str) -> Result<String> {
+            self.counts.wraps.fetch_add(1, Ordering::SeqCst);
+            self.inner.wrap_key(key_bytes, master_key_identifier)
+        }
+
+        fn unwrap_key(&self, wrapped_key: &str, master_key_identifier: &str) -> Result<Vec<u8>> {
+            self.counts.unwraps.fetch_add(1, Ordering::SeqCst);
+            self.inner.unwrap_key(wrapped_key, master_key_identifier)
+        }
+    }
+
+    fn factory() -> (CryptoFactory, Arc<Counts>) {
+        let kms = InMemoryKms::try_new(HashMap::from([
+            ("kf".to_string(), b"0123456789012345".to_vec()),
+            ("kc1".to_string(), b"1234567890123450".to_vec()),
+            ("kc2".to_string(), b"1234567890123451".to_vec()),
+        ]))
+        .unwrap();
+        let counts = Arc::new(Counts::default());
+        let counts_ = Arc::clone(&counts);
+        let factory = CryptoFactory::new(move |_: &KmsConnectionConfig| {
+            counts_.clients.fetch_add(1, Ordering::SeqCst);
+            Ok(Arc::new(CountingKms {
+                inner: kms.clone(),
+                counts: Arc::clone(&counts_),
+            }) as KmsClientRef)
+        });
+        (factory, counts)
+    }
+
+    fn unwrap_all(
+        factory: &CryptoFactory,
+        config: Arc<KmsConnectionConfig>,
+        properties: &FileEncryptionProperties,
+    ) {
+        let decryption = factory
+            .file_decryption_properties(config, DecryptionConfiguration::default())
+            .unwrap();
+        let footer = decryption
+            .footer_key(properties.footer_key_metadata().map(|m| m.as_slice()))
+            .unwrap();
+        assert_eq!(footer.as_slice(), properties.footer_key());
+        let (names, keys, metadata) = properties.column_keys();
+        for ((name, key), metadata) in names.iter().zip(keys).zip(metadata) {
+            let retrieved = decryption.column_key(name, Some(metadata.as_slice())).unwrap();
+            assert_eq!(*retrieved, key);
+        }
+    }
+
+    #[test]
+    fn test_double_wrapping() {
+        let (factory, counts) = factory();
+        let config = Arc::new(KmsConnectionConfig::default());
+        let encryption = EncryptionConfiguration::builder("kf".to_string())
+            .with_column_keys("kc1", vec!["a", "b"])
+            .with_column_keys("kc2", vec!["c"])
+            .build()
+            .unwrap();
+
+        let properties = factory
+            .file_encryption_properties(&config, &encryption)
+            .unwrap();
+        let (names, keys, _) = properties.column_keys();
+        assert_eq!(names.len(), 3);
+        assert_ne!(keys[0], keys[1]);
+        // One KEK per master key
+        assert_eq!(counts.wraps.load(Ordering::SeqCst), 3);
+
+        // KEKs are reused by subsequent files
+        let second = factory
+            .file_encryption_properties(&config, &encryption)
+            .unwrap();
+        assert_ne!(second.footer_key(), properties.footer_key());
+        assert_eq!(counts.wraps.load(Ordering::SeqCst), 3);
+
+        // KEKs written by this factory are cached for reading
+        unwrap_all(&factory, Arc::clone(&config), &properties);
+        assert_eq!(counts.unwraps.load(Ordering::SeqCst), 0);
+        assert_eq!(counts.clients.load(Ordering::SeqCst), 1);
+
+        // A new factory must unwrap each KEK once
+        let (reader, reader_counts) = self::factory();
+        unwrap_all(&reader, Arc::clone(&config), &properties);
+        unwrap_all(&reader, Arc::clone(&config), &second);
+        assert_eq!(reader_counts.unwraps.load(Ordering::SeqCst), 3);
+
+        // Other tokens do not share cached keys
+        config.refresh_key_access_token("new".to_string());
+        unwrap_all(&reader, Arc::clone(&config), &properties);
+        assert_eq!(reader_counts.unwraps.load(Ordering::SeqCst), 6);
+        assert_eq!(reader_counts.clients.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_single_wrapping() {
+        let (factory, counts) = factory();
+        let config = Arc::new(KmsConnectionConfig::default());
+        let encryption = EncryptionConfiguration::builder("kf".to_string())
+            .with_column_keys("kc1", vec!["a", "b"])
+            .with_double_wrapping(false)
+            .with_plaintext_footer(true)
+            .build()
+            .unwrap();
+
+        let properties = factory
+            .file_encryption_properties(&config, &encryption)
+            .unwrap();
+        assert!(!properties.encrypt_footer());
+        assert_eq!(counts.wraps.load(Ordering::SeqCst), 3);
+
+        let metadata = properties.footer_key_metadata().unwrap();
+        let material = KeyMaterial::parse(metadata).unwrap();
+        assert!(material.is_footer_key);
+        assert!(material.kek.is_none());
+        assert_eq!(material.kms_instance_id.as_deref(), Some("DEFAULT"));
+
+        unwrap_all(&factory, config, &properties);
+        assert_eq!(counts.unwraps.load(Ordering::SeqCst), 3);
+    }
+
+    #[test]
+    fn test_no_caching() {
+        let (factory, counts) = factory();
+        let config = Arc::new(KmsConnectionConfig::default());
+        let encryption = EncryptionConfiguration::builder("kf".to_string())
+            .with_cache_lifetime(None)
+            .build()
+            .unwrap();
+        factory
+            .file_encryption_properties(&config, &encryption)
+            .unwrap();
+        factory
+            .file_encryption_properties(&config, &encryption)
+            .unwrap();
+        assert_eq!(counts.clients.load(Ordering::SeqCst), 2);
+        assert_eq!(counts.wraps.load(Ordering::SeqCst), 2);
+
+        factory.clear_caches();
+    }
+
+    #[test]
+    fn test_invalid_configuration() {
+        let err = EncryptionConfiguration::builder("kf".to_string())
+            .with_column_keys("kc1", vec!["a"])
+            .with_column_keys("kc2", vec!["a"])
+            .build()
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Multiple keys specified for column 'a'"
+        );
+
+        let (factory, _) = factory();
+        let config = KmsConnectionConfig::default();
+        let encryption = EncryptionConfiguration::builder("unknown".to_string())
+            .build()
+            .unwrap();
+        let err = factory
+            .file_encryption_properties(&config, &encryption)
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Parquet error: Unknown master key 'unknown'");
+    }
+}
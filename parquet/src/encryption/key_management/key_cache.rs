@@ -0,0 +1,94 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+use crate::errors::Result;
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// A cache of values that expire a given time after they are inserted
+#[derive(Debug)]
+pub(crate) struct ExpiringCache<K, V> {
+    entries: Mutex<HashMap<K, (Instant, V)>>,
+}
+
+impl<K, V> Default for ExpiringCache<K, V> {
+    fn default() -> Self {
+        Self {
+            entries: Mutex::default(),
+        }
+    }
+}
+
+impl<K: Hash + Eq, V: Clone> ExpiringCache<K, V> {
+    /// Returns the value of `key`, computing it with `f` if it is absent or expired
+    ///
+    /// Values are not cached if `lifetime` is `None`. The lock is not held while
+    /// calling `f`, which may call a KMS, so concurrent callers may compute the
+    /// same value.
+    pub fn get_or_try_insert(
+        &self,
+        key: K,
+        lifetime: Option<Duration>,
+        f: impl FnOnce() -> Result<V>,
+    ) -> Result<V> {
+        let Some(lifetime) = lifetime else {
+            return f();
+        };
+        let now = Instant::now();
+        if let Some((expiry, value)) = self.entries.lock().unwrap().get(&key) {
+            if *expiry > now {
+                return Ok(value.clone());
+            }
+        }
+        let value = f()?;
+        let mut entries = self.entries.lock().unwrap();
+        entries.retain(|_, (expiry, _)| *expiry > now);
+        entries.insert(key, (now + lifetime, value.clone()));
+        Ok(value)
+    }
+
+    /// Removes all entries
+    pub fn clear(&self) {
+        self.entries.lock().unwrap().clear()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::errors::ParquetError;
+
+    #[test]
+    fn test_expiry() {
+        let cache = ExpiringCache::default();
+        let lifetime = Some(Duration::from_secs(60));
+        assert_eq!(cache.get_or_try_insert(1, lifetime, || Ok(1)).unwrap(), 1);
+        assert_eq!(cache.get_or_try_insert(1, lifetime, || Ok(2)).unwrap(), 1);
+
+        let err = cache.get_or_try_insert(2, lifetime, || Err(general_err!("failed")));
+        assert!(err.is_err());
+        assert_eq!(cache.get_or_try_insert(2, lifetime, || Ok(3)).unwrap(), 3);
+
+        // No lifetime is not cached
+        assert_eq!(cache.get_or_try_insert(1, None, || Ok(6)).unwrap(), 6);
+
+        cache.clear();
+        assert_eq!(cache.get_or_try_insert(1, lifetime, || Ok(7)).unwrap(), 7);
+    }
+}
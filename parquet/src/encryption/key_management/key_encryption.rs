@@ -0,0 +1,87 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Encryption of keys with other keys outside of a KMS
+
+use crate::encryption::ciphers::{
+    BlockDecryptor, BlockEncryptor, NONCE_LEN, RingGcmBlockDecryptor, RingGcmBlockEncryptor,
+    SIZE_LEN, TAG_LEN,
+};
+use crate::errors::{ParquetError, Result};
+use base64::Engine;
+use base64::prelude::BASE64_STANDARD;
+use ring::rand::{SecureRandom, SystemRandom};
+
+/// The length in bytes of generated keys and key identifiers
+pub(crate) const KEY_LEN: usize = 16;
+
+/// Returns `KEY_LEN` random bytes
+pub(crate) fn random_key() -> Result<Vec<u8>> {
+    let mut key = vec![0; KEY_LEN];
+    SystemRandom::new().fill(&mut key)?;
+    Ok(key)
+}
+
+/// Encrypts `key` with AES-GCM, returning the base64 encoded nonce, ciphertext and tag
+pub(crate) fn encrypt_key_locally(key: &[u8], master_key: &[u8], aad: &[u8]) -> Result<String> {
+    let encrypted = RingGcmBlockEncryptor::new(master_key)?.encrypt(key, aad)?;
+    // The length prefix of the Parquet module format is not part of the wrapped key
+    Ok(BASE64_STANDARD.encode(&encrypted[SIZE_LEN..]))
+}
+
+/// Decrypts a key encrypted by [`encrypt_key_locally`]
+pub(crate) fn decrypt_key_locally(
+    encrypted_key: &str,
+    master_key: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>> {
+    let encrypted = BASE64_STANDARD
+        .decode(encrypted_key)
+        .map_err(|e| general_err!("Invalid base64 in encrypted key: {}", e))?;
+    if encrypted.len() < NONCE_LEN + TAG_LEN {
+        return Err(general_err!(
+            "Encrypted key is too short: {} bytes",
+            encrypted.len()
+        ));
+    }
+    let mut buf = Vec::with_capacity(SIZE_LEN + encrypted.len());
+    buf.extend((encrypted.len() as u32).to_le_bytes());
+    buf.extend(encrypted);
+    RingGcmBlockDecryptor::new(master_key)?.decrypt(&buf, aad)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let master_key = random_key().unwrap();
+        let key = random_key().unwrap();
+
+        let encrypted = encrypt_key_locally(&key, &master_key, b"aad").unwrap();
+        let decrypted = decrypt_key_locally(&encrypted, &master_key, b"aad").unwrap();
+        assert_eq!(decrypted, key);
+
+        decrypt_key_locally(&encrypted, &master_key, b"other").unwrap_err();
+        let err = decrypt_key_locally("AAAA", &master_key, b"aad").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Encrypted key is too short: 3 bytes"
+        );
+    }
+}
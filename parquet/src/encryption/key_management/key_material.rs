@@ -0,0 +1,178 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! The JSON key material format stored in key metadata, as specified by the
+//! [parquet-java key tools](https://github.com/apache/parquet-java/blob/master/parquet-hadoop/src/main/java/org/apache/parquet/crypto/keytools/KeyMaterial.java)
+
+use crate::errors::{ParquetError, Result};
+use serde_json::{Map, Value};
+
+const KEY_MATERIAL_TYPE: &str = "PKMT1";
+
+/// The material required to unwrap a data encryption key
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct KeyMaterial {
+    pub is_footer_key: bool,
+    /// Only stored for footer keys
+    pub kms_instance_id: Option<String>,
+    /// Only stored for footer keys
+    pub kms_instance_url: Option<String>,
+    pub master_key_id: String,
+    pub wrapped_dek: String,
+    /// The ID and wrapped key encryption key, if double wrapping is used
+    pub kek: Option<WrappedKek>,
+}
+
+/// A key encryption key wrapped by a master key
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WrappedKek {
+    /// The base64 encoded ID of the key
+    pub id: String,
+    pub wrapped: String,
+}
+
+impl KeyMaterial {
+    /// Serializes this key material to JSON
+    pub fn serialize(&self) -> String {
+        let mut map = Map::new();
+        let mut insert = |k: &str, v: Value| map.insert(k.to_string(), v);
+        insert("keyMaterialType", KEY_MATERIAL_TYPE.into());
+        insert("internalStorage", true.into());
+        insert("isFooterKey", self.is_footer_key.into());
+        if let Some(id) = &self.kms_instance_id {
+            insert("kmsInstanceID", id.as_str().into());
+        }
+        if let Some(url) = &self.kms_instance_url {
+            insert("kmsInstanceURL", url.as_str().into());
+        }
+        insert("masterKeyID", self.master_key_id.as_str().into());
+        insert("wrappedDEK", self.wrapped_dek.as_str().into());
+        insert("doubleWrapping", self.kek.is_some().into());
+        if let Some(kek) = &self.kek {
+            insert("keyEncryptionKeyID", kek.id.as_str().into());
+            insert("wrappedKEK", kek.wrapped.as_str().into());
+        }
+        Value::Object(map).to_string()
+    }
+
+    /// Parses key material from the key metadata of a file
+    pub fn parse(key_metadata: &[u8]) -> Result<Self> {
+        let value: Value = serde_json::from_slice(key_metadata)
+            .map_err(|e| general_err!("Failed to parse key material: {}", e))?;
+        let map = value
+            .as_object()
+            .ok_or_else(|| general_err!("Key material must be a JSON object"))?;
+
+        let get_str = |key: &str| -> Result<Option<String>> {
+            match map.get(key) {
+                None | Some(Value::Null) => Ok(None),
+                Some(Value::String(s)) => Ok(Some(s.clone())),
+                Some(_) => Err(general_err!("Key material field {} must be a string", key)),
+            }
+        };
+        let get_bool = |key: &str| -> Result<bool> {
+            match map.get(key) {
+                Some(Value::Bool(b)) => Ok(*b),
+                None => Err(general_err!("Key material field {} is missing", key)),
+                Some(_) => Err(general_err!("Key material field {} must be a boolean", key)),
+            }
+        };
+        let require = |key: &str| -> Result<String> {
+            get_str(key)?.ok_or_else(|| general_err!("Key material field {} is missing", key))
+        };
+
+        match get_str("keyMaterialType")?.as_deref() {
+            Some(KEY_MATERIAL_TYPE) => {}
+            Some(other) => return Err(nyi_err!("Unsupported key material type {}", other)),
+            None => return Err(general_err!("Key material field keyMaterialType is missing")),
+        }
+        if !get_bool("internalStorage")? {
+            return Err(nyi_err!(
+                "Key material stored outside of the file is not supported"
+            ));
+        }
+
+        let kek = match get_bool("doubleWrapping")? {
+            true => Some(WrappedKek {
+                id: require("keyEncryptionKeyID")?,
+                wrapped: require("wrappedKEK")?,
+            }),
+            false => None,
+        };
+        Ok(Self {
+            is_footer_key: get_bool("isFooterKey")?,
+            kms_instance_id: get_str("kmsInstanceID")?,
+            kms_instance_url: get_str("kmsInstanceURL")?,
+            master_key_id: require("masterKeyID")?,
+            wrapped_dek: require("wrappedDEK")?,
+            kek,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_roundtrip() {
+        let material = KeyMaterial {
+            is_footer_key: true,
+            kms_instance_id: Some("DEFAULT".to_string()),
+            kms_instance_url: Some("DEFAULT".to_string()),
+            master_key_id: "kf".to_string(),
+            wrapped_dek: "dek".to_string(),
+            kek: Some(WrappedKek {
+                id: "id".to_string(),
+                wrapped: "kek".to_string(),
+            }),
+        };
+        let json = material.serialize();
+        assert_eq!(KeyMaterial::parse(json.as_bytes()).unwrap(), material);
+
+        let material = KeyMaterial {
+            is_footer_key: false,
+            kms_instance_id: None,
+            kms_instance_url: None,
+            kek: None,
+            ..material
+        };
+        let json = material.serialize();
+        assert!(!json.contains("wrappedKEK"));
+        assert_eq!(KeyMaterial::parse(json.as_bytes()).unwrap(), material);
+    }
+
+    #[test]
+    fn test_parse_parquet_java() {
+        // Column key material written by parquet-java
+        let json = r#"{"keyMaterialType":"PKMT1","internalStorage":true,"isFooterKey":false,"masterKeyID":"kc1","wrappedDEK":"AAAA","doubleWrapping":true,"keyEncryptionKeyID":"BBBB","wrappedKEK":"CCCC"}"#;
+        let material = KeyMaterial::parse(json.as_bytes()).unwrap();
+        assert!(!material.is_footer_key);
+        assert_eq!(material.master_key_id, "kc1");
+        assert_eq!(material.kek.unwrap().id, "BBBB");
+
+        let json = r#"{"keyMaterialType":"PKMT1","internalStorage":false,"keyReference":"x"}"#;
+        let err = KeyMaterial::parse(json.as_bytes()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "NYI: Key material stored outside of the file is not supported"
+        );
+
+        let err = KeyMaterial::parse(b"kf").unwrap_err();
+        assert!(err.to_string().contains("Failed to parse key material"));
+    }
+}
@@ -0,0 +1,317 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Interfaces to Key Management Servers (KMS)
+
+use crate::encryption::key_management::key_encryption::{decrypt_key_locally, encrypt_key_locally};
+use crate::errors::{ParquetError, Result};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+/// The value of [`KmsConnectionConfig`] properties that have not been set
+pub const DEFAULT: &str = "DEFAULT";
+
+/// A client of a Key Management Server, which wraps (encrypts) and unwraps
+/// (decrypts) keys with master keys that never leave the server
+///
+/// This corresponds to the `KmsClient` interface of parquet-java and PyArrow,
+/// so any wrapped keys produced by an implementation in those libraries can be
+/// unwrapped by an equivalent implementation of this trait.
+pub trait KmsClient: Send + Sync {
+    /// Wraps `key_bytes` with the master key identified by `master_key_identifier`,
+    /// returning the wrapped key as a string that will be stored in file metadata
+    fn wrap_key(&self, key_bytes: &[u8], master_key_identifier: &str) -> Result<String>;
+
+    /// Unwraps `wrapped_key`, as returned by [`Self::wrap_key`], with the master key
+    /// identified by `master_key_identifier`
+    fn unwrap_key(&self, wrapped_key: &str, master_key_identifier: &str) -> Result<Vec<u8>>;
+}
+
+/// A reference counted [`KmsClient`]
+pub type KmsClientRef = Arc<dyn KmsClient>;
+
+/// Creates [`KmsClient`]s, called whenever no client for a
+/// [`KmsConnectionConfig`] and access token is cached
+///
+/// This is implemented for closures taking a `&KmsConnectionConfig`:
+///
+/// ```
+/// # use std::collections::HashMap;
+/// # use std::sync::Arc;
+/// # use parquet::encryption::key_management::crypto_factory::CryptoFactory;
+/// # use parquet::encryption::key_management::kms::{InMemoryKms, KmsClientRef, KmsConnectionConfig};
+/// let kms = InMemoryKms::try_new(HashMap::from([(
+///     "kf".to_string(),
+///     b"0123456789012345".to_vec(),
+/// )]))?;
+/// let crypto_factory = CryptoFactory::new(move |config: &KmsConnectionConfig| {
+///     println!("Connecting to {}", config.kms_instance_url());
+///     Ok(Arc::new(kms.clone()) as KmsClientRef)
+/// });
+/// # Ok::<(), parquet::errors::ParquetError>(())
+/// ```
+pub trait KmsClientFactory: Send + Sync {
+    /// Creates a client of the KMS described by `config`
+    fn create_client(&self, config: &KmsConnectionConfig) -> Result<KmsClientRef>;
+}
+
+impl<F> KmsClientFactory for F
+where
+    F: Fn(&KmsConnectionConfig) -> Result<KmsClientRef> + Send + Sync,
+{
+    fn create_client(&self, config: &KmsConnectionConfig) -> Result<KmsClientRef> {
+        self(config)
+    }
+}
+
+/// How to connect to a Key Management Server
+///
+/// Properties that are not set have the value [`DEFAULT`]. When reading a file,
+/// a `DEFAULT` instance ID and URL are replaced with those stored in the footer
+/// key material, if any.
+#[derive(Debug)]
+pub struct KmsConnectionConfig {
+    kms_instance_id: String,
+    kms_instance_url: String,
+    key_access_token: RwLock<String>,
+    custom_kms_conf: HashMap<String, String>,
+}
+
+impl Default for KmsConnectionConfig {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl Clone for KmsConnectionConfig {
+    fn clone(&self) -> Self {
+        Self {
+            kms_instance_id: self.kms_instance_id.clone(),
+            kms_instance_url: self.kms_instance_url.clone(),
+            key_access_token: RwLock::new(self.key_access_token()),
+            custom_kms_conf: self.custom_kms_conf.clone(),
+        }
+    }
+}
+
+impl KmsConnectionConfig {
+    /// Returns a new [`KmsConnectionConfigBuilder`]
+    pub fn builder() -> KmsConnectionConfigBuilder {
+        KmsConnectionConfigBuilder::default()
+    }
+
+    /// The ID of the KMS instance
+    pub fn kms_instance_id(&self) -> &str {
+        &self.kms_instance_id
+    }
+
+    /// The URL of the KMS instance
+    pub fn kms_instance_url(&self) -> &str {
+        &self.kms_instance_url
+    }
+
+    /// The token used to authorize access to master keys
+    pub fn key_access_token(&self) -> String {
+        self.key_access_token.read().unwrap().clone()
+    }
+
+    /// KMS type specific configuration
+    pub fn custom_kms_conf(&self) -> &HashMap<String, String> {
+        &self.custom_kms_conf
+    }
+
+    /// Replaces the key access token, for example after it has expired
+    ///
+    /// Clients and keys are cached per token, so subsequent operations will
+    /// create new clients with the new token.
+    pub fn refresh_key_access_token(&self, key_access_token: String) {
+        *self.key_access_token.write().unwrap() = key_access_token;
+    }
+
+    /// Returns this config with the instance ID and URL, if they are not set
+    pub(crate) fn with_defaults(&self, id: Option<&str>, url: Option<&str>) -> Option<Self> {
+        let id = id.filter(|_| self.kms_instance_id == DEFAULT);
+        let url = url.filter(|_| self.kms_instance_url == DEFAULT);
+        if id.is_none() && url.is_none() {
+            return None;
+        }
+        let mut config = self.clone();
+        if let Some(id) = id {
+            config.kms_instance_id = id.to_string();
+        }
+        if let Some(url) = url {
+            config.kms_instance_url = url.to_string();
+        }
+        Some(config)
+    }
+}
+
+/// Builder for [`KmsConnectionConfig`]
+#[derive(Debug)]
+pub struct KmsConnectionConfigBuilder {
+    kms_instance_id: String,
+    kms_instance_url: String,
+    key_access_token: String,
+    custom_kms_conf: HashMap<String, String>,
+}
+
+impl Default for KmsConnectionConfigBuilder {
+    fn default() -> Self {
+        Self {
+            kms_instance_id: DEFAULT.to_string(),
+            kms_instance_url: DEFAULT.to_string(),
+            key_access_token: DEFAULT.to_string(),
+            custom_kms_conf: HashMap::new(),
+        }
+    }
+}
+
+impl KmsConnectionConfigBuilder {
+    /// Set the ID of the KMS instance
+    pub fn with_kms_instance_id(mut self, kms_instance_id: String) -> Self {
+        self.kms_instance_id = kms_instance_id;
+        self
+    }
+
+    /// Set the URL of the KMS instance
+    pub fn with_kms_instance_url(mut self, kms_instance_url: String) -> Self {
+        self.kms_instance_url = kms_instance_url;
+        self
+    }
+
+    /// Set the token used to authorize access to master keys
+    pub fn with_key_access_token(mut self, key_access_token: String) -> Self {
+        self.key_access_token = key_access_token;
+        self
+    }
+
+    /// Set a KMS type specific configuration value
+    pub fn with_custom_kms_conf_option(mut self, key: String, value: String) -> Self {
+        self.custom_kms_conf.insert(key, value);
+        self
+    }
+
+    /// Finalize the builder and return the created [`KmsConnectionConfig`]
+    pub fn build(self) -> KmsConnectionConfig {
+        KmsConnectionConfig {
+            kms_instance_id: self.kms_instance_id,
+            kms_instance_url: self.kms_instance_url,
+            key_access_token: RwLock::new(self.key_access_token),
+            custom_kms_conf: self.custom_kms_conf,
+        }
+    }
+}
+
+/// A [`KmsClient`] holding master keys in memory, for testing and examples
+///
+/// Keys are wrapped with AES-GCM using the master key identifier as additional
+/// authenticated data, as done by the `InMemoryKMS` of parquet-java, so files
+/// written with either can be read by the other given the same master keys.
+///
+/// This provides no protection of the master keys and must not be used in production.
+#[derive(Debug, Clone)]
+pub struct InMemoryKms {
+    master_keys: Arc<HashMap<String, Vec<u8>>>,
+}
+
+impl InMemoryKms {
+    /// Creates a KMS with 16 byte `master_keys` by identifier
+    pub fn try_new(master_keys: HashMap<String, Vec<u8>>) -> Result<Self> {
+        if let Some((id, key)) = master_keys.iter().find(|(_, k)| k.len() != 16) {
+            return Err(general_err!(
+                "Master key '{}' must be 16 bytes, got {}",
+                id,
+                key.len()
+            ));
+        }
+        Ok(Self {
+            master_keys: Arc::new(master_keys),
+        })
+    }
+
+    fn master_key(&self, master_key_identifier: &str) -> Result<&[u8]> {
+        self.master_keys
+            .get(master_key_identifier)
+            .map(Vec::as_slice)
+            .ok_or_else(|| general_err!("Unknown master key '{}'", master_key_identifier))
+    }
+}
+
+impl KmsClient for InMemoryKms {
+    fn wrap_key(&self, key_bytes: &[u8], master_key_identifier: &str) -> Result<String> {
+        let master_key = self.master_key(master_key_identifier)?;
+        encrypt_key_locally(key_bytes, master_key, master_key_identifier.as_bytes())
+    }
+
+    fn unwrap_key(&self, wrapped_key: &str, master_key_identifier: &str) -> Result<Vec<u8>> {
+        let master_key = self.master_key(master_key_identifier)?;
+        decrypt_key_locally(wrapped_key, master_key, master_key_identifier.as_bytes())
+    }
+}
+
+impl KmsClientFactory for InMemoryKms {
+    fn create_client(&self, _config: &KmsConnectionConfig) -> Result<KmsClientRef> {
+        Ok(Arc::new(self.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_in_memory_kms() {
+        let kms = InMemoryKms::try_new(HashMap::from([
+            ("a".to_string(), b"0123456789012345".to_vec()),
+            ("b".to_string(), b"1234567890123450".to_vec()),
+        ]))
+        .unwrap();
+
+        let wrapped = kms.wrap_key(b"key", "a").unwrap();
+        assert_eq!(kms.unwrap_key(&wrapped, "a").unwrap(), b"key");
+        kms.unwrap_key(&wrapped, "b").unwrap_err();
+
+        let err = kms.wrap_key(b"key", "c").unwrap_err();
+        assert_eq!(err.to_string(), "Parquet error: Unknown master key 'c'");
+
+        let err = InMemoryKms::try_new(HashMap::from([("a".to_string(), vec![0; 3])]))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Master key 'a' must be 16 bytes, got 3"
+        );
+    }
+
+    #[test]
+    fn test_connection_config_defaults() {
+        let config = KmsConnectionConfig::builder()
+            .with_kms_instance_url("https://kms".to_string())
+            .build();
+        assert_eq!(config.kms_instance_id(), DEFAULT);
+        assert_eq!(config.key_access_token(), DEFAULT);
+
+        let config = config
+            .with_defaults(Some("instance"), Some("https://other"))
+            .unwrap();
+        assert_eq!(config.kms_instance_id(), "instance");
+        assert_eq!(config.kms_instance_url(), "https://kms");
+        assert!(config.with_defaults(Some("a"), Some("b")).is_none());
+
+        config.refresh_key_access_token("token".to_string());
+        assert_eq!(config.key_access_token(), "token");
+    }
+}
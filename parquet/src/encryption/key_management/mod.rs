@@ -0,0 +1,96 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Envelope encryption with keys managed by a Key Management Server (KMS),
+//! compatible with the key tools of parquet-java, which are also used by
+//! Spark and PyArrow.
+//!
+//! Each file is encrypted with new random data encryption keys (DEKs). These
+//! are wrapped with master keys held by a KMS, accessed with a [`KmsClient`],
+//! and the wrapped keys are stored in the key metadata of the file as JSON key
+//! material. By default, double wrapping is used: DEKs are wrapped locally by
+//! key encryption keys (KEKs), and only the KEKs are wrapped by the KMS. KEKs
+//! and KMS clients are cached by the [`CryptoFactory`], so that writing or
+//! reading many files requires few KMS calls.
+//!
+//! Only key material stored within the file (the default of parquet-java) is
+//! supported.
+//!
+//! # Example
+//!
+//! ```
+//! use arrow::array::{ArrayRef, Int32Array, RecordBatch};
+//! use parquet::arrow::arrow_reader::{ArrowReaderOptions, ParquetRecordBatchReaderBuilder};
+//! use parquet::arrow::ArrowWriter;
+//! use parquet::encryption::key_management::crypto_factory::{
+//!     CryptoFactory, DecryptionConfiguration, EncryptionConfiguration,
+//! };
+//! use parquet::encryption::key_management::kms::{InMemoryKms, KmsConnectionConfig};
+//! use parquet::file::properties::WriterProperties;
+//! use std::collections::HashMap;
+//! use std::sync::Arc;
+//!
+//! // A KMS holding master keys in memory, in practice this would be a
+//! // client of an external KMS
+//! let kms = InMemoryKms::try_new(HashMap::from([
+//!     ("kf".to_string(), b"0123456789012345".to_vec()),
+//!     ("kc1".to_string(), b"1234567890123450".to_vec()),
+//! ]))?;
+//! let crypto_factory = CryptoFactory::new(kms);
+//! let kms_config = Arc::new(KmsConnectionConfig::default());
+//!
+//! // Encrypt the footer with a key wrapped by master key "kf",
+//! // and column "x" with a key wrapped by master key "kc1"
+//! let encryption_config = EncryptionConfiguration::builder("kf".into())
+//!     .with_column_keys("kc1", vec!["x"])
+//!     .build()?;
+//! let encryption_properties =
+//!     crypto_factory.file_encryption_properties(&kms_config, &encryption_config)?;
+//! let writer_properties = WriterProperties::builder()
+//!     .with_file_encryption_properties(encryption_properties)
+//!     .build();
+//!
+//! let batch = RecordBatch::try_from_iter([
+//!     ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+//!     ("x", Arc::new(Int32Array::from(vec![4, 5, 6])) as ArrayRef),
+//! ])?;
+//! let mut file = tempfile::tempfile()?;
+//! let mut writer =
+//!     ArrowWriter::try_new(file.try_clone()?, batch.schema(), Some(writer_properties))?;
+//! writer.write(&batch)?;
+//! writer.close()?;
+//!
+//! // Keys are unwrapped from the key material in the file
+//! let decryption_properties = crypto_factory
+//!     .file_decryption_properties(kms_config, DecryptionConfiguration::default())?;
+//! let options =
+//!     ArrowReaderOptions::new().with_file_decryption_properties(decryption_properties);
+//! let reader = ParquetRecordBatchReaderBuilder::try_new_with_options(file, options)?.build()?;
+//! for read in reader {
+//!     assert_eq!(read?, batch);
+//! }
+//! # Ok::<(), Box<dyn std::error::Error>>(())
+//! ```
+//!
+//! [`KmsClient`]: kms::KmsClient
+//! [`CryptoFactory`]: crypto_factory::CryptoFactory
+
+pub mod crypto_factory;
+mod key_cache;
+mod key_encryption;
+mod key_material;
+pub mod kms;
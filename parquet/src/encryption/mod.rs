@@ -109,4 +109,5 @@
 pub(crate) mod ciphers;
 pub mod decrypt;
 pub mod encrypt;
+pub mod key_management;
 pub(crate) mod modules;
@@ -2063,6 +2063,7 @@ mod tests {
     #[cfg(feature = "encryption")]
     fn test_memory_size_with_decryptor() {
         use crate::encryption::decrypt::FileDecryptionProperties;
+        use crate::encryption::encrypt::ParquetCipher;
         use crate::file::metadata::thrift::encryption::AesGcmV1;
 
         let schema_descr = get_test_schema_descr();
@@ -2117,6 +2118,7 @@ mod tests {
             footer_key_metadata.as_deref(),
             aad_file_unique,
             aad_prefix,
+            ParquetCipher::AesGcmV1,
         )
         .unwrap();
 
@@ -2125,7 +2127,7 @@ mod tests {
             .set_file_decryptor(Some(decryptor))
             .build();
 
-        let expected_size_with_decryptor = 3072;
+        let expected_size_with_decryptor = 3096;
         assert!(expected_size_with_decryptor > base_expected_size);
 
         assert_eq!(
@@ -27,11 +27,16 @@ use crate::{
 #[cfg(feature = "encryption")]
 use crate::{
     encryption::{
-        encrypt::{FileEncryptor, encrypt_thrift_object, write_signed_plaintext_thrift_object},
+        encrypt::{
+            FileEncryptor, ParquetCipher, encrypt_thrift_object,
+            write_signed_plaintext_thrift_object,
+        },
         modules::{ModuleType, create_footer_aad, create_module_aad},
     },
     file::column_crypto_metadata::ColumnCryptoMetaData,
-    file::metadata::thrift::encryption::{AesGcmV1, EncryptionAlgorithm, FileCryptoMetaData},
+    file::metadata::thrift::encryption::{
+        AesGcmCtrV1, AesGcmV1, EncryptionAlgorithm, FileCryptoMetaData,
+    },
 };
 use crate::{errors::Result, file::page_index::column_index::ColumnIndexMetaData};
 
@@ -739,7 +744,7 @@ impl MetadataObjectWriter {
                 column_index,
                 None,
             )?;
-            let mut encryptor = file_encryptor.get_column_encryptor(column_path)?;
+            let mut encryptor = file_encryptor.get_column_metadata_encryptor(column_path)?;
             encrypt_thrift_object(object, &mut encryptor, &mut sink, &aad)
         } else {
             Self::write_thrift_object(object, sink)
@@ -772,11 +777,20 @@ impl MetadataObjectWriter {
         } else {
             None
         };
-        EncryptionAlgorithm::AES_GCM_V1(AesGcmV1 {
-            aad_prefix: aad_prefix.cloned(),
-            aad_file_unique: Some(file_encryptor.aad_file_unique().clone()),
-            supply_aad_prefix,
-        })
+        let aad_prefix = aad_prefix.cloned();
+        let aad_file_unique = Some(file_encryptor.aad_file_unique().clone());
+        match file_encryptor.properties().algorithm() {
+            ParquetCipher::AesGcmV1 => EncryptionAlgorithm::AES_GCM_V1(AesGcmV1 {
+                aad_prefix,
+                aad_file_unique,
+                supply_aad_prefix,
+            }),
+            ParquetCipher::AesGcmCtrV1 => EncryptionAlgorithm::AES_GCM_CTR_V1(AesGcmCtrV1 {
+                aad_prefix,
+                aad_file_unique,
+                supply_aad_prefix,
+            }),
+        }
     }
 
     fn file_crypto_metadata(file_encryptor: &'_ FileEncryptor) -> Result<FileCryptoMetaData<'_>> {
@@ -835,7 +849,7 @@ impl MetadataObjectWriter {
             }
             Some(ColumnCryptoMetaData::ENCRYPTION_WITH_COLUMN_KEY(col_key)) => {
                 let column_path = col_key.path_in_schema.join(".");
-                Some(file_encryptor.get_column_encryptor(&column_path)?)
+                Some(file_encryptor.get_column_metadata_encryptor(&column_path)?)
             }
         };
 
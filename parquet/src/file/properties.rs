@@ -458,6 +458,45 @@ impl WriterProperties {
             .or_else(|| self.default_column_properties.bloom_filter_properties())
     }
 
+    /// Returns `true` if the bloom filter for the given column is sized from the number of
+    /// distinct values observed in each column chunk.
+    ///
+    /// If `true`, the writer buffers the hashes of the distinct values of each column chunk, and
+    /// when the column chunk is flushed creates the smallest bloom filter achieving the configured
+    /// `fpp` for their number. Should the buffered hashes exceed the size of the bloom filter for
+    /// the configured `ndv`, that filter is created instead, along with a small sketch estimating
+    /// the number of distinct values, and shrunk to the estimate when flushed. This avoids writing
+    /// oversized filters for column chunks with fewer distinct values than `ndv`.
+    ///
+    /// For more details see [`WriterPropertiesBuilder::set_bloom_filter_auto_size`]
+    pub fn bloom_filter_auto_size(&self, col: &ColumnPath) -> bool {
+        self.column_properties
+            .get(col)
+            .and_then(|c| c.bloom_filter_auto_size())
+            .or_else(|| self.default_column_properties.bloom_filter_auto_size())
+            .unwrap_or(false)
+    }
+
+    /// Returns `true` if the bloom filter for the given column is skipped for column chunks that
+    /// are fully dictionary encoded.
+    ///
+    /// A column chunk is fully dictionary encoded if the number of its distinct values is small
+    /// enough for the dictionary page not to exceed the dictionary page size limit. Readers can
+    /// then prune the column chunk using the dictionary page, which is usually cheaper than reading
+    /// and checking a bloom filter.
+    ///
+    /// For more details see [`WriterPropertiesBuilder::set_bloom_filter_skip_dictionary_encoded`]
+    pub fn bloom_filter_skip_dictionary_encoded(&self, col: &ColumnPath) -> bool {
+        self.column_properties
+            .get(col)
+            .and_then(|c| c.bloom_filter_skip_dictionary_encoded())
+            .or_else(|| {
+                self.default_column_properties
+                    .bloom_filter_skip_dictionary_encoded()
+            })
+            .unwrap_or(false)
+    }
+
     /// Return file encryption properties
     ///
     /// For more details see [`WriterPropertiesBuilder::with_file_encryption_properties`]
@@ -908,6 +947,34 @@ impl WriterPropertiesBuilder {
         self
     }
 
+    /// Sets whether bloom filters of all columns are sized from the number of
+    /// distinct values observed in each column chunk, treating the ndv as an upper
+    /// bound (defaults to `false`). See [`WriterProperties::bloom_filter_auto_size`].
+    ///
+    /// Implicitly enables bloom writing, as if [`set_bloom_filter_enabled`] had
+    /// been called.
+    ///
+    /// [`set_bloom_filter_enabled`]: Self::set_bloom_filter_enabled
+    pub fn set_bloom_filter_auto_size(mut self, value: bool) -> Self {
+        self.default_column_properties
+            .set_bloom_filter_auto_size(value);
+        self
+    }
+
+    /// Sets whether bloom filters are skipped for column chunks that are fully
+    /// dictionary encoded, for all columns (defaults to `false`).
+    /// See [`WriterProperties::bloom_filter_skip_dictionary_encoded`].
+    ///
+    /// Implicitly enables bloom writing, as if [`set_bloom_filter_enabled`] had
+    /// been called.
+    ///
+    /// [`set_bloom_filter_enabled`]: Self::set_bloom_filter_enabled
+    pub fn set_bloom_filter_skip_dictionary_encoded(mut self, value: bool) -> Self {
+        self.default_column_properties
+            .set_bloom_filter_skip_dictionary_encoded(value);
+        self
+    }
+
     // ----------------------------------------------------------------------
     // Setters for a specific column
 
@@ -1013,6 +1080,29 @@ impl WriterPropertiesBuilder {
         self.get_mut_props(col).set_bloom_filter_ndv(value);
         self
     }
+
+    /// Sets whether the bloom filter for a specific column is sized from the
+    /// number of distinct values observed.
+    ///
+    /// Takes precedence over [`Self::set_bloom_filter_auto_size`].
+    pub fn set_column_bloom_filter_auto_size(mut self, col: ColumnPath, value: bool) -> Self {
+        self.get_mut_props(col).set_bloom_filter_auto_size(value);
+        self
+    }
+
+    /// Sets whether the bloom filter for a specific column is skipped for column
+    /// chunks that are fully dictionary encoded.
+    ///
+    /// Takes precedence over [`Self::set_bloom_filter_skip_dictionary_encoded`].
+    pub fn set_column_bloom_filter_skip_dictionary_encoded(
+        mut self,
+        col: ColumnPath,
+        value: bool,
+    ) -> Self {
+        self.get_mut_props(col)
+            .set_bloom_filter_skip_dictionary_encoded(value);
+        self
+    }
 }
 
 impl From<WriterProperties> for WriterPropertiesBuilder {
@@ -1114,6 +1204,9 @@ pub struct BloomFilterProperties {
     /// anyway.
     ///
     /// Increasing this value (without increasing fpp) will result in an increase in disk or memory size.
+    ///
+    /// If [`WriterProperties::bloom_filter_auto_size`] is `true`, this is instead an upper bound
+    /// for the number of distinct values in a column chunk.
     pub ndv: u64,
 }
 
@@ -1141,6 +1234,8 @@ struct ColumnProperties {
     write_page_header_statistics: Option<bool>,
     /// bloom filter related properties
     bloom_filter_properties: Option<BloomFilterProperties>,
+    bloom_filter_auto_size: Option<bool>,
+    bloom_filter_skip_dictionary_encoded: Option<bool>,
 }
 
 impl ColumnProperties {
@@ -1226,6 +1321,22 @@ impl ColumnProperties {
             .ndv = value;
     }
 
+    /// Sets whether the bloom filter for this column is sized from the number of distinct values
+    /// observed, and implicitly enables bloom filter if not previously enabled.
+    fn set_bloom_filter_auto_size(&mut self, value: bool) {
+        self.bloom_filter_properties
+            .get_or_insert_with(Default::default);
+        self.bloom_filter_auto_size = Some(value);
+    }
+
+    /// Sets whether the bloom filter for this column is skipped for fully dictionary encoded
+    /// column chunks, and implicitly enables bloom filter if not previously enabled.
+    fn set_bloom_filter_skip_dictionary_encoded(&mut self, value: bool) {
+        self.bloom_filter_properties
+            .get_or_insert_with(Default::default);
+        self.bloom_filter_skip_dictionary_encoded = Some(value);
+    }
+
     /// Returns optional encoding for this column.
     fn encoding(&self) -> Option<Encoding> {
         self.encoding
@@ -1271,6 +1382,18 @@ impl ColumnProperties {
     fn bloom_filter_properties(&self) -> Option<&BloomFilterProperties> {
         self.bloom_filter_properties.as_ref()
     }
+
+    /// Returns `Some(true)` if the bloom filter is sized from the number of distinct values
+    /// observed, or `None` if not set
+    fn bloom_filter_auto_size(&self) -> Option<bool> {
+        self.bloom_filter_auto_size
+    }
+
+    /// Returns `Some(true)` if the bloom filter is skipped for fully dictionary encoded column
+    /// chunks, or `None` if not set
+    fn bloom_filter_skip_dictionary_encoded(&self) -> Option<bool> {
+        self.bloom_filter_skip_dictionary_encoded
+    }
 }
 
 /// Reference counted reader properties.
@@ -1661,6 +1784,29 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_writer_properties_bloom_filter_auto_size() {
+        let props = WriterProperties::builder()
+            .set_bloom_filter_auto_size(true)
+            .set_column_bloom_filter_auto_size(ColumnPath::from("col"), false)
+            .set_column_bloom_filter_skip_dictionary_encoded(ColumnPath::from("col"), true)
+            .build();
+        let col = ColumnPath::from("col");
+        let other = ColumnPath::from("other");
+        assert_eq!(
+            props.bloom_filter_properties(&col),
+            Some(&BloomFilterProperties::default())
+        );
+        assert!(!props.bloom_filter_auto_size(&col));
+        assert!(props.bloom_filter_skip_dictionary_encoded(&col));
+        assert_eq!(
+            props.bloom_filter_properties(&other),
+            Some(&BloomFilterProperties::default())
+        );
+        assert!(props.bloom_filter_auto_size(&other));
+        assert!(!props.bloom_filter_skip_dictionary_encoded(&other));
+    }
+
     #[test]
     fn test_writer_properties_column_dictionary_page_size_limit() {
         let props = WriterProperties::builder()
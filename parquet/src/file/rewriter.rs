@@ -0,0 +1,1056 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Rewriting of Parquet files without decoding their values
+//!
+//! [`ParquetRewriter`] copies the column chunks of a Parquet file to a new
+//! file written with different [`WriterProperties`], doing as little work as
+//! possible for each column chunk:
+//!
+//! * Chunks whose compression is unchanged, and which are neither encrypted
+//!   in the input nor to be encrypted in the output, are copied byte-for-byte
+//! * Other chunks are rewritten page by page: pages are decrypted and
+//!   decompressed, then compressed and encrypted according to the output
+//!   properties, without decoding any values. Page headers, statistics and
+//!   page indexes are preserved
+//! * Only when row groups are merged are values decoded and encoded again,
+//!   as a column chunk can contain at most one dictionary page
+//!
+//! Rows are never reordered, as sorting a file requires decoding all of its
+//! values, for which [`ArrowWriter`] can be used instead.
+//!
+//! [`WriterProperties`]: crate::file::properties::WriterProperties
+//! [`ArrowWriter`]: crate::arrow::arrow_writer::ArrowWriter
+
+use crate::basic::{PageType, Type};
+use crate::bloom_filter::Sbbf;
+use crate::column::page::{CompressedPage, Page, PageReader, PageWriteSpec, PageWriter};
+use crate::column::reader::ColumnReaderImpl;
+use crate::column::writer::{ColumnCloseResult, ColumnWriterImpl};
+use crate::compression::{Codec, CodecOptionsBuilder, create_codec};
+use crate::data_type::{
+    BoolType, ByteArrayType, DataType, DoubleType, FixedLenByteArrayType, FloatType, Int32Type,
+    Int64Type, Int96Type,
+};
+#[cfg(feature = "encryption")]
+use crate::encryption::{decrypt::FileDecryptionProperties, encrypt::get_column_crypto_metadata};
+use crate::errors::{ParquetError, Result};
+use crate::file::metadata::{
+    ColumnChunkMetaData, KeyValue, PageIndexPolicy, ParquetMetaData, ParquetMetaDataReader,
+};
+use crate::file::page_index::column_index::ColumnIndexMetaData;
+use crate::file::page_index::offset_index::{OffsetIndexMetaData, PageLocation};
+use crate::file::properties::{ReaderProperties, WriterProperties, WriterPropertiesPtr};
+use crate::file::reader::ChunkReader;
+use crate::file::serialized_reader::SerializedPageReader;
+use crate::file::writer::{SerializedFileWriter, SerializedRowGroupWriter};
+use crate::schema::types::{ColumnDescPtr, ColumnPath, Type as SchemaType, TypePtr};
+use bytes::Bytes;
+use std::io::Write;
+use std::sync::Arc;
+
+/// The key value metadata key of the serialized Arrow schema, which is no
+/// longer valid once columns have been dropped
+const ARROW_SCHEMA_META_KEY: &str = "ARROW:schema";
+
+/// Rewrites a Parquet file with new [`WriterProperties`], without decoding
+/// values unless row groups are merged
+///
+/// The output properties determine the compression and encryption of each
+/// column, and the size of merged row groups. Properties only affecting the
+/// encoding of values, such as the writer version, dictionary encoding,
+/// statistics or bloom filters, are only applied to merged row groups, as
+/// other column chunks keep the pages, statistics, page indexes and bloom
+/// filters of the input.
+///
+/// Bloom filters of encrypted input columns are not read, and so are not
+/// written unless their row group is merged.
+///
+/// # Example
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use bytes::Bytes;
+/// # use parquet::basic::Compression;
+/// # use parquet::file::properties::WriterProperties;
+/// # use parquet::file::rewriter::ParquetRewriter;
+/// # use parquet::file::writer::SerializedFileWriter;
+/// # use parquet::schema::parser::parse_message_type;
+/// # use parquet::schema::types::ColumnPath;
+/// # let schema = Arc::new(parse_message_type("message m { REQUIRED INT32 a; REQUIRED INT32 b; }")?);
+/// # let mut input = vec![];
+/// # SerializedFileWriter::new(&mut input, schema, Default::default())?.close()?;
+/// let props = WriterProperties::builder()
+///     .set_compression(Compression::SNAPPY)
+///     .build();
+/// let mut output = vec![];
+/// let metadata = ParquetRewriter::new(Bytes::from(input), Arc::new(props))
+///     .with_dropped_columns([ColumnPath::from("b")])
+///     .rewrite(&mut output)?;
+/// assert_eq!(metadata.file_metadata().schema_descr().num_columns(), 1);
+/// # Ok::<(), parquet::errors::ParquetError>(())
+/// ```
+///
+/// [`WriterProperties`]: crate::file::properties::WriterProperties
+pub struct ParquetRewriter<R: ChunkReader> {
+    input: Arc<R>,
+    props: WriterPropertiesPtr,
+    dropped_columns: Vec<ColumnPath>,
+    merge_row_groups: bool,
+    preserve_compression: bool,
+    #[cfg(feature = "encryption")]
+    file_decryption_properties: Option<Arc<FileDecryptionProperties>>,
+}
+
+impl<R: ChunkReader + 'static> ParquetRewriter<R> {
+    /// Creates a rewriter of the Parquet file in `input`, which is written
+    /// with the properties `props`
+    pub fn new(input: R, props: WriterPropertiesPtr) -> Self {
+        Self {
+            input: Arc::new(input),
+            props,
+            dropped_columns: vec![],
+            merge_row_groups: false,
+            preserve_compression: false,
+            #[cfg(feature = "encryption")]
+            file_decryption_properties: None,
+        }
+    }
+
+    /// Drop the given columns from the output
+    ///
+    /// A path may refer to a leaf column or a group, in which case all
+    /// columns within the group are dropped.
+    pub fn with_dropped_columns(mut self, columns: impl IntoIterator<Item = ColumnPath>) -> Self {
+        self.dropped_columns.extend(columns);
+        self
+    }
+
+    /// Merge adjacent row groups, as long as the merged row group does not
+    /// exceed the maximum row count and size of the output properties
+    /// (defaults to `false`)
+    ///
+    /// Merged row groups are decoded and encoded again.
+    pub fn with_merged_row_groups(mut self, merge_row_groups: bool) -> Self {
+        self.merge_row_groups = merge_row_groups;
+        self
+    }
+
+    /// Keep the compression of each input column chunk, instead of using the
+    /// compression of the output properties (defaults to `false`)
+    ///
+    /// Merged row groups use the compression of the column in the first row
+    /// group of the input.
+    pub fn with_preserved_compression(mut self, preserve_compression: bool) -> Self {
+        self.preserve_compression = preserve_compression;
+        self
+    }
+
+    /// Provide the [`FileDecryptionProperties`] used to decrypt the input
+    #[cfg(feature = "encryption")]
+    pub fn with_file_decryption_properties(
+        mut self,
+        file_decryption_properties: Arc<FileDecryptionProperties>,
+    ) -> Self {
+        self.file_decryption_properties = Some(file_decryption_properties);
+        self
+    }
+
+    /// Rewrites the input to `writer`, returning the metadata of the output
+    pub fn rewrite<W: Write + Send>(&self, writer: W) -> Result<ParquetMetaData> {
+        let metadata = self.read_metadata()?;
+        let file_metadata = metadata.file_metadata();
+        let schema_descr = file_metadata.schema_descr();
+
+        for path in &self.dropped_columns {
+            let exists = schema_descr
+                .columns()
+                .iter()
+                .any(|c| c.path().parts().starts_with(path.parts()));
+            if !exists {
+                return Err(general_err!("Column {} not found in schema", path.string()));
+            }
+        }
+        let kept_columns = schema_descr
+            .columns()
+            .iter()
+            .map(|c| !self.is_dropped(c.path()))
+            .collect::<Vec<_>>();
+        let schema = match prune_type(&schema_descr.root_schema_ptr(), &mut vec![], self)? {
+            Some(schema) => schema,
+            None => return Err(general_err!("Cannot drop all columns")),
+        };
+
+        let props = match self.preserve_compression {
+            true => Arc::new(self.preserved_compression_props(&metadata)),
+            false => Arc::clone(&self.props),
+        };
+        let mut writer = SerializedFileWriter::new(writer, schema, props)?;
+        let columns_dropped = kept_columns.iter().any(|keep| !keep);
+        let props_metadata = self.props.key_value_metadata();
+        for kv in file_metadata.key_value_metadata().into_iter().flatten() {
+            let overridden = props_metadata.is_some_and(|m| m.iter().any(|x| x.key == kv.key));
+            if overridden || (columns_dropped && kv.key == ARROW_SCHEMA_META_KEY) {
+                continue;
+            }
+            writer.append_key_value_metadata(KeyValue::new(kv.key.clone(), kv.value.clone()));
+        }
+
+        for row_groups in self.merged_row_groups(&metadata) {
+            let mut row_group_writer = writer.next_row_group()?;
+            match row_groups.as_slice() {
+                [rg_idx] => {
+                    for (col_idx, _) in kept_columns.iter().enumerate().filter(|(_, k)| **k) {
+                        self.rewrite_column_chunk(
+                            &metadata,
+                            *rg_idx,
+                            col_idx,
+                            &mut row_group_writer,
+                        )?;
+                    }
+                }
+                _ => {
+                    for (col_idx, _) in kept_columns.iter().enumerate().filter(|(_, k)| **k) {
+                        self.reencode_column(
+                            &metadata,
+                            &row_groups,
+                            col_idx,
+                            &mut row_group_writer,
+                        )?;
+                    }
+                }
+            }
+            row_group_writer.close()?;
+        }
+        writer.close()
+    }
+
+    fn read_metadata(&self) -> Result<ParquetMetaData> {
+        let reader = ParquetMetaDataReader::new().with_page_index_policy(PageIndexPolicy::Optional);
+        #[cfg(feature = "encryption")]
+        let reader = reader.with_decryption_properties(self.file_decryption_properties.clone());
+        reader.parse_and_finish(self.input.as_ref())
+    }
+
+    /// Returns the output properties with the compression of each column
+    /// set to that of the first row group of the input
+    fn preserved_compression_props(&self, metadata: &ParquetMetaData) -> WriterProperties {
+        let mut builder = self.props.as_ref().clone().into_builder();
+        for column in metadata
+            .row_groups()
+            .first()
+            .into_iter()
+            .flat_map(|rg| rg.columns())
+        {
+            builder =
+                builder.set_column_compression(column.column_path().clone(), column.compression());
+        }
+        builder.build()
+    }
+
+    fn is_dropped(&self, path: &ColumnPath) -> bool {
+        self.dropped_columns
+            .iter()
+            .any(|dropped| path.parts().starts_with(dropped.parts()))
+    }
+
+    /// Groups the indexes of the input row groups into output row groups
+    fn merged_row_groups(&self, metadata: &ParquetMetaData) -> Vec<Vec<usize>> {
+        let max_rows = self.props.max_row_group_row_count().unwrap_or(usize::MAX);
+        let max_bytes = self.props.max_row_group_bytes().unwrap_or(usize::MAX);
+
+        let mut groups: Vec<Vec<usize>> = vec![];
+        let (mut rows, mut bytes) = (0, 0);
+        for (idx, rg) in metadata.row_groups().iter().enumerate() {
+            let rg_rows = rg.num_rows() as usize;
+            let rg_bytes = rg.total_byte_size() as usize;
+            match groups.last_mut() {
+                Some(group)
+                    if self.merge_row_groups
+                        && rows + rg_rows <= max_rows
+                        && bytes + rg_bytes <= max_bytes =>
+                {
+                    group.push(idx);
+                    rows += rg_rows;
+                    bytes += rg_bytes;
+                }
+                _ => {
+                    groups.push(vec![idx]);
+                    (rows, bytes) = (rg_rows, rg_bytes);
+                }
+            }
+        }
+        groups
+    }
+
+    fn page_reader(
+        &self,
+        metadata: &ParquetMetaData,
+        rg_idx: usize,
+        col_idx: usize,
+    ) -> Result<SerializedPageReader<R>> {
+        let row_group = metadata.row_group(rg_idx);
+        let column = row_group.column(col_idx);
+        let props = ReaderProperties::builder()
+            .set_read_page_statistics(true)
+            .build();
+        SerializedPageReader::new_with_properties(
+            Arc::clone(&self.input),
+            column,
+            row_group.num_rows() as usize,
+            None,
+            Arc::new(props),
+        )?
+        .add_crypto_context(rg_idx, col_idx, metadata, column)
+    }
+
+    /// Copies a single column chunk to `row_group_writer`, either byte-for-byte
+    /// or page by page
+    fn rewrite_column_chunk<W: Write + Send>(
+        &self,
+        metadata: &ParquetMetaData,
+        rg_idx: usize,
+        col_idx: usize,
+        row_group_writer: &mut SerializedRowGroupWriter<'_, W>,
+    ) -> Result<()> {
+        let row_group = metadata.row_group(rg_idx);
+        let column = row_group.column(col_idx);
+        let column_index = metadata
+            .column_index()
+            .and_then(|ci| ci.get(rg_idx)?.get(col_idx))
+            .filter(|ci| !matches!(ci, ColumnIndexMetaData::NONE))
+            .cloned();
+        let offset_index = metadata
+            .offset_index()
+            .and_then(|oi| oi.get(rg_idx)?.get(col_idx))
+            .cloned();
+        let bloom_filter = match is_encrypted_input(column) {
+            true => None,
+            false => Sbbf::read_from_column_chunk(column, self.input.as_ref())?,
+        };
+
+        let compression = match self.preserve_compression {
+            true => column.compression(),
+            false => self.props.compression(column.column_path()),
+        };
+        if compression == column.compression()
+            && !is_encrypted_input(column)
+            && !self.is_encrypted_output(column.column_descr_ptr())
+        {
+            let close = ColumnCloseResult {
+                bytes_written: column.compressed_size() as u64,
+                rows_written: row_group.num_rows() as u64,
+                metadata: column.clone(),
+                bloom_filter,
+                column_index,
+                offset_index,
+            };
+            return row_group_writer.append_column(self.input.as_ref(), close);
+        }
+
+        let page_reader = self.page_reader(metadata, rg_idx, col_idx)?;
+        row_group_writer.next_column_with_factory(|descr, props, page_writer, on_close| {
+            let mut copier = PageCopier {
+                descr: descr.clone(),
+                compressor: create_codec(compression, &CodecOptionsBuilder::default().build())?,
+                page_writer,
+                specs: vec![],
+                data_page_rows: vec![],
+            };
+            for page in page_reader {
+                copier.write_page(page?)?;
+            }
+            copier.page_writer.close()?;
+
+            let mut builder = ColumnChunkMetaData::builder(descr.clone())
+                .set_compression(compression)
+                .set_encodings_mask(*column.encodings_mask())
+                .set_num_values(column.num_values())
+                .set_unencoded_byte_array_data_bytes(column.unencoded_byte_array_data_bytes())
+                .set_repetition_level_histogram(column.repetition_level_histogram().cloned())
+                .set_definition_level_histogram(column.definition_level_histogram().cloned());
+            if let Some(statistics) = column.statistics() {
+                builder = builder.set_statistics(statistics.clone());
+            }
+            if let Some(geo_statistics) = column.geo_statistics() {
+                builder = builder.set_geo_statistics(Box::new(geo_statistics.clone()));
+            }
+            if let Some(page_encoding_stats) = column.page_encoding_stats() {
+                builder = builder.set_page_encoding_stats(page_encoding_stats.clone());
+            }
+            #[cfg(feature = "encryption")]
+            if let Some(properties) = props.file_encryption_properties() {
+                builder = builder
+                    .set_column_crypto_metadata(get_column_crypto_metadata(properties, &descr));
+            }
+            #[cfg(not(feature = "encryption"))]
+            let _ = props;
+
+            let mut bytes_written = 0;
+            let mut compressed_size = 0;
+            let mut uncompressed_size = 0;
+            let mut page_locations = vec![];
+            let mut first_row_index = 0;
+            for spec in &copier.specs {
+                bytes_written += spec.bytes_written;
+                compressed_size += spec.compressed_size as i64;
+                uncompressed_size += spec.uncompressed_size as i64;
+                match spec.page_type {
+                    PageType::DICTIONARY_PAGE => {
+                        builder = builder.set_dictionary_page_offset(Some(spec.offset as i64));
+                    }
+                    _ => {
+                        if page_locations.is_empty() {
+                            builder = builder.set_data_page_offset(spec.offset as i64);
+                        }
+                        let idx = page_locations.len();
+                        page_locations.push(PageLocation {
+                            offset: spec.offset as i64,
+                            compressed_page_size: spec.compressed_size as i32,
+                            first_row_index,
+                        });
+                        first_row_index += copier.data_page_rows[idx].unwrap_or(0) as i64;
+                    }
+                }
+            }
+            builder = builder
+                .set_total_compressed_size(compressed_size)
+                .set_total_uncompressed_size(uncompressed_size);
+
+            // The first row of each page is only known from the input offset
+            // index, or from the number of values if there are no repeated fields
+            let offset_index = match offset_index {
+                Some(input) if input.page_locations.len() == page_locations.len() => {
+                    for (location, input) in page_locations.iter_mut().zip(&input.page_locations) {
+                        location.first_row_index = input.first_row_index;
+                    }
+                    Some(OffsetIndexMetaData {
+                        page_locations,
+                        unencoded_byte_array_data_bytes: input.unencoded_byte_array_data_bytes,
+                    })
+                }
+                _ if copier.data_page_rows.iter().all(Option::is_some) => {
+                    Some(OffsetIndexMetaData {
+                        page_locations,
+                        unencoded_byte_array_data_bytes: None,
+                    })
+                }
+                _ => None,
+            };
+
+            on_close(ColumnCloseResult {
+                bytes_written,
+                rows_written: row_group.num_rows() as u64,
+                metadata: builder.build()?,
+                bloom_filter,
+                column_index,
+                offset_index,
+            })
+        })?;
+        Ok(())
+    }
+
+    /// Decodes the column `col_idx` of the input `row_groups`, and encodes it
+    /// to a single column chunk of `row_group_writer`
+    fn reencode_column<W: Write + Send>(
+        &self,
+        metadata: &ParquetMetaData,
+        row_groups: &[usize],
+        col_idx: usize,
+        row_group_writer: &mut SerializedRowGroupWriter<'_, W>,
+    ) -> Result<()> {
+        let mut column_writer = row_group_writer
+            .next_column()?
+            .ok_or_else(|| general_err!("Missing column writer"))?;
+        let descr = metadata.file_metadata().schema_descr().column(col_idx);
+        let batch_size = self.props.write_batch_size();
+        for rg_idx in row_groups {
+            let page_reader = Box::new(self.page_reader(metadata, *rg_idx, col_idx)?);
+            let descr = descr.clone();
+            match descr.physical_type() {
+                Type::BOOLEAN => {
+                    copy_values::<BoolType>(descr, page_reader, column_writer.typed(), batch_size)
+                }
+                Type::INT32 => {
+                    copy_values::<Int32Type>(descr, page_reader, column_writer.typed(), batch_size)
+                }
+                Type::INT64 => {
+                    copy_values::<Int64Type>(descr, page_reader, column_writer.typed(), batch_size)
+                }
+                Type::INT96 => {
+                    copy_values::<Int96Type>(descr, page_reader, column_writer.typed(), batch_size)
+                }
+                Type::FLOAT => {
+                    copy_values::<FloatType>(descr, page_reader, column_writer.typed(), batch_size)
+                }
+                Type::DOUBLE => {
+                    copy_values::<DoubleType>(descr, page_reader, column_writer.typed(), batch_size)
+                }
+                Type::BYTE_ARRAY => copy_values::<ByteArrayType>(
+                    descr,
+                    page_reader,
+                    column_writer.typed(),
+                    batch_size,
+                ),
+                Type::FIXED_LEN_BYTE_ARRAY => copy_values::<FixedLenByteArrayType>(
+                    descr,
+                    page_reader,
+                    column_writer.typed(),
+                    batch_size,
+                ),
+            }?;
+        }
+        column_writer.close()
+    }
+
+    #[cfg(feature = "encryption")]
+    fn is_encrypted_output(&self, descr: ColumnDescPtr) -> bool {
+        self.props
+            .file_encryption_properties()
+            .is_some_and(|p| get_column_crypto_metadata(p, &descr).is_some())
+    }
+
+    #[cfg(not(feature = "encryption"))]
+    fn is_encrypted_output(&self, _descr: ColumnDescPtr) -> bool {
+        false
+    }
+}
+
+#[cfg(feature = "encryption")]
+fn is_encrypted_input(column: &ColumnChunkMetaData) -> bool {
+    column.crypto_metadata().is_some()
+}
+
+#[cfg(not(feature = "encryption"))]
+fn is_encrypted_input(_column: &ColumnChunkMetaData) -> bool {
+    false
+}
+
+/// Removes the dropped columns from `tp`, returning `None` if no columns remain
+fn prune_type<R: ChunkReader + 'static>(
+    tp: &TypePtr,
+    path: &mut Vec<String>,
+    rewriter: &ParquetRewriter<R>,
+) -> Result<Option<TypePtr>> {
+    if !tp.is_group() {
+        path.push(tp.name().to_string());
+        let dropped = rewriter.is_dropped(&ColumnPath::new(path.clone()));
+        path.pop();
+        return Ok((!dropped).then(|| Arc::clone(tp)));
+    }
+
+    let is_root = path.is_empty() && !tp.get_basic_info().has_repetition();
+    if !is_root {
+        path.push(tp.name().to_string());
+    }
+    let mut fields = Vec::with_capacity(tp.get_fields().len());
+    for field in tp.get_fields() {
+        if let Some(field) = prune_type(field, path, rewriter)? {
+            fields.push(field);
+        }
+    }
+    if !is_root {
+        path.pop();
+    }
+
+    if fields.is_empty() {
+        return Ok(None);
+    }
+    if fields.len() == tp.get_fields().len() {
+        return Ok(Some(Arc::clone(tp)));
+    }
+    let info = tp.get_basic_info();
+    let mut builder = SchemaType::group_type_builder(info.name())
+        .with_converted_type(info.converted_type())
+        .with_logical_type(info.logical_type_ref().cloned())
+        .with_id(info.has_id().then(|| info.id()))
+        .with_fields(fields);
+    if info.has_repetition() {
+        builder = builder.with_repetition(info.repetition());
+    }
+    Ok(Some(Arc::new(builder.build()?)))
+}
+
+/// Compresses pages read from the input and writes them to a [`PageWriter`]
+struct PageCopier<'a> {
+    descr: ColumnDescPtr,
+    compressor: Option<Box<dyn Codec>>,
+    page_writer: Box<dyn PageWriter + 'a>,
+    specs: Vec<PageWriteSpec>,
+    /// The number of rows of each data page, if known
+    data_page_rows: Vec<Option<u32>>,
+}
+
+impl PageCopier<'_> {
+    fn write_page(&mut self, page: Page) -> Result<()> {
+        let page = match page {
+            Page::DictionaryPage {
+                buf,
+                num_values,
+                encoding,
+                is_sorted,
+            } => {
+                let uncompressed_size = buf.len();
+                let dict_page = Page::DictionaryPage {
+                    buf: self.compress(buf)?,
+                    num_values,
+                    encoding,
+                    is_sorted,
+                };
+                CompressedPage::new(dict_page, uncompressed_size)
+            }
+            Page::DataPage {
+                buf,
+                num_values,
+                encoding,
+                def_level_encoding,
+                rep_level_encoding,
+                statistics,
+            } => {
+                let rows = (self.descr.max_rep_level() == 0).then_some(num_values);
+                self.data_page_rows.push(rows);
+                let uncompressed_size = buf.len();
+                let data_page = Page::DataPage {
+                    buf: self.compress(buf)?,
+                    num_values,
+                    encoding,
+                    def_level_encoding,
+                    rep_level_encoding,
+                    statistics,
+                };
+                CompressedPage::new(data_page, uncompressed_size)
+            }
+            Page::DataPageV2 {
+                buf,
+                num_values,
+                encoding,
+                num_nulls,
+                num_rows,
+                def_levels_byte_len,
+                rep_levels_byte_len,
+                is_compressed: _,
+                statistics,
+            } => {
+                self.data_page_rows.push(Some(num_rows));
+                let uncompressed_size = buf.len();
+                let levels_len = (def_levels_byte_len + rep_levels_byte_len) as usize;
+                if levels_len > buf.len() {
+                    return Err(general_err!("Invalid data page v2 levels length"));
+                }
+
+                // Data Page v2 compresses values only, and only if that reduces its size
+                let mut output = buf[..levels_len].to_vec();
+                let values = &buf[levels_len..];
+                let is_compressed = match self.compressor.as_mut() {
+                    Some(compressor) => {
+                        compressor.compress(values, &mut output)?;
+                        if output.len() - levels_len < values.len() {
+                            true
+                        } else {
+                            output.truncate(levels_len);
+                            output.extend_from_slice(values);
+                            false
+                        }
+                    }
+                    None => {
+                        output.extend_from_slice(values);
+                        false
+                    }
+                };
+                let data_page = Page::DataPageV2 {
+                    buf: output.into(),
+                    num_values,
+                    encoding,
+                    num_nulls,
+                    num_rows,
+                    def_levels_byte_len,
+                    rep_levels_byte_len,
+                    is_compressed,
+                    statistics,
+                };
+                CompressedPage::new(data_page, uncompressed_size)
+            }
+        };
+        self.specs.push(self.page_writer.write_page(page)?);
+        Ok(())
+    }
+
+    fn compress(&mut self, buf: Bytes) -> Result<Bytes> {
+        match self.compressor.as_mut() {
+            Some(compressor) => {
+                let mut output = Vec::with_capacity(buf.len());
+                compressor.compress(&buf, &mut output)?;
+                Ok(output.into())
+            }
+            None => Ok(buf),
+        }
+    }
+}
+
+/// Decodes all values and levels from `page_reader`, and writes them to `writer`
+fn copy_values<T: DataType>(
+    descr: ColumnDescPtr,
+    page_reader: Box<dyn PageReader>,
+    writer: &mut ColumnWriterImpl<'_, T>,
+    batch_size: usize,
+) -> Result<()> {
+    let max_def_level = descr.max_def_level();
+    let max_rep_level = descr.max_rep_level();
+    let mut reader = ColumnReaderImpl::<T>::new(descr, page_reader);
+    let mut values = Vec::with_capacity(batch_size);
+    let mut def_levels = Vec::with_capacity(batch_size);
+    let mut rep_levels = Vec::with_capacity(batch_size);
+    loop {
+        values.clear();
+        def_levels.clear();
+        rep_levels.clear();
+        let (_, _, levels) = reader.read_records(
+            batch_size,
+            Some(&mut def_levels),
+            Some(&mut rep_levels),
+            &mut values,
+        )?;
+        if levels == 0 {
+            return Ok(());
+        }
+        writer.write_batch(
+            &values,
+            (max_def_level > 0).then_some(&def_levels),
+            (max_rep_level > 0).then_some(&rep_levels),
+        )?;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::basic::Compression;
+    use crate::data_type::ByteArray;
+    use crate::file::properties::{EnabledStatistics, WriterProperties, WriterVersion};
+    use crate::file::reader::{FileReader, SerializedFileReader};
+    use crate::record::{Row, RowAccessor};
+    use crate::schema::parser::parse_message_type;
+
+    const NUM_ROW_GROUPS: usize = 3;
+    const ROWS_PER_ROW_GROUP: i32 = 100;
+
+    fn test_file(props: WriterProperties) -> Bytes {
+        let schema = parse_message_type(
+            "message test {
+                REQUIRED INT32 a;
+                OPTIONAL BYTE_ARRAY b (UTF8);
+                OPTIONAL group c (LIST) {
+                    REPEATED group list {
+                        OPTIONAL INT64 element;
+                    }
+                }
+            }",
+        )
+        .unwrap();
+        let mut buf = vec![];
+        let mut writer =
+            SerializedFileWriter::new(&mut buf, Arc::new(schema), Arc::new(props)).unwrap();
+        for rg in 0..NUM_ROW_GROUPS as i32 {
+            let rows = (0..ROWS_PER_ROW_GROUP).map(|i| rg * ROWS_PER_ROW_GROUP + i);
+            let mut row_group_writer = writer.next_row_group().unwrap();
+
+            let mut column = row_group_writer.next_column().unwrap().unwrap();
+            let values = rows.clone().collect::<Vec<_>>();
+            column
+                .typed::<Int32Type>()
+                .write_batch(&values, None, None)
+                .unwrap();
+            column.close().unwrap();
+
+            let mut column = row_group_writer.next_column().unwrap().unwrap();
+            let def_levels = rows
+                .clone()
+                .map(|i| (i % 4 != 0) as i16)
+                .collect::<Vec<_>>();
+            let values = rows
+                .clone()
+                .filter(|i| i % 4 != 0)
+                .map(|i| ByteArray::from(format!("value {}", i % 10).as_str()))
+                .collect::<Vec<_>>();
+            column
+                .typed::<ByteArrayType>()
+                .write_batch(&values, Some(&def_levels), None)
+                .unwrap();
+            column.close().unwrap();
+
+            // Rows divisible by 3 are empty lists, others contain a value and a null
+            let mut column = row_group_writer.next_column().unwrap().unwrap();
+            let (mut values, mut def_levels, mut rep_levels) = (vec![], vec![], vec![]);
+            for i in rows {
+                if i % 3 == 0 {
+                    def_levels.push(1);
+                    rep_levels.push(0);
+                } else {
+                    values.push(i as i64);
+                    def_levels.extend([3, 2]);
+                    rep_levels.extend([0, 1]);
+                }
+            }
+            column
+                .typed::<Int64Type>()
+                .write_batch(&values, Some(&def_levels), Some(&rep_levels))
+                .unwrap();
+            column.close().unwrap();
+
+            row_group_writer.close().unwrap();
+        }
+        writer.close().unwrap();
+        buf.into()
+    }
+
+    fn default_props() -> WriterProperties {
+        WriterProperties::builder()
+            .set_data_page_row_count_limit(10)
+            .set_write_batch_size(10)
+            .set_statistics_enabled(EnabledStatistics::Page)
+            .set_bloom_filter_enabled(true)
+            .build()
+    }
+
+    fn read_rows(data: Bytes) -> Vec<Row> {
+        SerializedFileReader::new(data)
+            .unwrap()
+            .get_row_iter(None)
+            .unwrap()
+            .map(|r| r.unwrap())
+            .collect()
+    }
+
+    fn rewrite(rewriter: ParquetRewriter<Bytes>) -> (Bytes, ParquetMetaData) {
+        let mut output = vec![];
+        let metadata = rewriter.rewrite(&mut output).unwrap();
+        let output = Bytes::from(output);
+        let read = ParquetMetaDataReader::new()
+            .with_page_index_policy(PageIndexPolicy::Optional)
+            .parse_and_finish(&output)
+            .unwrap();
+        assert_eq!(read.num_row_groups(), metadata.num_row_groups());
+        (output, read)
+    }
+
+    #[test]
+    fn test_copy() {
+        let input = test_file(default_props());
+        let rewriter = ParquetRewriter::new(input.clone(), Arc::new(default_props()));
+        let (output, metadata) = rewrite(rewriter);
+        assert_eq!(read_rows(output.clone()), read_rows(input.clone()));
+
+        let input_metadata = ParquetMetaDataReader::new()
+            .with_page_index_policy(PageIndexPolicy::Optional)
+            .parse_and_finish(&input)
+            .unwrap();
+        for (input_rg, rg) in input_metadata
+            .row_groups()
+            .iter()
+            .zip(metadata.row_groups())
+        {
+            for (input_col, col) in input_rg.columns().iter().zip(rg.columns()) {
+                let (start, len) = input_col.byte_range();
+                let (output_start, output_len) = col.byte_range();
+                assert_eq!(len, output_len);
+                assert_eq!(
+                    input.slice(start as usize..(start + len) as usize),
+                    output.slice(output_start as usize..(output_start + output_len) as usize)
+                );
+                assert!(col.bloom_filter_offset().is_some());
+            }
+        }
+        assert_eq!(metadata.column_index(), input_metadata.column_index());
+        assert_eq!(
+            metadata.offset_index().unwrap()[0][0].page_locations.len(),
+            10
+        );
+    }
+
+    #[test]
+    fn test_recompress() {
+        for version in [WriterVersion::PARQUET_1_0, WriterVersion::PARQUET_2_0] {
+            let props = WriterProperties::builder()
+                .set_writer_version(version)
+                .set_data_page_row_count_limit(10)
+                .set_write_batch_size(10)
+                .set_statistics_enabled(EnabledStatistics::Page)
+                .build();
+            let input = test_file(props);
+            let input_metadata = ParquetMetaDataReader::new()
+                .with_page_index_policy(PageIndexPolicy::Optional)
+                .parse_and_finish(&input)
+                .unwrap();
+
+            let props = WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .set_column_compression("a".into(), Compression::UNCOMPRESSED)
+                .build();
+            let rewriter = ParquetRewriter::new(input.clone(), Arc::new(props));
+            let (output, metadata) = rewrite(rewriter);
+            assert_eq!(read_rows(output), read_rows(input));
+
+            let rg = metadata.row_group(0);
+            assert_eq!(rg.column(0).compression(), Compression::UNCOMPRESSED);
+            assert_eq!(rg.column(1).compression(), Compression::SNAPPY);
+            assert_eq!(rg.column(2).compression(), Compression::SNAPPY);
+            assert_eq!(
+                rg.column(1).statistics(),
+                input_metadata.row_group(0).column(1).statistics()
+            );
+            assert_eq!(metadata.column_index(), input_metadata.column_index());
+
+            let offset_index = metadata.offset_index().unwrap();
+            let input_offset_index = input_metadata.offset_index().unwrap();
+            for (rg, input_rg) in offset_index.iter().zip(input_offset_index) {
+                for (col, input_col) in rg.iter().zip(input_rg) {
+                    let rows = |o: &OffsetIndexMetaData| {
+                        o.page_locations
+                            .iter()
+                            .map(|l| l.first_row_index)
+                            .collect::<Vec<_>>()
+                    };
+                    assert_eq!(rows(col), rows(input_col));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_drop_columns() {
+        let input = test_file(default_props());
+        let rewriter = ParquetRewriter::new(input.clone(), Arc::new(default_props()))
+            .with_dropped_columns([ColumnPath::from("b"), ColumnPath::from("c")]);
+        let (output, metadata) = rewrite(rewriter);
+        let schema = metadata.file_metadata().schema_descr();
+        assert_eq!(schema.num_columns(), 1);
+        assert_eq!(schema.column(0).name(), "a");
+
+        let expected = read_rows(input.clone())
+            .into_iter()
+            .map(|row| row.get_int(0).unwrap())
+            .collect::<Vec<_>>();
+        let actual = read_rows(output)
+            .into_iter()
+            .map(|row| row.get_int(0).unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(actual, expected);
+
+        let rewriter = ParquetRewriter::new(input.clone(), Arc::new(default_props()))
+            .with_dropped_columns([ColumnPath::new(vec!["c".into(), "list".into()])]);
+        let (_, metadata) = rewrite(rewriter);
+        assert_eq!(metadata.file_metadata().schema_descr().num_columns(), 2);
+
+        let err = ParquetRewriter::new(input.clone(), Arc::new(default_props()))
+            .with_dropped_columns([ColumnPath::from("d")])
+            .rewrite(vec![])
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Column d not found in schema"
+        );
+
+        let err = ParquetRewriter::new(input, Arc::new(default_props()))
+            .with_dropped_columns(["a", "b", "c"].map(ColumnPath::from))
+            .rewrite(vec![])
+            .unwrap_err();
+        assert_eq!(err.to_string(), "Parquet error: Cannot drop all columns");
+    }
+
+    #[test]
+    fn test_merge_row_groups() {
+        let input = test_file(default_props());
+        let props = WriterProperties::builder()
+            .set_max_row_group_row_count(Some(2 * ROWS_PER_ROW_GROUP as usize))
+            .set_bloom_filter_enabled(true)
+            .build();
+        let rewriter =
+            ParquetRewriter::new(input.clone(), Arc::new(props)).with_merged_row_groups(true);
+        let (output, metadata) = rewrite(rewriter);
+        assert_eq!(metadata.num_row_groups(), 2);
+        assert_eq!(metadata.row_group(0).num_rows(), 200);
+        assert_eq!(metadata.row_group(1).num_rows(), 100);
+        assert!(
+            metadata
+                .row_group(0)
+                .column(1)
+                .bloom_filter_offset()
+                .is_some()
+        );
+        assert_eq!(read_rows(output), read_rows(input));
+    }
+
+    #[test]
+    fn test_preserve_compression() {
+        let input = test_file(
+            WriterProperties::builder()
+                .set_compression(Compression::SNAPPY)
+                .set_column_compression("a".into(), Compression::UNCOMPRESSED)
+                .build(),
+        );
+        let props = WriterProperties::builder()
+            .set_max_row_group_row_count(Some(2 * ROWS_PER_ROW_GROUP as usize))
+            .build();
+        let rewriter = ParquetRewriter::new(input.clone(), Arc::new(props))
+            .with_merged_row_groups(true)
+            .with_preserved_compression(true);
+        let (output, metadata) = rewrite(rewriter);
+        assert_eq!(metadata.num_row_groups(), 2);
+        for rg in metadata.row_groups() {
+            assert_eq!(rg.column(0).compression(), Compression::UNCOMPRESSED);
+            assert_eq!(rg.column(1).compression(), Compression::SNAPPY);
+            assert_eq!(rg.column(2).compression(), Compression::SNAPPY);
+        }
+        assert_eq!(read_rows(output), read_rows(input));
+    }
+
+    #[cfg(feature = "encryption")]
+    #[test]
+    fn test_encrypt_and_decrypt() {
+        use crate::encryption::encrypt::FileEncryptionProperties;
+
+        let footer_key = b"0123456789012345".to_vec();
+        let column_key = b"1234567890123450".to_vec();
+        let input = test_file(default_props());
+
+        let encryption_properties = FileEncryptionProperties::builder(footer_key.clone())
+            .with_column_key("b", column_key.clone())
+            .build()
+            .unwrap();
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(encryption_properties)
+            .build();
+        let mut encrypted = vec![];
+        ParquetRewriter::new(input.clone(), Arc::new(props))
+            .rewrite(&mut encrypted)
+            .unwrap();
+        let encrypted = Bytes::from(encrypted);
+        ParquetMetaDataReader::new()
+            .parse_and_finish(&encrypted)
+            .unwrap_err();
+
+        let decryption_properties = FileDecryptionProperties::builder(footer_key)
+            .with_column_key("b", column_key)
+            .build()
+            .unwrap();
+        let rewriter = ParquetRewriter::new(encrypted, Arc::new(default_props()))
+            .with_file_decryption_properties(decryption_properties);
+        let (output, metadata) = rewrite(rewriter);
+        assert!(metadata.row_group(0).column(1).crypto_metadata().is_none());
+        assert_eq!(read_rows(output), read_rows(input));
+    }
+}
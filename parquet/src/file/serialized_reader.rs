@@ -597,7 +597,7 @@ impl<R: ChunkReader> SerializedPageReader<R> {
     }
 
     /// Stub No-op implementation when encryption is disabled.
-    #[cfg(all(feature = "arrow", not(feature = "encryption")))]
+    #[cfg(not(feature = "encryption"))]
     pub(crate) fn add_crypto_context(
         self,
         _rg_idx: usize,
@@ -832,10 +832,10 @@ impl SerializedPageReaderContext {
                 }
             }
             Some(page_crypto_context) => {
-                let data_decryptor = page_crypto_context.data_decryptor();
+                let metadata_decryptor = page_crypto_context.metadata_decryptor();
                 let aad = page_crypto_context.create_page_header_aad()?;
 
-                let buf = read_and_decrypt(data_decryptor, input, aad.as_ref()).map_err(|_| {
+                let buf = read_and_decrypt(metadata_decryptor, input, aad.as_ref()).map_err(|_| {
                     ParquetError::General(format!(
                         "Error decrypting page header for column {}, decryption key may be wrong",
                         page_crypto_context.column_ordinal
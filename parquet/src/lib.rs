@@ -197,6 +197,8 @@ experimental!(pub mod encryption);
 pub mod file;
 pub mod record;
 pub mod schema;
+#[cfg(feature = "serde")]
+pub mod serde;
 
 mod parquet_macros;
 mod parquet_thrift;
@@ -18,6 +18,7 @@
 //! Contains record-based API for reading Parquet files.
 
 mod api;
+pub mod parquet_field;
 pub mod reader;
 mod record_reader;
 mod record_writer;
@@ -27,6 +28,7 @@ pub use self::{
     api::{
         Field, List, ListAccessor, Map, MapAccessor, Row, RowAccessor, RowColumnIter, RowFormatter,
     },
+    parquet_field::{LeafColumn, Levels, ParquetField},
     record_reader::RecordReader,
     record_writer::RecordWriter,
 };
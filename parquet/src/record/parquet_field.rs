@@ -0,0 +1,1042 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Support for reading and writing nested types, used by the code generated by
+//! [`parquet_derive`] for fields that are not flat primitives, see [`ParquetField`]
+//!
+//! [`parquet_derive`]: https://crates.io/crates/parquet_derive
+
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash;
+use std::ops::Range;
+
+use chrono::{NaiveDate, NaiveDateTime};
+
+use crate::basic::{LogicalType, Repetition, TimeUnit, Type as PhysicalType};
+use crate::column::reader::ColumnReader;
+use crate::column::writer::ColumnWriter;
+use crate::data_type::{ByteArray, FixedLenByteArray, Int96};
+use crate::errors::{ParquetError, Result};
+use crate::file::reader::RowGroupReader;
+use crate::file::writer::SerializedRowGroupWriter;
+use crate::schema::types::{Type, TypePtr};
+
+/// The levels at which a value is written or read, see [`ParquetField`]
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub struct Levels {
+    /// The definition level of the value if it is present
+    pub def: i16,
+    /// The repetition level of the first leaf value written
+    pub rep: i16,
+    /// The number of repeated ancestors of the value
+    pub depth: i16,
+}
+
+impl Levels {
+    /// Returns the levels of the child `node` of a value at these levels
+    pub fn child(self, node: &Type) -> Self {
+        match node.get_basic_info().has_repetition() {
+            true => match node.get_basic_info().repetition() {
+                Repetition::REQUIRED => self,
+                Repetition::OPTIONAL => Self {
+                    def: self.def + 1,
+                    ..self
+                },
+                Repetition::REPEATED => Self {
+                    def: self.def + 1,
+                    depth: self.depth + 1,
+                    ..self
+                },
+            },
+            false => self,
+        }
+    }
+}
+
+/// A type that can be shredded into, and assembled from, the leaf columns of a
+/// parquet field
+///
+/// This is implemented for primitives, [`String`], [`NaiveDate`], [`NaiveDateTime`],
+/// byte arrays, [`Option`], [`Vec`] (as a `LIST`, with the exception of `Vec<u8>`
+/// which is a `BYTE_ARRAY`), and [`HashMap`] and [`BTreeMap`] (as a `MAP`).
+///
+/// `#[derive(ParquetField)]` in [`parquet_derive`] implements this for structs, which
+/// are written as groups, and for enums with only unit variants, which are written as
+/// strings or integers. Such types can then be used as fields of types deriving
+/// `ParquetRecordWriter` and `ParquetRecordReader`.
+///
+/// [`parquet_derive`]: https://crates.io/crates/parquet_derive
+pub trait ParquetField: Sized {
+    /// Returns the number of leaf columns of this type
+    fn leaf_count() -> usize {
+        1
+    }
+
+    /// Returns the parquet type of a field of this type called `name`
+    fn parquet_type(name: &str, repetition: Repetition) -> Result<Type>;
+
+    /// Appends this value to `columns`, the [`Self::leaf_count`] leaf columns of
+    /// this type
+    ///
+    /// `levels.def` is the definition level of this value, [`Option`] incrementing it
+    /// for the value it contains
+    fn write(&self, columns: &mut [LeafColumn], levels: Levels) -> Result<()>;
+
+    /// Assembles a value from `columns`, the leaf columns of `node`
+    ///
+    /// `levels.def` is the definition level of `node` if it is present, i.e. including
+    /// its own repetition, see [`Levels::child`]
+    fn read(node: &Type, columns: &mut [LeafColumn], levels: Levels) -> Result<Self>;
+
+    #[doc(hidden)]
+    fn vec_parquet_type(name: &str, repetition: Repetition) -> Result<Type> {
+        list_type(
+            name,
+            repetition,
+            Self::parquet_type("element", Repetition::REQUIRED)?,
+        )
+    }
+
+    #[doc(hidden)]
+    fn write_vec(values: &[Self], columns: &mut [LeafColumn], levels: Levels) -> Result<()> {
+        write_repeated(values, columns, levels, |v, columns, levels| {
+            v.write(columns, levels)
+        })
+    }
+
+    #[doc(hidden)]
+    fn read_vec(node: &Type, columns: &mut [LeafColumn], levels: Levels) -> Result<Vec<Self>> {
+        let (element, repeated_levels, element_levels) = list_element(node, levels)?;
+        read_repeated(columns, repeated_levels, |columns| {
+            Self::read(element, columns, element_levels)
+        })
+    }
+}
+
+/// The values of a leaf column
+#[derive(Debug, Clone, PartialEq)]
+#[allow(missing_docs)]
+pub enum LeafValues {
+    Boolean(Vec<bool>),
+    Int32(Vec<i32>),
+    Int64(Vec<i64>),
+    Int96(Vec<Int96>),
+    Float(Vec<f32>),
+    Double(Vec<f64>),
+    ByteArray(Vec<ByteArray>),
+    FixedLenByteArray(Vec<FixedLenByteArray>),
+}
+
+impl LeafValues {
+    fn new(physical_type: PhysicalType) -> Self {
+        match physical_type {
+            PhysicalType::BOOLEAN => Self::Boolean(vec![]),
+            PhysicalType::INT32 => Self::Int32(vec![]),
+            PhysicalType::INT64 => Self::Int64(vec![]),
+            PhysicalType::INT96 => Self::Int96(vec![]),
+            PhysicalType::FLOAT => Self::Float(vec![]),
+            PhysicalType::DOUBLE => Self::Double(vec![]),
+            PhysicalType::BYTE_ARRAY => Self::ByteArray(vec![]),
+            PhysicalType::FIXED_LEN_BYTE_ARRAY => Self::FixedLenByteArray(vec![]),
+        }
+    }
+
+    /// Moves the values of `other`, which must be of the same type, to the end of `self`
+    #[cfg(feature = "serde")]
+    fn append(&mut self, other: &mut Self) {
+        match (self, other) {
+            (Self::Boolean(a), Self::Boolean(b)) => a.append(b),
+            (Self::Int32(a), Self::Int32(b)) => a.append(b),
+            (Self::Int64(a), Self::Int64(b)) => a.append(b),
+            (Self::Int96(a), Self::Int96(b)) => a.append(b),
+            (Self::Float(a), Self::Float(b)) => a.append(b),
+            (Self::Double(a), Self::Double(b)) => a.append(b),
+            (Self::ByteArray(a), Self::ByteArray(b)) => a.append(b),
+            (Self::FixedLenByteArray(a), Self::FixedLenByteArray(b)) => a.append(b),
+            _ => unreachable!("leaf values of different types"),
+        }
+    }
+}
+
+/// The values and levels of a leaf column, either being shredded from values for
+/// writing, or read from a column chunk for assembling values
+#[derive(Debug, Clone, PartialEq)]
+pub struct LeafColumn {
+    values: LeafValues,
+    def_levels: Vec<i16>,
+    rep_levels: Vec<i16>,
+    max_def_level: i16,
+    /// The position of the next level to read
+    level_offset: usize,
+    /// The position of the next value to read
+    value_offset: usize,
+}
+
+impl LeafColumn {
+    fn new(values: LeafValues, max_def_level: i16) -> Self {
+        Self {
+            values,
+            def_levels: vec![],
+            rep_levels: vec![],
+            max_def_level,
+            level_offset: 0,
+            value_offset: 0,
+        }
+    }
+
+    /// Returns empty leaf columns for each leaf of `ty`, in schema order
+    pub fn for_type(ty: &Type) -> Vec<Self> {
+        fn visit(ty: &Type, max_def_level: i16, out: &mut Vec<LeafColumn>) {
+            let max_def_level = Levels {
+                def: max_def_level,
+                ..Default::default()
+            }
+            .child(ty)
+            .def;
+            match ty {
+                Type::PrimitiveType { physical_type, .. } => out.push(LeafColumn::new(
+                    LeafValues::new(*physical_type),
+                    max_def_level,
+                )),
+                Type::GroupType { fields, .. } => {
+                    fields.iter().for_each(|f| visit(f, max_def_level, out))
+                }
+            }
+        }
+        let mut out = vec![];
+        visit(ty, 0, &mut out);
+        out
+    }
+
+    /// Returns the values of this column
+    pub fn values(&self) -> &LeafValues {
+        &self.values
+    }
+
+    /// Returns a mutable reference to the values of this column
+    pub fn values_mut(&mut self) -> &mut LeafValues {
+        &mut self.values
+    }
+
+    /// Appends the levels of a value, which should also be appended to
+    /// [`Self::values_mut`] if `def` is the maximum definition level
+    pub fn push_levels(&mut self, def: i16, rep: i16) {
+        self.def_levels.push(def);
+        self.rep_levels.push(rep);
+    }
+
+    /// Moves the values and levels of `other`, a column of the same leaf being
+    /// shredded, to the end of `self`
+    #[cfg(feature = "serde")]
+    pub(crate) fn append(&mut self, other: &mut Self) {
+        self.values.append(&mut other.values);
+        self.def_levels.append(&mut other.def_levels);
+        self.rep_levels.append(&mut other.rep_levels);
+    }
+
+    /// Returns the definition and repetition levels of the next value to read
+    pub fn peek(&self) -> Option<(i16, i16)> {
+        (self.level_offset < self.def_levels.len()).then(|| {
+            (
+                self.def_levels[self.level_offset],
+                self.rep_levels[self.level_offset],
+            )
+        })
+    }
+
+    /// Advances past the next value, returning its index within [`Self::values`]
+    /// if it is not null
+    pub fn advance(&mut self) -> Result<Option<usize>> {
+        let (def, _) = self
+            .peek()
+            .ok_or_else(|| general_err!("Unexpected end of column while assembling values"))?;
+        self.level_offset += 1;
+        Ok((def == self.max_def_level).then(|| {
+            self.value_offset += 1;
+            self.value_offset - 1
+        }))
+    }
+
+    /// Like [`Self::advance`] but returns an error if the value is null
+    pub fn advance_non_null(&mut self) -> Result<usize> {
+        self.advance()?
+            .ok_or_else(|| general_err!("Found null value for non-optional field"))
+    }
+
+    /// Reads the first `num_records` records of column `i` of `row_group_reader`
+    pub fn read(
+        row_group_reader: &dyn RowGroupReader,
+        i: usize,
+        num_records: usize,
+    ) -> Result<Self> {
+        let descr = row_group_reader.metadata().column(i).column_descr_ptr();
+        let mut def_levels = vec![];
+        let mut rep_levels = vec![];
+
+        macro_rules! read {
+            ($reader:ident, $variant:ident) => {{
+                let mut values = vec![];
+                let (_, _, levels) = $reader.read_records(
+                    num_records,
+                    Some(&mut def_levels),
+                    Some(&mut rep_levels),
+                    &mut values,
+                )?;
+                (LeafValues::$variant(values), levels)
+            }};
+        }
+
+        let (values, levels) = match row_group_reader.get_column_reader(i)? {
+            ColumnReader::BoolColumnReader(mut r) => read!(r, Boolean),
+            ColumnReader::Int32ColumnReader(mut r) => read!(r, Int32),
+            ColumnReader::Int64ColumnReader(mut r) => read!(r, Int64),
+            ColumnReader::Int96ColumnReader(mut r) => read!(r, Int96),
+            ColumnReader::FloatColumnReader(mut r) => read!(r, Float),
+            ColumnReader::DoubleColumnReader(mut r) => read!(r, Double),
+            ColumnReader::ByteArrayColumnReader(mut r) => read!(r, ByteArray),
+            ColumnReader::FixedLenByteArrayColumnReader(mut r) => read!(r, FixedLenByteArray),
+        };
+
+        // Levels are only decoded if the maximum level is non-zero
+        if descr.max_def_level() == 0 {
+            def_levels = vec![0; levels];
+        }
+        if descr.max_rep_level() == 0 {
+            rep_levels = vec![0; levels];
+        }
+        Ok(Self {
+            values,
+            def_levels,
+            rep_levels,
+            max_def_level: descr.max_def_level(),
+            level_offset: 0,
+            value_offset: 0,
+        })
+    }
+
+    /// Writes this column to `writer`
+    pub fn write(&self, writer: &mut ColumnWriter<'_>) -> Result<()> {
+        let levels = (
+            Some(self.def_levels.as_slice()),
+            Some(self.rep_levels.as_slice()),
+        );
+        match (&self.values, writer) {
+            (LeafValues::Boolean(v), ColumnWriter::BoolColumnWriter(w)) => {
+                w.write_batch(v, levels.0, levels.1)?
+            }
+            (LeafValues::Int32(v), ColumnWriter::Int32ColumnWriter(w)) => {
+                w.write_batch(v, levels.0, levels.1)?
+            }
+            (LeafValues::Int64(v), ColumnWriter::Int64ColumnWriter(w)) => {
+                w.write_batch(v, levels.0, levels.1)?
+            }
+            (LeafValues::Int96(v), ColumnWriter::Int96ColumnWriter(w)) => {
+                w.write_batch(v, levels.0, levels.1)?
+            }
+            (LeafValues::Float(v), ColumnWriter::FloatColumnWriter(w)) => {
+                w.write_batch(v, levels.0, levels.1)?
+            }
+            (LeafValues::Double(v), ColumnWriter::DoubleColumnWriter(w)) => {
+                w.write_batch(v, levels.0, levels.1)?
+            }
+            (LeafValues::ByteArray(v), ColumnWriter::ByteArrayColumnWriter(w)) => {
+                w.write_batch(v, levels.0, levels.1)?
+            }
+            (LeafValues::FixedLenByteArray(v), ColumnWriter::FixedLenByteArrayColumnWriter(w)) => {
+                w.write_batch(v, levels.0, levels.1)?
+            }
+            _ => {
+                return Err(general_err!(
+                    "Schema and struct disagree on type of leaf column"
+                ));
+            }
+        };
+        Ok(())
+    }
+}
+
+/// Writes `columns` to the next columns of `row_group_writer`
+pub fn write_columns<W: std::io::Write + Send>(
+    columns: &[LeafColumn],
+    row_group_writer: &mut SerializedRowGroupWriter<'_, W>,
+) -> Result<()> {
+    for column in columns {
+        let mut writer = row_group_writer
+            .next_column()?
+            .ok_or_else(|| general_err!("Failed to get next column"))?;
+        column.write(writer.untyped())?;
+        writer.close()?;
+    }
+    Ok(())
+}
+
+/// Returns the child of the group `node` called `name`, and the range of its
+/// leaf columns within those of `node`
+pub fn child<'a>(node: &'a Type, name: &str) -> Result<(&'a TypePtr, Range<usize>)> {
+    if !node.is_group() {
+        return Err(general_err!(
+            "Expected group for field '{}', found primitive",
+            node.name()
+        ));
+    }
+    let mut offset = 0;
+    for field in node.get_fields() {
+        let count = leaf_count(field);
+        if field.name() == name {
+            return Ok((field, offset..offset + count));
+        }
+        offset += count;
+    }
+    Err(general_err!(
+        "column name '{}' is not found in parquet file!",
+        name
+    ))
+}
+
+/// Reads the leaf columns of the top-level field `name` of `row_group_reader`
+pub fn read_field_columns(
+    row_group_reader: &dyn RowGroupReader,
+    name: &str,
+    num_records: usize,
+) -> Result<(TypePtr, Vec<LeafColumn>)> {
+    let schema = row_group_reader.metadata().schema_descr();
+    let (node, range) = child(schema.root_schema(), name)?;
+    let columns = range
+        .map(|i| LeafColumn::read(row_group_reader, i, num_records))
+        .collect::<Result<_>>()?;
+    Ok((node.clone(), columns))
+}
+
+/// Returns `ty` with its logical type replaced by `logical_type`
+pub fn with_logical_type(ty: Type, logical_type: Option<LogicalType>) -> Result<Type> {
+    match ty {
+        Type::PrimitiveType {
+            basic_info,
+            physical_type,
+            type_length,
+            scale,
+            precision,
+        } => {
+            let mut builder = Type::primitive_type_builder(basic_info.name(), physical_type)
+                .with_logical_type(logical_type)
+                .with_length(type_length)
+                .with_precision(precision)
+                .with_scale(scale);
+            if basic_info.has_repetition() {
+                builder = builder.with_repetition(basic_info.repetition());
+            }
+            builder.build()
+        }
+        Type::GroupType { basic_info, .. } => Err(general_err!(
+            "Cannot set the logical type of group '{}'",
+            basic_info.name()
+        )),
+    }
+}
+
+/// Returns the number of leaf columns of `ty`
+pub(crate) fn leaf_count(ty: &Type) -> usize {
+    match ty {
+        Type::PrimitiveType { .. } => 1,
+        Type::GroupType { fields, .. } => fields.iter().map(|f| leaf_count(f)).sum(),
+    }
+}
+
+/// Appends a null value to each of `columns`
+pub(crate) fn write_null(columns: &mut [LeafColumn], levels: Levels) {
+    for column in columns {
+        column.push_levels(levels.def, levels.rep);
+    }
+}
+
+/// Skips a null value in each of `columns`
+pub(crate) fn skip_null(columns: &mut [LeafColumn]) -> Result<()> {
+    for column in columns {
+        column.advance()?;
+    }
+    Ok(())
+}
+
+/// Returns the definition level of the next value of `columns`
+pub(crate) fn peek_def(columns: &[LeafColumn]) -> Result<i16> {
+    columns
+        .first()
+        .and_then(|c| c.peek())
+        .map(|(def, _)| def)
+        .ok_or_else(|| general_err!("Unexpected end of column while assembling values"))
+}
+
+/// Returns a three-level `LIST` called `name`, with the given `element` field
+pub(crate) fn list_type(name: &str, repetition: Repetition, element: Type) -> Result<Type> {
+    let list = Type::group_type_builder("list")
+        .with_repetition(Repetition::REPEATED)
+        .with_fields(vec![element.into()])
+        .build()?;
+    Type::group_type_builder(name)
+        .with_repetition(repetition)
+        .with_logical_type(Some(LogicalType::List))
+        .with_fields(vec![list.into()])
+        .build()
+}
+
+/// Returns the element of the list `node` at `levels`, along with the levels of its
+/// repeated field and of the element
+///
+/// Legacy two-level lists, whose repeated field is the element, are also supported
+pub fn list_element(node: &Type, levels: Levels) -> Result<(&Type, Levels, Levels)> {
+    let repeated = match node {
+        Type::GroupType { fields, .. } if fields.len() == 1 => &fields[0],
+        _ => return Err(general_err!("Invalid list type '{}'", node.name())),
+    };
+    let repeated_levels = levels.child(repeated);
+    match repeated.as_ref() {
+        Type::GroupType { fields, .. } if fields.len() == 1 && fields[0].name() != "array" => {
+            let element = &fields[0];
+            Ok((element, repeated_levels, repeated_levels.child(element)))
+        }
+        _ => Ok((repeated, repeated_levels, repeated_levels)),
+    }
+}
+
+/// Writes each of `values` with `f` as a repeated field
+fn write_repeated<T>(
+    values: impl IntoIterator<Item = T>,
+    columns: &mut [LeafColumn],
+    levels: Levels,
+    f: impl Fn(T, &mut [LeafColumn], Levels) -> Result<()>,
+) -> Result<()> {
+    let mut values = values.into_iter().peekable();
+    if values.peek().is_none() {
+        write_null(columns, levels);
+        return Ok(());
+    }
+    let depth = levels.depth + 1;
+    for (i, v) in values.enumerate() {
+        let rep = if i == 0 { levels.rep } else { depth };
+        let levels = Levels {
+            def: levels.def + 1,
+            rep,
+            depth,
+        };
+        f(v, columns, levels)?;
+    }
+    Ok(())
+}
+
+/// Reads a repeated field with `f`, where `levels` are those of the repeated field
+fn read_repeated<T>(
+    columns: &mut [LeafColumn],
+    levels: Levels,
+    f: impl Fn(&mut [LeafColumn]) -> Result<T>,
+) -> Result<Vec<T>> {
+    let def = peek_def(columns)?;
+    if def < levels.def - 1 {
+        return Err(general_err!("Found null value for non-optional field"));
+    }
+    let mut out = vec![];
+    if def < levels.def {
+        skip_null(columns)?;
+        return Ok(out);
+    }
+    loop {
+        out.push(f(columns)?);
+        match columns[0].peek() {
+            Some((_, rep)) if rep == levels.depth => continue,
+            _ => return Ok(out),
+        }
+    }
+}
+
+const UNIX_EPOCH: NaiveDate = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+
+macro_rules! primitive_field {
+    ($t:ty, $variant:ident, $physical:ident, $logical:expr, $to:expr, $from:expr) => {
+        impl ParquetField for $t {
+            fn parquet_type(name: &str, repetition: Repetition) -> Result<Type> {
+                Type::primitive_type_builder(name, PhysicalType::$physical)
+                    .with_repetition(repetition)
+                    .with_logical_type($logical)
+                    .build()
+            }
+
+            fn write(&self, columns: &mut [LeafColumn], levels: Levels) -> Result<()> {
+                let column = &mut columns[0];
+                match column.values_mut() {
+                    LeafValues::$variant(v) => v.push($to(self)),
+                    _ => return Err(general_err!("Schema and struct disagree on type")),
+                }
+                column.push_levels(levels.def, levels.rep);
+                Ok(())
+            }
+
+            fn read(_node: &Type, columns: &mut [LeafColumn], _levels: Levels) -> Result<Self> {
+                let column = &mut columns[0];
+                let idx = column.advance_non_null()?;
+                match column.values() {
+                    LeafValues::$variant(v) => $from(&v[idx]),
+                    _ => Err(general_err!("Schema and struct disagree on type")),
+                }
+            }
+        }
+    };
+}
+
+macro_rules! integer_field {
+    ($t:ty, $variant:ident, $physical:ident, $native:ty, $bit_width:expr, $signed:expr) => {
+        primitive_field!(
+            $t,
+            $variant,
+            $physical,
+            ($bit_width < 32 || !$signed).then_some(LogicalType::Integer {
+                bit_width: $bit_width,
+                is_signed: $signed,
+            }),
+            |v: &$t| *v as $native,
+            |v: &$native| Ok(*v as $t)
+        );
+    };
+}
+
+primitive_field!(bool, Boolean, BOOLEAN, None, |v: &bool| *v, |v: &bool| Ok(
+    *v
+));
+integer_field!(i8, Int32, INT32, i32, 8, true);
+integer_field!(i16, Int32, INT32, i32, 16, true);
+integer_field!(i32, Int32, INT32, i32, 32, true);
+integer_field!(i64, Int64, INT64, i64, 64, true);
+integer_field!(u16, Int32, INT32, i32, 16, false);
+integer_field!(u32, Int32, INT32, i32, 32, false);
+integer_field!(u64, Int64, INT64, i64, 64, false);
+#[cfg(target_pointer_width = "64")]
+integer_field!(usize, Int64, INT64, i64, 64, false);
+#[cfg(target_pointer_width = "64")]
+integer_field!(isize, Int64, INT64, i64, 64, true);
+#[cfg(not(target_pointer_width = "64"))]
+integer_field!(usize, Int32, INT32, i32, 32, false);
+#[cfg(not(target_pointer_width = "64"))]
+integer_field!(isize, Int32, INT32, i32, 32, true);
+primitive_field!(f32, Float, FLOAT, None, |v: &f32| *v, |v: &f32| Ok(*v));
+primitive_field!(f64, Double, DOUBLE, None, |v: &f64| *v, |v: &f64| Ok(*v));
+primitive_field!(
+    String,
+    ByteArray,
+    BYTE_ARRAY,
+    Some(LogicalType::String),
+    |v: &String| ByteArray::from(v.as_str()),
+    |v: &ByteArray| v.as_utf8().map(str::to_string)
+);
+primitive_field!(
+    NaiveDate,
+    Int32,
+    INT32,
+    Some(LogicalType::Date),
+    |v: &NaiveDate| v.signed_duration_since(UNIX_EPOCH).num_days() as i32,
+    |v: &i32| UNIX_EPOCH
+        .checked_add_signed(chrono::Duration::days(*v as i64))
+        .ok_or_else(|| general_err!("Date {} out of range", v))
+);
+primitive_field!(
+    NaiveDateTime,
+    Int64,
+    INT64,
+    Some(LogicalType::Timestamp {
+        is_adjusted_to_u_t_c: false,
+        unit: TimeUnit::MILLIS,
+    }),
+    |v: &NaiveDateTime| v.and_utc().timestamp_millis(),
+    |v: &i64| chrono::DateTime::from_timestamp_millis(*v)
+        .map(|v| v.naive_utc())
+        .ok_or_else(|| general_err!("Timestamp {} out of range", v))
+);
+
+// `u8` is written as an integer, but `Vec<u8>` as a BYTE_ARRAY
+impl ParquetField for u8 {
+    fn parquet_type(name: &str, repetition: Repetition) -> Result<Type> {
+        Type::primitive_type_builder(name, PhysicalType::INT32)
+            .with_repetition(repetition)
+            .with_logical_type(Some(LogicalType::Integer {
+                bit_width: 8,
+                is_signed: false,
+            }))
+            .build()
+    }
+
+    fn write(&self, columns: &mut [LeafColumn], levels: Levels) -> Result<()> {
+        (*self as u16).write(columns, levels)
+    }
+
+    fn read(node: &Type, columns: &mut [LeafColumn], levels: Levels) -> Result<Self> {
+        let v = i32::read(node, columns, levels)?;
+        u8::try_from(v).map_err(|_| general_err!("Value {} out of range for u8", v))
+    }
+
+    fn vec_parquet_type(name: &str, repetition: Repetition) -> Result<Type> {
+        Type::primitive_type_builder(name, PhysicalType::BYTE_ARRAY)
+            .with_repetition(repetition)
+            .build()
+    }
+
+    fn write_vec(values: &[Self], columns: &mut [LeafColumn], levels: Levels) -> Result<()> {
+        let column = &mut columns[0];
+        match column.values_mut() {
+            LeafValues::ByteArray(v) => v.push(ByteArray::from(values.to_vec())),
+            _ => return Err(general_err!("Schema and struct disagree on type")),
+        }
+        column.push_levels(levels.def, levels.rep);
+        Ok(())
+    }
+
+    fn read_vec(_node: &Type, columns: &mut [LeafColumn], _levels: Levels) -> Result<Vec<Self>> {
+        let column = &mut columns[0];
+        let idx = column.advance_non_null()?;
+        match column.values() {
+            LeafValues::ByteArray(v) => Ok(v[idx].data().to_vec()),
+            _ => Err(general_err!("Schema and struct disagree on type")),
+        }
+    }
+}
+
+impl<const N: usize> ParquetField for [u8; N] {
+    fn parquet_type(name: &str, repetition: Repetition) -> Result<Type> {
+        Type::primitive_type_builder(name, PhysicalType::FIXED_LEN_BYTE_ARRAY)
+            .with_repetition(repetition)
+            .with_length(N as i32)
+            .build()
+    }
+
+    fn write(&self, columns: &mut [LeafColumn], levels: Levels) -> Result<()> {
+        let column = &mut columns[0];
+        match column.values_mut() {
+            LeafValues::FixedLenByteArray(v) => v.push(ByteArray::from(self.to_vec()).into()),
+            _ => return Err(general_err!("Schema and struct disagree on type")),
+        }
+        column.push_levels(levels.def, levels.rep);
+        Ok(())
+    }
+
+    fn read(_node: &Type, columns: &mut [LeafColumn], _levels: Levels) -> Result<Self> {
+        let column = &mut columns[0];
+        let idx = column.advance_non_null()?;
+        match column.values() {
+            LeafValues::FixedLenByteArray(v) => v[idx]
+                .data()
+                .try_into()
+                .map_err(|_| general_err!("Expected {N} bytes, got {}", v[idx].len())),
+            _ => Err(general_err!("Schema and struct disagree on type")),
+        }
+    }
+}
+
+impl<T: ParquetField> ParquetField for Option<T> {
+    fn leaf_count() -> usize {
+        T::leaf_count()
+    }
+
+    fn parquet_type(name: &str, _repetition: Repetition) -> Result<Type> {
+        T::parquet_type(name, Repetition::OPTIONAL)
+    }
+
+    fn write(&self, columns: &mut [LeafColumn], levels: Levels) -> Result<()> {
+        match self {
+            Some(v) => v.write(
+                columns,
+                Levels {
+                    def: levels.def + 1,
+                    ..levels
+                },
+            ),
+            None => {
+                write_null(columns, levels);
+                Ok(())
+            }
+        }
+    }
+
+    fn read(node: &Type, columns: &mut [LeafColumn], levels: Levels) -> Result<Self> {
+        // A required column is never null
+        if node.is_optional() && peek_def(columns)? < levels.def {
+            skip_null(columns)?;
+            return Ok(None);
+        }
+        T::read(node, columns, levels).map(Some)
+    }
+}
+
+impl<T: ParquetField> ParquetField for Vec<T> {
+    fn leaf_count() -> usize {
+        T::leaf_count()
+    }
+
+    fn parquet_type(name: &str, repetition: Repetition) -> Result<Type> {
+        T::vec_parquet_type(name, repetition)
+    }
+
+    fn write(&self, columns: &mut [LeafColumn], levels: Levels) -> Result<()> {
+        T::write_vec(self, columns, levels)
+    }
+
+    fn read(node: &Type, columns: &mut [LeafColumn], levels: Levels) -> Result<Self> {
+        T::read_vec(node, columns, levels)
+    }
+}
+
+/// Returns a `MAP` called `name`, with the given `key` and `value` fields
+pub(crate) fn map_type(name: &str, repetition: Repetition, key: Type, value: Type) -> Result<Type> {
+    let key_value = Type::group_type_builder("key_value")
+        .with_repetition(Repetition::REPEATED)
+        .with_fields(vec![key.into(), value.into()])
+        .build()?;
+    Type::group_type_builder(name)
+        .with_repetition(repetition)
+        .with_logical_type(Some(LogicalType::Map))
+        .with_fields(vec![key_value.into()])
+        .build()
+}
+
+fn write_map<'a, K: ParquetField + 'a, V: ParquetField + 'a>(
+    entries: impl IntoIterator<Item = (&'a K, &'a V)>,
+    columns: &mut [LeafColumn],
+    levels: Levels,
+) -> Result<()> {
+    write_repeated(entries, columns, levels, |(k, v), columns, levels| {
+        let (keys, values) = columns.split_at_mut(K::leaf_count());
+        k.write(keys, levels)?;
+        v.write(values, levels)
+    })
+}
+
+fn read_map<K: ParquetField, V: ParquetField>(
+    node: &Type,
+    columns: &mut [LeafColumn],
+    levels: Levels,
+) -> Result<Vec<(K, V)>> {
+    let key_value = match node {
+        Type::GroupType { fields, .. } if fields.len() == 1 => &fields[0],
+        _ => return Err(general_err!("Invalid map type '{}'", node.name())),
+    };
+    let (key, value) = match key_value.as_ref() {
+        Type::GroupType { fields, .. } if fields.len() == 2 => (&fields[0], &fields[1]),
+        _ => return Err(general_err!("Invalid map type '{}'", node.name())),
+    };
+    let levels = levels.child(key_value);
+    let key_count = leaf_count(key);
+    read_repeated(columns, levels, |columns| {
+        let (keys, values) = columns.split_at_mut(key_count);
+        let k = K::read(key, keys, levels.child(key))?;
+        let v = V::read(value, values, levels.child(value))?;
+        Ok((k, v))
+    })
+}
+
+impl<K: ParquetField + Eq + Hash, V: ParquetField, S: std::hash::BuildHasher + Default> ParquetField
+    for HashMap<K, V, S>
+{
+    fn leaf_count() -> usize {
+        K::leaf_count() + V::leaf_count()
+    }
+
+    fn parquet_type(name: &str, repetition: Repetition) -> Result<Type> {
+        map_type(
+            name,
+            repetition,
+            K::parquet_type("key", Repetition::REQUIRED)?,
+            V::parquet_type("value", Repetition::REQUIRED)?,
+        )
+    }
+
+    fn write(&self, columns: &mut [LeafColumn], levels: Levels) -> Result<()> {
+        write_map(self, columns, levels)
+    }
+
+    fn read(node: &Type, columns: &mut [LeafColumn], levels: Levels) -> Result<Self> {
+        Ok(read_map(node, columns, levels)?.into_iter().collect())
+    }
+}
+
+impl<K: ParquetField + Ord, V: ParquetField> ParquetField for BTreeMap<K, V> {
+    fn leaf_count() -> usize {
+        K::leaf_count() + V::leaf_count()
+    }
+
+    fn parquet_type(name: &str, repetition: Repetition) -> Result<Type> {
+        map_type(
+            name,
+            repetition,
+            K::parquet_type("key", Repetition::REQUIRED)?,
+            V::parquet_type("value", Repetition::REQUIRED)?,
+        )
+    }
+
+    fn write(&self, columns: &mut [LeafColumn], levels: Levels) -> Result<()> {
+        write_map(self, columns, levels)
+    }
+
+    fn read(node: &Type, columns: &mut [LeafColumn], levels: Levels) -> Result<Self> {
+        Ok(read_map(node, columns, levels)?.into_iter().collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schema::parser::parse_message_type;
+    use crate::schema::printer::print_schema;
+
+    fn roundtrip<T: ParquetField + PartialEq + std::fmt::Debug>(values: &[T]) {
+        let ty = T::parquet_type("f", Repetition::REQUIRED).unwrap();
+        let mut columns = LeafColumn::for_type(&ty);
+        assert_eq!(columns.len(), T::leaf_count());
+        for v in values {
+            v.write(&mut columns, Levels::default()).unwrap();
+        }
+
+        let levels = Levels::default().child(&ty);
+        let mut read = Vec::with_capacity(values.len());
+        for _ in values {
+            read.push(T::read(&ty, &mut columns, levels).unwrap());
+        }
+        assert_eq!(read, values);
+        assert!(columns.iter().all(|c| c.peek().is_none()));
+    }
+
+    #[test]
+    fn test_roundtrip_primitives() {
+        roundtrip(&[1_i8, -2]);
+        roundtrip(&[0_u8, u8::MAX]);
+        roundtrip(&[u64::MAX, 0]);
+        roundtrip(&[Some("a".to_string()), None]);
+        roundtrip(&[vec![1_u8, 2], vec![]]);
+        roundtrip(&[[1_u8, 2, 3]]);
+        roundtrip(&[NaiveDate::from_ymd_opt(2024, 2, 29).unwrap()]);
+    }
+
+    #[test]
+    fn test_read_u8_out_of_range() {
+        let ty = u8::parquet_type("f", Repetition::REQUIRED).unwrap();
+        for v in [256_i32, -1] {
+            let mut columns = LeafColumn::for_type(&ty);
+            v.write(&mut columns, Levels::default()).unwrap();
+            let err = u8::read(&ty, &mut columns, Levels::default()).unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                format!("Parquet error: Value {v} out of range for u8")
+            );
+        }
+    }
+
+    #[test]
+    fn test_roundtrip_lists() {
+        roundtrip(&[vec![1_i32, 2, 3], vec![], vec![4]]);
+        roundtrip(&[Some(vec![Some(1_i32), None]), None, Some(vec![])]);
+        roundtrip(&[vec![vec![1_i64], vec![], vec![2, 3]], vec![], vec![vec![]]]);
+        roundtrip(&[vec![b"ab".to_vec(), vec![]], vec![]]);
+    }
+
+    #[test]
+    fn test_roundtrip_maps() {
+        let a = BTreeMap::from([("a".to_string(), vec![1_i32]), ("b".to_string(), vec![])]);
+        roundtrip(&[a, BTreeMap::new()]);
+
+        let a = HashMap::from([(1_i32, Some(2.5_f64)), (2, None)]);
+        roundtrip(&[Some(a), None]);
+    }
+
+    #[test]
+    fn test_read_legacy_list() {
+        let ty = parse_message_type(
+            "message m {
+                REQUIRED group l (LIST) {
+                    REPEATED INT32 element;
+                }
+            }",
+        )
+        .unwrap();
+        let ty = &ty.get_fields()[0];
+        let values = vec![vec![1, 2], vec![], vec![3]];
+
+        let mut columns = LeafColumn::for_type(ty);
+        for v in &values {
+            v.write(&mut columns, Levels::default()).unwrap();
+        }
+        let read: Vec<Vec<i32>> = values
+            .iter()
+            .map(|_| Vec::read(ty, &mut columns, Levels::default()).unwrap())
+            .collect();
+        assert_eq!(read, values);
+    }
+
+    #[test]
+    fn test_child() {
+        let ty = parse_message_type(
+            "message m {
+                REQUIRED INT32 a;
+                REQUIRED group b {
+                    REQUIRED INT32 c;
+                    REQUIRED INT32 d;
+                }
+                REQUIRED INT64 e;
+            }",
+        )
+        .unwrap();
+        let (field, range) = child(&ty, "e").unwrap();
+        assert_eq!(field.name(), "e");
+        assert_eq!(range, 3..4);
+
+        let err = child(&ty, "f").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: column name 'f' is not found in parquet file!"
+        );
+    }
+
+    #[test]
+    fn test_schema() {
+        let ty =
+            <Option<HashMap<String, Vec<i32>>>>::parquet_type("m", Repetition::REQUIRED).unwrap();
+        let mut out = vec![];
+        print_schema(&mut out, &ty);
+        assert_eq!(
+            String::from_utf8(out).unwrap().trim(),
+            "OPTIONAL group m (MAP) {
+  REPEATED group key_value {
+    REQUIRED BYTE_ARRAY key (STRING);
+    REQUIRED group value (LIST) {
+      REPEATED group list {
+        REQUIRED INT32 element;
+      }
+    }
+  }
+}"
+        );
+    }
+
+    #[test]
+    fn test_levels() {
+        let ty = <Vec<Option<i32>>>::parquet_type("l", Repetition::REQUIRED).unwrap();
+        let mut columns = LeafColumn::for_type(&ty);
+        vec![Some(1), None, Some(2)]
+            .write(&mut columns, Levels::default())
+            .unwrap();
+        Vec::<Option<i32>>::new()
+            .write(&mut columns, Levels::default())
+            .unwrap();
+        assert_eq!(columns[0].def_levels, vec![2, 1, 2, 0]);
+        assert_eq!(columns[0].rep_levels, vec![0, 1, 1, 0]);
+        assert_eq!(columns[0].values, LeafValues::Int32(vec![1, 2]));
+        assert_eq!(columns[0].max_def_level, 2);
+    }
+}
@@ -0,0 +1,366 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Assembly of values implementing [`Deserialize`] from [`LeafColumn`]
+//!
+//! [`Deserialize`]: ::serde::Deserialize
+
+use std::ops::Range;
+
+use ::serde::de::{self, DeserializeSeed, IgnoredAny, IntoDeserializer, Visitor};
+use ::serde::forward_to_deserialize_any;
+
+use super::ser::{field_ranges, is_optional, is_unsigned};
+use crate::basic::{ConvertedType, LogicalType, Repetition};
+use crate::errors::{ParquetError, Result};
+use crate::record::parquet_field::{LeafValues, leaf_count, list_element, peek_def, skip_null};
+use crate::record::{LeafColumn, Levels};
+use crate::schema::types::Type;
+
+/// A value read from a primitive column
+enum Scalar {
+    Bool(bool),
+    I32(i32),
+    U32(u32),
+    I64(i64),
+    U64(u64),
+    F32(f32),
+    F64(f64),
+    String(String),
+    Bytes(Vec<u8>),
+}
+
+fn is_string(node: &Type) -> bool {
+    let info = node.get_basic_info();
+    matches!(
+        info.logical_type_ref(),
+        Some(&(LogicalType::String | LogicalType::Enum | LogicalType::Json))
+    ) || matches!(
+        info.converted_type(),
+        ConvertedType::UTF8 | ConvertedType::ENUM | ConvertedType::JSON
+    )
+}
+
+fn is_list(node: &Type) -> bool {
+    match node.get_basic_info().logical_type_ref() {
+        Some(LogicalType::List) => true,
+        // Unannotated repeated groups, as written by older writers
+        _ => matches!(
+            node.get_fields(),
+            [f] if f.get_basic_info().has_repetition()
+                && f.get_basic_info().repetition() == Repetition::REPEATED
+                && node.get_basic_info().logical_type_ref() != Some(&LogicalType::Map)
+                && !f.is_group()
+        ),
+    }
+}
+
+/// A [`de::Deserializer`] reading a value of `node` from its leaf `columns`
+///
+/// `levels.def` is the definition level of the value if it is present, i.e.
+/// including the repetition of `node`, see [`Levels::child`]
+pub(super) struct ValueDeserializer<'a> {
+    pub(super) node: &'a Type,
+    pub(super) columns: &'a mut [LeafColumn],
+    pub(super) levels: Levels,
+}
+
+impl ValueDeserializer<'_> {
+    fn is_null(&self) -> Result<bool> {
+        Ok(is_optional(self.node) && peek_def(self.columns)? < self.levels.def)
+    }
+
+    fn read_scalar(&mut self) -> Result<Scalar> {
+        let unsigned = is_unsigned(self.node);
+        let string = is_string(self.node);
+        let column = &mut self.columns[0];
+        let idx = column.advance_non_null().map_err(|_| {
+            general_err!(
+                "Found null value for non-optional field '{}'",
+                self.node.name()
+            )
+        })?;
+        Ok(match column.values() {
+            LeafValues::Boolean(v) => Scalar::Bool(v[idx]),
+            LeafValues::Int32(v) if unsigned => Scalar::U32(v[idx] as u32),
+            LeafValues::Int32(v) => Scalar::I32(v[idx]),
+            LeafValues::Int64(v) if unsigned => Scalar::U64(v[idx] as u64),
+            LeafValues::Int64(v) => Scalar::I64(v[idx]),
+            LeafValues::Int96(v) => Scalar::I64(v[idx].to_nanos()),
+            LeafValues::Float(v) => Scalar::F32(v[idx]),
+            LeafValues::Double(v) => Scalar::F64(v[idx]),
+            LeafValues::ByteArray(v) if string => Scalar::String(v[idx].as_utf8()?.to_string()),
+            LeafValues::ByteArray(v) => Scalar::Bytes(v[idx].data().to_vec()),
+            LeafValues::FixedLenByteArray(v) => Scalar::Bytes(v[idx].data().to_vec()),
+        })
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ValueDeserializer<'_> {
+    type Error = ParquetError;
+
+    fn deserialize_any<V: Visitor<'de>>(mut self, visitor: V) -> Result<V::Value> {
+        if self.is_null()? {
+            skip_null(self.columns)?;
+            return visitor.visit_none();
+        }
+        if !self.node.is_group() {
+            return match self.read_scalar()? {
+                Scalar::Bool(v) => visitor.visit_bool(v),
+                Scalar::I32(v) => visitor.visit_i32(v),
+                Scalar::U32(v) => visitor.visit_u32(v),
+                Scalar::I64(v) => visitor.visit_i64(v),
+                Scalar::U64(v) => visitor.visit_u64(v),
+                Scalar::F32(v) => visitor.visit_f32(v),
+                Scalar::F64(v) => visitor.visit_f64(v),
+                Scalar::String(v) => visitor.visit_string(v),
+                Scalar::Bytes(v) => visitor.visit_byte_buf(v),
+            };
+        }
+        if is_list(self.node) {
+            let mut seq = SeqDeserializer::new(self)?;
+            let value = visitor.visit_seq(&mut seq)?;
+            while de::SeqAccess::next_element::<IgnoredAny>(&mut seq)?.is_some() {}
+            return Ok(value);
+        }
+        if self.node.get_basic_info().logical_type_ref() == Some(&LogicalType::Map) {
+            let mut map = MapDeserializer::new(self)?;
+            let value = visitor.visit_map(&mut map)?;
+            while de::MapAccess::next_entry::<IgnoredAny, IgnoredAny>(&mut map)?.is_some() {}
+            return Ok(value);
+        }
+        let mut fields = StructDeserializer::new(self);
+        let value = visitor.visit_map(&mut fields)?;
+        while de::MapAccess::next_entry::<IgnoredAny, IgnoredAny>(&mut fields)?.is_some() {}
+        Ok(value)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value> {
+        if self.is_null()? {
+            skip_null(self.columns)?;
+            return visitor.visit_none();
+        }
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        mut self,
+        name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value> {
+        if self.node.is_group() {
+            return Err(general_err!(
+                "Cannot read enum {} from group '{}'",
+                name,
+                self.node.name()
+            ));
+        }
+        match self.read_scalar()? {
+            Scalar::String(v) => visitor.visit_enum(v.into_deserializer()),
+            Scalar::I32(v) => visitor.visit_enum((v as u32).into_deserializer()),
+            Scalar::U32(v) => visitor.visit_enum(v.into_deserializer()),
+            _ => Err(general_err!(
+                "Cannot read enum {} from field '{}'",
+                name,
+                self.node.name()
+            )),
+        }
+    }
+
+    forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier
+        ignored_any
+    }
+}
+
+/// The state of reading a repeated field
+struct Repeated {
+    /// The levels of the repeated field
+    levels: Levels,
+    /// If the next entry is the first
+    first: bool,
+    done: bool,
+}
+
+impl Repeated {
+    fn new(columns: &mut [LeafColumn], levels: Levels) -> Result<Self> {
+        // An empty list or map has a definition level less than its repeated field
+        let done = peek_def(columns)? < levels.def;
+        if done {
+            skip_null(columns)?;
+        }
+        Ok(Self {
+            levels,
+            first: true,
+            done,
+        })
+    }
+
+    /// Returns true if `columns` contain another entry of this repeated field
+    fn has_next(&mut self, columns: &[LeafColumn]) -> bool {
+        if self.done {
+            return false;
+        }
+        if !std::mem::take(&mut self.first) {
+            self.done = !matches!(columns[0].peek(), Some((_, rep)) if rep == self.levels.depth);
+        }
+        !self.done
+    }
+}
+
+/// Reads the elements of a `LIST`
+struct SeqDeserializer<'a> {
+    element: &'a Type,
+    columns: &'a mut [LeafColumn],
+    element_levels: Levels,
+    repeated: Repeated,
+}
+
+impl<'a> SeqDeserializer<'a> {
+    fn new(d: ValueDeserializer<'a>) -> Result<Self> {
+        let (element, repeated_levels, element_levels) = list_element(d.node, d.levels)?;
+        Ok(Self {
+            repeated: Repeated::new(d.columns, repeated_levels)?,
+            element,
+            columns: d.columns,
+            element_levels,
+        })
+    }
+}
+
+impl<'de> de::SeqAccess<'de> for SeqDeserializer<'_> {
+    type Error = ParquetError;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(&mut self, seed: T) -> Result<Option<T::Value>> {
+        if !self.repeated.has_next(self.columns) {
+            return Ok(None);
+        }
+        seed.deserialize(ValueDeserializer {
+            node: self.element,
+            columns: self.columns,
+            levels: self.element_levels,
+        })
+        .map(Some)
+    }
+}
+
+/// Reads the entries of a `MAP`
+struct MapDeserializer<'a> {
+    key: &'a Type,
+    value: &'a Type,
+    key_count: usize,
+    columns: &'a mut [LeafColumn],
+    repeated: Repeated,
+}
+
+impl<'a> MapDeserializer<'a> {
+    fn new(d: ValueDeserializer<'a>) -> Result<Self> {
+        let key_value = match d.node.get_fields() {
+            [key_value] if key_value.is_group() && key_value.get_fields().len() == 2 => key_value,
+            _ => return Err(general_err!("Invalid map type '{}'", d.node.name())),
+        };
+        let (key, value) = (&key_value.get_fields()[0], &key_value.get_fields()[1]);
+        Ok(Self {
+            repeated: Repeated::new(d.columns, d.levels.child(key_value))?,
+            key,
+            value,
+            key_count: leaf_count(key),
+            columns: d.columns,
+        })
+    }
+}
+
+impl<'de> de::MapAccess<'de> for MapDeserializer<'_> {
+    type Error = ParquetError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        if !self.repeated.has_next(self.columns) {
+            return Ok(None);
+        }
+        seed.deserialize(ValueDeserializer {
+            node: self.key,
+            columns: &mut self.columns[..self.key_count],
+            levels: self.repeated.levels.child(self.key),
+        })
+        .map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        seed.deserialize(ValueDeserializer {
+            node: self.value,
+            columns: &mut self.columns[self.key_count..],
+            levels: self.repeated.levels.child(self.value),
+        })
+    }
+}
+
+/// Reads the fields of a group as a map from field name to value
+struct StructDeserializer<'a> {
+    node: &'a Type,
+    columns: &'a mut [LeafColumn],
+    levels: Levels,
+    ranges: Vec<Range<usize>>,
+    next: usize,
+}
+
+impl<'a> StructDeserializer<'a> {
+    fn new(d: ValueDeserializer<'a>) -> Self {
+        Self {
+            ranges: field_ranges(d.node),
+            node: d.node,
+            columns: d.columns,
+            levels: d.levels,
+            next: 0,
+        }
+    }
+}
+
+impl<'de> de::MapAccess<'de> for StructDeserializer<'_> {
+    type Error = ParquetError;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(&mut self, seed: K) -> Result<Option<K::Value>> {
+        match self.node.get_fields().get(self.next) {
+            Some(field) => seed.deserialize(field.name().into_deserializer()).map(Some),
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(&mut self, seed: V) -> Result<V::Value> {
+        let field = &self.node.get_fields()[self.next];
+        let range = self.ranges[self.next].clone();
+        self.next += 1;
+        seed.deserialize(ValueDeserializer {
+            node: field,
+            columns: &mut self.columns[range],
+            levels: self.levels.child(field),
+        })
+    }
+
+    fn size_hint(&self) -> Option<usize> {
+        Some(self.ranges.len() - self.next)
+    }
+}
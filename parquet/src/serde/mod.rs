@@ -0,0 +1,516 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Reading and writing types implementing [`Serialize`] and [`Deserialize`]
+//!
+//! Each record is a row of the file, and must serialize as a struct. The
+//! schema is either inferred from the records with [`infer_schema`], or
+//! provided to [`SerdeWriter::try_new`]. Inference maps types as follows:
+//!
+//! | Rust / serde type                  | Parquet type                                  |
+//! |------------------------------------|-----------------------------------------------|
+//! | `bool`                             | `BOOLEAN`                                     |
+//! | `i8`, `i16`, `i32`                 | `INT32` (`INTEGER(n, true)` for `i8`, `i16`)  |
+//! | `u8`, `u16`, `u32`                 | `INT32` with `INTEGER(n, false)`              |
+//! | `i64`, `u64`                       | `INT64` (`INTEGER(64, false)` for `u64`)      |
+//! | `f32`, `f64`                       | `FLOAT`, `DOUBLE`                             |
+//! | `char`, `String`                   | `BYTE_ARRAY` with `STRING`                    |
+//! | bytes (e.g. `serde_bytes`)         | `BYTE_ARRAY`                                  |
+//! | unit enum variants                 | `BYTE_ARRAY` with `ENUM`                      |
+//! | `Option<T>`                        | `OPTIONAL` field of `T`                       |
+//! | sequences, tuples                  | 3-level `LIST`                                |
+//! | maps                               | `MAP` with a required key                     |
+//! | structs                            | group                                         |
+//!
+//! Fields missing from some records, for example due to
+//! `#[serde(skip_serializing_if = "Option::is_none")]`, are inferred as
+//! optional. With an explicit schema, a serde map may also be written to a
+//! group, in which case its keys are the field names, and unit enum variants
+//! written to an `INT32` or `INT64` column are written as their index.
+//!
+//! Values are assembled on read from the file schema, so any file whose
+//! schema matches the structure of the type can be read, including those
+//! written by other implementations.
+//!
+//! ```
+//! # use serde::{Deserialize, Serialize};
+//! # use std::collections::HashMap;
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Record {
+//!     id: i64,
+//!     name: Option<String>,
+//!     tags: Vec<String>,
+//!     scores: HashMap<String, f64>,
+//! }
+//!
+//! let records = vec![Record {
+//!     id: 1,
+//!     name: Some("a".to_string()),
+//!     tags: vec!["x".to_string()],
+//!     scores: HashMap::from([("b".to_string(), 1.5)]),
+//! }];
+//!
+//! let mut file = tempfile::tempfile().unwrap();
+//! parquet::serde::to_writer(file.try_clone().unwrap(), &records).unwrap();
+//!
+//! let read: Vec<Record> = parquet::serde::from_reader(file).unwrap();
+//! assert_eq!(read, records);
+//! ```
+//!
+//! [`Serialize`]: ::serde::Serialize
+//! [`Deserialize`]: ::serde::Deserialize
+
+use std::fmt::Display;
+use std::io::Write;
+use std::sync::Arc;
+
+use ::serde::Serialize;
+use ::serde::de::DeserializeOwned;
+
+use crate::errors::{ParquetError, Result};
+use crate::file::metadata::ParquetMetaData;
+use crate::file::properties::{WriterProperties, WriterPropertiesPtr};
+use crate::file::reader::{ChunkReader, FileReader, RowGroupReader, SerializedFileReader};
+use crate::file::writer::SerializedFileWriter;
+use crate::record::parquet_field::write_columns;
+use crate::record::{LeafColumn, Levels};
+use crate::schema::types::TypePtr;
+
+mod de;
+mod schema;
+mod ser;
+
+pub use schema::infer_schema;
+
+impl ::serde::ser::Error for ParquetError {
+    fn custom<T: Display>(msg: T) -> Self {
+        ParquetError::General(msg.to_string())
+    }
+}
+
+impl ::serde::de::Error for ParquetError {
+    fn custom<T: Display>(msg: T) -> Self {
+        ParquetError::General(msg.to_string())
+    }
+}
+
+/// Writes records implementing [`Serialize`] to a parquet file
+///
+/// Records are shredded into buffered columns across calls to [`Self::write`],
+/// and written as a row group once [`WriterProperties::max_row_group_row_count`]
+/// rows are buffered, or on [`Self::flush`] or [`Self::close`]
+pub struct SerdeWriter<W: Write + Send> {
+    writer: SerializedFileWriter<W>,
+    schema: TypePtr,
+    props: WriterPropertiesPtr,
+    /// The columns of the buffered records
+    columns: Vec<LeafColumn>,
+    /// The number of buffered records
+    buffered_rows: usize,
+    /// The columns of the record being serialized, which are appended to
+    /// `columns` once it has been serialized successfully
+    scratch: Vec<LeafColumn>,
+}
+
+impl<W: Write + Send> std::fmt::Debug for SerdeWriter<W> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SerdeWriter")
+            .field("schema", &self.schema)
+            .field("buffered_rows", &self.buffered_rows)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: Write + Send> SerdeWriter<W> {
+    /// Creates a writer of records matching `schema` to `writer`
+    pub fn try_new(writer: W, schema: TypePtr, props: WriterPropertiesPtr) -> Result<Self> {
+        Ok(Self {
+            writer: SerializedFileWriter::new(writer, schema.clone(), props.clone())?,
+            columns: LeafColumn::for_type(&schema),
+            buffered_rows: 0,
+            scratch: LeafColumn::for_type(&schema),
+            schema,
+            props,
+        })
+    }
+
+    /// Buffers `records`, writing a row group each time
+    /// [`WriterProperties::max_row_group_row_count`] rows are buffered
+    ///
+    /// If a record fails to serialize, the records before it are buffered,
+    /// and it and those after it are not
+    pub fn write<T: Serialize>(&mut self, records: &[T]) -> Result<()> {
+        let max_rows = self.props.max_row_group_row_count().unwrap_or(usize::MAX);
+        for record in records {
+            let result = record.serialize(ser::ValueSerializer {
+                node: &self.schema,
+                columns: &mut self.scratch,
+                levels: Levels::default(),
+            });
+            if let Err(e) = result {
+                self.scratch = LeafColumn::for_type(&self.schema);
+                return Err(e);
+            }
+            for (column, scratch) in self.columns.iter_mut().zip(&mut self.scratch) {
+                column.append(scratch);
+            }
+            self.buffered_rows += 1;
+            if self.buffered_rows >= max_rows {
+                self.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the number of buffered records not yet written to a row group
+    pub fn in_progress_rows(&self) -> usize {
+        self.buffered_rows
+    }
+
+    /// Writes the buffered records, if any, to a new row group
+    pub fn flush(&mut self) -> Result<()> {
+        if self.buffered_rows == 0 {
+            return Ok(());
+        }
+        let columns = std::mem::replace(&mut self.columns, LeafColumn::for_type(&self.schema));
+        self.buffered_rows = 0;
+        let mut row_group = self.writer.next_row_group()?;
+        write_columns(&columns, &mut row_group)?;
+        row_group.close()?;
+        Ok(())
+    }
+
+    /// Writes the buffered records and the file footer, returning the
+    /// metadata of the file
+    pub fn close(mut self) -> Result<ParquetMetaData> {
+        self.flush()?;
+        self.writer.close()
+    }
+}
+
+/// Writes `records` to `writer` with a schema inferred by [`infer_schema`]
+pub fn to_writer<W: Write + Send, T: Serialize>(
+    writer: W,
+    records: &[T],
+) -> Result<ParquetMetaData> {
+    let schema = Arc::new(infer_schema(records)?);
+    let mut writer = SerdeWriter::try_new(writer, schema, Arc::new(WriterProperties::default()))?;
+    writer.write(records)?;
+    writer.close()
+}
+
+/// Reads all records of `row_group`
+pub fn from_row_group<T: DeserializeOwned>(row_group: &dyn RowGroupReader) -> Result<Vec<T>> {
+    let metadata = row_group.metadata();
+    let num_rows = usize::try_from(metadata.num_rows())?;
+    let schema = metadata.schema_descr().root_schema();
+    let mut columns = (0..metadata.num_columns())
+        .map(|i| LeafColumn::read(row_group, i, num_rows))
+        .collect::<Result<Vec<_>>>()?;
+    (0..num_rows)
+        .map(|_| {
+            T::deserialize(de::ValueDeserializer {
+                node: schema,
+                columns: &mut columns,
+                levels: Levels::default(),
+            })
+        })
+        .collect()
+}
+
+/// Reads all records of the parquet file `reader`
+pub fn from_reader<R: ChunkReader + 'static, T: DeserializeOwned>(reader: R) -> Result<Vec<T>> {
+    let reader = SerializedFileReader::new(reader)?;
+    let mut out = vec![];
+    for i in 0..reader.num_row_groups() {
+        out.extend(from_row_group::<T>(reader.get_row_group(i)?.as_ref())?);
+    }
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::file::properties::WriterProperties;
+    use crate::schema::parser::parse_message_type;
+    use ::serde::Deserialize;
+    use bytes::Bytes;
+    use std::collections::{BTreeMap, HashMap};
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    enum Colour {
+        Red,
+        Green,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Point {
+        x: f64,
+        y: f32,
+    }
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Record {
+        id: i64,
+        small: u8,
+        flag: bool,
+        name: String,
+        maybe: Option<i32>,
+        colour: Colour,
+        point: Point,
+        points: Vec<Option<Point>>,
+        nested: Vec<Vec<u16>>,
+        attrs: BTreeMap<String, Option<String>>,
+        #[serde(skip_serializing_if = "Option::is_none", default)]
+        sparse: Option<u64>,
+    }
+
+    fn record(i: i64) -> Record {
+        Record {
+            id: i,
+            small: i as u8,
+            flag: i % 2 == 0,
+            name: format!("name {i}"),
+            maybe: (i % 3 != 0).then_some(i as i32),
+            colour: if i % 2 == 0 {
+                Colour::Red
+            } else {
+                Colour::Green
+            },
+            point: Point {
+                x: i as f64,
+                y: -i as f32,
+            },
+            points: (0..i % 3)
+                .map(|j| (j != 1).then_some(Point { x: 1.0, y: 2.0 }))
+                .collect(),
+            nested: (0..i % 4).map(|j| (0..j as u16).collect()).collect(),
+            attrs: (0..i % 2)
+                .map(|j| (format!("k{j}"), (i % 4 == 1).then(|| "v".to_string())))
+                .collect(),
+            sparse: (i % 5 == 0).then_some(u64::MAX - i as u64),
+        }
+    }
+
+    fn roundtrip<T: Serialize + DeserializeOwned>(records: &[T]) -> Vec<T> {
+        let mut buf = vec![];
+        to_writer(&mut buf, records).unwrap();
+        from_reader(Bytes::from(buf)).unwrap()
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let records: Vec<_> = (0..20).map(record).collect();
+        assert_eq!(roundtrip(&records), records);
+    }
+
+    #[test]
+    fn test_infer_schema() {
+        let records: Vec<_> = (0..6).map(record).collect();
+        let schema = infer_schema(&records).unwrap();
+        let fields: Vec<_> = schema.get_fields().iter().map(|f| f.name()).collect();
+        assert_eq!(
+            fields,
+            [
+                "id", "small", "flag", "name", "maybe", "colour", "point", "points", "nested",
+                "attrs", "sparse"
+            ]
+        );
+        let sparse = &schema.get_fields()[10];
+        assert!(sparse.is_optional());
+
+        let err = infer_schema::<Record>(&[]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Cannot infer a schema from no records"
+        );
+        let err = infer_schema(&[1_i32]).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Records must serialize as structs to infer a schema"
+        );
+    }
+
+    #[test]
+    fn test_explicit_schema() {
+        let schema = "
+            message schema {
+                REQUIRED INT64 a;
+                OPTIONAL BYTE_ARRAY b (UTF8);
+                REQUIRED INT32 colour;
+            }
+        ";
+        let schema = Arc::new(parse_message_type(schema).unwrap());
+        let props = WriterProperties::builder()
+            .set_max_row_group_row_count(Some(2))
+            .build();
+
+        let mut buf = vec![];
+        let mut writer = SerdeWriter::try_new(&mut buf, schema, Arc::new(props)).unwrap();
+        let records: Vec<HashMap<&str, serde_json::Value>> = (0..5)
+            .map(|i| {
+                HashMap::from([
+                    ("a", serde_json::json!(i)),
+                    ("colour", serde_json::json!(i % 2)),
+                ])
+            })
+            .collect();
+        writer.write(&records).unwrap();
+        let metadata = writer.close().unwrap();
+        assert_eq!(metadata.num_row_groups(), 3);
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Read {
+            a: i64,
+            b: Option<String>,
+            colour: Colour,
+        }
+        let read: Vec<Read> = from_reader(Bytes::from(buf)).unwrap();
+        assert_eq!(read.len(), 5);
+        assert_eq!(
+            read[3],
+            Read {
+                a: 3,
+                b: None,
+                colour: Colour::Green
+            }
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let schema = "
+            message schema {
+                REQUIRED INT32 a;
+            }
+        ";
+        let schema = Arc::new(parse_message_type(schema).unwrap());
+
+        #[derive(Serialize)]
+        struct A {
+            a: i64,
+        }
+        #[derive(Serialize)]
+        struct B {
+            b: i32,
+        }
+
+        let mut buf = vec![];
+        let props = Arc::new(WriterProperties::default());
+        let mut writer = SerdeWriter::try_new(&mut buf, schema, props).unwrap();
+        let err = writer.write(&[A { a: i64::MAX }]).unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err}");
+        let err = writer.write(&[B { b: 1 }]).unwrap_err();
+        assert!(err.to_string().contains("'b'"), "{err}");
+
+        // The failed records are not buffered
+        writer.write(&[A { a: 1 }]).unwrap();
+        let err = writer.write(&[A { a: 2 }, A { a: i64::MAX }]).unwrap_err();
+        assert!(err.to_string().contains("out of range"), "{err}");
+        assert_eq!(writer.in_progress_rows(), 2);
+        let metadata = writer.close().unwrap();
+        assert_eq!(metadata.file_metadata().num_rows(), 2);
+    }
+
+    #[test]
+    fn test_buffered_row_groups() {
+        let props = WriterProperties::builder()
+            .set_max_row_group_row_count(Some(5))
+            .build();
+        let records: Vec<_> = (0..12).map(record).collect();
+        let schema = Arc::new(infer_schema(&records).unwrap());
+
+        let mut buf = vec![];
+        let mut writer = SerdeWriter::try_new(&mut buf, schema, Arc::new(props)).unwrap();
+        for chunk in records.chunks(3) {
+            writer.write(chunk).unwrap();
+        }
+        assert_eq!(writer.in_progress_rows(), 2);
+        writer.flush().unwrap();
+        assert_eq!(writer.in_progress_rows(), 0);
+        writer.write(&records[..1]).unwrap();
+        let metadata = writer.close().unwrap();
+
+        let row_group_rows: Vec<_> = metadata
+            .row_groups()
+            .iter()
+            .map(|rg| rg.num_rows())
+            .collect();
+        assert_eq!(row_group_rows, [5, 5, 2, 1]);
+
+        let read: Vec<Record> = from_reader(Bytes::from(buf)).unwrap();
+        assert_eq!(read.len(), 13);
+        assert_eq!(read[..12], records);
+        assert_eq!(read[12], records[0]);
+    }
+
+    #[test]
+    #[cfg(feature = "arrow")]
+    fn test_read_arrow() {
+        use crate::arrow::ArrowWriter;
+        use arrow_array::builder::{Int32Builder, ListBuilder, MapBuilder, StringBuilder};
+        use arrow_array::{ArrayRef, RecordBatch};
+
+        let mut list = ListBuilder::new(Int32Builder::new());
+        list.append_value([Some(1), None, Some(3)]);
+        list.append_null();
+        list.append_value([]);
+
+        let mut map = MapBuilder::new(None, StringBuilder::new(), Int32Builder::new());
+        map.keys().append_value("a");
+        map.values().append_value(1);
+        map.append(true).unwrap();
+        map.append(false).unwrap();
+        map.keys().append_value("b");
+        map.values().append_null();
+        map.append(true).unwrap();
+
+        let batch = RecordBatch::try_from_iter([
+            ("list", Arc::new(list.finish()) as ArrayRef),
+            ("map", Arc::new(map.finish()) as ArrayRef),
+        ])
+        .unwrap();
+        let mut buf = vec![];
+        let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), None).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        struct Row {
+            list: Option<Vec<Option<i32>>>,
+            map: Option<HashMap<String, Option<i32>>>,
+        }
+        let read: Vec<Row> = from_reader(Bytes::from(buf)).unwrap();
+        assert_eq!(
+            read,
+            [
+                Row {
+                    list: Some(vec![Some(1), None, Some(3)]),
+                    map: Some(HashMap::from([("a".to_string(), Some(1))])),
+                },
+                Row {
+                    list: None,
+                    map: None,
+                },
+                Row {
+                    list: Some(vec![]),
+                    map: Some(HashMap::from([("b".to_string(), None)])),
+                },
+            ]
+        );
+    }
+}
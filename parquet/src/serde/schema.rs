@@ -0,0 +1,500 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Inference of a parquet schema from values implementing [`Serialize`]
+
+use ::serde::ser::{self, Impossible, Serialize};
+
+use crate::basic::{LogicalType, Repetition, Type as PhysicalType};
+use crate::errors::{ParquetError, Result};
+use crate::record::parquet_field::{list_type, map_type};
+use crate::schema::types::Type;
+
+/// The inferred type of a value
+#[derive(Debug, Clone, PartialEq)]
+enum Shape {
+    /// Only nulls, or empty sequences, have been seen
+    Unknown,
+    Primitive(PhysicalType, Option<LogicalType>),
+    Struct(Vec<(String, Field)>),
+    List(Box<Field>),
+    Map(Box<Field>, Box<Field>),
+}
+
+/// The inferred type of a value, and if it was ever null
+#[derive(Debug, Clone, PartialEq)]
+struct Field {
+    shape: Shape,
+    optional: bool,
+}
+
+impl Field {
+    fn required(shape: Shape) -> Self {
+        Self {
+            shape,
+            optional: false,
+        }
+    }
+
+    fn primitive(physical_type: PhysicalType, logical_type: Option<LogicalType>) -> Self {
+        Self::required(Shape::Primitive(physical_type, logical_type))
+    }
+
+    fn integer(physical_type: PhysicalType, bit_width: i8, is_signed: bool) -> Self {
+        let logical_type = (bit_width < 32 || !is_signed).then_some(LogicalType::Integer {
+            bit_width,
+            is_signed,
+        });
+        Self::primitive(physical_type, logical_type)
+    }
+
+    /// Merges the types `self` and `other`, inferred from different values
+    fn merge(self, other: Self, path: &str) -> Result<Self> {
+        let optional = self.optional || other.optional;
+        let shape = match (self.shape, other.shape) {
+            (Shape::Unknown, s) | (s, Shape::Unknown) => s,
+            (a @ Shape::Primitive(..), b @ Shape::Primitive(..)) if a == b => a,
+            (Shape::Struct(a), Shape::Struct(b)) => Shape::Struct(merge_fields(a, b, path)?),
+            (Shape::List(a), Shape::List(b)) => Shape::List(Box::new(a.merge(*b, path)?)),
+            (Shape::Map(ak, av), Shape::Map(bk, bv)) => Shape::Map(
+                Box::new(ak.merge(*bk, path)?),
+                Box::new(av.merge(*bv, path)?),
+            ),
+            (a, b) => {
+                return Err(general_err!(
+                    "Conflicting types for '{}', found {:?} and {:?}",
+                    path,
+                    a,
+                    b
+                ));
+            }
+        };
+        Ok(Self { shape, optional })
+    }
+
+    fn to_type(&self, name: &str, path: &str) -> Result<Type> {
+        let repetition = match self.optional {
+            true => Repetition::OPTIONAL,
+            false => Repetition::REQUIRED,
+        };
+        match &self.shape {
+            Shape::Unknown => Err(general_err!(
+                "Cannot infer the type of '{}' as only null values or empty sequences \
+                 were found, provide a schema instead",
+                path
+            )),
+            Shape::Primitive(physical_type, logical_type) => {
+                Type::primitive_type_builder(name, *physical_type)
+                    .with_repetition(repetition)
+                    .with_logical_type(logical_type.clone())
+                    .build()
+            }
+            Shape::Struct(fields) => Type::group_type_builder(name)
+                .with_repetition(repetition)
+                .with_fields(
+                    fields
+                        .iter()
+                        .map(|(n, f)| Ok(f.to_type(n, &join(path, n))?.into()))
+                        .collect::<Result<_>>()?,
+                )
+                .build(),
+            Shape::List(element) => {
+                let element = element.to_type("element", &join(path, "element"))?;
+                list_type(name, repetition, element)
+            }
+            Shape::Map(key, value) => {
+                if key.optional {
+                    return Err(general_err!("Map keys of '{}' cannot be null", path));
+                }
+                let key = key.to_type("key", &join(path, "key"))?;
+                let value = value.to_type("value", &join(path, "value"))?;
+                map_type(name, repetition, key, value)
+            }
+        }
+    }
+}
+
+fn join(path: &str, name: &str) -> String {
+    match path.is_empty() {
+        true => name.to_string(),
+        false => format!("{path}.{name}"),
+    }
+}
+
+/// Merges the fields of two structs, fields missing from either becoming optional
+fn merge_fields(
+    a: Vec<(String, Field)>,
+    b: Vec<(String, Field)>,
+    path: &str,
+) -> Result<Vec<(String, Field)>> {
+    let mut b: Vec<_> = b.into_iter().map(Some).collect();
+    let mut out = Vec::with_capacity(a.len());
+    for (name, field) in a {
+        let other = b
+            .iter_mut()
+            .find(|f| matches!(f, Some((n, _)) if *n == name))
+            .and_then(Option::take);
+        let field = match other {
+            Some((_, other)) => field.merge(other, &join(path, &name))?,
+            None => Field {
+                optional: true,
+                ..field
+            },
+        };
+        out.push((name, field));
+    }
+    for (name, field) in b.into_iter().flatten() {
+        out.push((
+            name,
+            Field {
+                optional: true,
+                ..field
+            },
+        ));
+    }
+    Ok(out)
+}
+
+/// Infers the schema of `records`, which must each serialize as a struct, see
+/// [`super`] for how types are mapped
+pub fn infer_schema<T: Serialize>(records: &[T]) -> Result<Type> {
+    let mut inferred: Option<Field> = None;
+    for record in records {
+        let field = record.serialize(Infer)?;
+        inferred = Some(match inferred {
+            Some(i) => i.merge(field, "")?,
+            None => field,
+        });
+    }
+    let fields = match inferred {
+        Some(Field {
+            shape: Shape::Struct(fields),
+            optional: false,
+        }) => fields,
+        Some(Field {
+            shape: Shape::Unknown,
+            ..
+        })
+        | None => return Err(general_err!("Cannot infer a schema from no records")),
+        Some(_) => {
+            return Err(general_err!(
+                "Records must serialize as structs to infer a schema"
+            ));
+        }
+    };
+    Type::group_type_builder("schema")
+        .with_fields(
+            fields
+                .iter()
+                .map(|(n, f)| Ok(f.to_type(n, n)?.into()))
+                .collect::<Result<_>>()?,
+        )
+        .build()
+}
+
+/// A [`ser::Serializer`] inferring the [`Field`] of a value
+struct Infer;
+
+fn unsupported(what: &str) -> ParquetError {
+    nyi_err!("Serializing {} to parquet is not supported", what)
+}
+
+impl ser::Serializer for Infer {
+    type Ok = Field;
+    type Error = ParquetError;
+    type SerializeSeq = InferSeq;
+    type SerializeTuple = InferSeq;
+    type SerializeTupleStruct = InferSeq;
+    type SerializeTupleVariant = Impossible<Field, ParquetError>;
+    type SerializeMap = InferMap;
+    type SerializeStruct = InferStruct;
+    type SerializeStructVariant = Impossible<Field, ParquetError>;
+
+    fn serialize_bool(self, _v: bool) -> Result<Field> {
+        Ok(Field::primitive(PhysicalType::BOOLEAN, None))
+    }
+
+    fn serialize_i8(self, _v: i8) -> Result<Field> {
+        Ok(Field::integer(PhysicalType::INT32, 8, true))
+    }
+
+    fn serialize_i16(self, _v: i16) -> Result<Field> {
+        Ok(Field::integer(PhysicalType::INT32, 16, true))
+    }
+
+    fn serialize_i32(self, _v: i32) -> Result<Field> {
+        Ok(Field::integer(PhysicalType::INT32, 32, true))
+    }
+
+    fn serialize_i64(self, _v: i64) -> Result<Field> {
+        Ok(Field::integer(PhysicalType::INT64, 64, true))
+    }
+
+    fn serialize_u8(self, _v: u8) -> Result<Field> {
+        Ok(Field::integer(PhysicalType::INT32, 8, false))
+    }
+
+    fn serialize_u16(self, _v: u16) -> Result<Field> {
+        Ok(Field::integer(PhysicalType::INT32, 16, false))
+    }
+
+    fn serialize_u32(self, _v: u32) -> Result<Field> {
+        Ok(Field::integer(PhysicalType::INT32, 32, false))
+    }
+
+    fn serialize_u64(self, _v: u64) -> Result<Field> {
+        Ok(Field::integer(PhysicalType::INT64, 64, false))
+    }
+
+    fn serialize_f32(self, _v: f32) -> Result<Field> {
+        Ok(Field::primitive(PhysicalType::FLOAT, None))
+    }
+
+    fn serialize_f64(self, _v: f64) -> Result<Field> {
+        Ok(Field::primitive(PhysicalType::DOUBLE, None))
+    }
+
+    fn serialize_char(self, _v: char) -> Result<Field> {
+        Ok(Field::primitive(
+            PhysicalType::BYTE_ARRAY,
+            Some(LogicalType::String),
+        ))
+    }
+
+    fn serialize_str(self, _v: &str) -> Result<Field> {
+        Ok(Field::primitive(
+            PhysicalType::BYTE_ARRAY,
+            Some(LogicalType::String),
+        ))
+    }
+
+    fn serialize_bytes(self, _v: &[u8]) -> Result<Field> {
+        Ok(Field::primitive(PhysicalType::BYTE_ARRAY, None))
+    }
+
+    fn serialize_none(self) -> Result<Field> {
+        Ok(Field {
+            shape: Shape::Unknown,
+            optional: true,
+        })
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<Field> {
+        Ok(Field {
+            optional: true,
+            ..value.serialize(self)?
+        })
+    }
+
+    fn serialize_unit(self) -> Result<Field> {
+        Err(unsupported("unit"))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Field> {
+        Err(unsupported(&format!("unit struct {name}")))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+    ) -> Result<Field> {
+        Ok(Field::primitive(
+            PhysicalType::BYTE_ARRAY,
+            Some(LogicalType::Enum),
+        ))
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<Field> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<Field> {
+        Err(unsupported(&format!("enum variant {name}::{variant}")))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<InferSeq> {
+        Ok(InferSeq(Field::required(Shape::Unknown)))
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<InferSeq> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<InferSeq> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(unsupported(&format!("enum variant {name}::{variant}")))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<InferMap> {
+        Ok(InferMap {
+            key: Field::required(Shape::Unknown),
+            value: Field::required(Shape::Unknown),
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, len: usize) -> Result<InferStruct> {
+        Ok(InferStruct(Vec::with_capacity(len)))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(unsupported(&format!("enum variant {name}::{variant}")))
+    }
+}
+
+/// Infers the element type of a sequence
+struct InferSeq(Field);
+
+impl InferSeq {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let element = std::mem::replace(&mut self.0, Field::required(Shape::Unknown));
+        self.0 = element.merge(value.serialize(Infer)?, "element")?;
+        Ok(())
+    }
+
+    fn finish(self) -> Result<Field> {
+        Ok(Field::required(Shape::List(Box::new(self.0))))
+    }
+}
+
+impl ser::SerializeSeq for InferSeq {
+    type Ok = Field;
+    type Error = ParquetError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Field> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for InferSeq {
+    type Ok = Field;
+    type Error = ParquetError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Field> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for InferSeq {
+    type Ok = Field;
+    type Error = ParquetError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<Field> {
+        self.finish()
+    }
+}
+
+/// Infers the key and value types of a map
+struct InferMap {
+    key: Field,
+    value: Field,
+}
+
+impl ser::SerializeMap for InferMap {
+    type Ok = Field;
+    type Error = ParquetError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        let k = std::mem::replace(&mut self.key, Field::required(Shape::Unknown));
+        self.key = k.merge(key.serialize(Infer)?, "key")?;
+        Ok(())
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let v = std::mem::replace(&mut self.value, Field::required(Shape::Unknown));
+        self.value = v.merge(value.serialize(Infer)?, "value")?;
+        Ok(())
+    }
+
+    fn end(self) -> Result<Field> {
+        Ok(Field::required(Shape::Map(
+            Box::new(self.key),
+            Box::new(self.value),
+        )))
+    }
+}
+
+/// Infers the fields of a struct
+struct InferStruct(Vec<(String, Field)>);
+
+impl ser::SerializeStruct for InferStruct {
+    type Ok = Field;
+    type Error = ParquetError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.0.push((key.to_string(), value.serialize(Infer)?));
+        Ok(())
+    }
+
+    fn skip_field(&mut self, key: &'static str) -> Result<()> {
+        // A field skipped by `skip_serializing_if` may be present in other values
+        self.0.push((
+            key.to_string(),
+            Field {
+                shape: Shape::Unknown,
+                optional: true,
+            },
+        ));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Field> {
+        Ok(Field::required(Shape::Struct(self.0)))
+    }
+}
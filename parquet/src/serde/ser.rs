@@ -0,0 +1,791 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Shredding of values implementing [`Serialize`] into [`LeafColumn`]
+
+use std::ops::Range;
+
+use ::serde::ser::{self, Impossible, Serialize};
+
+use crate::basic::{LogicalType, Repetition, Type as PhysicalType};
+use crate::data_type::ByteArray;
+use crate::errors::{ParquetError, Result};
+use crate::record::parquet_field::{LeafValues, leaf_count, list_element, write_null};
+use crate::record::{LeafColumn, Levels};
+use crate::schema::types::Type;
+
+/// A scalar value to be written to a primitive column
+#[derive(Debug)]
+enum Scalar<'a> {
+    Bool(bool),
+    Int(i64),
+    UInt(u64),
+    Float(f32),
+    Double(f64),
+    Bytes(&'a [u8]),
+}
+
+/// Returns the ranges of the leaf columns of each of the fields of `node`
+pub(super) fn field_ranges(node: &Type) -> Vec<Range<usize>> {
+    let mut offset = 0;
+    node.get_fields()
+        .iter()
+        .map(|f| {
+            let start = offset;
+            offset += leaf_count(f);
+            start..offset
+        })
+        .collect()
+}
+
+pub(super) fn is_unsigned(node: &Type) -> bool {
+    matches!(
+        node.get_basic_info().logical_type_ref(),
+        Some(&LogicalType::Integer {
+            is_signed: false,
+            ..
+        })
+    )
+}
+
+pub(super) fn is_optional(node: &Type) -> bool {
+    node.get_basic_info().has_repetition()
+        && node.get_basic_info().repetition() == Repetition::OPTIONAL
+}
+
+/// A [`ser::Serializer`] writing a value of `node` to its leaf `columns`
+///
+/// `levels.def` is the definition level of the value if it is present, i.e.
+/// including the repetition of `node`, see [`Levels::child`]
+pub(super) struct ValueSerializer<'a> {
+    pub(super) node: &'a Type,
+    pub(super) columns: &'a mut [LeafColumn],
+    pub(super) levels: Levels,
+}
+
+impl<'a> ValueSerializer<'a> {
+    fn mismatch(&self, what: impl std::fmt::Debug) -> ParquetError {
+        general_err!(
+            "Cannot write {:?} to field '{}' of type {}",
+            what,
+            self.node.name(),
+            describe(self.node)
+        )
+    }
+
+    fn write(self, scalar: Scalar<'_>) -> Result<()> {
+        let physical_type = match self.node {
+            Type::PrimitiveType { physical_type, .. } => *physical_type,
+            Type::GroupType { .. } => return Err(self.mismatch(scalar)),
+        };
+        let unsigned = is_unsigned(self.node);
+        let out_of_range = || {
+            general_err!(
+                "Value {:?} out of range for field '{}'",
+                scalar,
+                self.node.name()
+            )
+        };
+
+        let column = &mut self.columns[0];
+        match (column.values_mut(), &scalar) {
+            (LeafValues::Boolean(v), Scalar::Bool(x)) => v.push(*x),
+            (LeafValues::Int32(v), Scalar::Int(x)) if unsigned => {
+                v.push(u32::try_from(*x).map_err(|_| out_of_range())? as i32)
+            }
+            (LeafValues::Int32(v), Scalar::Int(x)) => {
+                v.push(i32::try_from(*x).map_err(|_| out_of_range())?)
+            }
+            (LeafValues::Int32(v), Scalar::UInt(x)) if unsigned => {
+                v.push(u32::try_from(*x).map_err(|_| out_of_range())? as i32)
+            }
+            (LeafValues::Int32(v), Scalar::UInt(x)) => {
+                v.push(i32::try_from(*x).map_err(|_| out_of_range())?)
+            }
+            (LeafValues::Int64(v), Scalar::Int(x)) if unsigned => {
+                v.push(u64::try_from(*x).map_err(|_| out_of_range())? as i64)
+            }
+            (LeafValues::Int64(v), Scalar::Int(x)) => v.push(*x),
+            (LeafValues::Int64(v), Scalar::UInt(x)) if unsigned => v.push(*x as i64),
+            (LeafValues::Int64(v), Scalar::UInt(x)) => {
+                v.push(i64::try_from(*x).map_err(|_| out_of_range())?)
+            }
+            (LeafValues::Float(v), Scalar::Float(x)) => v.push(*x),
+            (LeafValues::Float(v), Scalar::Double(x)) => v.push(*x as f32),
+            (LeafValues::Double(v), Scalar::Float(x)) => v.push(*x as f64),
+            (LeafValues::Double(v), Scalar::Double(x)) => v.push(*x),
+            (LeafValues::ByteArray(v), Scalar::Bytes(x)) => v.push(ByteArray::from(x.to_vec())),
+            (LeafValues::FixedLenByteArray(v), Scalar::Bytes(x)) => {
+                let type_length = match self.node {
+                    Type::PrimitiveType { type_length, .. } => *type_length,
+                    Type::GroupType { .. } => unreachable!("groups have no values"),
+                };
+                if x.len() != type_length as usize {
+                    return Err(general_err!(
+                        "Expected {} bytes for field '{}', got {}",
+                        type_length,
+                        self.node.name(),
+                        x.len()
+                    ));
+                }
+                v.push(ByteArray::from(x.to_vec()).into())
+            }
+            _ => {
+                return Err(general_err!(
+                    "Cannot write {:?} to field '{}' of type {}",
+                    scalar,
+                    self.node.name(),
+                    physical_type
+                ));
+            }
+        }
+        column.push_levels(self.levels.def, self.levels.rep);
+        Ok(())
+    }
+
+    fn is_logical(&self, logical_type: LogicalType) -> bool {
+        self.node.get_basic_info().logical_type_ref() == Some(&logical_type)
+    }
+}
+
+/// Returns a short description of the type of `node` for error messages
+fn describe(node: &Type) -> String {
+    match node {
+        Type::PrimitiveType { physical_type, .. } => physical_type.to_string(),
+        Type::GroupType { .. } => match node.get_basic_info().logical_type_ref() {
+            Some(LogicalType::List) => "LIST".to_string(),
+            Some(LogicalType::Map) => "MAP".to_string(),
+            _ => "group".to_string(),
+        },
+    }
+}
+
+impl<'a> ser::Serializer for ValueSerializer<'a> {
+    type Ok = ();
+    type Error = ParquetError;
+    type SerializeSeq = SeqSerializer<'a>;
+    type SerializeTuple = SeqSerializer<'a>;
+    type SerializeTupleStruct = SeqSerializer<'a>;
+    type SerializeTupleVariant = Impossible<(), ParquetError>;
+    type SerializeMap = MapSerializer<'a>;
+    type SerializeStruct = StructSerializer<'a>;
+    type SerializeStructVariant = Impossible<(), ParquetError>;
+
+    fn serialize_bool(self, v: bool) -> Result<()> {
+        self.write(Scalar::Bool(v))
+    }
+
+    fn serialize_i8(self, v: i8) -> Result<()> {
+        self.write(Scalar::Int(v as i64))
+    }
+
+    fn serialize_i16(self, v: i16) -> Result<()> {
+        self.write(Scalar::Int(v as i64))
+    }
+
+    fn serialize_i32(self, v: i32) -> Result<()> {
+        self.write(Scalar::Int(v as i64))
+    }
+
+    fn serialize_i64(self, v: i64) -> Result<()> {
+        self.write(Scalar::Int(v))
+    }
+
+    fn serialize_u8(self, v: u8) -> Result<()> {
+        self.write(Scalar::UInt(v as u64))
+    }
+
+    fn serialize_u16(self, v: u16) -> Result<()> {
+        self.write(Scalar::UInt(v as u64))
+    }
+
+    fn serialize_u32(self, v: u32) -> Result<()> {
+        self.write(Scalar::UInt(v as u64))
+    }
+
+    fn serialize_u64(self, v: u64) -> Result<()> {
+        self.write(Scalar::UInt(v))
+    }
+
+    fn serialize_f32(self, v: f32) -> Result<()> {
+        self.write(Scalar::Float(v))
+    }
+
+    fn serialize_f64(self, v: f64) -> Result<()> {
+        self.write(Scalar::Double(v))
+    }
+
+    fn serialize_char(self, v: char) -> Result<()> {
+        self.serialize_str(v.encode_utf8(&mut [0; 4]))
+    }
+
+    fn serialize_str(self, v: &str) -> Result<()> {
+        self.write(Scalar::Bytes(v.as_bytes()))
+    }
+
+    fn serialize_bytes(self, v: &[u8]) -> Result<()> {
+        self.write(Scalar::Bytes(v))
+    }
+
+    fn serialize_none(self) -> Result<()> {
+        if !is_optional(self.node) {
+            return Err(general_err!(
+                "Found null value for non-optional field '{}'",
+                self.node.name()
+            ));
+        }
+        let levels = Levels {
+            def: self.levels.def - 1,
+            ..self.levels
+        };
+        write_null(self.columns, levels);
+        Ok(())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, value: &T) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_unit(self) -> Result<()> {
+        Err(self.mismatch("unit"))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<()> {
+        Err(self.mismatch(name))
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        variant_index: u32,
+        variant: &'static str,
+    ) -> Result<()> {
+        match self.node {
+            Type::PrimitiveType {
+                physical_type: PhysicalType::INT32 | PhysicalType::INT64,
+                ..
+            } => self.write(Scalar::UInt(variant_index as u64)),
+            _ => self.serialize_str(variant),
+        }
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        value.serialize(self)
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<()> {
+        Err(self.mismatch(format!("{name}::{variant}")))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<SeqSerializer<'a>> {
+        if !self.is_logical(LogicalType::List) {
+            // Also accept unannotated repeated groups, as written by older writers
+            let repeated = self
+                .node
+                .get_fields()
+                .first()
+                .is_some_and(|f| f.get_basic_info().repetition() == Repetition::REPEATED);
+            if !self.node.is_group() || self.node.get_fields().len() != 1 || !repeated {
+                return Err(self.mismatch("sequence"));
+            }
+        }
+        let (element, repeated_levels, element_levels) = list_element(self.node, self.levels)?;
+        Ok(SeqSerializer {
+            element,
+            columns: self.columns,
+            levels: self.levels,
+            repeated_levels,
+            element_levels,
+            len: 0,
+        })
+    }
+
+    fn serialize_tuple(self, len: usize) -> Result<SeqSerializer<'a>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_struct(self, _name: &'static str, len: usize) -> Result<SeqSerializer<'a>> {
+        self.serialize_seq(Some(len))
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(self.mismatch(format!("{name}::{variant}")))
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<MapSerializer<'a>> {
+        if !self.node.is_group() {
+            return Err(self.mismatch("map"));
+        }
+        if !self.is_logical(LogicalType::Map) {
+            // A map serialized as a struct, e.g. due to `#[serde(flatten)]`
+            return Ok(MapSerializer::Struct {
+                inner: StructSerializer::new(self),
+                key: None,
+            });
+        }
+        let key_value = match self.node.get_fields() {
+            [key_value] if key_value.is_group() && key_value.get_fields().len() == 2 => key_value,
+            _ => return Err(general_err!("Invalid map type '{}'", self.node.name())),
+        };
+        let repeated_levels = self.levels.child(key_value);
+        let key_count = leaf_count(&key_value.get_fields()[0]);
+        Ok(MapSerializer::Map {
+            key_value,
+            columns: self.columns,
+            levels: self.levels,
+            repeated_levels,
+            key_count,
+            len: 0,
+        })
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<StructSerializer<'a>> {
+        if !self.node.is_group()
+            || self.is_logical(LogicalType::List)
+            || self.is_logical(LogicalType::Map)
+        {
+            return Err(self.mismatch("struct"));
+        }
+        Ok(StructSerializer::new(self))
+    }
+
+    fn serialize_struct_variant(
+        self,
+        name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(self.mismatch(format!("{name}::{variant}")))
+    }
+}
+
+/// Writes the elements of a sequence to a `LIST`
+pub(super) struct SeqSerializer<'a> {
+    element: &'a Type,
+    columns: &'a mut [LeafColumn],
+    /// The levels of the list
+    levels: Levels,
+    /// The levels of its repeated field
+    repeated_levels: Levels,
+    /// The levels of its elements
+    element_levels: Levels,
+    len: usize,
+}
+
+impl SeqSerializer<'_> {
+    fn element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        let rep = match self.len {
+            0 => self.levels.rep,
+            _ => self.repeated_levels.depth,
+        };
+        self.len += 1;
+        value.serialize(ValueSerializer {
+            node: self.element,
+            columns: self.columns,
+            levels: Levels {
+                rep,
+                ..self.element_levels
+            },
+        })
+    }
+
+    fn finish(self) -> Result<()> {
+        if self.len == 0 {
+            write_null(self.columns, self.levels);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeSeq for SeqSerializer<'_> {
+    type Ok = ();
+    type Error = ParquetError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTuple for SeqSerializer<'_> {
+    type Ok = ();
+    type Error = ParquetError;
+
+    fn serialize_element<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+impl ser::SerializeTupleStruct for SeqSerializer<'_> {
+    type Ok = ();
+    type Error = ParquetError;
+
+    fn serialize_field<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        self.element(value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+/// Writes the entries of a map to a `MAP`, or its fields to a group
+pub(super) enum MapSerializer<'a> {
+    Map {
+        key_value: &'a Type,
+        columns: &'a mut [LeafColumn],
+        /// The levels of the map
+        levels: Levels,
+        /// The levels of its repeated `key_value` field
+        repeated_levels: Levels,
+        key_count: usize,
+        len: usize,
+    },
+    Struct {
+        inner: StructSerializer<'a>,
+        key: Option<String>,
+    },
+}
+
+impl ser::SerializeMap for MapSerializer<'_> {
+    type Ok = ();
+    type Error = ParquetError;
+
+    fn serialize_key<T: ?Sized + Serialize>(&mut self, key: &T) -> Result<()> {
+        match self {
+            Self::Map {
+                key_value,
+                columns,
+                levels,
+                repeated_levels,
+                key_count,
+                len,
+            } => {
+                let rep = match len {
+                    0 => levels.rep,
+                    _ => repeated_levels.depth,
+                };
+                let key_node = &key_value.get_fields()[0];
+                key.serialize(ValueSerializer {
+                    node: key_node,
+                    columns: &mut columns[..*key_count],
+                    levels: Levels {
+                        rep,
+                        ..repeated_levels.child(key_node)
+                    },
+                })
+            }
+            Self::Struct { key: k, .. } => {
+                *k = Some(key.serialize(KeySerializer)?);
+                Ok(())
+            }
+        }
+    }
+
+    fn serialize_value<T: ?Sized + Serialize>(&mut self, value: &T) -> Result<()> {
+        match self {
+            Self::Map {
+                key_value,
+                columns,
+                levels,
+                repeated_levels,
+                key_count,
+                len,
+            } => {
+                let rep = match len {
+                    0 => levels.rep,
+                    _ => repeated_levels.depth,
+                };
+                *len += 1;
+                let value_node = &key_value.get_fields()[1];
+                value.serialize(ValueSerializer {
+                    node: value_node,
+                    columns: &mut columns[*key_count..],
+                    levels: Levels {
+                        rep,
+                        ..repeated_levels.child(value_node)
+                    },
+                })
+            }
+            Self::Struct { inner, key } => {
+                let key = key
+                    .take()
+                    .ok_or_else(|| general_err!("serialize_value called before serialize_key"))?;
+                inner.field(&key, value)
+            }
+        }
+    }
+
+    fn end(self) -> Result<()> {
+        match self {
+            Self::Map {
+                columns,
+                levels,
+                len,
+                ..
+            } => {
+                if len == 0 {
+                    write_null(columns, levels);
+                }
+                Ok(())
+            }
+            Self::Struct { inner, .. } => inner.finish(),
+        }
+    }
+}
+
+/// Writes the fields of a struct to a group
+pub(super) struct StructSerializer<'a> {
+    node: &'a Type,
+    columns: &'a mut [LeafColumn],
+    levels: Levels,
+    ranges: Vec<Range<usize>>,
+    written: Vec<bool>,
+}
+
+impl<'a> StructSerializer<'a> {
+    fn new(s: ValueSerializer<'a>) -> Self {
+        let ranges = field_ranges(s.node);
+        Self {
+            written: vec![false; ranges.len()],
+            node: s.node,
+            columns: s.columns,
+            levels: s.levels,
+            ranges,
+        }
+    }
+
+    fn field<T: ?Sized + Serialize>(&mut self, key: &str, value: &T) -> Result<()> {
+        let fields = self.node.get_fields();
+        let idx = fields.iter().position(|f| f.name() == key).ok_or_else(|| {
+            general_err!(
+                "Field '{}' not found in schema of '{}'",
+                key,
+                self.node.name()
+            )
+        })?;
+        if std::mem::replace(&mut self.written[idx], true) {
+            return Err(general_err!("Duplicate field '{}'", key));
+        }
+        let child = &fields[idx];
+        value.serialize(ValueSerializer {
+            node: child,
+            columns: &mut self.columns[self.ranges[idx].clone()],
+            levels: self.levels.child(child),
+        })
+    }
+
+    /// Writes nulls for any fields that were not written
+    fn finish(self) -> Result<()> {
+        let fields = self.node.get_fields();
+        for (idx, written) in self.written.iter().enumerate() {
+            if *written {
+                continue;
+            }
+            let child = &fields[idx];
+            if !is_optional(child) {
+                return Err(general_err!(
+                    "Missing value for non-optional field '{}'",
+                    child.name()
+                ));
+            }
+            write_null(&mut self.columns[self.ranges[idx].clone()], self.levels);
+        }
+        Ok(())
+    }
+}
+
+impl ser::SerializeStruct for StructSerializer<'_> {
+    type Ok = ();
+    type Error = ParquetError;
+
+    fn serialize_field<T: ?Sized + Serialize>(
+        &mut self,
+        key: &'static str,
+        value: &T,
+    ) -> Result<()> {
+        self.field(key, value)
+    }
+
+    fn end(self) -> Result<()> {
+        self.finish()
+    }
+}
+
+macro_rules! unsupported_keys {
+    ($($method:ident: $t:ty),*) => {
+        $(
+            fn $method(self, _v: $t) -> Result<String> {
+                Err(key_must_be_string())
+            }
+        )*
+    };
+}
+
+/// A [`ser::Serializer`] accepting only strings, for the keys of maps written to
+/// groups
+struct KeySerializer;
+
+impl ser::Serializer for KeySerializer {
+    type Ok = String;
+    type Error = ParquetError;
+    type SerializeSeq = Impossible<String, ParquetError>;
+    type SerializeTuple = Impossible<String, ParquetError>;
+    type SerializeTupleStruct = Impossible<String, ParquetError>;
+    type SerializeTupleVariant = Impossible<String, ParquetError>;
+    type SerializeMap = Impossible<String, ParquetError>;
+    type SerializeStruct = Impossible<String, ParquetError>;
+    type SerializeStructVariant = Impossible<String, ParquetError>;
+
+    fn serialize_str(self, v: &str) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_char(self, v: char) -> Result<String> {
+        Ok(v.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<String> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        value: &T,
+    ) -> Result<String> {
+        value.serialize(self)
+    }
+
+    unsupported_keys! {
+        serialize_bool: bool,
+        serialize_i8: i8,
+        serialize_i16: i16,
+        serialize_i32: i32,
+        serialize_i64: i64,
+        serialize_u8: u8,
+        serialize_u16: u16,
+        serialize_u32: u32,
+        serialize_u64: u64,
+        serialize_f32: f32,
+        serialize_f64: f64,
+        serialize_bytes: &[u8]
+    }
+
+    fn serialize_none(self) -> Result<String> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_unit(self) -> Result<String> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_some<T: ?Sized + Serialize>(self, _value: &T) -> Result<String> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_unit_struct(self, _name: &'static str) -> Result<String> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_newtype_variant<T: ?Sized + Serialize>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _value: &T,
+    ) -> Result<String> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_struct(self, _name: &'static str, _len: usize) -> Result<Self::SerializeStruct> {
+        Err(key_must_be_string())
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        _variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant> {
+        Err(key_must_be_string())
+    }
+}
+
+fn key_must_be_string() -> ParquetError {
+    general_err!("Keys of maps written to groups must be strings")
+}
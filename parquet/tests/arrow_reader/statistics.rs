@@ -29,6 +29,8 @@ use arrow::datatypes::{
     Date32Type, Date64Type, TimestampMicrosecondType, TimestampMillisecondType,
     TimestampNanosecondType, TimestampSecondType, i256,
 };
+use arrow_array::StructArray;
+use arrow_array::builder::{Int32Builder, Int64Builder, ListBuilder, MapBuilder, StringBuilder};
 use arrow_array::{
     Array, ArrayRef, BinaryArray, BinaryViewArray, BooleanArray, Date32Array, Date64Array,
     Decimal32Array, Decimal64Array, Decimal128Array, Decimal256Array, FixedSizeBinaryArray,
@@ -39,7 +41,9 @@ use arrow_array::{
     TimestampSecondArray, UInt8Array, UInt16Array, UInt32Array, UInt64Array, make_array,
     new_null_array,
 };
-use arrow_schema::{DataType, Field, Schema, SchemaRef, TimeUnit};
+use arrow_buffer::NullBuffer;
+use arrow_schema::{DataType, Field, Fields, Schema, SchemaRef, TimeUnit};
+use bytes::Bytes;
 use half::f16;
 use parquet::arrow::ArrowWriter;
 use parquet::arrow::arrow_reader::statistics::StatisticsConverter;
@@ -47,7 +51,7 @@ use parquet::arrow::arrow_reader::{
     ArrowReaderBuilder, ArrowReaderOptions, ParquetRecordBatchReaderBuilder,
 };
 use parquet::file::metadata::PageIndexPolicy;
-use parquet::file::metadata::{ColumnChunkMetaData, RowGroupMetaData};
+use parquet::file::metadata::{ColumnChunkMetaData, ParquetMetaData, RowGroupMetaData};
 use parquet::file::properties::{EnabledStatistics, WriterProperties};
 use parquet::file::statistics::{Statistics, ValueStatistics};
 use parquet::schema::types::{SchemaDescPtr, SchemaDescriptor};
@@ -2622,6 +2626,209 @@ async fn test_column_non_existent() {
 /// The following tests were initially in `arrow-rs/parquet/src/arrow/arrow_reader/statistics.rs`.
 /// Part of them was moved here to avoid confusion and duplication,
 /// including edge conditions and data file validations for only `row_group` statistics.
+/// Writes a file with a struct column `s` of a list `list` and a string `name`,
+/// and a map column `m`, as two row groups of three rows, with a data page
+/// for every two rows
+fn nested_test_file() -> (SchemaRef, Arc<ParquetMetaData>) {
+    let mut list = ListBuilder::new(Int32Builder::new());
+    for value in [
+        Some(vec![Some(1), Some(2), Some(3)]),
+        Some(vec![]),
+        None,
+        Some(vec![Some(4), None]),
+        None,
+        Some(vec![Some(7)]),
+    ] {
+        list.append_option(value);
+    }
+    let name: ArrayRef = Arc::new(StringArray::from(vec![
+        Some("a"),
+        Some("bb"),
+        Some("ccc"),
+        Some("dddd"),
+        Some("e"),
+        None,
+    ]));
+    let list: ArrayRef = Arc::new(list.finish());
+    let fields = Fields::from(vec![
+        Field::new("list", list.data_type().clone(), true),
+        Field::new("name", DataType::Utf8, true),
+    ]);
+    let struct_nulls = NullBuffer::from(vec![true, true, false, true, true, true]);
+    let s = StructArray::new(fields, vec![list, name], Some(struct_nulls));
+
+    let mut m = MapBuilder::new(None, StringBuilder::new(), Int64Builder::new());
+    for entries in [
+        Some(vec![("x", 10)]),
+        Some(vec![]),
+        None,
+        Some(vec![("y", 20), ("z", 30)]),
+        Some(vec![("w", 5)]),
+        Some(vec![("v", 1)]),
+    ] {
+        if let Some(entries) = &entries {
+            for (key, value) in entries {
+                m.keys().append_value(key);
+                m.values().append_value(*value);
+            }
+        }
+        m.append(entries.is_some()).unwrap();
+    }
+
+    let batch =
+        RecordBatch::try_from_iter([("s", Arc::new(s) as ArrayRef), ("m", Arc::new(m.finish()))])
+            .unwrap();
+    let props = WriterProperties::builder()
+        .set_max_row_group_row_count(Some(3))
+        .set_data_page_row_count_limit(2)
+        .set_write_batch_size(1)
+        .build();
+    let mut buffer = Vec::new();
+    let mut writer = ArrowWriter::try_new(&mut buffer, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+
+    let options = ArrowReaderOptions::new().with_page_index_policy(PageIndexPolicy::Required);
+    let reader =
+        ParquetRecordBatchReaderBuilder::try_new_with_options(Bytes::from(buffer), options)
+            .unwrap();
+    (reader.schema().clone(), reader.metadata().clone())
+}
+
+#[test]
+fn test_nested_column_path() {
+    let (arrow_schema, metadata) = nested_test_file();
+    let parquet_schema = metadata.file_metadata().schema_descr();
+    let row_groups = metadata.row_groups();
+    let column_index = metadata.column_index().unwrap();
+    let offset_index = metadata.offset_index().unwrap();
+    let row_group_indices = [0, 1];
+
+    let converter = |path| {
+        StatisticsConverter::try_new_from_column_path(path, &arrow_schema, parquet_schema).unwrap()
+    };
+
+    let list = converter("s.list.list.item");
+    assert_eq!(list.parquet_column_index(), Some(0));
+    assert_eq!(
+        list.arrow_field(),
+        &Field::new("item", DataType::Int32, true)
+    );
+    // Leaf values of the list, with empty and null lists counted as nulls
+    assert_eq!(
+        &list.row_group_mins(row_groups).unwrap(),
+        &i32_array([1, 4])
+    );
+    assert_eq!(
+        &list.row_group_maxes(row_groups).unwrap(),
+        &i32_array([3, 7])
+    );
+    assert_eq!(
+        list.row_group_null_counts(row_groups).unwrap(),
+        UInt64Array::from(vec![2, 2])
+    );
+    assert_eq!(
+        list.row_group_value_counts(row_groups).unwrap(),
+        Some(UInt64Array::from(vec![5, 4]))
+    );
+    assert_eq!(
+        list.row_group_row_counts(row_groups).unwrap(),
+        Some(UInt64Array::from(vec![3, 3]))
+    );
+
+    // Each row group has a page of two rows and a page of one row
+    let page_mins = list
+        .data_page_mins(column_index, offset_index, &row_group_indices)
+        .unwrap();
+    let expected: ArrayRef = Arc::new(Int32Array::from(vec![Some(1), None, None, Some(7)]));
+    assert_eq!(&page_mins, &expected);
+    assert_eq!(
+        list.data_page_null_counts(column_index, offset_index, &row_group_indices)
+            .unwrap(),
+        UInt64Array::from(vec![1, 1, 2, 0])
+    );
+    assert_eq!(
+        list.data_page_value_counts(column_index, offset_index, &row_group_indices)
+            .unwrap(),
+        UInt64Array::from(vec![4, 1, 3, 1])
+    );
+    assert_eq!(
+        list.data_page_row_counts(offset_index, row_groups, &row_group_indices)
+            .unwrap(),
+        Some(UInt64Array::from(vec![2, 1, 2, 1]))
+    );
+    assert_eq!(
+        list.data_page_unencoded_byte_array_data_bytes(offset_index, &row_group_indices)
+            .unwrap(),
+        Int64Array::new_null(4)
+    );
+
+    let name = converter("s.name");
+    assert_eq!(
+        name.arrow_field(),
+        &Field::new("name", DataType::Utf8, true)
+    );
+    let expected: ArrayRef = Arc::new(StringArray::from(vec!["a", "dddd"]));
+    assert_eq!(&name.row_group_mins(row_groups).unwrap(), &expected);
+    assert_eq!(
+        name.row_group_unencoded_byte_array_data_bytes(row_groups)
+            .unwrap(),
+        Int64Array::from(vec![3, 5])
+    );
+    assert_eq!(
+        name.data_page_unencoded_byte_array_data_bytes(offset_index, &row_group_indices)
+            .unwrap(),
+        Int64Array::from(vec![3, 0, 5, 0])
+    );
+
+    let values = converter("m.entries.values");
+    assert_eq!(values.arrow_field().name(), "values");
+    assert_eq!(
+        &values.row_group_mins(row_groups).unwrap(),
+        &i64_array([10, 1])
+    );
+    assert_eq!(
+        &values.row_group_maxes(row_groups).unwrap(),
+        &i64_array([10, 30])
+    );
+    assert_eq!(
+        values.row_group_value_counts(row_groups).unwrap(),
+        Some(UInt64Array::from(vec![3, 4]))
+    );
+    assert_eq!(
+        values
+            .data_page_row_counts(offset_index, row_groups, &row_group_indices)
+            .unwrap(),
+        Some(UInt64Array::from(vec![2, 1, 2, 1]))
+    );
+
+    let err =
+        StatisticsConverter::try_new_from_column_path("s.list", &arrow_schema, parquet_schema)
+            .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "Arrow: Column path 's.list' not found in parquet schema for statistics conversion"
+    );
+
+    // The arrow schema must have the structure of the parquet schema
+    let mismatched = Schema::new(vec![Field::new("s", DataType::Int32, true)]);
+    let err = StatisticsConverter::try_new_from_column_path("s.name", &mismatched, parquet_schema)
+        .unwrap_err();
+    assert!(
+        err.to_string()
+            .contains("does not match the parquet schema of column path 's.name'"),
+        "{err}"
+    );
+}
+
+fn i32_array(values: impl IntoIterator<Item = i32>) -> ArrayRef {
+    Arc::new(Int32Array::from_iter_values(values))
+}
+
+fn i64_array(values: impl IntoIterator<Item = i64>) -> ArrayRef {
+    Arc::new(Int64Array::from_iter_values(values))
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
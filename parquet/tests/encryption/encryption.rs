@@ -32,7 +32,7 @@ use parquet::arrow::arrow_reader::{
 };
 use parquet::data_type::{ByteArray, ByteArrayType};
 use parquet::encryption::decrypt::FileDecryptionProperties;
-use parquet::encryption::encrypt::FileEncryptionProperties;
+use parquet::encryption::encrypt::{FileEncryptionProperties, ParquetCipher};
 use parquet::errors::ParquetError;
 use parquet::file::metadata::{ColumnChunkMetaData, PageIndexPolicy, ParquetMetaData};
 use parquet::file::properties::WriterProperties;
@@ -227,18 +227,56 @@ fn test_aes_ctr_encryption() {
         .build()
         .unwrap();
 
-    let options =
-        ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
-    let metadata = ArrowReaderMetadata::load(&file, options);
+    verify_encryption_test_file_read(file, decryption_properties);
+}
 
-    match metadata {
-        Err(parquet::errors::ParquetError::NYI(s)) => {
-            assert!(s.contains("AES_GCM_CTR_V1"));
+#[test]
+fn test_write_aes_ctr_encryption() {
+    let footer_key = b"0123456789012345".to_vec();
+    let column_key = b"1234567890123450".to_vec();
+
+    let schema = Arc::new(Schema::new(vec![
+        Field::new("a", DataType::Int32, false),
+        Field::new("b", DataType::Utf8, true),
+    ]));
+    let a = Int32Array::from_iter_values(0..1000);
+    let b = StringArray::from_iter((0..1000).map(|i| (i % 3 != 0).then(|| format!("v{}", i % 7))));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(a), Arc::new(b)]).unwrap();
+
+    for column_keys in [false, true] {
+        let mut builder = FileEncryptionProperties::builder(footer_key.clone())
+            .with_algorithm(ParquetCipher::AesGcmCtrV1);
+        if column_keys {
+            builder = builder.with_column_key("b", column_key.clone());
         }
-        _ => {
-            panic!("Expected ParquetError::NYI");
+        let file_encryption_properties = builder.build().unwrap();
+        let props = WriterProperties::builder()
+            .with_file_encryption_properties(file_encryption_properties)
+            .set_data_page_row_count_limit(100)
+            .set_write_batch_size(100)
+            .build();
+
+        let file = tempfile::tempfile().unwrap();
+        let mut writer =
+            ArrowWriter::try_new(file.try_clone().unwrap(), schema.clone(), Some(props)).unwrap();
+        writer.write(&batch).unwrap();
+        writer.close().unwrap();
+
+        let mut builder = FileDecryptionProperties::builder(footer_key.clone());
+        if column_keys {
+            builder = builder.with_column_key("b", column_key.clone());
         }
-    };
+        let decryption_properties = builder.build().unwrap();
+        let options =
+            ArrowReaderOptions::default().with_file_decryption_properties(decryption_properties);
+        let reader = ParquetRecordBatchReaderBuilder::try_new_with_options(file, options)
+            .unwrap()
+            .build()
+            .unwrap();
+        let read = reader.collect::<ArrowResult<Vec<_>>>().unwrap();
+        let read = arrow::compute::concat_batches(&schema, &read).unwrap();
+        assert_eq!(read, batch);
+    }
 }
 
 #[test]
@@ -0,0 +1,119 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Tests for encryption with keys managed by a KMS
+
+use arrow_array::{ArrayRef, Int32Array, RecordBatch, StringArray};
+use bytes::Bytes;
+use parquet::arrow::ArrowWriter;
+use parquet::arrow::arrow_reader::{ArrowReaderOptions, ParquetRecordBatchReaderBuilder};
+use parquet::encryption::key_management::crypto_factory::{
+    CryptoFactory, DecryptionConfiguration, EncryptionConfiguration,
+};
+use parquet::encryption::key_management::kms::{InMemoryKms, KmsConnectionConfig};
+use parquet::file::properties::WriterProperties;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+fn kms() -> InMemoryKms {
+    InMemoryKms::try_new(HashMap::from([
+        ("kf".to_string(), b"0123456789012345".to_vec()),
+        ("kc1".to_string(), b"1234567890123450".to_vec()),
+        ("kc2".to_string(), b"1234567890123451".to_vec()),
+    ]))
+    .unwrap()
+}
+
+fn write(crypto_factory: &CryptoFactory, config: &EncryptionConfiguration) -> (Bytes, RecordBatch) {
+    let batch = RecordBatch::try_from_iter([
+        ("id", Arc::new(Int32Array::from(vec![1, 2, 3])) as ArrayRef),
+        ("x", Arc::new(Int32Array::from(vec![4, 5, 6])) as ArrayRef),
+        (
+            "y",
+            Arc::new(StringArray::from(vec!["a", "b", "c"])) as ArrayRef,
+        ),
+    ])
+    .unwrap();
+
+    let kms_config = KmsConnectionConfig::default();
+    let encryption_properties = crypto_factory
+        .file_encryption_properties(&kms_config, config)
+        .unwrap();
+    let props = WriterProperties::builder()
+        .with_file_encryption_properties(encryption_properties)
+        .build();
+
+    let mut buf = vec![];
+    let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), Some(props)).unwrap();
+    writer.write(&batch).unwrap();
+    writer.close().unwrap();
+    (Bytes::from(buf), batch)
+}
+
+fn read(crypto_factory: &CryptoFactory, data: Bytes) -> parquet::errors::Result<RecordBatch> {
+    let decryption_properties = crypto_factory.file_decryption_properties(
+        Arc::new(KmsConnectionConfig::default()),
+        DecryptionConfiguration::default(),
+    )?;
+    let options = ArrowReaderOptions::new().with_file_decryption_properties(decryption_properties);
+    let mut reader =
+        ParquetRecordBatchReaderBuilder::try_new_with_options(data, options)?.build()?;
+    Ok(reader.next().unwrap()?)
+}
+
+#[test]
+fn test_key_management_roundtrip() {
+    let writer_factory = CryptoFactory::new(kms());
+    let config = EncryptionConfiguration::builder("kf".to_string())
+        .with_column_keys("kc1", vec!["x"])
+        .with_column_keys("kc2", vec!["y"])
+        .build()
+        .unwrap();
+    let (data, batch) = write(&writer_factory, &config);
+
+    // A separate factory has no cached keys
+    let reader_factory = CryptoFactory::new(kms());
+    assert_eq!(read(&reader_factory, data.clone()).unwrap(), batch);
+
+    // Master keys are required to read the file
+    let other_keys = InMemoryKms::try_new(HashMap::from([(
+        "kf".to_string(),
+        b"9999999999999999".to_vec(),
+    )]))
+    .unwrap();
+    read(&CryptoFactory::new(other_keys), data).unwrap_err();
+}
+
+#[test]
+fn test_key_management_plaintext_footer() {
+    let crypto_factory = CryptoFactory::new(kms());
+    let config = EncryptionConfiguration::builder("kf".to_string())
+        .with_column_keys("kc1", vec!["x", "y"])
+        .with_plaintext_footer(true)
+        .with_double_wrapping(false)
+        .build()
+        .unwrap();
+    let (data, batch) = write(&crypto_factory, &config);
+    assert_eq!(read(&crypto_factory, data.clone()).unwrap(), batch);
+
+    // Unencrypted columns can be read without keys
+    let builder = ParquetRecordBatchReaderBuilder::try_new(data).unwrap();
+    let mask = parquet::arrow::ProjectionMask::roots(builder.parquet_schema(), [0]);
+    let mut reader = builder.with_projection(mask).build().unwrap();
+    let read = reader.next().unwrap().unwrap();
+    assert_eq!(read.column(0).as_ref(), batch.column(0).as_ref());
+}
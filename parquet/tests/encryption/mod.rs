@@ -23,4 +23,6 @@ mod encryption_async;
 #[cfg(not(feature = "encryption"))]
 mod encryption_disabled;
 #[cfg(feature = "encryption")]
+mod encryption_key_management;
+#[cfg(feature = "encryption")]
 mod encryption_util;
@@ -22,12 +22,19 @@ mod test {
 
     use std::{fs::File, iter::zip, sync::Arc};
 
-    use arrow_array::{ArrayRef, BinaryArray, RecordBatch, create_array};
+    use arrow_array::{
+        Array, ArrayRef, BinaryArray, Float64Array, ListArray, RecordBatch, create_array,
+        types::Int32Type,
+    };
     use arrow_schema::{DataType, Field, Schema, SchemaRef, extension::ExtensionType as _};
     use bytes::Bytes;
     use parquet::{
         arrow::{
-            ArrowSchemaConverter, ArrowWriter, arrow_reader::ParquetRecordBatchReaderBuilder,
+            ArrowSchemaConverter, ArrowWriter,
+            arrow_reader::{
+                ParquetRecordBatchReaderBuilder,
+                geospatial::{GeospatialPredicate, GeospatialStatisticsConverter},
+            },
             arrow_writer::ArrowWriterOptions,
         },
         basic::{EdgeInterpolationAlgorithm, LogicalType},
@@ -414,6 +421,122 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_prune_row_groups_geospatial_predicate() {
+        let arrow_schema = Arc::new(Schema::new(vec![Field::new("geo", DataType::Binary, true)]));
+        let column_values = [
+            wkb_array_xy([Some((1.0, 2.0)), Some((11.0, 12.0))]),
+            wkb_array_xy([Some((21.0, 22.0)), Some((31.0, 32.0))]),
+            wkb_array_xy([Some((3.0, 4.0)), None]),
+            wkb_array_xy([None, None]),
+        ];
+
+        let props = WriterProperties::builder()
+            .set_statistics_enabled(EnabledStatistics::Chunk)
+            .build();
+        let options = ArrowWriterOptions::new()
+            .with_parquet_schema(parquet_schema_geometry())
+            .with_properties(props);
+        let mut buf = Vec::with_capacity(1024);
+        let mut file_writer =
+            ArrowWriter::try_new_with_options(&mut buf, arrow_schema.clone(), options).unwrap();
+        for values in &column_values {
+            let batch = RecordBatch::try_new(arrow_schema.clone(), vec![values.clone()]).unwrap();
+            file_writer.write(&batch).unwrap();
+            file_writer.flush().unwrap();
+        }
+        file_writer.close().unwrap();
+        let buf = Bytes::from(buf);
+
+        let read = |predicate: GeospatialPredicate| -> Vec<RecordBatch> {
+            ParquetRecordBatchReaderBuilder::try_new(buf.clone())
+                .unwrap()
+                .with_geospatial_predicate(predicate)
+                .unwrap()
+                .build()
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap()
+        };
+        let num_rows =
+            |batches: &[RecordBatch]| -> usize { batches.iter().map(|b| b.num_rows()).sum() };
+
+        // Row groups 0 and 2 intersect, row group 3 has no bounding box
+        let batches = read(GeospatialPredicate::intersects(
+            0,
+            &BoundingBox::new(0.0, 5.0, 0.0, 5.0),
+        ));
+        assert_eq!(num_rows(&batches), 6);
+
+        // Only the bounding box of row group 0 contains the envelope
+        let batches = read(GeospatialPredicate::contains(
+            0,
+            &BoundingBox::new(2.0, 3.0, 5.0, 6.0),
+        ));
+        assert_eq!(num_rows(&batches), 4);
+
+        // Wraparound boxes only intersect row group 1, crossing the antimeridian
+        let batches = read(GeospatialPredicate::intersects(
+            0,
+            &BoundingBox::new(20.0, -170.0, 20.0, 25.0),
+        ));
+        assert_eq!(num_rows(&batches), 4);
+
+        let err = ParquetRecordBatchReaderBuilder::try_new(buf.clone())
+            .unwrap()
+            .with_geospatial_predicate(GeospatialPredicate::intersects(
+                1,
+                &BoundingBox::new(0.0, 5.0, 0.0, 5.0),
+            ))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: Geospatial predicate column 1 out of bounds, file has 1 columns"
+        );
+
+        // Statistics conversion
+        let reader = SerializedFileReader::new(buf).unwrap();
+        let metadata = reader.metadata();
+        let converter = GeospatialStatisticsConverter::try_new(
+            "geo",
+            &arrow_schema,
+            metadata.file_metadata().schema_descr(),
+        )
+        .unwrap();
+        assert_eq!(converter.parquet_column_index(), Some(0));
+
+        let bboxes = converter
+            .row_group_bounding_boxes(metadata.row_groups())
+            .unwrap();
+        assert_eq!(
+            bboxes.data_type(),
+            &GeospatialStatisticsConverter::bounding_box_data_type()
+        );
+        let column = |name: &str| bboxes.column_by_name(name).unwrap().clone();
+        assert_eq!(
+            column("xmin").as_ref(),
+            &Float64Array::from(vec![Some(1.0), Some(21.0), Some(3.0), None]) as &dyn Array
+        );
+        assert_eq!(
+            column("ymax").as_ref(),
+            &Float64Array::from(vec![Some(12.0), Some(32.0), Some(4.0), None]) as &dyn Array
+        );
+        assert_eq!(column("zmin").null_count(), 4);
+        assert_eq!(bboxes.null_count(), 1);
+        assert!(bboxes.is_null(3));
+
+        let types = converter
+            .row_group_geospatial_types(metadata.row_groups())
+            .unwrap();
+        let expected = ListArray::from_iter_primitive::<Int32Type, _, _>([
+            Some(vec![Some(1)]),
+            Some(vec![Some(1)]),
+            Some(vec![Some(1)]),
+            None,
+        ]);
+        assert_eq!(types, expected);
+    }
+
     fn parquet_schema_geometry() -> SchemaDescriptor {
         let wkb_meta = WkbMetadata::new(None, None);
         let wkb_type = WkbType::new(Some(wkb_meta));
@@ -0,0 +1,238 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Parsing of `#[parquet(...)]` attributes
+
+use syn::meta::ParseNestedMeta;
+use syn::{Attribute, Expr, ExprLit, ExprPath, Lit};
+
+/// The attributes of a struct field
+///
+/// ```text
+/// #[parquet(rename = "name")]
+/// #[parquet(skip)]
+/// #[parquet(logical_type = "json")]
+/// ```
+#[derive(Debug, Default)]
+pub struct FieldAttrs {
+    /// The column name, if different from the field name
+    pub rename: Option<String>,
+    /// If the field should be neither written nor read
+    pub skip: bool,
+    /// An expression of type `Option<LogicalType>` overriding the logical type
+    pub logical_type: Option<proc_macro2::TokenStream>,
+}
+
+impl FieldAttrs {
+    pub fn parse(field: &syn::Field) -> syn::Result<Self> {
+        let mut out = Self::default();
+        for_each_meta(&field.attrs, |meta| {
+            if meta.path.is_ident("rename") {
+                out.rename = Some(parse_string(&meta)?);
+            } else if meta.path.is_ident("skip") {
+                out.skip = true;
+            } else if meta.path.is_ident("logical_type") {
+                let value = parse_string(&meta)?;
+                out.logical_type = Some(logical_type(&value).ok_or_else(|| {
+                    meta.error(format!(
+                        "unknown logical type '{value}', expected one of {}",
+                        LOGICAL_TYPES.join(", ")
+                    ))
+                })?);
+            } else {
+                return Err(meta.error("unknown parquet field attribute"));
+            }
+            Ok(())
+        })?;
+        Ok(out)
+    }
+
+    /// Returns the column name of `field`
+    pub fn name(&self, field: &syn::Field) -> String {
+        match (&self.rename, &field.ident) {
+            (Some(name), _) => name.clone(),
+            (None, Some(ident)) => ident.to_string(),
+            (None, None) => unreachable!("tuple fields are rejected"),
+        }
+    }
+}
+
+/// How an enum is written, `#[parquet(repr = "string")]` (the default) or
+/// `#[parquet(repr = "int")]`
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq)]
+pub enum EnumRepr {
+    /// The variant name as a `BYTE_ARRAY` with the `ENUM` logical type
+    #[default]
+    String,
+    /// The discriminant as an `INT32`
+    Int,
+}
+
+impl EnumRepr {
+    pub fn parse(attrs: &[Attribute]) -> syn::Result<Self> {
+        let mut out = Self::default();
+        for_each_meta(attrs, |meta| {
+            if meta.path.is_ident("repr") {
+                out = match parse_string(&meta)?.as_str() {
+                    "string" => Self::String,
+                    "int" => Self::Int,
+                    _ => return Err(meta.error("expected repr = \"string\" or repr = \"int\"")),
+                };
+                Ok(())
+            } else {
+                Err(meta.error("unknown parquet enum attribute"))
+            }
+        })?;
+        Ok(out)
+    }
+}
+
+/// Returns the value of `#[parquet(rename = "...")]` of an enum variant
+pub fn variant_rename(variant: &syn::Variant) -> syn::Result<Option<String>> {
+    let mut out = None;
+    for_each_meta(&variant.attrs, |meta| {
+        if meta.path.is_ident("rename") {
+            out = Some(parse_string(&meta)?);
+            Ok(())
+        } else {
+            Err(meta.error("unknown parquet variant attribute"))
+        }
+    })?;
+    Ok(out)
+}
+
+/// Returns an error if any of `attrs` is a `#[parquet(...)]` attribute
+pub fn reject(attrs: &[Attribute], what: &str) -> syn::Result<()> {
+    match attrs.iter().find(|a| a.path().is_ident("parquet")) {
+        Some(attr) => Err(syn::Error::new_spanned(
+            attr,
+            format!("parquet attributes are not supported on {what}"),
+        )),
+        None => Ok(()),
+    }
+}
+
+const LOGICAL_TYPES: &[&str] = &[
+    "string",
+    "json",
+    "bson",
+    "enum",
+    "uuid",
+    "date",
+    "timestamp_millis",
+    "timestamp_micros",
+    "none",
+];
+
+fn logical_type(name: &str) -> Option<proc_macro2::TokenStream> {
+    Some(match name {
+        "string" => quote! { Some(::parquet::basic::LogicalType::String) },
+        "json" => quote! { Some(::parquet::basic::LogicalType::Json) },
+        "bson" => quote! { Some(::parquet::basic::LogicalType::Bson) },
+        "enum" => quote! { Some(::parquet::basic::LogicalType::Enum) },
+        "uuid" => quote! { Some(::parquet::basic::LogicalType::Uuid) },
+        "date" => quote! { Some(::parquet::basic::LogicalType::Date) },
+        "timestamp_millis" => quote! {
+            Some(::parquet::basic::LogicalType::Timestamp {
+                is_adjusted_to_u_t_c: false,
+                unit: ::parquet::basic::TimeUnit::MILLIS,
+            })
+        },
+        "timestamp_micros" => quote! {
+            Some(::parquet::basic::LogicalType::Timestamp {
+                is_adjusted_to_u_t_c: false,
+                unit: ::parquet::basic::TimeUnit::MICROS,
+            })
+        },
+        "none" => quote! { None },
+        _ => return None,
+    })
+}
+
+fn for_each_meta(
+    attrs: &[Attribute],
+    mut f: impl FnMut(ParseNestedMeta<'_>) -> syn::Result<()>,
+) -> syn::Result<()> {
+    for attr in attrs.iter().filter(|a| a.path().is_ident("parquet")) {
+        attr.parse_nested_meta(&mut f)?;
+    }
+    Ok(())
+}
+
+/// Parses the value of `name = "value"`, also accepting `name = value`
+fn parse_string(meta: &ParseNestedMeta<'_>) -> syn::Result<String> {
+    match meta.value()?.parse::<Expr>()? {
+        Expr::Lit(ExprLit {
+            lit: Lit::Str(s), ..
+        }) => Ok(s.value()),
+        Expr::Path(ExprPath { path, .. }) if path.get_ident().is_some() => {
+            Ok(path.get_ident().unwrap().to_string())
+        }
+        other => Err(syn::Error::new_spanned(other, "expected a string literal")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_attrs() {
+        let field: syn::Field = syn::parse_quote! {
+            #[parquet(rename = "b", logical_type = json)]
+            a: String
+        };
+        let attrs = FieldAttrs::parse(&field).unwrap();
+        assert_eq!(attrs.name(&field), "b");
+        assert!(!attrs.skip);
+        assert_eq!(
+            attrs.logical_type.unwrap().to_string(),
+            quote! { Some(::parquet::basic::LogicalType::Json) }.to_string()
+        );
+
+        let field: syn::Field = syn::parse_quote! {
+            #[parquet(logical_type = "decimal")]
+            a: String
+        };
+        let err = FieldAttrs::parse(&field).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("unknown logical type 'decimal'")
+        );
+
+        let field: syn::Field = syn::parse_quote! {
+            #[parquet(flatten)]
+            a: String
+        };
+        let err = FieldAttrs::parse(&field).unwrap_err();
+        assert_eq!(err.to_string(), "unknown parquet field attribute");
+    }
+
+    #[test]
+    fn test_enum_repr() {
+        let input: syn::DeriveInput = syn::parse_quote! {
+            #[parquet(repr = "int")]
+            enum E { A }
+        };
+        assert_eq!(EnumRepr::parse(&input.attrs).unwrap(), EnumRepr::Int);
+
+        let input: syn::DeriveInput = syn::parse_quote! {
+            enum E { A }
+        };
+        assert_eq!(EnumRepr::parse(&input.attrs).unwrap(), EnumRepr::String);
+    }
+}
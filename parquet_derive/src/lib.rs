@@ -16,7 +16,8 @@
 // under the License.
 
 //! This crate provides a procedural macro to derive
-//! implementations of a RecordWriter and RecordReader
+//! implementations of a RecordWriter and RecordReader, and
+//! of ParquetField for types nested within them
 
 #![doc(
     html_logo_url = "https://raw.githubusercontent.com/apache/parquet-format/25f05e73d8cd7f5c83532ce51cb4f4de8ba5f2a2/logo/parquet-logos_1.svg",
@@ -34,16 +35,23 @@ extern crate quote;
 
 extern crate parquet;
 
-use ::syn::{Data, DataStruct, DeriveInput, parse_macro_input};
+use ::syn::{DeriveInput, parse_macro_input};
 
+use attributes::FieldAttrs;
+
+mod attributes;
+mod nested;
 mod parquet_field;
 
-/// Derive flat, simple RecordWriter implementations.
+/// Derive RecordWriter implementations.
 ///
 /// Works by parsing a struct tagged with `#[derive(ParquetRecordWriter)]` and emitting
 /// the correct writing code for each field of the struct. Column writers
 /// are generated in the order they are defined.
 ///
+/// Fields may be nested structs, lists, maps and enums, see [`macro@ParquetField`],
+/// which also describes the supported `#[parquet(...)]` field attributes.
+///
 /// It is up to the programmer to keep the order of the struct
 /// fields lined up with the schema.
 ///
@@ -125,76 +133,93 @@ mod parquet_field;
 /// }
 /// ```
 ///
-#[proc_macro_derive(ParquetRecordWriter)]
+#[proc_macro_derive(ParquetRecordWriter, attributes(parquet))]
 pub fn parquet_record_writer(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input: DeriveInput = parse_macro_input!(input as DeriveInput);
-    let fields = match input.data {
-        Data::Struct(DataStruct { fields, .. }) => fields,
-        Data::Enum(_) => unimplemented!("Enum currently is not supported"),
-        Data::Union(_) => unimplemented!("Union currently is not supported"),
-    };
-
-    let field_infos: Vec<_> = fields.iter().map(parquet_field::Field::from).collect();
-
-    let writer_snippets: Vec<proc_macro2::TokenStream> =
-        field_infos.iter().map(|x| x.writer_snippet()).collect();
-
-    let derived_for = input.ident;
-    let generics = input.generics;
-
-    let field_types: Vec<proc_macro2::TokenStream> =
-        field_infos.iter().map(|x| x.parquet_type()).collect();
-
-    (quote! {
-    impl #generics ::parquet::record::RecordWriter<#derived_for #generics> for &[#derived_for #generics] {
-      fn write_to_row_group<W: ::std::io::Write + Send>(
-        &self,
-        row_group_writer: &mut ::parquet::file::writer::SerializedRowGroupWriter<'_, W>
-      ) -> ::std::result::Result<(), ::parquet::errors::ParquetError> {
-        use ::parquet::column::writer::ColumnWriter;
-
-        let mut row_group_writer = row_group_writer;
-        let records = &self; // Used by all the writer snippets to be more clear
-
-        #(
-          {
-              let mut some_column_writer = row_group_writer.next_column().unwrap();
-              if let Some(mut column_writer) = some_column_writer {
-                  #writer_snippets
-                  column_writer.close()?;
-              } else {
-                  return Err(::parquet::errors::ParquetError::General("Failed to get next column".into()))
-              }
-          }
-        );*
+    record_writer(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-        Ok(())
-      }
+fn record_writer(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut writer_snippets: Vec<proc_macro2::TokenStream> = vec![];
+    let mut field_types: Vec<proc_macro2::TokenStream> = vec![];
+    for field in nested::named_fields(input, "ParquetRecordWriter")? {
+        let attrs = FieldAttrs::parse(field)?;
+        if attrs.skip {
+            continue;
+        }
+        let name = attrs.name(field);
 
-      fn schema(&self) -> ::std::result::Result<::parquet::schema::types::TypePtr, ::parquet::errors::ParquetError> {
-        use ::parquet::schema::types::Type as ParquetType;
-        use ::parquet::schema::types::TypePtr;
-        use ::parquet::basic::LogicalType;
-
-        let mut fields: ::std::vec::Vec<TypePtr> = ::std::vec::Vec::new();
-        #(
-          #field_types
-        );*;
-        let group = ParquetType::group_type_builder("rust_schema")
-          .with_fields(fields)
-          .build()?;
-        Ok(group.into())
-      }
+        if nested::is_nested(&field.ty)? {
+            writer_snippets.push(nested::writer_snippet(field, &name, &attrs));
+            field_types.push(nested::parquet_type(field, &name, &attrs));
+        } else {
+            let field_info = parquet_field::Field::from(field);
+            let writer_snippet = field_info.writer_snippet()?;
+            writer_snippets.push(quote! {
+                let mut some_column_writer = row_group_writer.next_column().unwrap();
+                if let Some(mut column_writer) = some_column_writer {
+                    #writer_snippet
+                    column_writer.close()?;
+                } else {
+                    return Err(::parquet::errors::ParquetError::General("Failed to get next column".into()))
+                }
+            });
+            field_types.push(field_info.parquet_type(&name, attrs.logical_type.as_ref()));
+        }
     }
-  }).into()
+
+    let derived_for = &input.ident;
+    let generics = &input.generics;
+
+    Ok(quote! {
+      impl #generics ::parquet::record::RecordWriter<#derived_for #generics> for &[#derived_for #generics] {
+        fn write_to_row_group<W: ::std::io::Write + Send>(
+          &self,
+          row_group_writer: &mut ::parquet::file::writer::SerializedRowGroupWriter<'_, W>
+        ) -> ::std::result::Result<(), ::parquet::errors::ParquetError> {
+          use ::parquet::column::writer::ColumnWriter;
+
+          let mut row_group_writer = row_group_writer;
+          let records = &self; // Used by all the writer snippets to be more clear
+
+          #(
+            {
+                #writer_snippets
+            }
+          );*
+
+          Ok(())
+        }
+
+        fn schema(&self) -> ::std::result::Result<::parquet::schema::types::TypePtr, ::parquet::errors::ParquetError> {
+          use ::parquet::schema::types::Type as ParquetType;
+          use ::parquet::schema::types::TypePtr;
+          use ::parquet::basic::LogicalType;
+
+          let mut fields: ::std::vec::Vec<TypePtr> = ::std::vec::Vec::new();
+          #(
+            fields.push(#field_types?.into())
+          );*;
+          let group = ParquetType::group_type_builder("rust_schema")
+            .with_fields(fields)
+            .build()?;
+          Ok(group.into())
+        }
+      }
+    })
 }
 
-/// Derive flat, simple RecordReader implementations.
+/// Derive RecordReader implementations.
 ///
 /// Works by parsing a struct tagged with `#[derive(ParquetRecordReader)]` and emitting
 /// the correct writing code for each field of the struct. Column readers
 /// are generated by matching names in the schema to the names in the struct.
 ///
+/// Fields may be nested structs, lists, maps and enums, see [`macro@ParquetField`],
+/// which also describes the supported `#[parquet(...)]` field attributes.
+///
 /// It is up to the programmer to ensure the names in the struct
 /// fields line up with the schema.
 ///
@@ -223,70 +248,193 @@ pub fn parquet_record_writer(input: proc_macro::TokenStream) -> proc_macro::Toke
 /// }
 /// ```
 ///
-#[proc_macro_derive(ParquetRecordReader)]
+#[proc_macro_derive(ParquetRecordReader, attributes(parquet))]
 pub fn parquet_record_reader(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     let input: DeriveInput = parse_macro_input!(input as DeriveInput);
-    let fields = match input.data {
-        Data::Struct(DataStruct { fields, .. }) => fields,
-        Data::Enum(_) => unimplemented!("Enum currently is not supported"),
-        Data::Union(_) => unimplemented!("Union currently is not supported"),
-    };
-
-    let field_infos: Vec<_> = fields.iter().map(parquet_field::Field::from).collect();
-    let field_names: Vec<_> = fields.iter().map(|f| f.ident.clone()).collect();
-    let reader_snippets: Vec<proc_macro2::TokenStream> =
-        field_infos.iter().map(|x| x.reader_snippet()).collect();
-
-    let derived_for = input.ident;
-    let generics = input.generics;
-
-    (quote! {
-
-    impl #generics ::parquet::record::RecordReader<#derived_for #generics> for Vec<#derived_for #generics> {
-      fn read_from_row_group(
-        &mut self,
-        row_group_reader: &mut dyn ::parquet::file::reader::RowGroupReader,
-        num_records: usize,
-      ) -> ::std::result::Result<(), ::parquet::errors::ParquetError> {
-        use ::parquet::column::reader::ColumnReader;
-
-        let mut row_group_reader = row_group_reader;
-
-        // key: parquet file column name, value: column index
-        let mut name_to_index = std::collections::HashMap::new();
-        for (idx, col) in row_group_reader.metadata().schema_descr().columns().iter().enumerate() {
-            name_to_index.insert(col.name().to_string(), idx);
-        }
+    record_reader(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
 
-        for _ in 0..num_records {
-          self.push(#derived_for {
-            #(
-              #field_names: Default::default()
-            ),*
-          })
+fn record_reader(input: &DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let mut field_names = vec![];
+    let mut reader_snippets: Vec<proc_macro2::TokenStream> = vec![];
+    for field in nested::named_fields(input, "ParquetRecordReader")? {
+        field_names.push(field.ident.clone());
+        let attrs = FieldAttrs::parse(field)?;
+        if attrs.skip {
+            continue;
         }
+        let name = attrs.name(field);
 
-        let records = self; // Used by all the reader snippets to be more clear
+        // The flat reader snippets only support required primitives
+        let flat = match &field.ty {
+            syn::Type::Path(_) => !nested::is_nested(&field.ty)? && !is_option(&field.ty),
+            _ => false,
+        };
+        if !flat {
+            reader_snippets.push(nested::reader_snippet(field, &name)?);
+            continue;
+        }
 
-        #(
-          {
-              let idx: usize = match name_to_index.get(stringify!(#field_names)) {
-                Some(&col_idx) => col_idx,
-                None => {
-                  let error_msg = format!("column name '{}' is not found in parquet file!", stringify!(#field_names));
-                  return Err(::parquet::errors::ParquetError::General(error_msg));
-                }
-              };
-              if let Ok(column_reader) = row_group_reader.get_column_reader(idx) {
-                  #reader_snippets
-              } else {
-                  return Err(::parquet::errors::ParquetError::General("Failed to get next column".into()))
+        let reader_snippet = parquet_field::Field::from(field).reader_snippet();
+        reader_snippets.push(quote! {
+            let idx: usize = match name_to_index.get(&[#name.to_string()][..]) {
+              Some(&col_idx) => col_idx,
+              None => {
+                let error_msg = format!("column name '{}' is not found in parquet file!", #name);
+                return Err(::parquet::errors::ParquetError::General(error_msg));
               }
+            };
+            if let Ok(column_reader) = row_group_reader.get_column_reader(idx) {
+                #reader_snippet
+            } else {
+                return Err(::parquet::errors::ParquetError::General("Failed to get next column".into()))
+            }
+        });
+    }
+
+    let derived_for = &input.ident;
+    let generics = &input.generics;
+
+    Ok(quote! {
+
+      impl #generics ::parquet::record::RecordReader<#derived_for #generics> for Vec<#derived_for #generics> {
+        fn read_from_row_group(
+          &mut self,
+          row_group_reader: &mut dyn ::parquet::file::reader::RowGroupReader,
+          num_records: usize,
+        ) -> ::std::result::Result<(), ::parquet::errors::ParquetError> {
+          use ::parquet::column::reader::ColumnReader;
+
+          let mut row_group_reader = row_group_reader;
+
+          // key: parquet file column path, value: column index
+          let mut name_to_index = std::collections::HashMap::new();
+          for (idx, col) in row_group_reader.metadata().schema_descr().columns().iter().enumerate() {
+              name_to_index.insert(col.path().parts().to_vec(), idx);
+          }
+
+          for _ in 0..num_records {
+            self.push(#derived_for {
+              #(
+                #field_names: Default::default()
+              ),*
+            })
           }
-        );*
 
-        Ok(())
+          let records = self; // Used by all the reader snippets to be more clear
+
+          #(
+            {
+                #reader_snippets
+            }
+          );*
+
+          Ok(())
+        }
       }
+    })
+}
+
+/// Derive a `ParquetField` implementation, allowing a type to be used as a nested
+/// field of a type deriving [`ParquetRecordWriter`] or [`ParquetRecordReader`].
+///
+/// Structs with named fields are written as groups, whose fields may themselves be
+/// primitives, `Option`, `Vec` (as a `LIST`), `HashMap` or `BTreeMap` (as a `MAP`),
+/// or other types deriving `ParquetField`.
+///
+/// Enums with only unit variants are written as their variant names, as a
+/// `BYTE_ARRAY` with the `ENUM` logical type, or with `#[parquet(repr = "int")]` as
+/// their discriminants, as an `INT32`. Variants may be renamed with
+/// `#[parquet(rename = "...")]`.
+///
+/// As [`ParquetRecordReader`] fills records with `Default::default()` before reading
+/// them, nested types must also implement `Default` to be read.
+///
+/// The following attributes are supported on fields, for this and the record derives:
+///
+/// * `#[parquet(rename = "name")]`: use `name` as the column name
+/// * `#[parquet(skip)]`: neither write nor read the field, which is read as `Default::default()`
+/// * `#[parquet(logical_type = "json")]`: override the logical type of a primitive
+///   field, one of `string`, `json`, `bson`, `enum`, `uuid`, `date`, `timestamp_millis`,
+///   `timestamp_micros` or `none`
+///
+/// Example:
+///
+/// ```rust
+/// use parquet::file::properties::WriterProperties;
+/// use parquet::file::reader::{FileReader, SerializedFileReader};
+/// use parquet::file::writer::SerializedFileWriter;
+/// use parquet::record::{RecordReader, RecordWriter};
+/// use parquet_derive::{ParquetField, ParquetRecordReader, ParquetRecordWriter};
+/// use std::collections::BTreeMap;
+/// use std::fs::File;
+/// use std::sync::Arc;
+///
+/// #[derive(Debug, Default, PartialEq, ParquetField)]
+/// enum Unit {
+///     #[default]
+///     #[parquet(rename = "m")]
+///     Metre,
+///     #[parquet(rename = "s")]
+///     Second,
+/// }
+///
+/// #[derive(Debug, Default, PartialEq, ParquetField)]
+/// struct Measurement {
+///     value: f64,
+///     unit: Unit,
+/// }
+///
+/// #[derive(Debug, PartialEq, ParquetRecordWriter, ParquetRecordReader)]
+/// struct Experiment {
+///     #[parquet(rename = "experiment_name")]
+///     name: String,
+///     measurements: Vec<Measurement>,
+///     tags: BTreeMap<String, String>,
+///     #[parquet(skip)]
+///     cached: Option<usize>,
+/// }
+///
+/// let experiments = vec![Experiment {
+///     name: "pendulum".to_string(),
+///     measurements: vec![
+///         Measurement { value: 1.5, unit: Unit::Metre },
+///         Measurement { value: 2.4, unit: Unit::Second },
+///     ],
+///     tags: BTreeMap::from([("lab".to_string(), "b".to_string())]),
+///     cached: None,
+/// }];
+///
+/// let path = std::env::temp_dir().join("experiments.parquet");
+/// let schema = experiments.as_slice().schema().unwrap();
+/// let props = Arc::new(WriterProperties::builder().build());
+/// let file = File::create(&path).unwrap();
+/// let mut writer = SerializedFileWriter::new(file, schema, props).unwrap();
+/// let mut row_group = writer.next_row_group().unwrap();
+/// experiments.as_slice().write_to_row_group(&mut row_group).unwrap();
+/// row_group.close().unwrap();
+/// writer.close().unwrap();
+///
+/// let reader = SerializedFileReader::new(File::open(&path).unwrap()).unwrap();
+/// let mut row_group = reader.get_row_group(0).unwrap();
+/// let mut read: Vec<Experiment> = vec![];
+/// read.read_from_row_group(&mut *row_group, 1).unwrap();
+/// assert_eq!(read, experiments);
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[proc_macro_derive(ParquetField, attributes(parquet))]
+pub fn parquet_field(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    let input: DeriveInput = parse_macro_input!(input as DeriveInput);
+    nested::derive_parquet_field(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+fn is_option(ty: &syn::Type) -> bool {
+    match ty {
+        syn::Type::Path(p) => p.path.segments.last().is_some_and(|s| s.ident == "Option"),
+        _ => false,
     }
-  }).into()
 }
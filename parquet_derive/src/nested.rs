@@ -0,0 +1,477 @@
+// Licensed to the Apache Software Foundation (ASF) under one
+// or more contributor license agreements.  See the NOTICE file
+// distributed with this work for additional information
+// regarding copyright ownership.  The ASF licenses this file
+// to you under the Apache License, Version 2.0 (the
+// "License"); you may not use this file except in compliance
+// with the License.  You may obtain a copy of the License at
+//
+//   http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing,
+// software distributed under the License is distributed on an
+// "AS IS" BASIS, WITHOUT WARRANTIES OR CONDITIONS OF ANY
+// KIND, either express or implied.  See the License for the
+// specific language governing permissions and limitations
+// under the License.
+
+//! Code generation for fields that are not flat primitives, which are written and
+//! read through `parquet::record::ParquetField`
+
+use crate::attributes::{EnumRepr, FieldAttrs, reject, variant_rename};
+use proc_macro2::TokenStream;
+use syn::spanned::Spanned;
+use syn::{Data, DataEnum, DataStruct, DeriveInput, Fields, GenericArgument, PathArguments};
+
+/// Types written as a single primitive column by the snippets in `parquet_field`
+const FLAT_TYPES: &[&str] = &[
+    "bool",
+    "u8",
+    "u16",
+    "u32",
+    "u64",
+    "usize",
+    "i8",
+    "i16",
+    "i32",
+    "i64",
+    "isize",
+    "f32",
+    "f64",
+    "String",
+    "str",
+    "Arc",
+    "NaiveDate",
+    "NaiveDateTime",
+    "Uuid",
+];
+
+/// Returns the named fields of the struct `input`, or an error naming `derive`
+pub fn named_fields<'a>(
+    input: &'a DeriveInput,
+    derive: &str,
+) -> syn::Result<impl Iterator<Item = &'a syn::Field>> {
+    reject(&input.attrs, "structs")?;
+    match &input.data {
+        Data::Struct(DataStruct {
+            fields: Fields::Named(fields),
+            ..
+        }) => Ok(fields.named.iter()),
+        Data::Struct(_) => Err(syn::Error::new(
+            input.ident.span(),
+            format!("{derive} only supports structs with named fields"),
+        )),
+        Data::Enum(_) => Err(syn::Error::new(
+            input.ident.span(),
+            format!("{derive} does not support enums, derive ParquetField for use as a field"),
+        )),
+        Data::Union(_) => Err(syn::Error::new(
+            input.ident.span(),
+            format!("{derive} does not support unions"),
+        )),
+    }
+}
+
+/// Returns the single generic type argument of `segment`, if any
+fn generic_argument(segment: &syn::PathSegment) -> Option<&syn::Type> {
+    match &segment.arguments {
+        PathArguments::AngleBracketed(args) if args.args.len() == 1 => match &args.args[0] {
+            GenericArgument::Type(ty) => Some(ty),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn last_segment(ty: &syn::Type) -> Option<&syn::PathSegment> {
+    match ty {
+        syn::Type::Path(p) if p.qself.is_none() => p.path.segments.last(),
+        _ => None,
+    }
+}
+
+fn is_u8(ty: &syn::Type) -> bool {
+    last_segment(ty).is_some_and(|s| s.ident == "u8" && s.arguments.is_empty())
+}
+
+/// Returns true if a field of type `ty` must be written through `ParquetField`,
+/// false if it is a flat primitive handled by the snippets in `parquet_field`
+pub fn is_nested(ty: &syn::Type) -> syn::Result<bool> {
+    match ty {
+        syn::Type::Reference(r) => is_nested(&r.elem),
+        syn::Type::Array(syn::TypeArray { elem, .. })
+        | syn::Type::Slice(syn::TypeSlice { elem, .. })
+            if is_u8(elem) =>
+        {
+            Ok(false)
+        }
+        syn::Type::Array(_) | syn::Type::Slice(_) => Err(syn::Error::new(
+            ty.span(),
+            "only arrays and slices of u8 are supported, use Vec<T> for lists",
+        )),
+        syn::Type::Path(_) => {
+            let segment = last_segment(ty).ok_or_else(|| unsupported(ty))?;
+            let argument = generic_argument(segment);
+            match (segment.ident.to_string().as_str(), argument) {
+                ("Option", Some(inner)) => match last_segment(inner) {
+                    Some(s) if s.ident == "Option" => Err(syn::Error::new(
+                        ty.span(),
+                        "Option<Option<_>> is not supported, as parquet cannot distinguish the nulls",
+                    )),
+                    _ => is_nested(inner),
+                },
+                ("Vec", Some(inner)) => Ok(!is_u8(inner)),
+                (name, _) => Ok(!FLAT_TYPES.contains(&name)),
+            }
+        }
+        _ => Err(unsupported(ty)),
+    }
+}
+
+fn unsupported(ty: &syn::Type) -> syn::Error {
+    syn::Error::new(ty.span(), "unsupported field type")
+}
+
+/// Returns `ty` without any leading references
+fn strip_references(ty: &syn::Type) -> &syn::Type {
+    match ty {
+        syn::Type::Reference(r) => strip_references(&r.elem),
+        _ => ty,
+    }
+}
+
+/// Returns `&expr`, dereferencing any references of `ty` the type of `expr`
+fn borrow(ty: &syn::Type, expr: TokenStream) -> TokenStream {
+    match ty {
+        syn::Type::Reference(r) => borrow(&r.elem, quote! { *#expr }),
+        _ => quote! { &#expr },
+    }
+}
+
+/// Returns an expression evaluating to the `Result<Type>` of a field
+fn field_type(ty: &syn::Type, name: &str, attrs: &FieldAttrs) -> TokenStream {
+    let ty = strip_references(ty);
+    let parquet_type = quote! {
+        <#ty as ::parquet::record::ParquetField>::parquet_type(
+            #name,
+            ::parquet::basic::Repetition::REQUIRED,
+        )
+    };
+    match &attrs.logical_type {
+        Some(logical_type) => quote! {
+            #parquet_type.and_then(|t| {
+                ::parquet::record::parquet_field::with_logical_type(t, #logical_type)
+            })
+        },
+        None => parquet_type,
+    }
+}
+
+/// Returns an expression evaluating to the `Result<Type>` of the nested `field`
+pub fn parquet_type(field: &syn::Field, name: &str, attrs: &FieldAttrs) -> TokenStream {
+    field_type(&field.ty, name, attrs)
+}
+
+/// Generates code writing the nested `field` of each of `records` to the next
+/// columns of `row_group_writer`
+pub fn writer_snippet(field: &syn::Field, name: &str, attrs: &FieldAttrs) -> TokenStream {
+    let ident = &field.ident;
+    let ty = strip_references(&field.ty);
+    let parquet_type = field_type(&field.ty, name, attrs);
+    let value = borrow(&field.ty, quote! { rec.#ident });
+    quote! {
+        let ty = #parquet_type?;
+        let mut columns = ::parquet::record::LeafColumn::for_type(&ty);
+        for rec in records.iter() {
+            <#ty as ::parquet::record::ParquetField>::write(
+                #value,
+                &mut columns,
+                ::parquet::record::Levels::default(),
+            )?;
+        }
+        ::parquet::record::parquet_field::write_columns(&columns, row_group_writer)?;
+    }
+}
+
+/// Generates code reading the field `name` of the first `num_records` of `records`
+pub fn reader_snippet(field: &syn::Field, name: &str) -> syn::Result<TokenStream> {
+    let ident = &field.ident;
+    let ty = &field.ty;
+    if let syn::Type::Reference(_) = ty {
+        return Err(syn::Error::new(
+            ty.span(),
+            "ParquetRecordReader does not support references",
+        ));
+    }
+    Ok(quote! {
+        let (node, mut columns) = ::parquet::record::parquet_field::read_field_columns(
+            &*row_group_reader,
+            #name,
+            num_records,
+        )?;
+        let levels = ::parquet::record::Levels::default().child(&node);
+        for r in &mut records[..num_records] {
+            r.#ident = <#ty as ::parquet::record::ParquetField>::read(&node, &mut columns, levels)?;
+        }
+    })
+}
+
+/// Implements `ParquetField` for a struct with named fields or a unit enum
+pub fn derive_parquet_field(input: &DeriveInput) -> syn::Result<TokenStream> {
+    match &input.data {
+        Data::Enum(data) => derive_enum(input, data),
+        _ => derive_struct(input),
+    }
+}
+
+fn derive_struct(input: &DeriveInput) -> syn::Result<TokenStream> {
+    let mut leaf_counts = vec![];
+    let mut types = vec![];
+    let mut writes = vec![];
+    let mut reads = vec![];
+    for field in named_fields(input, "ParquetField")? {
+        let attrs = FieldAttrs::parse(field)?;
+        let ident = &field.ident;
+        if attrs.skip {
+            reads.push(quote! { #ident: ::std::default::Default::default() });
+            continue;
+        }
+        // Validates the type
+        is_nested(&field.ty)?;
+        let name = attrs.name(field);
+        let ty = strip_references(&field.ty);
+        let value = borrow(&field.ty, quote! { self.#ident });
+
+        leaf_counts.push(quote! { <#ty as ::parquet::record::ParquetField>::leaf_count() });
+        types.push(field_type(&field.ty, &name, &attrs));
+        writes.push(quote! {
+            let count = <#ty as ::parquet::record::ParquetField>::leaf_count();
+            <#ty as ::parquet::record::ParquetField>::write(
+                #value,
+                &mut columns[offset..offset + count],
+                levels,
+            )?;
+            offset += count;
+        });
+        if let syn::Type::Reference(_) = &field.ty {
+            reads.push(quote! {
+                #ident: return Err(::parquet::errors::ParquetError::General(
+                    "Cannot read a struct containing references".into()
+                ))
+            });
+        } else {
+            reads.push(quote! {
+                #ident: {
+                    let (child, range) = ::parquet::record::parquet_field::child(node, #name)?;
+                    <#ty as ::parquet::record::ParquetField>::read(
+                        child,
+                        &mut columns[range],
+                        levels.child(child),
+                    )?
+                }
+            });
+        }
+    }
+
+    let derived_for = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::parquet::record::ParquetField for #derived_for #ty_generics #where_clause {
+            fn leaf_count() -> usize {
+                0 #(+ #leaf_counts)*
+            }
+
+            fn parquet_type(
+                name: &str,
+                repetition: ::parquet::basic::Repetition,
+            ) -> ::parquet::errors::Result<::parquet::schema::types::Type> {
+                ::parquet::schema::types::Type::group_type_builder(name)
+                    .with_repetition(repetition)
+                    .with_fields(vec![#(#types?.into()),*])
+                    .build()
+            }
+
+            #[allow(unused_variables, unused_mut, unused_assignments)]
+            fn write(
+                &self,
+                columns: &mut [::parquet::record::LeafColumn],
+                levels: ::parquet::record::Levels,
+            ) -> ::parquet::errors::Result<()> {
+                let mut offset = 0;
+                #(#writes)*
+                Ok(())
+            }
+
+            #[allow(unused_variables)]
+            fn read(
+                node: &::parquet::schema::types::Type,
+                columns: &mut [::parquet::record::LeafColumn],
+                levels: ::parquet::record::Levels,
+            ) -> ::parquet::errors::Result<Self> {
+                Ok(Self {
+                    #(#reads),*
+                })
+            }
+        }
+    })
+}
+
+fn derive_enum(input: &DeriveInput, data: &DataEnum) -> syn::Result<TokenStream> {
+    let repr = EnumRepr::parse(&input.attrs)?;
+    let mut idents = vec![];
+    let mut names = vec![];
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new(
+                variant.span(),
+                "ParquetField only supports enums with unit variants",
+            ));
+        }
+        let name = variant_rename(variant)?.unwrap_or_else(|| variant.ident.to_string());
+        idents.push(&variant.ident);
+        names.push(name);
+    }
+    if repr == EnumRepr::Int {
+        for variant in &data.variants {
+            reject(&variant.attrs, "variants of enums with repr = \"int\"")?;
+        }
+    }
+
+    let derived_for = &input.ident;
+    let enum_name = derived_for.to_string();
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let body = match repr {
+        EnumRepr::String => quote! {
+            fn parquet_type(
+                name: &str,
+                repetition: ::parquet::basic::Repetition,
+            ) -> ::parquet::errors::Result<::parquet::schema::types::Type> {
+                ::parquet::record::parquet_field::with_logical_type(
+                    <String as ::parquet::record::ParquetField>::parquet_type(name, repetition)?,
+                    Some(::parquet::basic::LogicalType::Enum),
+                )
+            }
+
+            fn write(
+                &self,
+                columns: &mut [::parquet::record::LeafColumn],
+                levels: ::parquet::record::Levels,
+            ) -> ::parquet::errors::Result<()> {
+                let name = match self {
+                    #(Self::#idents => #names,)*
+                };
+                ::parquet::record::ParquetField::write(&name.to_string(), columns, levels)
+            }
+
+            fn read(
+                node: &::parquet::schema::types::Type,
+                columns: &mut [::parquet::record::LeafColumn],
+                levels: ::parquet::record::Levels,
+            ) -> ::parquet::errors::Result<Self> {
+                let name = <String as ::parquet::record::ParquetField>::read(node, columns, levels)?;
+                match name.as_str() {
+                    #(#names => Ok(Self::#idents),)*
+                    other => Err(::parquet::errors::ParquetError::General(format!(
+                        "Unknown variant '{}' of {}", other, #enum_name
+                    ))),
+                }
+            }
+        },
+        EnumRepr::Int => quote! {
+            fn parquet_type(
+                name: &str,
+                repetition: ::parquet::basic::Repetition,
+            ) -> ::parquet::errors::Result<::parquet::schema::types::Type> {
+                <i32 as ::parquet::record::ParquetField>::parquet_type(name, repetition)
+            }
+
+            fn write(
+                &self,
+                columns: &mut [::parquet::record::LeafColumn],
+                levels: ::parquet::record::Levels,
+            ) -> ::parquet::errors::Result<()> {
+                let value = match self {
+                    #(Self::#idents => Self::#idents as i32,)*
+                };
+                ::parquet::record::ParquetField::write(&value, columns, levels)
+            }
+
+            fn read(
+                node: &::parquet::schema::types::Type,
+                columns: &mut [::parquet::record::LeafColumn],
+                levels: ::parquet::record::Levels,
+            ) -> ::parquet::errors::Result<Self> {
+                let value = <i32 as ::parquet::record::ParquetField>::read(node, columns, levels)?;
+                #(
+                    if value == Self::#idents as i32 {
+                        return Ok(Self::#idents);
+                    }
+                )*
+                Err(::parquet::errors::ParquetError::General(format!(
+                    "Unknown discriminant {} of {}", value, #enum_name
+                )))
+            }
+        },
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::parquet::record::ParquetField for #derived_for #ty_generics #where_clause {
+            #body
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nested(ty: syn::Type) -> syn::Result<bool> {
+        is_nested(&ty)
+    }
+
+    #[test]
+    fn test_is_nested() {
+        assert!(!nested(syn::parse_quote!(i32)).unwrap());
+        assert!(!nested(syn::parse_quote!(&'a Option<&'a str>)).unwrap());
+        assert!(!nested(syn::parse_quote!(Vec<u8>)).unwrap());
+        assert!(!nested(syn::parse_quote!(Option<[u8; 4]>)).unwrap());
+        assert!(!nested(syn::parse_quote!(std::sync::Arc<str>)).unwrap());
+        assert!(!nested(syn::parse_quote!(chrono::NaiveDateTime)).unwrap());
+
+        assert!(nested(syn::parse_quote!(Vec<i32>)).unwrap());
+        assert!(nested(syn::parse_quote!(Option<Vec<Vec<u8>>>)).unwrap());
+        assert!(nested(syn::parse_quote!(HashMap<String, i32>)).unwrap());
+        assert!(nested(syn::parse_quote!(Inner)).unwrap());
+
+        let err = nested(syn::parse_quote!(Option<Option<i32>>)).unwrap_err();
+        assert!(
+            err.to_string()
+                .starts_with("Option<Option<_>> is not supported")
+        );
+        let err = nested(syn::parse_quote!([i32; 4])).unwrap_err();
+        assert!(err.to_string().starts_with("only arrays and slices of u8"));
+        let err = nested(syn::parse_quote!((i32, i32))).unwrap_err();
+        assert_eq!(err.to_string(), "unsupported field type");
+    }
+
+    #[test]
+    fn test_derive_errors() {
+        let input: DeriveInput = syn::parse_quote! {
+            enum E { A(i32) }
+        };
+        let err = derive_parquet_field(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ParquetField only supports enums with unit variants"
+        );
+
+        let input: DeriveInput = syn::parse_quote! {
+            struct S(i32);
+        };
+        let err = derive_parquet_field(&input).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "ParquetField only supports structs with named fields"
+        );
+    }
+}
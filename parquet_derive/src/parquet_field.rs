@@ -79,8 +79,8 @@ impl Field {
     /// }
     ///
     /// because this parsing logic is not sophisticated enough for definition
-    /// levels beyond 2.
-    pub fn writer_snippet(&self) -> proc_macro2::TokenStream {
+    /// levels beyond 2. Returns an error for any other shape of field.
+    pub fn writer_snippet(&self) -> syn::Result<proc_macro2::TokenStream> {
         let ident = &self.ident;
         let column_writer = self.ty.column_writer();
 
@@ -90,13 +90,13 @@ impl Field {
                 Type::TypePath(_) => self.option_into_vals(),
                 Type::Reference(_, ref second_type) => match **second_type {
                     Type::TypePath(_) => self.option_into_vals(),
-                    _ => unimplemented!("Unsupported type encountered"),
+                    _ => return Err(self.unsupported()),
                 },
                 Type::Vec(ref first_type) => match **first_type {
                     Type::TypePath(_) => self.option_into_vals(),
-                    _ => unimplemented!("Unsupported type encountered"),
+                    _ => return Err(self.unsupported()),
                 },
-                ref f => unimplemented!("Unsupported: {:#?}", f),
+                _ => return Err(self.unsupported()),
             },
             Type::Reference(_, first_type) => match **first_type {
                 Type::TypePath(_) => self.copied_direct_vals(),
@@ -106,40 +106,40 @@ impl Field {
                         Type::TypePath(_) => self.option_into_vals(),
                         Type::Slice(ref second_type) => match **second_type {
                             Type::TypePath(_) => self.option_into_vals(),
-                            ref f => unimplemented!("Unsupported: {:#?}", f),
+                            _ => return Err(self.unsupported()),
                         },
-                        _ => unimplemented!("Unsupported type encountered"),
+                        _ => return Err(self.unsupported()),
                     },
                     Type::Vec(ref first_type) => match **first_type {
                         Type::TypePath(_) => self.option_into_vals(),
-                        _ => unimplemented!("Unsupported type encountered"),
+                        _ => return Err(self.unsupported()),
                     },
-                    ref f => unimplemented!("Unsupported: {:#?}", f),
+                    _ => return Err(self.unsupported()),
                 },
                 Type::Slice(ref second_type) => match **second_type {
                     Type::TypePath(_) => self.copied_direct_vals(),
-                    ref f => unimplemented!("Unsupported: {:#?}", f),
+                    _ => return Err(self.unsupported()),
                 },
-                ref f => unimplemented!("Unsupported: {:#?}", f),
+                _ => return Err(self.unsupported()),
             },
             Type::Vec(first_type) => match **first_type {
                 Type::TypePath(_) => self.copied_direct_vals(),
-                ref f => unimplemented!("Unsupported: {:#?}", f),
+                _ => return Err(self.unsupported()),
             },
-            f => unimplemented!("Unsupported: {:#?}", f),
+            _ => return Err(self.unsupported()),
         };
 
         let definition_levels = match &self.ty {
             Type::TypePath(_) => None,
             Type::Option(first_type) => match **first_type {
                 Type::TypePath(_) => Some(self.optional_definition_levels()),
-                Type::Option(_) => unimplemented!("Unsupported nesting encountered"),
+                Type::Option(_) => return Err(self.unsupported()),
                 Type::Reference(_, ref second_type)
                 | Type::Vec(ref second_type)
                 | Type::Array(ref second_type, _)
                 | Type::Slice(ref second_type) => match **second_type {
                     Type::TypePath(_) => Some(self.optional_definition_levels()),
-                    _ => unimplemented!("Unsupported nesting encountered"),
+                    _ => return Err(self.unsupported()),
                 },
             },
             Type::Reference(_, first_type)
@@ -153,9 +153,9 @@ impl Field {
                     Type::TypePath(_) => None,
                     Type::Reference(_, ref third_type) => match **third_type {
                         Type::TypePath(_) => None,
-                        _ => unimplemented!("Unsupported definition encountered"),
+                        _ => return Err(self.unsupported()),
                     },
-                    _ => unimplemented!("Unsupported definition encountered"),
+                    _ => return Err(self.unsupported()),
                 },
                 Type::Reference(_, ref second_type) | Type::Option(ref second_type) => {
                     match **second_type {
@@ -166,19 +166,19 @@ impl Field {
                             Type::TypePath(_) => Some(self.optional_definition_levels()),
                             Type::Reference(_, ref fourth_type) => match **fourth_type {
                                 Type::TypePath(_) => Some(self.optional_definition_levels()),
-                                _ => unimplemented!("Unsupported definition encountered"),
+                                _ => return Err(self.unsupported()),
                             },
-                            _ => unimplemented!("Unsupported definition encountered"),
+                            _ => return Err(self.unsupported()),
                         },
                         Type::Reference(_, ref third_type) => match **third_type {
                             Type::TypePath(_) => Some(self.optional_definition_levels()),
                             Type::Slice(ref fourth_type) => match **fourth_type {
                                 Type::TypePath(_) => Some(self.optional_definition_levels()),
-                                _ => unimplemented!("Unsupported definition encountered"),
+                                _ => return Err(self.unsupported()),
                             },
-                            _ => unimplemented!("Unsupported definition encountered"),
+                            _ => return Err(self.unsupported()),
                         },
-                        _ => unimplemented!("Unsupported definition encountered"),
+                        _ => return Err(self.unsupported()),
                     }
                 }
             },
@@ -208,7 +208,7 @@ impl Field {
             }
         };
 
-        quote! {
+        Ok(quote! {
             {
                 #definition_levels
 
@@ -216,7 +216,14 @@ impl Field {
 
                 #write_batch_expr
             }
-        }
+        })
+    }
+
+    fn unsupported(&self) -> syn::Error {
+        syn::Error::new(
+            self.ident.span(),
+            "unsupported field type, only a single level of references and options is supported",
+        )
     }
 
     /// Takes the parsed field of the struct and emits a valid
@@ -289,11 +296,13 @@ impl Field {
         }
     }
 
-    pub fn parquet_type(&self) -> proc_macro2::TokenStream {
-        // TODO: Support group types
-        // TODO: Add length if dealing with fixedlenbinary
-
-        let field_name = &self.ident.to_string();
+    /// Returns an expression evaluating to the `Result<Type>` of this field, called
+    /// `field_name` and annotated with `logical_type` if specified
+    pub fn parquet_type(
+        &self,
+        field_name: &str,
+        logical_type: Option<&proc_macro2::TokenStream>,
+    ) -> proc_macro2::TokenStream {
         let physical_type = match self.ty.physical_type() {
             parquet::basic::Type::BOOLEAN => quote! {
                 ::parquet::basic::Type::BOOLEAN
@@ -320,9 +329,11 @@ impl Field {
                 ::parquet::basic::Type::FIXED_LEN_BYTE_ARRAY
             },
         };
-        let logical_type = self.ty.logical_type();
+        let (logical_type, converted_type) = match logical_type {
+            Some(logical_type) => (logical_type.clone(), None),
+            None => (self.ty.logical_type(), self.ty.converted_type()),
+        };
         let repetition = self.ty.repetition();
-        let converted_type = self.ty.converted_type();
         let length = self.ty.length();
 
         let mut builder = quote! {
@@ -339,7 +350,7 @@ impl Field {
             builder = quote! { #builder.with_length(#length) };
         }
 
-        quote! {  fields.push(#builder.build().unwrap().into()) }
+        quote! { #builder.build() }
     }
 
     fn option_into_vals(&self) -> proc_macro2::TokenStream {
@@ -851,7 +862,7 @@ mod test {
         let fields = extract_fields(snippet);
         let counter = Field::from(&fields[0]);
 
-        let snippet = counter.writer_snippet().to_string();
+        let snippet = counter.writer_snippet().unwrap().to_string();
         assert_eq!(snippet,
                    (quote!{
                         {
@@ -904,6 +915,24 @@ mod test {
         )
     }
 
+    #[test]
+    fn test_unsupported_writer_snippet() {
+        let snippet: proc_macro2::TokenStream = quote! {
+          struct ABoringStruct<'a> {
+            double_ref: &'a &'a str,
+            maybe_double_ref: Option<&'a &'a str>,
+          }
+        };
+
+        for field in extract_fields(snippet) {
+            let err = Field::from(&field).writer_snippet().unwrap_err();
+            assert_eq!(
+                err.to_string(),
+                "unsupported field type, only a single level of references and options is supported"
+            );
+        }
+    }
+
     #[test]
     fn test_optional_to_writer_snippet() {
         let struct_def: proc_macro2::TokenStream = quote! {
@@ -917,7 +946,7 @@ mod test {
         let fields = extract_fields(struct_def);
 
         let optional = Field::from(&fields[0]);
-        let snippet = optional.writer_snippet();
+        let snippet = optional.writer_snippet().unwrap();
         assert_eq!(snippet.to_string(),
           (quote! {
           {
@@ -941,7 +970,7 @@ mod test {
           ).to_string());
 
         let optional = Field::from(&fields[1]);
-        let snippet = optional.writer_snippet();
+        let snippet = optional.writer_snippet().unwrap();
         assert_eq!(snippet.to_string(),
                    (quote!{
                    {
@@ -964,7 +993,7 @@ mod test {
         }).to_string());
 
         let optional = Field::from(&fields[2]);
-        let snippet = optional.writer_snippet();
+        let snippet = optional.writer_snippet().unwrap();
         assert_eq!(snippet.to_string(),
                    (quote!{
                     {
@@ -1258,7 +1287,7 @@ mod test {
 
         let fields = extract_fields(snippet);
         let when = Field::from(&fields[0]);
-        assert_eq!(when.writer_snippet().to_string(),(quote!{
+        assert_eq!(when.writer_snippet().unwrap().to_string(),(quote!{
             {
                 let vals : Vec<_> = records.iter().map(|rec| rec.henceforth.timestamp_millis() ).collect();
                 if let ColumnWriter::Int64ColumnWriter(typed) = column_writer.untyped() {
@@ -1270,7 +1299,7 @@ mod test {
         }).to_string());
 
         let maybe_happened = Field::from(&fields[1]);
-        assert_eq!(maybe_happened.writer_snippet().to_string(),(quote!{
+        assert_eq!(maybe_happened.writer_snippet().unwrap().to_string(),(quote!{
             {
                 let definition_levels : Vec<i16> = self.iter().map(|rec| if rec.maybe_happened.is_some() { 1 } else { 0 }).collect();
                 let vals : Vec<_> = records.iter().filter_map(|rec| {
@@ -1332,7 +1361,7 @@ mod test {
 
         let fields = extract_fields(snippet);
         let when = Field::from(&fields[0]);
-        assert_eq!(when.writer_snippet().to_string(),(quote!{
+        assert_eq!(when.writer_snippet().unwrap().to_string(),(quote!{
             {
                 let vals : Vec<_> = records.iter().map(|rec| rec.henceforth.signed_duration_since(::chrono::NaiveDate::from_ymd(1970, 1, 1)).num_days() as i32).collect();
                 if let ColumnWriter::Int32ColumnWriter(typed) = column_writer.untyped() {
@@ -1344,7 +1373,7 @@ mod test {
         }).to_string());
 
         let maybe_happened = Field::from(&fields[1]);
-        assert_eq!(maybe_happened.writer_snippet().to_string(),(quote!{
+        assert_eq!(maybe_happened.writer_snippet().unwrap().to_string(),(quote!{
             {
                 let definition_levels : Vec<i16> = self.iter().map(|rec| if rec.maybe_happened.is_some() { 1 } else { 0 }).collect();
                 let vals : Vec<_> = records.iter().filter_map(|rec| {
@@ -1406,7 +1435,7 @@ mod test {
 
         let fields = extract_fields(snippet);
         let when = Field::from(&fields[0]);
-        assert_eq!(when.writer_snippet().to_string(),(quote!{
+        assert_eq!(when.writer_snippet().unwrap().to_string(),(quote!{
             {
                 let vals : Vec<_> = records.iter().map(|rec| rec.unique_id.as_bytes().to_vec().into() ).collect();
                 if let ColumnWriter::FixedLenByteArrayColumnWriter(typed) = column_writer.untyped() {
@@ -1418,7 +1447,7 @@ mod test {
         }).to_string());
 
         let maybe_happened = Field::from(&fields[1]);
-        assert_eq!(maybe_happened.writer_snippet().to_string(),(quote!{
+        assert_eq!(maybe_happened.writer_snippet().unwrap().to_string(),(quote!{
             {
                 let definition_levels : Vec<i16> = self.iter().map(|rec| if rec.maybe_unique_id.is_some() { 1 } else { 0 }).collect();
                 let vals : Vec<_> = records.iter().filter_map(|rec| {
@@ -22,7 +22,8 @@
 #![cfg_attr(docsrs, feature(doc_cfg))]
 #![allow(clippy::approx_constant)]
 
-use parquet_derive::{ParquetRecordReader, ParquetRecordWriter};
+use parquet_derive::{ParquetField, ParquetRecordReader, ParquetRecordWriter};
+use std::collections::{BTreeMap, HashMap};
 use std::sync::Arc;
 
 #[derive(ParquetRecordWriter)]
@@ -109,6 +110,60 @@ struct APrunedRecord {
     pub isize: isize,
 }
 
+#[derive(PartialEq, ParquetField, Debug, Default, Clone, Copy)]
+enum Colour {
+    #[default]
+    Red,
+    #[parquet(rename = "GREEN")]
+    Green,
+}
+
+#[derive(PartialEq, ParquetField, Debug, Default, Clone, Copy)]
+#[parquet(repr = "int")]
+enum Priority {
+    #[default]
+    Low = 1,
+    High = 10,
+}
+
+#[derive(PartialEq, ParquetField, Debug, Default, Clone)]
+struct APoint {
+    x: i32,
+    y: Option<f64>,
+    colour: Colour,
+}
+
+#[derive(PartialEq, ParquetField, Debug, Default, Clone)]
+struct AShape {
+    name: String,
+    points: Vec<APoint>,
+    #[parquet(skip)]
+    area: f64,
+}
+
+#[derive(PartialEq, ParquetRecordWriter, ParquetRecordReader, Debug)]
+struct ANestedRecord {
+    id: i64,
+    origin: APoint,
+    maybe_origin: Option<APoint>,
+    shapes: Vec<AShape>,
+    numbers: Vec<i32>,
+    maybe_numbers: Option<Vec<Option<i32>>>,
+    names: Vec<String>,
+    blobs: Vec<Vec<u8>>,
+    counts: HashMap<String, u32>,
+    ordered: BTreeMap<i32, Vec<String>>,
+    colour: Colour,
+    maybe_colour: Option<Colour>,
+    priority: Priority,
+    #[parquet(rename = "label")]
+    name: String,
+    #[parquet(logical_type = "json")]
+    json: String,
+    #[parquet(skip)]
+    cache: Vec<u64>,
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -394,6 +449,203 @@ mod tests {
         assert!(foo.validate().is_ok());
     }
 
+    fn nested_records() -> Vec<ANestedRecord> {
+        let point = |x, y, colour| APoint { x, y, colour };
+        vec![
+            ANestedRecord {
+                id: 1,
+                origin: point(1, Some(2.5), Colour::Red),
+                maybe_origin: None,
+                shapes: vec![
+                    AShape {
+                        name: "triangle".into(),
+                        points: vec![
+                            point(0, Some(0.), Colour::Red),
+                            point(1, None, Colour::Green),
+                            point(2, Some(1.), Colour::Red),
+                        ],
+                        area: 0.,
+                    },
+                    AShape {
+                        name: "empty".into(),
+                        points: vec![],
+                        area: 0.,
+                    },
+                ],
+                numbers: vec![1, 2, 3],
+                maybe_numbers: Some(vec![Some(1), None]),
+                names: vec![],
+                blobs: vec![vec![1, 2], vec![]],
+                counts: HashMap::from([("a".into(), 1), ("b".into(), 2)]),
+                ordered: BTreeMap::from([(1, vec!["x".into()]), (2, vec![])]),
+                colour: Colour::Green,
+                maybe_colour: None,
+                priority: Priority::High,
+                name: "first".into(),
+                json: "{}".into(),
+                cache: vec![],
+            },
+            ANestedRecord {
+                id: 2,
+                origin: point(-1, None, Colour::Green),
+                maybe_origin: Some(point(3, Some(4.), Colour::Green)),
+                shapes: vec![],
+                numbers: vec![],
+                maybe_numbers: None,
+                names: vec!["a".into(), "b".into()],
+                blobs: vec![],
+                counts: HashMap::new(),
+                ordered: BTreeMap::new(),
+                colour: Colour::Red,
+                maybe_colour: Some(Colour::Green),
+                priority: Priority::Low,
+                name: "second".into(),
+                json: "[1]".into(),
+                cache: vec![],
+            },
+        ]
+    }
+
+    #[test]
+    fn test_parquet_derive_nested_schema() {
+        let drs = nested_records();
+        let generated_schema = drs.as_slice().schema().unwrap();
+
+        let schema_str = "message rust_schema {
+            REQUIRED INT64 id;
+            REQUIRED group origin {
+                REQUIRED INT32 x;
+                OPTIONAL DOUBLE y;
+                REQUIRED BINARY colour (ENUM);
+            }
+            OPTIONAL group maybe_origin {
+                REQUIRED INT32 x;
+                OPTIONAL DOUBLE y;
+                REQUIRED BINARY colour (ENUM);
+            }
+            REQUIRED group shapes (LIST) {
+                REPEATED group list {
+                    REQUIRED group element {
+                        REQUIRED BINARY name (STRING);
+                        REQUIRED group points (LIST) {
+                            REPEATED group list {
+                                REQUIRED group element {
+                                    REQUIRED INT32 x;
+                                    OPTIONAL DOUBLE y;
+                                    REQUIRED BINARY colour (ENUM);
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+            REQUIRED group numbers (LIST) {
+                REPEATED group list {
+                    REQUIRED INT32 element;
+                }
+            }
+            OPTIONAL group maybe_numbers (LIST) {
+                REPEATED group list {
+                    OPTIONAL INT32 element;
+                }
+            }
+            REQUIRED group names (LIST) {
+                REPEATED group list {
+                    REQUIRED BINARY element (STRING);
+                }
+            }
+            REQUIRED group blobs (LIST) {
+                REPEATED group list {
+                    REQUIRED BINARY element;
+                }
+            }
+            REQUIRED group counts (MAP) {
+                REPEATED group key_value {
+                    REQUIRED BINARY key (STRING);
+                    REQUIRED INT32 value (INTEGER(32,false));
+                }
+            }
+            REQUIRED group ordered (MAP) {
+                REPEATED group key_value {
+                    REQUIRED INT32 key;
+                    REQUIRED group value (LIST) {
+                        REPEATED group list {
+                            REQUIRED BINARY element (STRING);
+                        }
+                    }
+                }
+            }
+            REQUIRED BINARY colour (ENUM);
+            OPTIONAL BINARY maybe_colour (ENUM);
+            REQUIRED INT32 priority;
+            REQUIRED BINARY label (STRING);
+            REQUIRED BINARY json (JSON);
+        }";
+        let schema = Arc::new(parse_message_type(schema_str).unwrap());
+        assert_eq!(&schema, &generated_schema);
+    }
+
+    #[test]
+    fn test_parquet_derive_nested_read_write() {
+        use parquet::file::{reader::FileReader, serialized_reader::SerializedFileReader};
+
+        let file = get_temp_file("test_parquet_derive_nested", &[]);
+        let mut drs = nested_records();
+        drs[0].cache = vec![1, 2];
+        drs[0].shapes[0].area = 0.5;
+
+        let generated_schema = drs.as_slice().schema().unwrap();
+        let props = Default::default();
+        let mut writer =
+            SerializedFileWriter::new(file.try_clone().unwrap(), generated_schema, props).unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+        drs.as_slice().write_to_row_group(&mut row_group).unwrap();
+        row_group.close().unwrap();
+        writer.close().unwrap();
+
+        let reader = SerializedFileReader::new(file).unwrap();
+        let mut row_group = reader.get_row_group(0).unwrap();
+        let mut out: Vec<ANestedRecord> = Vec::new();
+        out.read_from_row_group(&mut *row_group, 2).unwrap();
+
+        // Skipped fields are read as their default
+        drs[0].cache = vec![];
+        drs[0].shapes[0].area = 0.;
+        assert_eq!(drs, out);
+    }
+
+    #[test]
+    fn test_parquet_derive_read_flat_field_ignores_nested_leaf() {
+        use parquet::file::{reader::FileReader, serialized_reader::SerializedFileReader};
+
+        #[derive(ParquetRecordReader)]
+        struct AFlatRecord {
+            x: i32,
+        }
+
+        let file = get_temp_file("test_parquet_derive_flat_nested_leaf", &[]);
+        let drs = nested_records();
+
+        let generated_schema = drs.as_slice().schema().unwrap();
+        let props = Default::default();
+        let mut writer =
+            SerializedFileWriter::new(file.try_clone().unwrap(), generated_schema, props).unwrap();
+        let mut row_group = writer.next_row_group().unwrap();
+        drs.as_slice().write_to_row_group(&mut row_group).unwrap();
+        row_group.close().unwrap();
+        writer.close().unwrap();
+
+        // "origin.x" must not be read as the top-level column "x"
+        let reader = SerializedFileReader::new(file).unwrap();
+        let mut row_group = reader.get_row_group(0).unwrap();
+        let mut out: Vec<AFlatRecord> = Vec::new();
+        let err = out.read_from_row_group(&mut *row_group, 2).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Parquet error: column name 'x' is not found in parquet file!"
+        );
+    }
+
     /// Returns file handle for a temp file in 'target' directory with a provided content
     pub fn get_temp_file(file_name: &str, content: &[u8]) -> fs::File {
         // build tmp path to a file in "target/debug/testdata"